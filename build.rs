@@ -0,0 +1,349 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+
+use image::DynamicImage;
+use image::imageops::FilterType;
+use sha2::{Digest, Sha256};
+
+const PUBLIC_DIR: &str = "public";
+const FRONT_DIR: &str = "front";
+/// 带指纹的 CSS / JS 副本目录，对应 `/public/assets/` 路径
+const FINGERPRINT_DIR: &str = "public/assets";
+/// 与 `web::ASSET_MANIFEST_PATH` 保持一致
+const ASSET_MANIFEST_PATH: &str = "front/manifest.json";
+/// 文件名中保留的 SHA-256 十六进制位数
+const FINGERPRINT_LEN: usize = 8;
+/// 需要检查占位符的模板目录
+const TEMPLATE_DIRS: [&str; 2] = ["front", "front/admin"];
+/// 代码中会替换的模板占位符，与 `replace_template` 调用的键保持一致；新增占位符时同步补充
+const KNOWN_PLACEHOLDERS: [&str; 34] = [
+    "beian_number",
+    "breadcrumb_items",
+    "csrf_token",
+    "description",
+    "expected_updated_at",
+    "keyword_cloud",
+    "keywords",
+    "list_url",
+    "live_updates",
+    "markdown_field",
+    "markdown_html",
+    "notice",
+    "page_count",
+    "page_list",
+    "page_title",
+    "page_title_encoded",
+    "page_uid",
+    "page_url",
+    "page_url_encoded",
+    "popular_pages",
+    "preview",
+    "preview_button",
+    "pwa_head",
+    "recent_updates",
+    "related_items",
+    "rows",
+    "save_url",
+    "seo_title",
+    "site_header",
+    "site_subtitle",
+    "site_title",
+    "title",
+    "updated_at",
+    "view_count",
+];
+/// `{{asset:NAME}}` 引用 `front/` 下的 CSS / JS，见 `web::replace_asset_placeholders`
+const ASSET_PLACEHOLDER_PREFIX: &str = "asset:";
+/// `{{public:NAME}}` 引用 `public/` 下的文件（如 `light.png`），见 `web::asset_url`
+const PUBLIC_PLACEHOLDER_PREFIX: &str = "public:";
+/// 需要转换为标准 PNG 的特殊图片名
+const SPECIAL_IMAGES: [&str; 3] = ["icon", "light", "night"];
+const SOURCE_EXTENSIONS: [&str; 7] = ["jpg", "jpeg", "webp", "gif", "bmp", "tiff", "tif"];
+/// 与 `web::PWA_ICON_SIZES` 保持一致
+const PWA_ICON_SIZES: [u32; 2] = [192, 512];
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    for name in SPECIAL_IMAGES {
+        convert_special_image(name);
+    }
+    for name in SPECIAL_IMAGES {
+        generate_special_webp(name);
+    }
+    generate_pwa_icons();
+    fingerprint_front_assets();
+    validate_templates();
+}
+
+/// 存在非 PNG 格式的特殊图片且对应 `.png` 缺失时转换生成，原文件保留
+fn convert_special_image(name: &str) {
+    let target = Path::new(PUBLIC_DIR).join(format!("{name}.png"));
+    let Some(source) = find_special_source(name) else {
+        return;
+    };
+    println!("cargo:rerun-if-changed={}", source.display());
+    if target.exists() {
+        return;
+    }
+    if let Err(err) = image::open(&source).and_then(|image| image.save(&target)) {
+        println!(
+            "cargo:warning=convert {} to png failed: {}",
+            source.display(),
+            err
+        );
+    }
+}
+
+fn find_special_source(name: &str) -> Option<PathBuf> {
+    SOURCE_EXTENSIONS
+        .iter()
+        .map(|ext| Path::new(PUBLIC_DIR).join(format!("{name}.{ext}")))
+        .find(|path| path.is_file())
+}
+
+/// 由特殊图片的 PNG 生成同名 `.webp`（无损编码），支持 WebP 的浏览器优先使用；
+/// PNG 比已有的 `.webp` 新时重新生成，PNG 本身保留给不支持 WebP 的浏览器
+fn generate_special_webp(name: &str) {
+    let source = Path::new(PUBLIC_DIR).join(format!("{name}.png"));
+    if !source.is_file() {
+        return;
+    }
+    println!("cargo:rerun-if-changed={}", source.display());
+    let target = Path::new(PUBLIC_DIR).join(format!("{name}.webp"));
+    let up_to_date = match (modified_time(&target), modified_time(&source)) {
+        (Some(target_time), Some(source_time)) => target_time >= source_time,
+        _ => false,
+    };
+    if up_to_date {
+        return;
+    }
+    if let Err(err) = image::open(&source).and_then(|image| image.save(&target)) {
+        println!(
+            "cargo:warning=convert {} to webp failed: {}",
+            source.display(),
+            err
+        );
+    }
+}
+
+/// 由 `public/icon.png` 生成 PWA 所需的多尺寸方形图标 `public/icon-{size}.png`
+fn generate_pwa_icons() {
+    let source = Path::new(PUBLIC_DIR).join("icon.png");
+    if !source.is_file() {
+        return;
+    }
+    println!("cargo:rerun-if-changed={}", source.display());
+    let source_modified = modified_time(&source);
+
+    let mut icon: Option<DynamicImage> = None;
+    for size in PWA_ICON_SIZES {
+        let target = Path::new(PUBLIC_DIR).join(format!("icon-{size}.png"));
+        let up_to_date = match (modified_time(&target), source_modified) {
+            (Some(target_time), Some(source_time)) => target_time >= source_time,
+            _ => false,
+        };
+        if up_to_date {
+            continue;
+        }
+        if icon.is_none() {
+            match image::open(&source) {
+                Ok(image) => icon = Some(image),
+                Err(err) => {
+                    println!("cargo:warning=load {} failed: {}", source.display(), err);
+                    return;
+                }
+            }
+        }
+        let Some(image) = icon.as_ref() else {
+            return;
+        };
+        let resized = image.resize_to_fill(size, size, FilterType::Lanczos3);
+        if let Err(err) = resized.save(&target) {
+            println!("cargo:warning=write {} failed: {}", target.display(), err);
+        }
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// 与 `web::is_fingerprinted_asset_name` 保持一致
+fn is_fingerprinted_asset_name(name: &str) -> bool {
+    name != "sw.js" && (name.ends_with(".css") || name.ends_with(".js"))
+}
+
+/// 为 `front/` 下的 CSS / JS 计算 SHA-256，以 `example.abc123de.css` 的名称复制到 `public/assets/`，
+/// 并写入 `front/manifest.json`（原文件名 → 指纹文件名）；同名资源的旧指纹副本一并删除
+fn fingerprint_front_assets() {
+    println!("cargo:rerun-if-changed={FRONT_DIR}");
+    let Ok(entries) = fs::read_dir(FRONT_DIR) else {
+        return;
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| is_fingerprinted_asset_name(name))
+        .collect();
+    names.sort();
+
+    let mut manifest = BTreeMap::new();
+    for name in names {
+        let source = Path::new(FRONT_DIR).join(&name);
+        println!("cargo:rerun-if-changed={}", source.display());
+        let content = match fs::read(&source) {
+            Ok(content) => content,
+            Err(err) => {
+                println!("cargo:warning=read {} failed: {}", source.display(), err);
+                continue;
+            }
+        };
+        let digest = format!("{:x}", Sha256::digest(&content));
+        let Some((stem, ext)) = name.rsplit_once('.') else {
+            continue;
+        };
+        let hashed = format!("{stem}.{}.{ext}", &digest[..FINGERPRINT_LEN]);
+        let target = Path::new(FINGERPRINT_DIR).join(&hashed);
+        if !target.is_file() {
+            let written =
+                fs::create_dir_all(FINGERPRINT_DIR).and_then(|_| fs::write(&target, &content));
+            if let Err(err) = written {
+                println!("cargo:warning=write {} failed: {}", target.display(), err);
+                continue;
+            }
+        }
+        remove_stale_fingerprints(stem, ext, &hashed);
+        manifest.insert(name, hashed);
+    }
+
+    let json = match serde_json::to_string_pretty(&manifest) {
+        Ok(json) => json + "\n",
+        Err(err) => {
+            println!("cargo:warning=serialize {ASSET_MANIFEST_PATH} failed: {err}");
+            return;
+        }
+    };
+    // 内容不变时不重写，避免清单的修改时间触发下一次构建重新运行
+    if fs::read_to_string(ASSET_MANIFEST_PATH).ok().as_deref() == Some(json.as_str()) {
+        return;
+    }
+    if let Err(err) = fs::write(ASSET_MANIFEST_PATH, json) {
+        println!("cargo:warning=write {ASSET_MANIFEST_PATH} failed: {err}");
+    }
+}
+
+/// 删除 `public/assets/` 中同一资源的其他指纹副本，如 `example.0badc0de.css`
+fn remove_stale_fingerprints(stem: &str, ext: &str, current: &str) {
+    let Ok(entries) = fs::read_dir(FINGERPRINT_DIR) else {
+        return;
+    };
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let Ok(file_name) = entry.file_name().into_string() else {
+            continue;
+        };
+        let is_stale = file_name != current
+            && file_name
+                .strip_prefix(stem)
+                .and_then(|rest| rest.strip_prefix('.'))
+                .and_then(|rest| rest.strip_suffix(ext))
+                .and_then(|rest| rest.strip_suffix('.'))
+                .is_some_and(|hash| {
+                    hash.len() == FINGERPRINT_LEN
+                        && hash.bytes().all(|byte| byte.is_ascii_hexdigit())
+                });
+        if is_stale && let Err(err) = fs::remove_file(entry.path()) {
+            println!(
+                "cargo:warning=remove {} failed: {}",
+                entry.path().display(),
+                err
+            );
+        }
+    }
+}
+
+/// 检查 `front/` 与 `front/admin/` 下 HTML 模板中的 `{{...}}` 是否都是代码认识的占位符，
+/// 否则运行时替换会漏掉或报错。默认只输出 `cargo:warning`，`TEMPLATE_CHECK=strict` 时构建失败
+fn validate_templates() {
+    println!("cargo:rerun-if-env-changed=TEMPLATE_CHECK");
+    let strict = std::env::var("TEMPLATE_CHECK")
+        .is_ok_and(|value| value.trim().eq_ignore_ascii_case("strict"));
+    let mut problems = Vec::new();
+    for dir in TEMPLATE_DIRS {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "html"))
+            .collect();
+        paths.sort();
+        for path in paths {
+            println!("cargo:rerun-if-changed={}", path.display());
+            match fs::read_to_string(&path) {
+                Ok(template) => problems.extend(
+                    find_unknown_placeholders(&template)
+                        .into_iter()
+                        .map(|(line, problem)| format!("{}:{}: {}", path.display(), line, problem)),
+                ),
+                Err(err) => println!("cargo:warning=read {} failed: {}", path.display(), err),
+            }
+        }
+    }
+    for problem in &problems {
+        println!("cargo:warning={problem}");
+    }
+    if strict && !problems.is_empty() {
+        panic!(
+            "{} unknown template placeholder(s), see warnings above (TEMPLATE_CHECK=strict)",
+            problems.len()
+        );
+    }
+}
+
+/// 返回 `(行号, 说明)`；`{{` 与 `}}` 之间不是标识符的内容（如内联脚本里的对象字面量）不算占位符
+fn find_unknown_placeholders(template: &str) -> Vec<(usize, String)> {
+    let mut problems = Vec::new();
+    let mut index = 0usize;
+    while let Some(found) = template[index..].find("{{") {
+        let start = index + found + 2;
+        let Some(len) = template[start..].find("}}") else {
+            break;
+        };
+        let name = &template[start..start + len];
+        index = start + len + 2;
+        let line = template[..start].matches('\n').count() + 1;
+        if let Some(asset) = name.strip_prefix(ASSET_PLACEHOLDER_PREFIX) {
+            let asset = asset.trim();
+            if !is_fingerprinted_asset_name(asset) || !Path::new(FRONT_DIR).join(asset).is_file() {
+                let problem = format!("{{{{{name}}}}} does not name a CSS / JS file in front/");
+                problems.push((line, problem));
+            }
+            continue;
+        }
+        // `public/` 下的特殊图片是可选的，文件不存在时渲染为不带指纹的地址，这里只检查路径形式
+        if let Some(file) = name.strip_prefix(PUBLIC_PLACEHOLDER_PREFIX) {
+            let file = file.trim();
+            let is_relative = !file.is_empty()
+                && Path::new(file)
+                    .components()
+                    .all(|component| matches!(component, Component::Normal(_)));
+            if !is_relative {
+                let problem = format!("{{{{{name}}}}} must be a relative path under public/");
+                problems.push((line, problem));
+            }
+            continue;
+        }
+        let is_identifier = !name.is_empty()
+            && name
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || byte == b'_');
+        if is_identifier && !KNOWN_PLACEHOLDERS.contains(&name) {
+            problems.push((line, format!("unknown placeholder {{{{{name}}}}}")));
+        }
+    }
+    problems
+}
@@ -0,0 +1,57 @@
+// SolinBlog service worker：仅缓存背景图与图标，页面内容始终走网络
+const CACHE_NAME = "solinblog-static-v1";
+const PRECACHE_URLS = [
+  "/favicon.ico",
+  "/icon.png",
+  "/apple-touch-icon.png",
+  "/public/icon-192.png",
+  "/public/icon-512.png",
+  "/public/light.png",
+  "/public/night.png",
+];
+
+self.addEventListener("install", (event) => {
+  event.waitUntil(
+    caches
+      .open(CACHE_NAME)
+      .then((cache) =>
+        Promise.all(PRECACHE_URLS.map((url) => cache.add(url).catch(() => undefined)))
+      )
+      .then(() => self.skipWaiting())
+  );
+});
+
+self.addEventListener("activate", (event) => {
+  event.waitUntil(
+    caches
+      .keys()
+      .then((keys) =>
+        Promise.all(keys.filter((key) => key !== CACHE_NAME).map((key) => caches.delete(key)))
+      )
+      .then(() => self.clients.claim())
+  );
+});
+
+self.addEventListener("fetch", (event) => {
+  const request = event.request;
+  if (request.method !== "GET") {
+    return;
+  }
+  const url = new URL(request.url);
+  if (url.origin !== self.location.origin || !PRECACHE_URLS.includes(url.pathname)) {
+    return;
+  }
+  event.respondWith(
+    caches.match(request).then(
+      (cached) =>
+        cached ||
+        fetch(request).then((response) => {
+          if (response.ok) {
+            const copy = response.clone();
+            caches.open(CACHE_NAME).then((cache) => cache.put(request, copy));
+          }
+          return response;
+        })
+    )
+  );
+});
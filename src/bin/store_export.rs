@@ -0,0 +1,191 @@
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use solin_blog::store::{PageMeta, PageStore, sanitize_page_id, to_url_slug};
+use solin_blog::web::html_to_markdown;
+
+struct ExportOptions {
+    output_dir: PathBuf,
+    only_with_markdown: bool,
+}
+
+#[derive(Default)]
+struct ExportStats {
+    total: usize,
+    copied_markdown: usize,
+    converted_html: usize,
+    skipped: usize,
+    failed: usize,
+}
+
+fn print_usage() {
+    println!("usage: store_export <output_dir> [--only-with-markdown]");
+    println!();
+    println!("  <output_dir>            导出目录（不存在时自动创建）");
+    println!("  --only-with-markdown    跳过没有 Markdown 源文件的 HTML 页面");
+}
+
+fn parse_args() -> Result<ExportOptions> {
+    let mut output_dir: Option<PathBuf> = None;
+    let mut only_with_markdown = false;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--only-with-markdown" => only_with_markdown = true,
+            "-h" | "--help" => {
+                print_usage();
+                std::process::exit(0);
+            }
+            value if value.starts_with("--") => bail!("unknown option: {}", value),
+            value => {
+                if output_dir.is_some() {
+                    bail!("unexpected argument: {}", value);
+                }
+                output_dir = Some(PathBuf::from(value));
+            }
+        }
+    }
+    let Some(output_dir) = output_dir else {
+        print_usage();
+        bail!("missing output directory");
+    };
+    Ok(ExportOptions {
+        output_dir,
+        only_with_markdown,
+    })
+}
+
+fn build_frontmatter(meta: &PageMeta) -> Result<String> {
+    let title = if meta.seo.title.is_empty() {
+        &meta.seo.seo_title
+    } else {
+        &meta.seo.title
+    };
+    let keywords = meta.seo.keywords.clone().unwrap_or_default();
+    // YAML 的双引号字符串兼容 JSON 转义，直接复用 serde_json 序列化
+    let mut out = String::from("---\n");
+    out.push_str(&format!(
+        "title: {}\n",
+        serde_json::to_string(title).context("serialize title")?
+    ));
+    out.push_str(&format!(
+        "description: {}\n",
+        serde_json::to_string(&meta.seo.description).context("serialize description")?
+    ));
+    out.push_str(&format!(
+        "keywords: {}\n",
+        serde_json::to_string(&keywords).context("serialize keywords")?
+    ));
+    out.push_str(&format!("created_at: {}\n", meta.created_at));
+    out.push_str(&format!("updated_at: {}\n", meta.updated_at));
+    out.push_str(&format!(
+        "page_uid: {}\n",
+        serde_json::to_string(&meta.page_uid).context("serialize page_uid")?
+    ));
+    out.push_str("---\n\n");
+    Ok(out)
+}
+
+fn build_export_filename(page_id: &str, meta: &PageMeta) -> String {
+    let uid = if meta.page_uid.is_empty() {
+        page_id
+    } else {
+        meta.page_uid.as_str()
+    };
+    let title = if meta.seo.title.is_empty() {
+        &meta.seo.seo_title
+    } else {
+        &meta.seo.title
+    };
+    let slug = to_url_slug(title);
+    if slug.is_empty() {
+        format!("{}.md", sanitize_page_id(uid))
+    } else {
+        format!("{}_{}.md", sanitize_page_id(uid), sanitize_page_id(&slug))
+    }
+}
+
+fn export_page(
+    store: &PageStore,
+    page_id: &str,
+    options: &ExportOptions,
+    stats: &mut ExportStats,
+) -> Result<()> {
+    let (meta, html) = store
+        .load_page(page_id)
+        .with_context(|| format!("load page {}", page_id))?;
+    let markdown = store
+        .load_page_markdown(page_id)
+        .with_context(|| format!("load markdown {}", page_id))?;
+    let (body, from_markdown) = match markdown {
+        Some(markdown) => (markdown, true),
+        None if options.only_with_markdown => {
+            stats.skipped += 1;
+            println!("skip {} (no markdown source)", page_id);
+            return Ok(());
+        }
+        None => (html_to_markdown(&html), false),
+    };
+
+    let mut content = build_frontmatter(&meta)?;
+    content.push_str(&body);
+    if !content.ends_with('\n') {
+        content.push('\n');
+    }
+    let output_path = options
+        .output_dir
+        .join(build_export_filename(page_id, &meta));
+    fs::write(&output_path, content.as_bytes())
+        .with_context(|| format!("write export file {:?}", output_path))?;
+
+    if from_markdown {
+        stats.copied_markdown += 1;
+        println!(
+            "export {} -> {} (markdown)",
+            page_id,
+            display_path(&output_path)
+        );
+    } else {
+        stats.converted_html += 1;
+        println!(
+            "export {} -> {} (html)",
+            page_id,
+            display_path(&output_path)
+        );
+    }
+    Ok(())
+}
+
+fn display_path(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+fn main() -> Result<()> {
+    let options = parse_args()?;
+    fs::create_dir_all(&options.output_dir)
+        .with_context(|| format!("create output dir {:?}", options.output_dir))?;
+
+    let store = PageStore::new("data");
+    let entries = store.list_page_entries().context("list page entries")?;
+    let mut stats = ExportStats::default();
+    for entry in entries {
+        stats.total += 1;
+        if let Err(err) = export_page(&store, &entry.page_id, &options, &mut stats) {
+            stats.failed += 1;
+            println!("export {} failed: {:#}", entry.page_id, err);
+        }
+    }
+
+    println!();
+    println!("export finished: {}", display_path(&options.output_dir));
+    println!("  total pages:      {}", stats.total);
+    println!("  from markdown:    {}", stats.copied_markdown);
+    println!("  converted html:   {}", stats.converted_html);
+    println!("  skipped:          {}", stats.skipped);
+    println!("  failed:           {}", stats.failed);
+
+    if stats.failed > 0 {
+        bail!("{} page(s) failed to export", stats.failed);
+    }
+    Ok(())
+}
@@ -1,10 +1,24 @@
-use anyhow::{ensure, Context, Result};
+use anyhow::{Context, Result, ensure};
 use serde_json::Map;
 use std::fs;
+use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use solin_blog::store::{sanitize_page_id, PageMeta, PageStore, SeoMeta};
+use axum::http::{HeaderMap, HeaderValue};
+use solin_blog::config::{IpCidr, resolve_site_title_from_env};
+use solin_blog::server::{
+    ClientLru, RateLimitClass, RateLimiter, check_validate_rate, readiness_failed_check,
+    resolve_client_ip,
+};
+use solin_blog::store::{
+    HtmlError, PageMeta, PageStore, SeoMeta, TRASH_DIR, UpdateConflict,
+    html_util::{find_bytes, find_bytes_case_insensitive},
+    sanitize_page_id, validate_html,
+};
+use solin_blog::web::{build_breadcrumb_json_ld, build_page_url, render_served_page};
 
 struct PageDirGuard {
     page_dir: PathBuf,
@@ -16,6 +30,45 @@ impl Drop for PageDirGuard {
     }
 }
 
+/// 清理自检留在回收站中的条目，回收站为空时一并删除
+struct TrashGuard {
+    trash_dir: PathBuf,
+    prefix: String,
+}
+
+impl Drop for TrashGuard {
+    fn drop(&mut self) {
+        let Ok(entries) = fs::read_dir(&self.trash_dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            if entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(&self.prefix)
+            {
+                let _ = fs::remove_dir_all(entry.path());
+            }
+        }
+        let _ = fs::remove_dir(&self.trash_dir);
+    }
+}
+
+/// 恢复只读探测目录的权限并删除
+#[cfg(unix)]
+struct ReadOnlyDirGuard {
+    dir: PathBuf,
+}
+
+#[cfg(unix)]
+impl Drop for ReadOnlyDirGuard {
+    fn drop(&mut self) {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(&self.dir, fs::Permissions::from_mode(0o755));
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
 struct IndexSnapshotGuard {
     data_dir: PathBuf,
     index_path: PathBuf,
@@ -83,7 +136,23 @@ fn main() -> Result<()> {
         data_dir_existed,
     };
 
-    let store = PageStore::new("data");
+    let created_hooks = Arc::new(AtomicUsize::new(0));
+    let updated_hooks = Arc::new(AtomicUsize::new(0));
+    let store = {
+        let created_hooks = Arc::clone(&created_hooks);
+        let updated_hooks = Arc::clone(&updated_hooks);
+        PageStore::builder()
+            .base_dir("data")
+            .on_create(move |meta| {
+                if !meta.page_uid.is_empty() {
+                    created_hooks.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+            .on_update(move |_| {
+                updated_hooks.fetch_add(1, Ordering::SeqCst);
+            })
+            .build()
+    };
 
     let unix_secs = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -95,6 +164,10 @@ fn main() -> Result<()> {
     let _page_guard = PageDirGuard {
         page_dir: data_dir.join(&safe_id),
     };
+    let _trash_guard = TrashGuard {
+        trash_dir: data_dir.join(TRASH_DIR),
+        prefix: format!("{}-", safe_id),
+    };
 
     let meta = PageMeta {
         seo: SeoMeta {
@@ -103,11 +176,16 @@ fn main() -> Result<()> {
             description: "CRUD selfcheck for store".to_string(),
             keywords: Some(vec!["selfcheck".to_string(), "store".to_string()]),
             extra: Map::new(),
+            structured_data: None,
         },
         page_uid: String::new(),
         created_at: 0,
         updated_at: 0,
         view_count: 0,
+        unique_view_count: 0,
+        tags: None,
+        sort_order: None,
+        published_at: None,
         extra: Map::new(),
     };
     let html = concat!(
@@ -153,11 +231,16 @@ fn main() -> Result<()> {
             description: "Updated description".to_string(),
             keywords: Some(vec!["selfcheck".to_string(), "update".to_string()]),
             extra: Map::new(),
+            structured_data: None,
         },
         page_uid: String::new(),
         created_at: 0,
         updated_at: 0,
         view_count: 0,
+        unique_view_count: 0,
+        tags: None,
+        sort_order: None,
+        published_at: None,
         extra: Map::new(),
     };
 
@@ -191,7 +274,7 @@ fn main() -> Result<()> {
     );
     println!("update html");
     store
-        .update_page_html(&page_id, html2)
+        .update_page_html(&page_id, html2, true)
         .context("update html")?;
     let (_, updated_html) = store.load_page(&page_id).context("load after html")?;
     let (updated_meta_after_html, _) = store.load_page(&page_id).context("load after html meta")?;
@@ -206,16 +289,657 @@ fn main() -> Result<()> {
     ensure!(updated_html == html2, "updated html mismatch");
     println!("update html ok");
 
+    println!("update html without touching updated_at");
+    // 模板重新渲染不算内容修改，updated_at 保持不变
+    store
+        .update_page_html(&page_id, html2, false)
+        .context("update html without touch")?;
+    let untouched = store
+        .get_page_meta(&page_id)
+        .context("get meta after untouched html")?;
+    ensure!(
+        untouched.updated_at == updated_meta_after_html.updated_at,
+        "updated_at changed by untouched html update"
+    );
+    println!("update html without touching updated_at ok");
+
+    println!("page hooks");
+    // 创建一次；更新 meta、更新 HTML 与不改 updated_at 的 HTML 更新各一次
+    ensure!(
+        created_hooks.load(Ordering::SeqCst) == 1,
+        "create hook count mismatch"
+    );
+    ensure!(
+        updated_hooks.load(Ordering::SeqCst) == 3,
+        "update hook count mismatch"
+    );
+    println!("page hooks ok");
+
+    let html3 = concat!(
+        "<!doctype html>",
+        "<html>",
+        "<head><meta charset=\"utf-8\"><title>Store Selfcheck Revision</title></head>",
+        "<body><main><h1>Revision</h1><p>revision body</p></main></body>",
+        "</html>"
+    );
+    println!("revisions");
+    let oldest = store.create_revision(&page_id).context("create revision")?;
+    ensure!(oldest.revision_id == 1, "first revision id mismatch");
+    ensure!(oldest.html_bytes == html2.len(), "revision size mismatch");
+    store
+        .update_page_html(&page_id, html3, true)
+        .context("update html before restore")?;
+    let backup = store
+        .restore_revision(&page_id, oldest.revision_id)
+        .context("restore oldest revision")?;
+    ensure!(backup.revision_id == 2, "backup revision id mismatch");
+    let (restored_meta, restored_html) = store.load_page(&page_id).context("load after restore")?;
+    ensure!(restored_html == html2, "restored html mismatch");
+    ensure!(
+        restored_meta.page_uid == initial_uid,
+        "page uid changed after restore"
+    );
+    let backup_revision = store
+        .load_revision(&page_id, backup.revision_id)
+        .context("load backup revision")?;
+    ensure!(
+        backup_revision.html == html3,
+        "backup revision html mismatch"
+    );
+    let revision_ids: Vec<u64> = store
+        .list_revisions(&page_id)
+        .context("list revisions")?
+        .iter()
+        .map(|revision| revision.revision_id)
+        .collect();
+    ensure!(revision_ids == [1, 2], "revision list mismatch");
+    ensure!(
+        store.load_revision(&page_id, 99).is_err(),
+        "missing revision should fail"
+    );
+    println!("revisions ok");
+
+    println!("prune revisions");
+    ensure!(
+        store.prune_revisions(&page_id, 0).is_err(),
+        "keep 0 should be rejected"
+    );
+    let deleted = store
+        .prune_revisions(&page_id, 1)
+        .context("prune revisions")?;
+    ensure!(deleted == 1, "pruned revision count mismatch");
+    let remaining: Vec<u64> = store
+        .list_revisions(&page_id)
+        .context("list revisions after prune")?
+        .iter()
+        .map(|revision| revision.revision_id)
+        .collect();
+    ensure!(remaining == [2], "newest revision should be kept");
+    let next = store
+        .create_revision(&page_id)
+        .context("create revision after prune")?;
+    ensure!(next.revision_id == 3, "revision id reused after prune");
+    println!("prune revisions ok");
+
     println!("list pages");
     let pages = store.list_pages().context("list pages")?;
     ensure!(pages.iter().any(|id| id == &safe_id), "page not in index");
     println!("list pages ok");
 
+    println!("probe health");
+    let health = store.probe_health().context("probe health")?;
+    ensure!(health.index_ok, "index should parse: {:?}", health.errors);
+    ensure!(health.readable == Some(true), "data dir should be readable");
+    ensure!(health.writable == Some(true), "data dir should be writable");
+    ensure!(
+        !data_dir.join(".write-probe").exists(),
+        "write probe not removed"
+    );
+    println!("probe health ok");
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        println!("probe health read-only dir");
+        let readonly_dir = std::env::temp_dir().join(format!("{}-readonly", safe_id));
+        fs::create_dir_all(&readonly_dir).context("create read-only probe dir")?;
+        let _readonly_guard = ReadOnlyDirGuard {
+            dir: readonly_dir.clone(),
+        };
+        fs::set_permissions(&readonly_dir, fs::Permissions::from_mode(0o555))
+            .context("chmod read-only probe dir")?;
+        // root 不受目录权限限制，能写入时跳过
+        if fs::write(readonly_dir.join(".root-check"), b"ok").is_ok() {
+            println!("probe health read-only dir skipped: permissions are not enforced");
+        } else {
+            let readonly_store = PageStore::builder().base_dir(&readonly_dir).build();
+            let health = readonly_store
+                .probe_health()
+                .context("probe read-only health")?;
+            ensure!(
+                health.writable == Some(false),
+                "read-only dir should not be writable"
+            );
+            ensure!(
+                health.readable == Some(true),
+                "read-only dir should still be readable"
+            );
+            ensure!(
+                health
+                    .errors
+                    .iter()
+                    .any(|err| err.starts_with("data dir not writable")),
+                "missing data dir not writable error: {:?}",
+                health.errors
+            );
+            ensure!(
+                readiness_failed_check(&health) == Some("data_dir_writable"),
+                "readyz should name data_dir_writable, got {:?}",
+                readiness_failed_check(&health)
+            );
+            println!("probe health read-only dir ok");
+        }
+    }
+
+    println!("idempotency key");
+    store
+        .record_idempotency_key("selfcheck-key", &page_id, 0)
+        .context("record idempotency key")?;
+    let found = store
+        .find_idempotency_key("selfcheck-key", 0)
+        .context("find idempotency key")?;
+    ensure!(
+        found.as_deref() == Some(page_id.as_str()),
+        "idempotency key mismatch"
+    );
+    let expired = store
+        .find_idempotency_key("selfcheck-key", i64::MAX)
+        .context("find expired idempotency key")?;
+    ensure!(expired.is_none(), "expired idempotency key still returned");
+    println!("idempotency key ok");
+
+    println!("expected updated_at");
+    // 两个写入者读到同一版本，后保存的一方应得到冲突而不是覆盖前一方的修改
+    let (read_meta, read_html) = store
+        .load_page(&page_id)
+        .context("load page for conflict")?;
+    let mut first_meta = read_meta.clone();
+    first_meta.seo.description = "first writer".to_string();
+    store
+        .update_page_if_unmodified(
+            &page_id,
+            &first_meta,
+            &read_html,
+            None,
+            read_meta.updated_at,
+        )
+        .context("first writer update")?;
+    let saved = store
+        .get_page_meta(&page_id)
+        .context("get meta after first writer")?;
+    ensure!(
+        saved.updated_at > read_meta.updated_at,
+        "updated_at not advanced"
+    );
+    let mut second_meta = read_meta.clone();
+    second_meta.seo.description = "second writer".to_string();
+    let err = store
+        .update_page_if_unmodified(
+            &page_id,
+            &second_meta,
+            &read_html,
+            None,
+            read_meta.updated_at,
+        )
+        .err()
+        .context("second writer update should conflict")?;
+    let conflict = err
+        .downcast_ref::<UpdateConflict>()
+        .context("second writer error is not UpdateConflict")?;
+    ensure!(
+        conflict.current_updated_at == saved.updated_at,
+        "conflict current_updated_at mismatch"
+    );
+    let after = store
+        .get_page_meta(&page_id)
+        .context("get meta after conflict")?;
+    ensure!(
+        after.seo.description == "first writer",
+        "first writer edit lost"
+    );
+    println!("expected updated_at ok");
+
+    println!("recent updates");
+    // 索引中的 updated_at 随页面写入同步，最近更新列表不读取 meta.json
+    let recent = store
+        .list_recent_updates(usize::MAX)
+        .context("list recent updates")?;
+    let entry = recent
+        .iter()
+        .find(|entry| entry.page_id == page_id)
+        .context("page missing from recent updates")?;
+    ensure!(
+        entry.updated_at == after.updated_at,
+        "index updated_at not synced"
+    );
+    let sorted = recent
+        .windows(2)
+        .all(|pair| pair[0].updated_at >= pair[1].updated_at);
+    ensure!(sorted, "recent updates not sorted");
+    println!("recent updates ok");
+
+    println!("page extra head_html");
+    // head_html 会改变渲染结果：写入前校验，并推进 updated_at（HTML 接口的 ETag 由它派生）
+    let before_head = store
+        .get_page_meta(&page_id)
+        .context("get meta before head_html")?;
+    let snippet = "<link rel=\"preload\" href=\"/public/a.css\" as=\"style\">";
+    store
+        .set_page_extra(&page_id, "head_html", format!(" {snippet} ").into())
+        .context("set head_html")?;
+    let with_head = store
+        .get_page_meta(&page_id)
+        .context("get meta after head_html")?;
+    ensure!(
+        with_head
+            .extra
+            .get("head_html")
+            .and_then(|value| value.as_str())
+            == Some(snippet),
+        "head_html should be stored trimmed"
+    );
+    ensure!(
+        with_head.updated_at > before_head.updated_at,
+        "head_html should bump updated_at"
+    );
+    let head_entry = store
+        .list_recent_updates(usize::MAX)
+        .context("list recent updates after head_html")?
+        .into_iter()
+        .find(|entry| entry.page_id == page_id)
+        .context("page missing from recent updates after head_html")?;
+    ensure!(
+        head_entry.updated_at == with_head.updated_at,
+        "index updated_at not synced after head_html"
+    );
+    for (label, value) in [
+        ("non-string", serde_json::json!({ "a": 1 })),
+        ("script", "<script>alert(1)</script>".into()),
+        ("unclosed tag", "<div>".into()),
+    ] {
+        let err = match store.set_page_extra(&page_id, "head_html", value) {
+            Ok(()) => anyhow::bail!("{label} head_html should be rejected"),
+            Err(err) => err,
+        };
+        ensure!(
+            err.chain().any(|cause| cause.is::<HtmlError>())
+                && err.to_string().starts_with("invalid head_html"),
+            "{label} head_html: expected a validation error, got {err:#}"
+        );
+    }
+    store
+        .set_page_extra(&page_id, "score", 3.into())
+        .context("set other extra key")?;
+    let rejected = store
+        .get_page_meta(&page_id)
+        .context("get meta after rejected head_html")?;
+    ensure!(
+        rejected.updated_at == with_head.updated_at
+            && rejected.extra.get("head_html") == with_head.extra.get("head_html"),
+        "rejected head_html and other keys should not change the page"
+    );
+    store
+        .set_page_extra(&page_id, "head_html", serde_json::Value::Null)
+        .context("remove head_html")?;
+    let without_head = store
+        .get_page_meta(&page_id)
+        .context("get meta after removing head_html")?;
+    ensure!(
+        !without_head.extra.contains_key("head_html")
+            && without_head.updated_at > with_head.updated_at,
+        "removing head_html should drop the key and bump updated_at"
+    );
+    println!("page extra head_html ok");
+
     println!("delete page");
     store.delete_page(&page_id).context("delete page")?;
-    ensure!(!store.page_exists(&page_id)?, "page still exists after delete");
+    ensure!(
+        !store.page_exists(&page_id)?,
+        "page still exists after delete"
+    );
     println!("delete ok");
 
+    println!("trash");
+    // 同一页面删除两次（通常在同一秒内），两个回收站条目的 trash_id 不能冲突
+    store
+        .create_page(&page_id, &meta, html)
+        .context("recreate page")?;
+    store
+        .delete_page(&page_id)
+        .context("delete recreated page")?;
+    let trashed: Vec<_> = store
+        .list_deleted_pages()
+        .context("list deleted pages")?
+        .into_iter()
+        .filter(|page| page.page_id == safe_id)
+        .collect();
+    ensure!(
+        trashed.len() == 2,
+        "expected 2 trash entries, got {}",
+        trashed.len()
+    );
+    ensure!(
+        trashed[0].trash_id != trashed[1].trash_id,
+        "trash id collision"
+    );
+    ensure!(
+        trashed.iter().any(|page| page.page_uid == initial_uid),
+        "trash entry lost page uid"
+    );
+    ensure!(
+        trashed
+            .iter()
+            .all(|page| !page.title.is_empty() && page.deleted_at > 0),
+        "trash entry missing title or deleted_at"
+    );
+    ensure!(
+        store.purge_deleted_page(&safe_id).is_err(),
+        "purge of a non-trash id succeeded"
+    );
+    ensure!(
+        store.purge_deleted_page("../index").is_err(),
+        "purge accepted a path outside the trash"
+    );
+    for page in &trashed {
+        let purged = store
+            .purge_deleted_page(&page.trash_id)
+            .context("purge deleted page")?;
+        ensure!(purged == *page, "purged entry mismatch");
+        ensure!(
+            !data_dir.join(TRASH_DIR).join(&page.trash_id).exists(),
+            "trash entry still on disk after purge"
+        );
+    }
+    ensure!(
+        store.purge_deleted_page(&trashed[0].trash_id).is_err(),
+        "purge of an already purged entry succeeded"
+    );
+    println!("trash ok");
+
+    println!("html_util");
+    ensure!(
+        find_bytes(b"<p>a</p>", 0, b"</p>") == Some(4),
+        "find_bytes basic match"
+    );
+    ensure!(
+        find_bytes(b"<p>a</p>", 5, b"</p>").is_none(),
+        "find_bytes matched before start"
+    );
+    ensure!(
+        find_bytes(b"abc", 1, b"") == Some(1) && find_bytes(b"abc", 3, b"") == Some(3),
+        "find_bytes empty needle should return start"
+    );
+    ensure!(
+        find_bytes(b"abc", 10, b"").is_none() && find_bytes(b"abc", 10, b"a").is_none(),
+        "find_bytes start past end should return None"
+    );
+    ensure!(
+        find_bytes(b"ab", 0, b"abc").is_none() && find_bytes(b"", 0, b"a").is_none(),
+        "find_bytes needle longer than haystack"
+    );
+    ensure!(
+        find_bytes(b"aaaa", 1, b"aa") == Some(1)
+            && find_bytes(b"aaaa", 2, b"aa") == Some(2)
+            && find_bytes(b"aaaa", 3, b"aa").is_none(),
+        "find_bytes overlapping matches"
+    );
+    ensure!(
+        find_bytes(b"a\0b\0c", 0, b"\0c") == Some(3),
+        "find_bytes should treat NUL as a normal byte"
+    );
+    ensure!(
+        find_bytes_case_insensitive(b"<BODY>", 0, b"<body") == Some(0),
+        "find_bytes_case_insensitive ascii match"
+    );
+    ensure!(
+        find_bytes_case_insensitive("é<P>".as_bytes(), 0, "É<p>".as_bytes()).is_none(),
+        "find_bytes_case_insensitive should compare non-ascii bytes as is"
+    );
+    ensure!(
+        find_bytes_case_insensitive(b"abc", 2, b"") == Some(2)
+            && find_bytes_case_insensitive(b"abc", 10, b"").is_none()
+            && find_bytes_case_insensitive(b"ab", 0, b"ABC").is_none(),
+        "find_bytes_case_insensitive bounds"
+    );
+    ensure!(
+        find_bytes_case_insensitive(b"xAaAa", 2, b"AA") == Some(2)
+            && find_bytes_case_insensitive(b"\0X\0x", 1, b"\0X") == Some(2),
+        "find_bytes_case_insensitive overlapping and NUL"
+    );
+    println!("html_util ok");
+
+    println!("ip cidr");
+    let v4 = IpCidr::parse("10.1.2.3/8").context("parse ipv4 cidr")?;
+    ensure!(
+        v4 == IpCidr::parse("10.0.0.0/8").context("parse ipv4 network")?,
+        "host bits beyond the prefix should be cleared"
+    );
+    ensure!(
+        v4.contains("10.255.0.1".parse()?) && !v4.contains("11.0.0.1".parse()?),
+        "ipv4 cidr contains"
+    );
+    ensure!(
+        v4.contains("::ffff:10.0.0.1".parse()?),
+        "ipv4-mapped ipv6 address should match ipv4 cidr"
+    );
+    let single = IpCidr::parse(" 203.0.113.7 ").context("parse single ipv4")?;
+    ensure!(
+        single.contains("203.0.113.7".parse()?) && !single.contains("203.0.113.8".parse()?),
+        "address without prefix should match only itself"
+    );
+    let v6 = IpCidr::parse("2001:db8::/32").context("parse ipv6 cidr")?;
+    ensure!(
+        v6.contains("2001:db8:ffff::1".parse()?) && !v6.contains("2001:db9::1".parse()?),
+        "ipv6 cidr contains"
+    );
+    ensure!(
+        !v6.contains("10.0.0.1".parse()?) && !v4.contains("::a00:1".parse()?),
+        "ipv4 and ipv6 should not match each other"
+    );
+    let all_v4 = IpCidr::parse("0.0.0.0/0").context("parse zero-length prefix")?;
+    ensure!(
+        all_v4.contains("198.51.100.1".parse()?),
+        "zero-length prefix should match all ipv4 addresses"
+    );
+    ensure!(
+        IpCidr::parse("10.0.0.0/33").is_none()
+            && IpCidr::parse("2001:db8::/129").is_none()
+            && IpCidr::parse("10.0.0.0/").is_none()
+            && IpCidr::parse("example.com").is_none(),
+        "invalid cidr should not parse"
+    );
+    println!("ip cidr ok");
+
+    println!("client ip");
+    let peer: SocketAddr = "198.51.100.20:54321".parse()?;
+    let mut headers = HeaderMap::new();
+    headers.insert("x-forwarded-for", HeaderValue::from_static("10.0.0.1"));
+    headers.insert("x-real-ip", HeaderValue::from_static("10.0.0.2"));
+    ensure!(
+        resolve_client_ip(&headers, Some(peer), false) == Some(peer.ip()),
+        "spoofed proxy headers should be ignored without TRUST_PROXY"
+    );
+    ensure!(
+        resolve_client_ip(&headers, None, false).is_none(),
+        "proxy headers alone should not yield an ip without TRUST_PROXY"
+    );
+    ensure!(
+        resolve_client_ip(&headers, Some(peer), true) == Some("10.0.0.2".parse()?),
+        "X-Real-IP should win with TRUST_PROXY"
+    );
+    headers.remove("x-real-ip");
+    ensure!(
+        resolve_client_ip(&headers, Some(peer), true) == Some("10.0.0.1".parse()?),
+        "X-Forwarded-For should be used with TRUST_PROXY"
+    );
+    headers.insert("x-forwarded-for", HeaderValue::from_static("not-an-ip"));
+    ensure!(
+        resolve_client_ip(&headers, Some(peer), true) == Some(peer.ip()),
+        "unparsable forwarded ip should fall back to the peer address"
+    );
+    println!("client ip ok");
+
+    println!("rate limit");
+    let mut lru = ClientLru::new(2);
+    *lru.get_or_insert_with(1u8, || 0) += 1;
+    *lru.get_or_insert_with(2u8, || 0) += 1;
+    *lru.get_or_insert_with(1u8, || 0) += 1;
+    lru.get_or_insert_with(3u8, || 0);
+    ensure!(lru.len() == 2, "client lru should stay within its capacity");
+    ensure!(
+        !lru.contains_key(&2) && lru.contains_key(&3),
+        "client lru should evict the least recently used key"
+    );
+    ensure!(
+        *lru.get_or_insert_with(1u8, || 0) == 2,
+        "recently used keys should keep their value"
+    );
+    let client = "203.0.113.7".parse()?;
+    let limiter = RateLimiter::new(2, 0, 0);
+    let start = Instant::now();
+    ensure!(
+        limiter.check(client, RateLimitClass::Page, start).is_ok()
+            && limiter.check(client, RateLimitClass::Page, start).is_ok(),
+        "requests within the limit should pass"
+    );
+    let wait = limiter
+        .check(client, RateLimitClass::Page, start)
+        .err()
+        .context("exhausted bucket should be rejected")?;
+    ensure!(
+        wait > Duration::ZERO && wait <= Duration::from_secs(30),
+        "retry wait should be one token interval, got {wait:?}"
+    );
+    ensure!(
+        limiter.check(client, RateLimitClass::Asset, start).is_ok(),
+        "classes without a limit should not be rejected"
+    );
+    // 每分钟 2 个令牌，31 秒后补回一个
+    let later = start + Duration::from_secs(31);
+    ensure!(
+        limiter.check(client, RateLimitClass::Page, later).is_ok(),
+        "bucket should refill after the retry wait"
+    );
+    ensure!(
+        limiter.check(client, RateLimitClass::Page, later).is_err(),
+        "refilled bucket should hold only the elapsed tokens"
+    );
+    for _ in 0..30 {
+        ensure!(
+            check_validate_rate(client, start).is_none(),
+            "validate requests within the limit should pass"
+        );
+    }
+    let retry_after = check_validate_rate(client, start)
+        .context("31st validate request in a window should be rejected")?;
+    ensure!(
+        retry_after == 60,
+        "validate retry should last until the window ends, got {retry_after}"
+    );
+    ensure!(
+        check_validate_rate(client, start + Duration::from_secs(60)).is_none(),
+        "validate limit should reset in the next window"
+    );
+    println!("rate limit ok");
+
+    println!("template");
+    validate_html("<template><div><p></template>").context("template content is inert")?;
+    validate_html("<template><template><b></template></template><p>x</p>")
+        .context("nested template")?;
+    validate_html("<template><!-- </template> --><p></template><p>x</p>")
+        .context("</template> inside a comment")?;
+    validate_html(concat!(
+        "<user-card><template shadowrootmode=\"open\">",
+        "<style>:host{display:block}</style><slot name=\"title\"></slot>",
+        "</template><h2 slot=\"title\">Hi</h2></user-card>"
+    ))
+    .context("slot inside template")?;
+    validate_html("<slot><template><span></template></slot>").context("template inside slot")?;
+    for (html, expected) in [
+        ("<template><p>x</p>", "unterminated <template>"),
+        ("<template><template></template>", "unterminated <template>"),
+        ("<template><!-- </template>", "unterminated <template>"),
+        ("<template></template></template>", "unexpected closing tag"),
+        ("<slot><span></slot>", "mismatched closing tag"),
+    ] {
+        let err = match validate_html(html) {
+            Ok(()) => anyhow::bail!("{html:?} should not validate"),
+            Err(err) => err.to_string(),
+        };
+        ensure!(
+            err.contains(expected),
+            "{html:?}: expected {expected:?}, got {err:?}"
+        );
+    }
+    println!("template ok");
+
+    println!("breadcrumb json-ld");
+    let script = build_breadcrumb_json_ld(
+        "Solin & Blog",
+        "https://example.com/",
+        "Title </script><b>",
+        "https://example.com/pages/abc",
+    );
+    let json = script
+        .strip_prefix("<script type=\"application/ld+json\">")
+        .and_then(|rest| rest.strip_suffix("</script>"))
+        .context("breadcrumb json-ld should be a ld+json script")?;
+    ensure!(
+        !json.contains(['<', '>', '&']),
+        "breadcrumb json-ld should escape < > &: {json}"
+    );
+    let ld: serde_json::Value =
+        serde_json::from_str(json).context("breadcrumb json-ld is not valid json")?;
+    ensure!(
+        ld["@context"] == "https://schema.org" && ld["@type"] == "BreadcrumbList",
+        "breadcrumb json-ld type mismatch: {ld}"
+    );
+    let items = ld["itemListElement"]
+        .as_array()
+        .context("breadcrumb json-ld itemListElement missing")?;
+    ensure!(
+        items.len() == 2
+            && items[0]["position"] == 1
+            && items[0]["name"] == "Solin & Blog"
+            && items[0]["item"] == "https://example.com/"
+            && items[1]["position"] == 2
+            && items[1]["name"] == "Title </script><b>"
+            && items[1]["item"] == "https://example.com/pages/abc",
+        "breadcrumb json-ld items mismatch: {ld}"
+    );
+    let mut served_meta = meta.clone();
+    served_meta.page_uid = "selfcheckpage001".to_string();
+    served_meta
+        .extra
+        .insert("category".to_string(), "Notes".into());
+    let served = render_served_page(&served_meta, html, "https://example.com", &[]);
+    let page_url = format!(
+        "https://example.com{}",
+        build_page_url(&served_meta.page_uid, &served_meta.seo.seo_title)
+    );
+    let expected = build_breadcrumb_json_ld(
+        &resolve_site_title_from_env(),
+        "https://example.com/",
+        &served_meta.seo.title,
+        &page_url,
+    );
+    ensure!(
+        served.contains(&expected),
+        "served page should embed the two-level BreadcrumbList"
+    );
+    println!("breadcrumb json-ld ok");
+
     println!("store selfcheck done");
     Ok(())
 }
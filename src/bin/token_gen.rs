@@ -0,0 +1,82 @@
+use anyhow::{Context, Result, bail};
+
+use solin_blog::config::{TokenFormat, generate_token};
+
+const DEFAULT_LENGTH: usize = 32;
+const MAX_LENGTH: usize = 1024;
+const MAX_COUNT: usize = 1000;
+
+struct TokenOptions {
+    length: usize,
+    format: TokenFormat,
+    count: usize,
+}
+
+fn print_usage() {
+    println!("usage: token_gen [--length N] [--format hex|base64url|alphanumeric] [--count N]");
+    println!();
+    println!("  --length N      token 长度，默认 {}", DEFAULT_LENGTH);
+    println!("  --format FMT    字符集：hex、base64url、alphanumeric（默认）");
+    println!("  --count N       一次生成的 token 数量，默认 1");
+}
+
+fn parse_number(option: &str, value: Option<String>, max: usize) -> Result<usize> {
+    let Some(value) = value else {
+        bail!("missing value for {}", option);
+    };
+    let number: usize = value
+        .trim()
+        .parse()
+        .with_context(|| format!("invalid value for {}: {}", option, value))?;
+    if number == 0 || number > max {
+        bail!("{} must be between 1 and {}", option, max);
+    }
+    Ok(number)
+}
+
+fn parse_args() -> Result<TokenOptions> {
+    let mut options = TokenOptions {
+        length: DEFAULT_LENGTH,
+        format: TokenFormat::Alphanumeric,
+        count: 1,
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--length" => options.length = parse_number("--length", args.next(), MAX_LENGTH)?,
+            "--count" => options.count = parse_number("--count", args.next(), MAX_COUNT)?,
+            "--format" => {
+                let Some(value) = args.next() else {
+                    bail!("missing value for --format");
+                };
+                let Some(format) = TokenFormat::parse(&value) else {
+                    bail!(
+                        "unknown format: {} (expected hex|base64url|alphanumeric)",
+                        value
+                    );
+                };
+                options.format = format;
+            }
+            "-h" | "--help" => {
+                print_usage();
+                std::process::exit(0);
+            }
+            value => bail!("unexpected argument: {}", value),
+        }
+    }
+    Ok(options)
+}
+
+fn main() -> Result<()> {
+    let options = parse_args()?;
+    let mut tokens = Vec::with_capacity(options.count);
+    for _ in 0..options.count {
+        tokens.push(generate_token(options.length, options.format)?);
+    }
+    for token in &tokens {
+        println!("{}", token);
+    }
+    println!();
+    println!("MCP_TOKEN={}", tokens[0]);
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use anyhow::{Context, Result, bail};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use solin_blog::store::{ConsistencyReport, PageStore};
+
+struct ValidateOptions {
+    data_dir: PathBuf,
+    fix: bool,
+}
+
+fn print_usage() {
+    println!("usage: validate_store [data_dir] [--fix]");
+    println!();
+    println!("  [data_dir]    数据目录，默认 data");
+    println!("  --fix         自动修复可修复的问题（索引、uid 冲突、残留 .tmp 文件）");
+    println!();
+    println!("exit code: 0 无问题，1 仅有警告，2 存在错误");
+}
+
+fn parse_args() -> Result<ValidateOptions> {
+    let mut data_dir: Option<PathBuf> = None;
+    let mut fix = false;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--fix" => fix = true,
+            "-h" | "--help" => {
+                print_usage();
+                std::process::exit(0);
+            }
+            value if value.starts_with("--") => bail!("unknown option: {}", value),
+            value => {
+                if data_dir.is_some() {
+                    bail!("unexpected argument: {}", value);
+                }
+                data_dir = Some(PathBuf::from(value));
+            }
+        }
+    }
+    Ok(ValidateOptions {
+        data_dir: data_dir.unwrap_or_else(|| PathBuf::from("data")),
+        fix,
+    })
+}
+
+fn print_list(label: &str, items: &[String]) {
+    println!("  {:<24}{}", label, items.len());
+    for item in items {
+        println!("    - {}", item);
+    }
+}
+
+fn print_report(report: &ConsistencyReport) {
+    println!("  {:<24}{}", "pages:", report.page_count);
+    match &report.index_error {
+        Some(err) => println!("  {:<24}{}", "index.json:", err),
+        None => println!("  {:<24}ok", "index.json:"),
+    }
+    println!("[errors]");
+    print_list("missing meta.json:", &report.missing_meta);
+    print_list("missing index.html:", &report.missing_html);
+    println!("  {:<24}{}", "uid collisions:", report.uid_collisions.len());
+    for (uid, owners) in &report.uid_collisions {
+        println!("    - {} -> {}", uid, owners.join(", "));
+    }
+    println!("[warnings]");
+    print_list("orphan index entries:", &report.orphan_index_entries);
+    print_list("unindexed pages:", &report.unindexed_pages);
+    println!("  {:<24}{}", "invalid html:", report.invalid_html.len());
+    for (page_id, err) in &report.invalid_html {
+        println!("    - {}: {}", page_id, err);
+    }
+    println!(
+        "  {:<24}{}",
+        "leftover tmp files:",
+        report.leftover_tmp_files.len()
+    );
+    for path in &report.leftover_tmp_files {
+        println!("    - {}", path.to_string_lossy());
+    }
+}
+
+fn run() -> Result<ExitCode> {
+    let options = parse_args()?;
+    let store = PageStore::new(options.data_dir.clone());
+    let mut report = store
+        .validate_consistency()
+        .context("validate store consistency")?;
+
+    println!("validate store: {}", options.data_dir.to_string_lossy());
+    print_report(&report);
+
+    if options.fix && !report.is_clean() {
+        println!();
+        let actions = store
+            .repair_consistency(&report)
+            .context("repair store consistency")?;
+        if actions.is_empty() {
+            println!("nothing can be fixed automatically");
+        }
+        for action in &actions {
+            println!("fix: {}", action);
+        }
+        report = store
+            .validate_consistency()
+            .context("re-validate store consistency")?;
+        println!();
+        println!("after fix:");
+        print_report(&report);
+    }
+
+    println!();
+    println!(
+        "result: {} error(s), {} warning(s)",
+        report.error_count(),
+        report.warning_count()
+    );
+    if report.error_count() > 0 {
+        Ok(ExitCode::from(2))
+    } else if report.warning_count() > 0 {
+        Ok(ExitCode::from(1))
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("validate_store failed: {:#}", err);
+            ExitCode::from(2)
+        }
+    }
+}
@@ -1,26 +1,555 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    path::PathBuf,
+    time::Duration,
+};
+
+use anyhow::{Result, anyhow};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
 use getrandom::getrandom;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// `SITE_URL` 未设置时依次尝试的变量：通用的 `PUBLIC_URL`，以及 Vercel / Render / Railway 自动注入的地址
+const SITE_URL_FALLBACK_KEYS: &[&str] = &[
+    "PUBLIC_URL",
+    "VERCEL_URL",
+    "RENDER_EXTERNAL_URL",
+    "RAILWAY_STATIC_URL",
+];
 
 pub fn resolve_site_url_from_env() -> String {
-    let value = std::env::var("SITE_URL").unwrap_or_default();
-    let trimmed = value.trim().trim_end_matches('/');
+    match find_site_url_from_env() {
+        Some(url) => url,
+        None => {
+            warn!("SITE_URL is not set, MCP response URLs will be relative paths");
+            String::new()
+        }
+    }
+}
+
+/// 按 `SITE_URL` → `SITE_URL_FALLBACK_KEYS` 的顺序取第一个非空值，去掉末尾 `/`；
+/// 回退变量不带协议时（如 `VERCEL_URL=my-blog.vercel.app`）补上 `https://`
+pub fn find_site_url_from_env() -> Option<String> {
+    let read = |key: &str| {
+        std::env::var(key)
+            .ok()
+            .map(|value| value.trim().trim_end_matches('/').to_string())
+            .filter(|value| !value.is_empty())
+    };
+    if let Some(url) = read("SITE_URL") {
+        return Some(url);
+    }
+    SITE_URL_FALLBACK_KEYS
+        .iter()
+        .find_map(|key| read(key))
+        .map(|url| {
+            if url.contains("://") {
+                url
+            } else {
+                format!("https://{}", url)
+            }
+        })
+}
+
+/// 站点名称，用于 manifest 等需要站点标题的场景，默认 `SolinBlog`
+pub fn resolve_site_title_from_env() -> String {
+    let value = std::env::var("SITE_TITLE").unwrap_or_default();
+    let trimmed = value.trim();
     if trimmed.is_empty() {
-        eprintln!(
-            "[solin-blog] WARNING: SITE_URL is not set, MCP response URLs will be relative paths"
+        "SolinBlog".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// PWA 主题色与背景色：`PWA_THEME_COLOR` / `PWA_BACKGROUND_COLOR`，默认取页面浅色背景
+pub fn resolve_pwa_colors_from_env() -> (String, String) {
+    let read = |key: &str| {
+        std::env::var(key)
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| "#F5F4ED".to_string())
+    };
+    (read("PWA_THEME_COLOR"), read("PWA_BACKGROUND_COLOR"))
+}
+
+/// 文章页分享栏开关，`SHARE_LINKS=off` 时关闭，默认开启
+pub fn resolve_share_links_enabled_from_env() -> bool {
+    let value = std::env::var("SHARE_LINKS").unwrap_or_default();
+    !value.trim().eq_ignore_ascii_case("off")
+}
+
+/// 第三方评论服务，评论数据托管在 GitHub，无需本地数据库
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentsProvider {
+    Giscus,
+    Utterances,
+}
+
+/// 评论嵌入配置，由 `resolve_comments_config_from_env` 读取
+#[derive(Debug, Clone)]
+pub struct CommentsConfig {
+    pub provider: CommentsProvider,
+    /// `owner/repo`
+    pub repo: String,
+    /// 以下三项仅 giscus 使用
+    pub repo_id: String,
+    pub category: String,
+    pub category_id: String,
+    pub mapping: String,
+    pub theme: String,
+    pub lang: String,
+}
+
+/// 评论配置，`COMMENTS_PROVIDER` 为空时关闭评论
+/// - `COMMENTS_PROVIDER`：`giscus` / `utterances`
+/// - `COMMENTS_REPO`：必填，`owner/repo`
+/// - `COMMENTS_REPO_ID`、`COMMENTS_CATEGORY_ID`：giscus 必填，`COMMENTS_CATEGORY` 可选
+/// - `COMMENTS_MAPPING`：默认 `pathname`；`COMMENTS_THEME`：默认跟随系统配色
+/// - `COMMENTS_LANG`：giscus 界面语言，默认 `zh-CN`
+pub fn resolve_comments_config_from_env() -> Option<CommentsConfig> {
+    let read = |key: &str| std::env::var(key).unwrap_or_default().trim().to_string();
+    let provider = match read("COMMENTS_PROVIDER").to_ascii_lowercase().as_str() {
+        "" | "off" => return None,
+        "giscus" => CommentsProvider::Giscus,
+        "utterances" => CommentsProvider::Utterances,
+        other => {
+            warn!("unsupported COMMENTS_PROVIDER {other}, comments disabled");
+            return None;
+        }
+    };
+    let config = CommentsConfig {
+        provider,
+        repo: read("COMMENTS_REPO"),
+        repo_id: read("COMMENTS_REPO_ID"),
+        category: read("COMMENTS_CATEGORY"),
+        category_id: read("COMMENTS_CATEGORY_ID"),
+        mapping: Some(read("COMMENTS_MAPPING"))
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| "pathname".to_string()),
+        theme: Some(read("COMMENTS_THEME"))
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| match provider {
+                CommentsProvider::Giscus => "preferred_color_scheme".to_string(),
+                CommentsProvider::Utterances => "preferred-color-scheme".to_string(),
+            }),
+        lang: Some(read("COMMENTS_LANG"))
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| "zh-CN".to_string()),
+    };
+    let missing_giscus_ids = provider == CommentsProvider::Giscus
+        && (config.repo_id.is_empty() || config.category_id.is_empty());
+    if config.repo.is_empty() || missing_giscus_ids {
+        warn!(
+            "COMMENTS_REPO (and COMMENTS_REPO_ID / COMMENTS_CATEGORY_ID for giscus) must be set, comments disabled"
         );
-        return String::new();
+        return None;
+    }
+    Some(config)
+}
+
+/// `upload_asset` 单个文件的大小上限（字节），`UPLOAD_MAX_BYTES` 未设置或无效时为 5 MiB
+pub fn resolve_upload_max_bytes_from_env() -> usize {
+    std::env::var("UPLOAD_MAX_BYTES")
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(5 * 1024 * 1024)
+}
+
+/// MCP 入口单个请求体的大小上限（字节），`MCP_MAX_BODY_BYTES` 未设置或无效时为 8 MiB，
+/// 足够容纳接近 `UPLOAD_MAX_BYTES` 的 base64 上传与较大的 HTML 推送
+pub fn resolve_mcp_max_body_bytes_from_env() -> usize {
+    std::env::var("MCP_MAX_BODY_BYTES")
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(8 * 1024 * 1024)
+}
+
+/// 单个 HTTP 请求与单次 MCP 工具调用的处理时限，`REQUEST_TIMEOUT_SECS` 未设置或无效时为 60 秒，
+/// 设为 `0` 时不限时
+pub fn resolve_request_timeout_from_env() -> Option<Duration> {
+    let secs = std::env::var("REQUEST_TIMEOUT_SECS")
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .unwrap_or(60);
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+/// `push_page` 幂等键的有效期（秒），`IDEMPOTENCY_TTL_SECS` 未设置或无效时为 24 小时
+pub fn resolve_idempotency_ttl_from_env() -> i64 {
+    std::env::var("IDEMPOTENCY_TTL_SECS")
+        .ok()
+        .and_then(|value| value.trim().parse::<i64>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(24 * 60 * 60)
+}
+
+/// 启动时每个页面保留的历史版本数，`VERSION_MAX_KEEP` 未设置时不清理，非正整数时警告并忽略
+pub fn resolve_version_max_keep_from_env() -> Option<usize> {
+    let value = std::env::var("VERSION_MAX_KEEP").unwrap_or_default();
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    match value.parse::<usize>() {
+        Ok(keep) if keep > 0 => Some(keep),
+        _ => {
+            warn!("VERSION_MAX_KEEP must be a positive integer, ignored");
+            None
+        }
+    }
+}
+
+/// 收到退出信号后等待进行中请求完成的秒数，`SHUTDOWN_TIMEOUT_SECS` 未设置或无效时为 30 秒
+pub fn resolve_shutdown_timeout_from_env() -> Duration {
+    let secs = std::env::var("SHUTDOWN_TIMEOUT_SECS")
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(30);
+    Duration::from_secs(secs)
+}
+
+/// HTTPS 配置，由 `resolve_tls_config_from_env` 读取
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+    /// 设置后在该端口另开明文 HTTP 监听，所有请求 301 跳转到 https 地址
+    pub redirect_port: Option<u16>,
+}
+
+/// `TLS_CERT_PATH` 与 `TLS_KEY_PATH` 都为空时返回 `None`，使用明文 HTTP；
+/// 只设置其中一个或 `TLS_REDIRECT_PORT` 不是合法端口时返回错误，启动失败
+pub fn resolve_tls_config_from_env() -> Result<Option<TlsConfig>> {
+    let read = |key: &str| {
+        std::env::var(key)
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let (cert_path, key_path) = match (read("TLS_CERT_PATH"), read("TLS_KEY_PATH")) {
+        (None, None) => return Ok(None),
+        (Some(cert), Some(key)) => (PathBuf::from(cert), PathBuf::from(key)),
+        (Some(_), None) => return Err(anyhow!("TLS_CERT_PATH is set but TLS_KEY_PATH is not")),
+        (None, Some(_)) => return Err(anyhow!("TLS_KEY_PATH is set but TLS_CERT_PATH is not")),
+    };
+    let redirect_port = match read("TLS_REDIRECT_PORT") {
+        None => None,
+        Some(value) => Some(
+            value
+                .parse::<u16>()
+                .ok()
+                .filter(|port| *port > 0)
+                .ok_or_else(|| anyhow!("TLS_REDIRECT_PORT must be a port number, got {value}"))?,
+        ),
+    };
+    Ok(Some(TlsConfig {
+        cert_path,
+        key_path,
+        redirect_port,
+    }))
+}
+
+/// 页面变更 Webhook 配置，由 `resolve_webhook_config_from_env` 读取
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// 为空时不签名，请求不带 `X-Solin-Signature`
+    pub secret: String,
+}
+
+/// `WEBHOOK_URL` 为空时关闭 Webhook；`WEBHOOK_SECRET` 用于 HMAC-SHA256 签名
+pub fn resolve_webhook_config_from_env() -> Option<WebhookConfig> {
+    let url = std::env::var("WEBHOOK_URL").unwrap_or_default();
+    let url = url.trim();
+    if url.is_empty() {
+        return None;
+    }
+    let secret = std::env::var("WEBHOOK_SECRET").unwrap_or_default();
+    Some(WebhookConfig {
+        url: url.to_string(),
+        secret: secret.trim().to_string(),
+    })
+}
+
+/// 首页与 `/api/*` 的 HTTP Basic Auth 账号，由 `resolve_admin_basic_auth_from_env` 读取
+#[derive(Debug, Clone)]
+pub struct AdminBasicAuth {
+    pub username: String,
+    pub password: String,
+}
+
+/// `ADMIN_USERNAME` 与 `ADMIN_PASSWORD` 都设置时启用，只设置其中一个时不启用并警告
+pub fn resolve_admin_basic_auth_from_env() -> Option<AdminBasicAuth> {
+    let username = std::env::var("ADMIN_USERNAME").unwrap_or_default();
+    let password = std::env::var("ADMIN_PASSWORD").unwrap_or_default();
+    let (username, password) = (username.trim(), password.trim());
+    match (username.is_empty(), password.is_empty()) {
+        (false, false) => Some(AdminBasicAuth {
+            username: username.to_string(),
+            password: password.to_string(),
+        }),
+        (true, true) => None,
+        _ => {
+            warn!("ADMIN_USERNAME and ADMIN_PASSWORD must both be set, basic auth disabled");
+            None
+        }
+    }
+}
+
+/// IndexNow 密钥，需为 8–128 位字母、数字或 `-`；未设置或格式不合法时不提交 IndexNow
+pub fn resolve_indexnow_key_from_env() -> Option<String> {
+    let value = std::env::var("INDEXNOW_KEY").unwrap_or_default();
+    let key = value.trim();
+    if key.is_empty() {
+        return None;
+    }
+    let valid_chars = key
+        .chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || ch == '-');
+    if !(8..=128).contains(&key.len()) || !valid_chars {
+        warn!("INDEXNOW_KEY must be 8-128 letters, digits or '-', ignored");
+        return None;
+    }
+    Some(key.to_string())
+}
+
+/// `AUTO_PING=1` 时 MCP 创建公开页面成功后自动通知搜索引擎，默认关闭
+pub fn resolve_auto_ping_enabled_from_env() -> bool {
+    let value = std::env::var("AUTO_PING").unwrap_or_default();
+    matches!(value.trim(), "1" | "true" | "on")
+}
+
+/// `TRUST_PROXY=true` 时信任反向代理设置的 `X-Forwarded-Host` / `X-Forwarded-Prefix`，默认关闭
+pub fn resolve_trust_proxy_from_env() -> bool {
+    let value = std::env::var("TRUST_PROXY").unwrap_or_default();
+    matches!(value.trim(), "1" | "true" | "on")
+}
+
+/// 单个 IP 或 CIDR 网段（IPv4 / IPv6），如 `10.0.0.0/8`、`2001:db8::/32`、`203.0.113.7`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpCidr {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpCidr {
+    /// 不带前缀长度时按单个地址处理；网络地址中超出前缀的位会被清零
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let (addr, prefix) = match value.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (value, None),
+        };
+        let addr = addr.trim().parse::<IpAddr>().ok()?;
+        let max_len = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match prefix {
+            Some(prefix) => prefix
+                .trim()
+                .parse::<u8>()
+                .ok()
+                .filter(|len| *len <= max_len)?,
+            None => max_len,
+        };
+        Some(Self {
+            network: mask_ip(addr, prefix_len),
+            prefix_len,
+        })
+    }
+
+    /// IPv4 映射的 IPv6 地址（`::ffff:10.0.0.1`）按 IPv4 比较
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        ip.is_ipv4() == self.network.is_ipv4() && mask_ip(ip, self.prefix_len) == self.network
+    }
+}
+
+fn mask_ip(ip: IpAddr, prefix_len: u8) -> IpAddr {
+    match ip.to_canonical() {
+        IpAddr::V4(ip) => {
+            let mask = u32::MAX
+                .checked_shl(32 - u32::from(prefix_len))
+                .unwrap_or(0);
+            IpAddr::V4(Ipv4Addr::from(u32::from(ip) & mask))
+        }
+        IpAddr::V6(ip) => {
+            let mask = u128::MAX
+                .checked_shl(128 - u32::from(prefix_len))
+                .unwrap_or(0);
+            IpAddr::V6(Ipv6Addr::from(u128::from(ip) & mask))
+        }
     }
-    trimmed.to_string()
 }
 
+/// `MCP_ALLOWED_IPS`：逗号分隔的 IP / CIDR，未设置时不限制来源；
+/// 任一项无法解析时返回错误，启动失败，避免写错的配置悄悄放开访问
+pub fn resolve_mcp_allowed_ips_from_env() -> Result<Option<Vec<IpCidr>>> {
+    let value = std::env::var("MCP_ALLOWED_IPS").unwrap_or_default();
+    let items: Vec<&str> = value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .collect();
+    if items.is_empty() {
+        return Ok(None);
+    }
+    items
+        .into_iter()
+        .map(|item| {
+            IpCidr::parse(item)
+                .ok_or_else(|| anyhow!("MCP_ALLOWED_IPS: invalid IP or CIDR {item:?}"))
+        })
+        .collect::<Result<Vec<_>>>()
+        .map(Some)
+}
+
+/// `MCP_LEGACY_PATH=1` 时额外挂载旧的 `/{token}/mcp` 入口，供无法设置请求头的客户端使用；
+/// 默认只挂载 `/mcp`，token 通过 `Authorization: Bearer` 传递
+pub fn resolve_mcp_legacy_path_from_env() -> bool {
+    let value = std::env::var("MCP_LEGACY_PATH").unwrap_or_default();
+    matches!(value.trim(), "1" | "true" | "on")
+}
+
+/// MCP token 的权限范围，只读 token 调用写入类工具时返回 `permission_denied`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum McpScope {
+    #[serde(rename = "rw")]
+    ReadWrite,
+    #[serde(rename = "ro")]
+    ReadOnly,
+}
+
+impl McpScope {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "rw" => Some(Self::ReadWrite),
+            "ro" => Some(Self::ReadOnly),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ReadWrite => "rw",
+            Self::ReadOnly => "ro",
+        }
+    }
+}
+
+/// 环境变量或命令行参数中的 MCP token，首次启动时导入 `data/tokens.json`（见 `tokens::TokenStore`）
+#[derive(Debug, Clone)]
+pub struct McpToken {
+    pub token: String,
+    pub scope: McpScope,
+}
+
+/// `MCP_TOKENS=rw:<tok1>,ro:<tok2>` 配置多个 token，格式不正确或重复的条目打印警告后忽略
+/// 未设置 `MCP_TOKENS`（或其中没有有效条目）时回退到 `MCP_TOKEN`，作为 read-write token；都为空时返回空列表
+pub fn resolve_mcp_tokens_from_env() -> Vec<McpToken> {
+    let mut tokens: Vec<McpToken> = Vec::new();
+    let value = std::env::var("MCP_TOKENS").unwrap_or_default();
+    for item in value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+    {
+        let parsed = item.split_once(':').and_then(|(scope, token)| {
+            let token = token.trim();
+            let valid = !token.is_empty() && !token.contains('/');
+            McpScope::parse(scope)
+                .filter(|_| valid)
+                .map(|scope| McpToken {
+                    token: token.to_string(),
+                    scope,
+                })
+        });
+        match parsed {
+            Some(token) if tokens.iter().any(|existing| existing.token == token.token) => {
+                warn!("duplicate token in MCP_TOKENS ignored");
+            }
+            Some(token) => tokens.push(token),
+            None => warn!("MCP_TOKENS entry must be rw:<token> or ro:<token>, ignored"),
+        }
+    }
+    if !tokens.is_empty() {
+        return tokens;
+    }
+    let token = std::env::var("MCP_TOKEN")
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    if token.is_empty() {
+        return Vec::new();
+    }
+    vec![McpToken {
+        token,
+        scope: McpScope::ReadWrite,
+    }]
+}
+
+/// token 字符集，均为 URL 安全字符，可直接用于 MCP 路径
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenFormat {
+    Hex,
+    Base64Url,
+    Alphanumeric,
+}
+
+impl TokenFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "hex" => Some(Self::Hex),
+            "base64url" => Some(Self::Base64Url),
+            "alphanumeric" => Some(Self::Alphanumeric),
+            _ => None,
+        }
+    }
+
+    fn charset(self) -> &'static [u8] {
+        match self {
+            Self::Hex => b"0123456789abcdef",
+            Self::Base64Url => b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_",
+            Self::Alphanumeric => b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+        }
+    }
+}
+
+/// 生成指定长度的随机 token，熵全部来自 getrandom
+/// 对字符集大小不整除 256 的情况做拒绝采样，避免取模偏差
+pub fn generate_token(length: usize, format: TokenFormat) -> Result<String> {
+    let charset = format.charset();
+    let limit = 256 - (256 % charset.len());
+    let mut out = String::with_capacity(length);
+    let mut buffer = [0u8; 64];
+    while out.len() < length {
+        getrandom(&mut buffer).map_err(|err| anyhow!("getrandom token failed: {}", err))?;
+        for value in buffer {
+            if out.len() >= length {
+                break;
+            }
+            let value = value as usize;
+            if value < limit {
+                out.push(charset[value % charset.len()] as char);
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// 未设置 `MCP_TOKEN` 时自动生成的 token，格式见 `generate_mcp_token_base64url`
+/// 已配置的旧字母数字 token 按原样比对路径，不受影响
 pub fn generate_mcp_token() -> String {
-    const CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+    generate_mcp_token_base64url()
+}
+
+/// 16 字节随机数按 base64url（无填充）编码为 22 个字符，共 128 位熵
+pub fn generate_mcp_token_base64url() -> String {
     let mut bytes = [0u8; 16];
     getrandom(&mut bytes).expect("generate mcp token");
-    bytes
-        .iter()
-        .map(|value| {
-            let index = (*value as usize) % CHARSET.len();
-            CHARSET[index] as char
-        })
-        .collect()
+    URL_SAFE_NO_PAD.encode(bytes)
 }
@@ -0,0 +1,183 @@
+/// 每个 hunk 前后保留的上下文行数
+const CONTEXT_LINES: usize = 3;
+/// LCS 表的最大单元格数，超过时把首尾相同部分之间的内容整体视为删除 + 新增
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineOp {
+    Equal,
+    Delete,
+    Insert,
+}
+
+#[derive(Debug, Clone)]
+pub struct UnifiedDiff {
+    pub text: String,
+    pub added_lines: usize,
+    pub removed_lines: usize,
+    /// 输出超过 `max_bytes` 被截断
+    pub truncated: bool,
+}
+
+/// 按行生成 unified diff，内容相同时 `text` 为空
+/// 输出超过 `max_bytes` 时在行边界截断，并追加 `... diff truncated` 标记行
+pub fn unified_diff(
+    old: &str,
+    new: &str,
+    old_label: &str,
+    new_label: &str,
+    max_bytes: usize,
+) -> UnifiedDiff {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let ops = diff_lines(&old_lines, &new_lines);
+    let added_lines = ops.iter().filter(|(op, _)| *op == LineOp::Insert).count();
+    let removed_lines = ops.iter().filter(|(op, _)| *op == LineOp::Delete).count();
+    let mut lines = Vec::new();
+    if added_lines + removed_lines > 0 {
+        lines.push(format!("--- {}", old_label));
+        lines.push(format!("+++ {}", new_label));
+        lines.extend(render_hunks(&ops));
+    }
+    let (text, truncated) = join_lines_capped(&lines, max_bytes);
+    UnifiedDiff {
+        text,
+        added_lines,
+        removed_lines,
+        truncated,
+    }
+}
+
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(LineOp, &'a str)> {
+    let prefix = old
+        .iter()
+        .zip(new)
+        .take_while(|(left, right)| left == right)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(left, right)| left == right)
+        .count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    let mut ops: Vec<(LineOp, &str)> = old[..prefix]
+        .iter()
+        .map(|line| (LineOp::Equal, *line))
+        .collect();
+    if old_middle.len().saturating_mul(new_middle.len()) <= MAX_LCS_CELLS {
+        ops.extend(lcs_ops(old_middle, new_middle));
+    } else {
+        ops.extend(old_middle.iter().map(|line| (LineOp::Delete, *line)));
+        ops.extend(new_middle.iter().map(|line| (LineOp::Insert, *line)));
+    }
+    ops.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|line| (LineOp::Equal, *line)),
+    );
+    ops
+}
+
+fn lcs_ops<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(LineOp, &'a str)> {
+    let width = new.len() + 1;
+    // table[i * width + j]：old[i..] 与 new[j..] 的最长公共子序列长度
+    let mut table = vec![0u32; (old.len() + 1) * width];
+    for (i, old_line) in old.iter().enumerate().rev() {
+        for (j, new_line) in new.iter().enumerate().rev() {
+            table[i * width + j] = if old_line == new_line {
+                table[(i + 1) * width + j + 1] + 1
+            } else {
+                table[(i + 1) * width + j].max(table[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::with_capacity(old.len() + new.len());
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            ops.push((LineOp::Equal, old[i]));
+            i += 1;
+            j += 1;
+        } else if table[(i + 1) * width + j] >= table[i * width + j + 1] {
+            ops.push((LineOp::Delete, old[i]));
+            i += 1;
+        } else {
+            ops.push((LineOp::Insert, new[j]));
+            j += 1;
+        }
+    }
+    ops.extend(old[i..].iter().map(|line| (LineOp::Delete, *line)));
+    ops.extend(new[j..].iter().map(|line| (LineOp::Insert, *line)));
+    ops
+}
+
+/// 相邻改动之间的相同行不超过两倍上下文时合并为一个 hunk
+fn render_hunks(ops: &[(LineOp, &str)]) -> Vec<String> {
+    let changes: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, (op, _))| *op != LineOp::Equal)
+        .map(|(index, _)| index)
+        .collect();
+    let mut lines = Vec::new();
+    let mut group_start = 0;
+    while group_start < changes.len() {
+        let mut group_end = group_start;
+        while group_end + 1 < changes.len()
+            && changes[group_end + 1] - changes[group_end] <= 2 * CONTEXT_LINES + 1
+        {
+            group_end += 1;
+        }
+        let start = changes[group_start].saturating_sub(CONTEXT_LINES);
+        let end = (changes[group_end] + CONTEXT_LINES + 1).min(ops.len());
+        let hunk = &ops[start..end];
+        let old_before = count_lines(&ops[..start], LineOp::Insert);
+        let new_before = count_lines(&ops[..start], LineOp::Delete);
+        lines.push(format!(
+            "@@ -{} +{} @@",
+            hunk_range(old_before, count_lines(hunk, LineOp::Insert)),
+            hunk_range(new_before, count_lines(hunk, LineOp::Delete))
+        ));
+        for (op, line) in hunk {
+            let sign = match op {
+                LineOp::Equal => ' ',
+                LineOp::Delete => '-',
+                LineOp::Insert => '+',
+            };
+            lines.push(format!("{}{}", sign, line));
+        }
+        group_start = group_end + 1;
+    }
+    lines
+}
+
+/// 统计除 `skip` 以外的行数：旧文件跳过新增行，新文件跳过删除行
+fn count_lines(ops: &[(LineOp, &str)], skip: LineOp) -> usize {
+    ops.iter().filter(|(op, _)| *op != skip).count()
+}
+
+/// unified diff 约定：空范围的起始行号取前一行
+fn hunk_range(before: usize, count: usize) -> String {
+    let start = if count == 0 { before } else { before + 1 };
+    format!("{},{}", start, count)
+}
+
+fn join_lines_capped(lines: &[String], max_bytes: usize) -> (String, bool) {
+    let mut text = String::new();
+    for (index, line) in lines.iter().enumerate() {
+        if text.len() + line.len() + 1 > max_bytes {
+            text.push_str(&format!(
+                "... diff truncated, {} more lines\n",
+                lines.len() - index
+            ));
+            return (text, true);
+        }
+        text.push_str(line);
+        text.push('\n');
+    }
+    (text, false)
+}
@@ -1,4 +1,3 @@
-
 use anyhow::{Context, Result, anyhow};
 use regex::{Captures, Regex};
 use reqwest::Client;
@@ -8,6 +7,7 @@ use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::path::Path;
 use tokio::fs;
+use tracing::warn;
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ImageSearchItem {
@@ -73,7 +73,6 @@ pub async fn search_images(keywords: &[String], limit: usize) -> ImageSearchResp
     for (index, keyword) in filtered.into_iter().enumerate() {
         let client = client.clone();
         let base_url = base_url.clone();
-        let limit = limit;
         join_set.spawn(async move {
             (index, search_single(&client, &base_url, &keyword, limit).await)
         });
@@ -85,10 +84,10 @@ pub async fn search_images(keywords: &[String], limit: usize) -> ImageSearchResp
     while let Some(task) = join_set.join_next().await {
         match task {
             Ok((index, result)) => {
-                if !result.success {
-                    if let Some(error) = result.error.clone() {
-                        errors.push(format!("{}: {}", result.query, error));
-                    }
+                if !result.success
+                    && let Some(error) = result.error.clone()
+                {
+                    errors.push(format!("{}: {}", result.query, error));
                 }
                 results.push((index, result));
             }
@@ -292,7 +291,7 @@ pub async fn process_markdown_images(markdown: &str) -> Result<String> {
                 replacements.insert(url, format!("/public/images/{filename}"));
             }
             Err(err) => {
-                eprintln!("[solin-blog] download markdown image failed: {err}");
+                warn!("download markdown image failed: {err}");
             }
         }
     }
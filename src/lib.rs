@@ -1,6 +1,17 @@
 pub mod config;
+pub mod diff;
 pub mod image;
 pub mod mcp;
+pub mod ping;
+pub mod server;
 pub mod store;
+#[cfg(feature = "test-helpers")]
+pub mod store_memory;
+#[cfg(feature = "s3")]
+pub mod store_s3;
+pub mod store_wal;
+pub mod style;
+pub mod tokens;
+pub mod upload;
 pub mod web;
-pub mod server;
+pub mod webhook;
@@ -1,69 +1,589 @@
 use std::{
     net::{IpAddr, SocketAddr},
+    path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 
-use axum::{Router, middleware, routing::get};
-use rmcp::transport::streamable_http_server::{
-    StreamableHttpServerConfig, StreamableHttpService, session::local::LocalSessionManager,
+use axum::{
+    Router, middleware,
+    routing::{any, get, post},
 };
+use axum_server::tls_rustls::RustlsConfig;
+use clap::{Parser, Subcommand};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+use tracing_subscriber::EnvFilter;
 
 use solin_blog::{
-    config::generate_mcp_token,
-    mcp::BlogMcpServer,
+    config::{
+        McpScope, McpToken, TlsConfig, resolve_admin_basic_auth_from_env,
+        resolve_indexnow_key_from_env, resolve_mcp_legacy_path_from_env,
+        resolve_mcp_tokens_from_env, resolve_shutdown_timeout_from_env,
+        resolve_tls_config_from_env, resolve_version_max_keep_from_env,
+    },
     server::{
-        index_handler, log_request, page_handler, public_asset_handler, sitemap_handler,
-        token_generator_handler,
+        AccessLog, Cors, DashboardState, HealthState, McpBodyLimit, McpEndpoints, McpIpAllowlist,
+        McpTokenAuth, PageEvents, RateLimiter, RequestTimeout, SecurityHeaders, access_log,
+        admin_auth_middleware, api_page_detail_handler, api_page_list_handler,
+        apple_touch_icon_handler, cors, create_page_api_handler, dashboard_delete_handler,
+        dashboard_edit_handler, dashboard_list_handler, dashboard_preview_handler,
+        dashboard_save_handler, delete_page_api_handler, events_handler, favicon_handler,
+        healthz_handler, icon_png_handler, icon_webp_handler, index_handler, indexnow_key_handler,
+        load_rustls_config, log_request, mcp_body_limit, mcp_endpoint_handler, mcp_ip_allowlist,
+        mcp_token_auth, page_handler, page_html_api_handler, page_raw_handler, page_stats_handler,
+        public_asset_handler, rate_limit, readyz_handler, rebuild_index_handler,
+        reload_tls_on_sighup, request_timeout, security_headers, service_worker_handler,
+        sitemap_handler, spawn_https_redirect, tag_handler, token_generator_handler,
+        update_page_api_handler, validate_html_api_handler, web_manifest_handler,
     },
-    store::PageStore,
+    store::{PageStore, PageStoreTrait, SyncPageStore},
+    tokens::TokenStore,
 };
 
+/// 命令行参数优先于对应的环境变量，环境变量（含 `.env`）优先于默认值
+#[derive(Debug, Parser)]
+#[command(version, about = "SolinBlog：通过 MCP 发布页面的博客服务")]
+struct Cli {
+    /// 监听地址，无法解析为 IP 时回退到 127.0.0.1
+    #[arg(short = 'H', long, env = "WEB_HOST", default_value = "127.0.0.1")]
+    host: String,
+    /// 监听端口
+    #[arg(short, long, env = "WEB_PORT", default_value_t = 3000)]
+    port: u16,
+    /// 数据目录，保存 tokens.json 与本地存储的页面（设置 S3_BUCKET 时页面存入 S3）
+    #[arg(short, long, env = "DATA_DIR", default_value = "data", global = true)]
+    data_dir: PathBuf,
+    /// 首次启动时导入为名为 default 的 read-write token，优先于 MCP_TOKENS 与 MCP_TOKEN 环境变量
+    #[arg(short = 't', long)]
+    mcp_token: Option<String>,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// 管理 tokens.json 中的 MCP token，服务运行中修改同样立即生效
+    Token {
+        #[command(subcommand)]
+        action: TokenAction,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum TokenAction {
+    /// 新增 token 并输出 MCP 入口路径
+    Add {
+        name: String,
+        /// 只读 token，调用写入类工具时返回 permission_denied，且不能访问管理后台
+        #[arg(long)]
+        read_only: bool,
+        /// 使用指定的 token 值，默认随机生成
+        #[arg(long)]
+        token: Option<String>,
+    },
+    /// 列出全部 token 的名称、权限、创建时间与最近使用时间
+    List,
+    /// 吊销 token，使用该 token 的请求随即返回 404
+    Revoke { name: String },
+}
+
 #[tokio::main]
 async fn main() {
     dotenvy::dotenv().ok();
+    let mut cli = Cli::parse();
+    if let Some(Command::Token { action }) = cli.command.take() {
+        std::process::exit(run_token_command(&cli.data_dir, action));
+    }
+    init_tracing();
+    let tls = load_tls_from_env().await;
 
-    let store = Arc::new(PageStore::new("data"));
-    let mut mcp_token = std::env::var("MCP_TOKEN")
-        .unwrap_or_default()
-        .trim()
-        .to_string();
-    if mcp_token.is_empty() {
-        mcp_token = generate_mcp_token();
-        println!("[solin-blog] MCP token generated: {mcp_token}");
-    }
-
-    let mcp_path = format!("/{}/mcp", mcp_token);
-    let mcp_server = BlogMcpServer::new(Arc::clone(&store));
-    let mcp_service = StreamableHttpService::new(
-        move || Ok(mcp_server.clone()),
-        LocalSessionManager::default().into(),
-        StreamableHttpServerConfig::default(),
-    );
+    // 退出信号到达时取消，SSE 与 MCP 的长连接随之结束
+    let shutdown = CancellationToken::new();
+    let events = PageEvents::new(shutdown.child_token());
+    let store = build_page_store(&events, &cli.data_dir).await;
+    if let Some(keep) = resolve_version_max_keep_from_env() {
+        tokio::spawn(prune_revisions_on_startup(Arc::clone(&store), keep));
+    }
+    let tokens = open_token_store(&cli.data_dir, cli.mcp_token.as_deref());
 
-    let app = Router::new()
+    // 首页与 `/api/*` 在配置了 Basic Auth 账号时需要登录，页面、标签页与 sitemap 保持公开
+    let mut admin_routes = Router::new()
         .route("/", get(index_handler))
+        .route(
+            "/api/pages",
+            get(api_page_list_handler).post(create_page_api_handler),
+        )
+        .route(
+            "/api/pages/{page_uid}",
+            get(api_page_detail_handler)
+                .put(update_page_api_handler)
+                .delete(delete_page_api_handler),
+        )
+        .route("/api/pages/{page_uid}/html", get(page_html_api_handler))
+        .route(
+            "/api/pages/{page_uid}/validate",
+            post(validate_html_api_handler),
+        );
+    if let Some(auth) = resolve_admin_basic_auth_from_env() {
+        info!("basic auth enabled for / and /api/*");
+        admin_routes =
+            admin_routes.route_layer(middleware::from_fn_with_state(auth, admin_auth_middleware));
+    }
+
+    let mut router = Router::new()
         .route("/tools/token-generator", get(token_generator_handler))
         .route("/pages/{slug}", get(page_handler))
+        .route("/pages/{slug}/raw", get(page_raw_handler))
+        .route("/pages/{slug}/stats", get(page_stats_handler))
+        .route("/tags/{tag}", get(tag_handler))
         .route("/sitemap.xml", get(sitemap_handler))
         .route("/public/{*path}", get(public_asset_handler))
-        .nest_service(mcp_path.as_str(), mcp_service)
-        .with_state(store)
-        .layer(middleware::from_fn(log_request));
-
-    let host = std::env::var("WEB_HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
-    let port = std::env::var("WEB_PORT")
-        .ok()
-        .and_then(|value| value.parse::<u16>().ok())
-        .unwrap_or(3000);
-    let addr = match host.parse::<IpAddr>() {
-        Ok(ip) => SocketAddr::from((ip, port)),
-        Err(_) => SocketAddr::from(([127, 0, 0, 1], port)),
+        .route("/favicon.ico", get(favicon_handler))
+        .route("/icon.png", get(icon_png_handler))
+        .route("/icon.webp", get(icon_webp_handler))
+        .route("/apple-touch-icon.png", get(apple_touch_icon_handler))
+        .route("/manifest.webmanifest", get(web_manifest_handler))
+        .route("/sw.js", get(service_worker_handler))
+        .route("/admin/rebuild-index", post(rebuild_index_handler))
+        .route("/readyz", get(readyz_handler))
+        .merge(
+            Router::new()
+                .route("/healthz", get(healthz_handler))
+                .with_state(HealthState::new()),
+        )
+        .merge(
+            Router::new()
+                .route("/events", get(events_handler))
+                .with_state(events),
+        )
+        .merge(admin_routes);
+    // 所有 token 共用 read-write / read-only 两个 MCP 服务实例，token 在请求时校验，
+    // `token add` / `token revoke` 无需重启即可生效
+    let body_limit = McpBodyLimit::from_env();
+    let ip_allowlist = load_mcp_ip_allowlist_from_env();
+    // 请求体按上限读取后才交给 MCP 服务，超限的请求直接返回 413；
+    // 白名单在最外层，不在名单内的来源不会读取请求体
+    // 默认只挂载 `/mcp`，token 放在 `Authorization` 头中，不会出现在代理日志与浏览器历史里；
+    // 旧客户端使用的 `/{token}/mcp` 需设置 `MCP_LEGACY_PATH=1`
+    let legacy_path = resolve_mcp_legacy_path_from_env();
+    let mut mcp_routes = Router::new().route("/mcp", any(mcp_endpoint_handler));
+    if legacy_path {
+        mcp_routes = mcp_routes.route("/{token}/mcp", any(mcp_endpoint_handler));
+    }
+    let mut mcp_routes = mcp_routes
+        .with_state(McpEndpoints::new(Arc::clone(&store), &shutdown))
+        .layer(middleware::from_fn_with_state(body_limit, mcp_body_limit))
+        .layer(middleware::from_fn_with_state(
+            McpTokenAuth::new(Arc::clone(&tokens)),
+            mcp_token_auth,
+        ));
+    if let Some(allowlist) = ip_allowlist {
+        mcp_routes = mcp_routes.layer(middleware::from_fn_with_state(allowlist, mcp_ip_allowlist));
+    }
+    // 管理后台与 MCP 入口共用 token 路径，只接受 read-write token；表单提交另需 CSRF token
+    let dashboard_routes = Router::new()
+        .route("/", get(dashboard_list_handler))
+        .route(
+            "/pages/{page_uid}",
+            get(dashboard_edit_handler).post(dashboard_save_handler),
+        )
+        .route("/pages/{page_uid}/preview", post(dashboard_preview_handler))
+        .route("/pages/{page_uid}/delete", post(dashboard_delete_handler))
+        .with_state(DashboardState::new(Arc::clone(&store)))
+        .route_layer(middleware::from_fn_with_state(
+            McpTokenAuth::read_write_only(Arc::clone(&tokens)),
+            mcp_token_auth,
+        ));
+    router = router
+        .merge(mcp_routes)
+        .nest("/{token}/admin", dashboard_routes);
+    // `/{key}.txt` 无法用路由参数匹配部分路径段，只在配置了密钥时注册固定路径
+    if let Some(key) = resolve_indexnow_key_from_env() {
+        router = router.route(&format!("/{}.txt", key), get(indexnow_key_handler));
+    }
+    let mut app = router.with_state(store);
+    if let Some(timeout) = RequestTimeout::from_env() {
+        app = app.layer(middleware::from_fn_with_state(timeout, request_timeout));
+    }
+    app = app.layer(middleware::from_fn_with_state(
+        SecurityHeaders::from_env(),
+        security_headers,
+    ));
+    if let Some(limiter) = RateLimiter::from_env() {
+        info!("rate limiting enabled");
+        app = app.layer(middleware::from_fn_with_state(limiter, rate_limit));
+    }
+    // 位于限流之外：预检请求不消耗令牌，429 响应也带 CORS 头，前端能读到状态码
+    if let Some(config) = Cors::from_env() {
+        info!(
+            "CORS enabled for {}",
+            std::env::var("CORS_ALLOWED_ORIGINS").unwrap_or_default()
+        );
+        app = app.layer(middleware::from_fn_with_state(config, cors));
+    }
+    // 访问日志位于请求日志之内，落在同一个请求 span 下
+    if let Some(log) = AccessLog::from_env() {
+        app = app.layer(middleware::from_fn_with_state(log, access_log));
+    }
+    let app = app.layer(middleware::from_fn_with_state(
+        Arc::clone(&tokens),
+        log_request,
+    ));
+
+    let addr = match cli.host.trim().parse::<IpAddr>() {
+        Ok(ip) => SocketAddr::from((ip, cli.port)),
+        Err(_) => SocketAddr::from(([127, 0, 0, 1], cli.port)),
     };
 
     let listener = tokio::net::TcpListener::bind(addr)
         .await
         .expect("bind http listener");
-    println!("[solin-blog] http server listening on http://{addr}");
-    println!("[solin-blog] MCP endpoint: http://{addr}{mcp_path}");
-    axum::serve(listener, app).await.expect("serve http");
+    let scheme = if tls.is_some() { "https" } else { "http" };
+    info!("http server listening on {scheme}://{addr}");
+    info!("MCP endpoint: {scheme}://{addr}/mcp (Authorization: Bearer <token>)");
+    if legacy_path {
+        info!("legacy MCP endpoint: {scheme}://{addr}/{{token}}/mcp");
+    } else {
+        info!("legacy /{{token}}/mcp endpoints disabled, set MCP_LEGACY_PATH=1 to enable");
+    }
+    info!("admin dashboard: {scheme}://{addr}/{{token}}/admin (read-write tokens)");
+    // 日志会被持久化，token 只输出前 4 位；完整值见 `tokens.json` 或 `token add` 的输出
+    for stored in tokens.list().unwrap_or_default() {
+        info!(
+            "MCP token {} ({}): {}",
+            stored.name,
+            stored.scope.as_str(),
+            stored.masked()
+        );
+    }
+    // 限流按连接的对端地址区分客户端
+    let app = app.into_make_service_with_connect_info::<SocketAddr>();
+    let drain_timeout = resolve_shutdown_timeout_from_env();
+    if let Some((tls, rustls)) = tls {
+        if let Some(redirect_port) = tls.redirect_port {
+            spawn_https_redirect(
+                SocketAddr::new(addr.ip(), redirect_port),
+                cli.port,
+                shutdown.child_token(),
+            )
+            .await
+            .expect("bind http redirect listener");
+        }
+        reload_tls_on_sighup(rustls.clone(), tls);
+        let handle = axum_server::Handle::new();
+        tokio::spawn({
+            let handle = handle.clone();
+            async move {
+                shutdown_signal(shutdown, drain_timeout).await;
+                // 不设上限，超时由 shutdown_signal 强制退出
+                handle.graceful_shutdown(None);
+            }
+        });
+        axum_server::from_tcp_rustls(listener.into_std().expect("convert https listener"), rustls)
+            .handle(handle)
+            .serve(app)
+            .await
+            .expect("serve https");
+    } else {
+        axum::serve(listener, app)
+            .with_graceful_shutdown(shutdown_signal(shutdown, drain_timeout))
+            .await
+            .expect("serve http");
+    }
+    // 浏览计数与 WAL 都在请求内同步写入并提交，请求处理完即已落盘；
+    // 运行时退出前还会等待仍在执行的 `spawn_blocking` 存储任务
+    info!("http server stopped, all in-flight requests finished");
+    // token 的最近使用时间按分钟节流落盘，退出前写入剩余部分
+    match tokens.flush() {
+        Ok(true) => info!("MCP token usage saved to {}", tokens.path().display()),
+        Ok(false) => {}
+        Err(err) => error!("save MCP token usage failed: {err:#}"),
+    }
+    info!("shutdown complete");
+}
+
+/// 等待 SIGTERM 或 Ctrl-C：停止接受新连接并取消长连接，进行中的请求继续处理；
+/// 超过 `SHUTDOWN_TIMEOUT_SECS` 仍未处理完时强制退出
+async fn shutdown_signal(shutdown: CancellationToken, drain_timeout: Duration) {
+    let ctrl_c = async {
+        if let Err(err) = tokio::signal::ctrl_c().await {
+            error!("listen for ctrl-c failed: {err}");
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(err) => {
+                error!("listen for SIGTERM failed: {err}");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+    info!(
+        "shutdown signal received, draining in-flight requests (timeout {}s)",
+        drain_timeout.as_secs()
+    );
+    shutdown.cancel();
+    tokio::spawn(async move {
+        tokio::time::sleep(drain_timeout).await;
+        error!(
+            "in-flight requests not finished within {}s, forcing exit",
+            drain_timeout.as_secs()
+        );
+        std::process::exit(1);
+    });
+}
+
+/// 设置了 `TLS_CERT_PATH` / `TLS_KEY_PATH` 时加载证书；配置不完整或证书无法解析时直接退出，
+/// 不回退到明文 HTTP
+async fn load_tls_from_env() -> Option<(TlsConfig, RustlsConfig)> {
+    let result = match resolve_tls_config_from_env() {
+        Ok(Some(tls)) => load_rustls_config(&tls)
+            .await
+            .map(|rustls| Some((tls, rustls))),
+        Ok(None) => Ok(None),
+        Err(err) => Err(err),
+    };
+    match result {
+        Ok(tls) => {
+            if tls.is_some() {
+                info!("TLS enabled, send SIGHUP to reload the certificate");
+            }
+            tls
+        }
+        Err(err) => {
+            error!("invalid TLS configuration: {err:#}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// 首次启动（`tokens.json` 不存在）时导入 `--mcp-token` / `MCP_TOKENS` / `MCP_TOKEN`，
+/// 第一个命名为 `default`，其余依次为 `default-2`、`default-3`……；都未设置时生成 `default`。
+/// 之后以文件为准，不在文件中的旧 token 只打印警告，重启不会恢复已吊销的 token
+fn open_token_store(data_dir: &Path, cli_token: Option<&str>) -> Arc<TokenStore> {
+    let tokens = TokenStore::open(data_dir).unwrap_or_else(|err| {
+        error!("open MCP token store failed: {err:#}");
+        std::process::exit(1);
+    });
+    let legacy = match cli_token.map(str::trim).filter(|token| !token.is_empty()) {
+        Some(token) => vec![McpToken {
+            token: token.to_string(),
+            scope: McpScope::ReadWrite,
+        }],
+        None => resolve_mcp_tokens_from_env(),
+    };
+    if tokens.file_exists() {
+        let stored = tokens.list().unwrap_or_default();
+        if legacy
+            .iter()
+            .any(|legacy| !stored.iter().any(|stored| stored.token == legacy.token))
+        {
+            warn!(
+                "MCP_TOKEN / MCP_TOKENS / --mcp-token not found in {} and ignored, \
+                 use `token add --token` to add them",
+                tokens.path().display()
+            );
+        }
+        if stored.is_empty() {
+            warn!("no MCP tokens configured, add one with `token add <name>`");
+        }
+        return Arc::new(tokens);
+    }
+    let result = if legacy.is_empty() {
+        tokens.add("default", McpScope::ReadWrite).map(|stored| {
+            // 完整 token 只在生成时输出到标准输出一次，不进入日志
+            println!("MCP token generated (default, rw): {}", stored.token);
+        })
+    } else {
+        legacy
+            .into_iter()
+            .enumerate()
+            .try_for_each(|(index, legacy)| {
+                let name = match index {
+                    0 => "default".to_string(),
+                    index => format!("default-{}", index + 1),
+                };
+                tokens.insert(&name, legacy.token, legacy.scope).map(|_| ())
+            })
+    };
+    match result {
+        Ok(()) => info!("MCP tokens saved to {}", tokens.path().display()),
+        Err(err) => {
+            error!("import MCP tokens failed: {err:#}");
+            std::process::exit(1);
+        }
+    }
+    Arc::new(tokens)
+}
+
+/// `token add|list|revoke`：只读写 `tokens.json`，不启动服务；返回进程退出码
+fn run_token_command(data_dir: &Path, action: TokenAction) -> i32 {
+    let tokens = match TokenStore::open(data_dir) {
+        Ok(tokens) => tokens,
+        Err(err) => {
+            eprintln!("open token store failed: {err:#}");
+            return 1;
+        }
+    };
+    match action {
+        TokenAction::Add {
+            name,
+            read_only,
+            token,
+        } => {
+            let scope = if read_only {
+                McpScope::ReadOnly
+            } else {
+                McpScope::ReadWrite
+            };
+            let result = match token {
+                Some(token) => tokens.insert(&name, token, scope),
+                None => tokens.add(&name, scope),
+            };
+            match result {
+                Ok(stored) => {
+                    println!("token {} ({}) added", stored.name, stored.scope.as_str());
+                    println!(
+                        "MCP endpoint: /mcp (Authorization: Bearer {})",
+                        stored.token
+                    );
+                    println!("legacy endpoint (MCP_LEGACY_PATH=1): /{}/mcp", stored.token);
+                    0
+                }
+                Err(err) => {
+                    eprintln!("add token failed: {err:#}");
+                    1
+                }
+            }
+        }
+        TokenAction::List => match tokens.list() {
+            Ok(list) if list.is_empty() => {
+                println!("no tokens in {}", tokens.path().display());
+                0
+            }
+            Ok(list) => {
+                println!(
+                    "{:<24} {:<5} {:<8} {:<17} LAST USED",
+                    "NAME", "SCOPE", "TOKEN", "CREATED"
+                );
+                for stored in list {
+                    println!(
+                        "{:<24} {:<5} {:<8} {:<17} {}",
+                        stored.name,
+                        stored.scope.as_str(),
+                        stored.masked(),
+                        format_token_time(Some(stored.created_at)),
+                        format_token_time(stored.last_used_at)
+                    );
+                }
+                0
+            }
+            Err(err) => {
+                eprintln!("list tokens failed: {err:#}");
+                1
+            }
+        },
+        TokenAction::Revoke { name } => match tokens.revoke(&name) {
+            Ok(true) => {
+                println!("token {name} revoked");
+                0
+            }
+            Ok(false) => {
+                eprintln!("token {name} not found");
+                1
+            }
+            Err(err) => {
+                eprintln!("revoke token failed: {err:#}");
+                1
+            }
+        },
+    }
+}
+
+fn format_token_time(timestamp: Option<i64>) -> String {
+    timestamp
+        .and_then(|timestamp| chrono::DateTime::from_timestamp(timestamp, 0))
+        .map(|datetime| datetime.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// `MCP_ALLOWED_IPS` 中有无法解析的条目时直接退出，不以放开访问的方式继续运行
+fn load_mcp_ip_allowlist_from_env() -> Option<McpIpAllowlist> {
+    match McpIpAllowlist::from_env() {
+        Ok(allowlist) => {
+            if allowlist.is_some() {
+                info!("MCP endpoints restricted by MCP_ALLOWED_IPS");
+            }
+            allowlist
+        }
+        Err(err) => {
+            error!("invalid MCP_ALLOWED_IPS: {err:#}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// `RUST_LOG` 控制日志级别与过滤（默认 `info`），`LOG_FORMAT=json` 时每条日志输出为一行 JSON
+fn init_tracing() {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let json =
+        std::env::var("LOG_FORMAT").is_ok_and(|value| value.trim().eq_ignore_ascii_case("json"));
+    let subscriber = tracing_subscriber::fmt().with_env_filter(filter);
+    if json {
+        subscriber.json().init();
+    } else {
+        subscriber.init();
+    }
+}
+
+/// 在后台清理超出 `VERSION_MAX_KEEP` 的历史版本，不阻塞服务启动
+async fn prune_revisions_on_startup(store: Arc<dyn PageStoreTrait>, keep: usize) {
+    match store.prune_all_revisions(keep).await {
+        Ok(pruned) => info!(
+            "pruned {} revisions from {} pages (keep {})",
+            pruned.values().sum::<usize>(),
+            pruned.len(),
+            keep
+        ),
+        Err(err) => error!("prune revisions failed: {:#}", err),
+    }
+}
+
+/// 设置 `S3_BUCKET` 时使用 S3 兼容对象存储，否则使用本地 `data_dir` 目录（默认 `data/`）；
+/// 页面变更事件依赖 `PageStore` 的钩子，S3 后端下 `/events` 不会推送
+async fn build_page_store(events: &PageEvents, data_dir: &Path) -> Arc<dyn PageStoreTrait> {
+    let bucket = std::env::var("S3_BUCKET").unwrap_or_default();
+    if bucket.trim().is_empty() {
+        let store = events
+            .attach(PageStore::builder().base_dir(data_dir))
+            .build();
+        return Arc::new(SyncPageStore::new(store));
+    }
+
+    #[cfg(feature = "s3")]
+    {
+        let store = solin_blog::store_s3::S3PageStore::from_env()
+            .await
+            .expect("init s3 page store");
+        info!("page store: s3 bucket {}", store.bucket());
+        Arc::new(store)
+    }
+    #[cfg(not(feature = "s3"))]
+    {
+        error!("S3_BUCKET is set but this binary was built without the `s3` feature");
+        std::process::exit(1);
+    }
 }
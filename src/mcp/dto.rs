@@ -1,14 +1,159 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::store::PageMeta;
+use crate::store::{HtmlError, PageMeta, UpdateConflict};
+
+/// 工具失败的分类，客户端据此分支处理，不必匹配错误文本
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// 页面、版本或资源不存在
+    NotFound,
+    /// 参数或内容未通过校验
+    ValidationFailed,
+    /// 与现有数据冲突，例如页面已存在、重复的 page_id
+    Conflict,
+    /// 超出大小或数量限制
+    TooLarge,
+    /// 读写数据目录或对象存储失败
+    Io,
+    /// 只读 token 调用了写入类工具
+    PermissionDenied,
+    /// 工具执行超过 `REQUEST_TIMEOUT_SECS`
+    Timeout,
+    Internal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ToolError {
+    pub code: ErrorCode,
+    pub message: String,
+    /// 完整的错误链，与 `message` 相同时为空
+    pub detail: Option<String>,
+}
+
+impl ToolError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            detail: None,
+        }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::ValidationFailed, message)
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Conflict, message)
+    }
+
+    pub fn too_large(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::TooLarge, message)
+    }
+
+    pub fn permission_denied(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::PermissionDenied, message)
+    }
+
+    pub fn timeout(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Timeout, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+
+    /// 存储层与渲染层的错误：`HtmlError` 为校验失败，`UpdateConflict` 为冲突，`io::Error` 按 kind
+    /// 区分不存在与读写失败，其余按 `bail!` 的固定文本（`not found` / `already exists` / `exceeds` / `is empty`）归类
+    pub fn from_store(err: &anyhow::Error) -> Self {
+        let message = err.to_string();
+        let io_kind = err
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<std::io::Error>())
+            .map(std::io::Error::kind);
+        let code = if err.chain().any(|cause| cause.is::<HtmlError>()) {
+            ErrorCode::ValidationFailed
+        } else if err.chain().any(|cause| cause.is::<UpdateConflict>()) {
+            ErrorCode::Conflict
+        } else if io_kind == Some(std::io::ErrorKind::NotFound) || message.contains("not found") {
+            ErrorCode::NotFound
+        } else if io_kind.is_some() {
+            ErrorCode::Io
+        } else if message.contains("already exists") {
+            ErrorCode::Conflict
+        } else if message.contains("exceeds") {
+            ErrorCode::TooLarge
+        } else if message.contains("is empty") {
+            ErrorCode::ValidationFailed
+        } else {
+            ErrorCode::Internal
+        };
+        let detail = format!("{err:#}");
+        Self {
+            code,
+            detail: Some(detail).filter(|detail| *detail != message),
+            message,
+        }
+    }
+
+    /// 在 `message` 前加上页面等上下文，错误码不变
+    pub fn with_context(mut self, context: impl std::fmt::Display) -> Self {
+        self.message = format!("{context}: {}", self.message);
+        self
+    }
+}
+
+/// 响应中的错误字段：`error` 为兼容旧客户端的文本，`error_info` 为带错误码的结构化信息
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ErrorFields {
+    pub error: Option<String>,
+    pub error_info: Option<ToolError>,
+}
+
+impl ErrorFields {
+    /// 批量操作的多个错误合并为一条，错误码一致时沿用，否则为 `internal`
+    pub fn from_errors(errors: Vec<ToolError>) -> Self {
+        let Some(first) = errors.first() else {
+            return Self::default();
+        };
+        let code = if errors.iter().all(|error| error.code == first.code) {
+            first.code
+        } else {
+            ErrorCode::Internal
+        };
+        let messages: Vec<&str> = errors.iter().map(|error| error.message.as_str()).collect();
+        ToolError::new(code, messages.join("; ")).into()
+    }
+}
+
+impl From<ToolError> for ErrorFields {
+    fn from(error: ToolError) -> Self {
+        Self {
+            error: Some(error.message.clone()),
+            error_info: Some(error),
+        }
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct PushPageRequest {
     pub seo_title: String,
     pub description: String,
     pub keywords: Option<Vec<String>>,
+    /// 结构化标签，支持 `language:rust` 这类命名空间写法；设置后优先于 keywords 建立标签索引
+    pub tags: Option<Vec<String>>,
     pub html: String,
+    /// 客户端生成的幂等键，重试时传入相同的值，有效期内重复推送返回首次创建的页面
+    /// `push_pages` 的条目忽略该字段
+    pub idempotency_key: Option<String>,
+    /// 自定义 JSON-LD，必须是 JSON 对象，输出为额外的 `<script type="application/ld+json">`
+    pub structured_data: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -16,7 +161,35 @@ pub struct PushMarkdownRequest {
     pub seo_title: String,
     pub description: String,
     pub keywords: Option<Vec<String>>,
+    /// 同 `PushPageRequest::tags`
+    pub tags: Option<Vec<String>>,
     pub markdown: String,
+    /// 同 `PushPageRequest::idempotency_key`
+    pub idempotency_key: Option<String>,
+}
+
+/// `push_pages` 的单个条目，用 `format` 区分 HTML 与 Markdown
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "format", rename_all = "snake_case")]
+pub enum PushPagesItem {
+    Html(PushPageRequest),
+    Markdown(PushMarkdownRequest),
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PushPagesRequest {
+    /// 待创建的页面，单次最多 20 个
+    pub pages: Vec<PushPagesItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PushPagesResponse {
+    /// 所有条目都创建成功时为 true
+    pub success: bool,
+    /// 与请求中 `pages` 顺序一致的逐条结果
+    pub results: Vec<PushPageResponse>,
+    #[serde(flatten)]
+    pub error: ErrorFields,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -24,6 +197,7 @@ pub struct SeoMetaResponse {
     pub seo_title: String,
     pub description: String,
     pub keywords: Option<Vec<String>>,
+    pub structured_data: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -33,6 +207,10 @@ pub struct PageMetaResponse {
     pub created_at: i64,
     pub updated_at: i64,
     pub view_count: u64,
+    pub unique_view_count: u64,
+    pub tags: Option<Vec<String>>,
+    pub sort_order: Option<i32>,
+    pub published_at: Option<i64>,
 }
 
 impl From<PageMeta> for PageMetaResponse {
@@ -42,11 +220,16 @@ impl From<PageMeta> for PageMetaResponse {
                 seo_title: meta.seo.seo_title,
                 description: meta.seo.description,
                 keywords: meta.seo.keywords,
+                structured_data: meta.seo.structured_data,
             },
             page_uid: meta.page_uid,
             created_at: meta.created_at,
             updated_at: meta.updated_at,
             view_count: meta.view_count,
+            unique_view_count: meta.unique_view_count,
+            tags: meta.tags,
+            sort_order: meta.sort_order,
+            published_at: meta.published_at,
         }
     }
 }
@@ -57,14 +240,44 @@ pub struct PushPageResponse {
     pub page_id: Option<String>,
     pub url: Option<String>,
     pub meta: Option<PageMetaResponse>,
-    pub error: Option<String>,
+    #[serde(flatten)]
+    pub error: ErrorFields,
+    /// HTML 校验失败时的出错位置，结构与 `validate_page_html` 一致
+    pub html_error: Option<HtmlValidationError>,
+    /// 幂等键命中，返回的是此前创建的页面
+    pub deduplicated: bool,
+}
+
+/// HTML 校验失败的详细信息，`line` / `column` 从 1 开始，`column` 按字符计数
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct HtmlValidationError {
+    pub message: String,
+    /// 出错位置的字节偏移，空文档或超出大小限制时为空
+    pub offset: Option<usize>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    /// 出错位置前后各 80 个字符
+    pub snippet: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct GetAllPageResponse {
     pub success: bool,
     pub pages: Vec<PageWithMeta>,
-    pub error: Option<String>,
+    #[serde(flatten)]
+    pub error: ErrorFields,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PageListResponse {
+    pub success: bool,
+    pub pages: Vec<PageWithMeta>,
+    /// 当前页码，从 1 开始
+    pub page: usize,
+    pub per_page: usize,
+    pub total: usize,
+    #[serde(flatten)]
+    pub error: ErrorFields,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -73,6 +286,62 @@ pub struct GetAllPageRequest {
     pub reserved: Option<bool>,
 }
 
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetMostPopularPagesRequest {
+    /// 返回的页面数量，默认 10
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetRecentUpdatesRequest {
+    /// 返回的页面数量，默认 10
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetRelatedPagesRequest {
+    pub page_id: String,
+    /// 返回的页面数量，默认 5
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchField {
+    Title,
+    Content,
+    Keywords,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SearchPagesRequest {
+    /// 搜索词，按空白拆分，不区分大小写，所有词都需命中
+    pub query: String,
+    /// 搜索范围，默认 `["title", "content", "keywords"]`；只搜标题时无需读取正文
+    pub search_in: Option<Vec<SearchField>>,
+    /// 返回的结果数量，默认 20
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SearchPagesResponse {
+    pub success: bool,
+    pub hits: Vec<SearchHit>,
+    #[serde(flatten)]
+    pub error: ErrorFields,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SearchHit {
+    pub page_id: String,
+    pub url: String,
+    pub title: String,
+    /// 命中搜索词的字段
+    pub matched_fields: Vec<SearchField>,
+    /// 搜索词在各字段中出现的总次数
+    pub score: usize,
+}
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct PageWithMeta {
     pub page_id: String,
@@ -89,27 +358,568 @@ pub struct PageIdRequest {
 pub struct GetPageByIdRequest {
     pub page_id: Option<String>,
     pub ids: Option<Vec<String>>,
+    /// 默认 true；为 false 时只读元数据、不读取 index.html，响应中省略 `html`
+    pub include_html: Option<bool>,
+    /// 只返回这些元数据字段，如 `["seo_title", "tags"]`；设置后以 `fields` 代替 `meta`
+    pub fields: Option<Vec<String>>,
+    /// html 超过该字节数时按字符边界截断，并标记 `truncated: true`
+    pub max_html_bytes: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetPageByUrlRequest {
+    /// 完整地址或相对路径，如 `https://blog.example.com/pages/标题+abc123`、`/pages/abc123/`
+    pub url: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct GetPageByIdResponse {
     pub success: bool,
     pub pages: Vec<PageWithHtml>,
+    #[serde(flatten)]
+    pub error: ErrorFields,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SetPageExtraRequest {
+    pub page_id: String,
+    pub key: String,
+    /// JSON 编码的值，例如 `"\"reviewed\""`、`{"score": 3}`；传 `null` 删除该键
+    pub value_json: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SetPageVisibilityRequest {
+    pub page_id: String,
+    /// true 发布，false 取消发布（页面从首页、sitemap 与 API 中移除，页面路由返回 404）
+    pub published: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PageVisibilityResponse {
+    pub success: bool,
+    /// 调用后的发布状态
+    pub published: Option<bool>,
+    /// 状态未变化时为 false，表示没有写入
+    pub changed: bool,
+    pub url: Option<String>,
+    #[serde(flatten)]
+    pub error: ErrorFields,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TagsMode {
+    /// 用 `tags` 替换全部标签（默认），传空数组清除
+    Replace,
+    /// 在现有标签后追加
+    Add,
+    /// 从现有标签中移除
+    Remove,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SetTagsRequest {
+    pub page_id: String,
+    /// 去除首尾空白并转小写后去重，不能为空字符串，单页最多 20 个
+    pub tags: Vec<String>,
+    pub mode: Option<TagsMode>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SetTagsResponse {
+    pub success: bool,
+    /// 写入后的完整标签列表
+    pub tags: Vec<String>,
+    #[serde(flatten)]
+    pub error: ErrorFields,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ListTagsRequest {
+    /// 预留参数，保持 schema 的 properties 非空
+    pub reserved: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct TagCount {
+    pub tag: String,
+    pub page_count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ListTagsResponse {
+    pub success: bool,
+    pub tags: Vec<TagCount>,
+    #[serde(flatten)]
+    pub error: ErrorFields,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct BulkUpdateSeoRequest {
+    pub page_ids: Vec<String>,
+    /// 追加到页面标题（`<title>`）末尾，已以该后缀结尾的页面跳过；不改变页面 URL
+    pub seo_title_suffix: Option<String>,
+    /// 加在 description 开头，已以该前缀开头的页面跳过
+    pub description_prefix: Option<String>,
+    /// 追加到 `seo.keywords`，忽略大小写去重
+    pub add_keywords: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct BulkUpdateSeoResponse {
+    /// 处理成功的 page_id，已符合要求而无需写入的页面也在其中
+    pub updated: Vec<String>,
+    /// `(page_id, error)`
+    pub errors: Vec<(String, String)>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RerenderMarkdownPagesRequest {
+    /// 只处理这些页面，不传时处理所有带 Markdown 源文件的页面
+    pub page_ids: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RerenderMarkdownPagesResponse {
+    /// HTML 有变化并已写入的 page_id
+    pub updated: Vec<String>,
+    /// 重新渲染结果与已保存 HTML 相同的 page_id
+    pub unchanged: Vec<String>,
+    /// `(page_id, error)`，渲染或校验失败的页面保持原样
+    pub errors: Vec<(String, String)>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ReplaceInPage {
+    /// 页面 HTML 中需要替换为资源地址的占位字符串，所有出现处都会被替换
+    pub placeholder: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct UploadAssetRequest {
+    /// 归属页面，不填时上传为全局资源
+    pub page_id: Option<String>,
+    /// 仅限字母数字与 `-` `_` `.`，同名文件会被覆盖
+    pub filename: String,
+    /// 文件内容的 base64，允许带 `data:...;base64,` 前缀
+    pub content_base64: String,
+    pub mime_type: String,
+    /// 上传后把页面 HTML 中的占位符替换为资源地址，需要同时指定 `page_id`
+    pub replace_in_page: Option<ReplaceInPage>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct UploadAssetResponse {
+    pub success: bool,
+    /// 完整访问地址（依赖 `SITE_URL`）
+    pub url: Option<String>,
+    /// 站点内路径，如 `/public/uploads/{page_uid}/cover.png`，替换占位符时写入的是该路径
+    pub path: Option<String>,
+    /// 设置 `replace_in_page` 时的替换次数
+    pub replacements: Option<usize>,
+    #[serde(flatten)]
+    pub error: ErrorFields,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ListAssetsRequest {
+    /// 列出该页面的资源，不填时只列出全局资源
+    pub page_id: Option<String>,
+    /// 指定 `page_id` 时是否同时列出全局资源，默认 false
+    pub include_global: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct AssetInfo {
+    pub name: String,
+    /// 页面资源的归属页面，全局资源为空
+    pub page_id: Option<String>,
+    pub size: u64,
+    pub mime_type: String,
+    pub url: String,
+    pub path: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ListAssetsResponse {
+    pub success: bool,
+    pub assets: Vec<AssetInfo>,
+    #[serde(flatten)]
+    pub error: ErrorFields,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DeleteAssetRequest {
+    /// 资源归属页面，不填时删除全局资源
+    pub page_id: Option<String>,
+    /// `list_assets` 返回的 `name`
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DeleteAssetResponse {
+    pub success: bool,
+    pub path: Option<String>,
+    /// HTML 中仍引用该资源路径的页面，删除不受影响
+    pub referenced_by: Vec<String>,
+    pub warning: Option<String>,
+    #[serde(flatten)]
+    pub error: ErrorFields,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SetPageOrderRequest {
+    pub page_id: String,
+    /// 首页排序值，越小越靠前
+    pub sort_order: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SetPageOrderResponse {
+    pub success: bool,
+    pub sort_order: Option<i32>,
+    #[serde(flatten)]
+    pub error: ErrorFields,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ReorderPagesRequest {
+    /// 按期望顺序排列的页面，依次写入 `sort_order` = 1, 2, 3…
+    pub ordered_page_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PageOrder {
+    pub page_id: String,
+    pub sort_order: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ReorderPagesResponse {
+    pub success: bool,
+    /// 已写入的排序，写入中途失败时只包含失败之前的页面
+    pub orders: Vec<PageOrder>,
+    #[serde(flatten)]
+    pub error: ErrorFields,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetPageExtraRequest {
+    pub page_id: String,
+    pub key: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PageExtraResponse {
+    pub success: bool,
+    pub key: String,
+    /// 键不存在时为空
+    pub value: Option<serde_json::Value>,
+    #[serde(flatten)]
+    pub error: ErrorFields,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetPageUrlRequest {
+    pub page_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetPageUrlResponse {
+    pub success: bool,
+    pub url: Option<String>,
+    #[serde(flatten)]
+    pub error: ErrorFields,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct NotifySearchEnginesRequest {
+    /// 页面 ID（page_uid），必须是已公开的页面
+    pub page_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PingEndpointResult {
+    pub endpoint: String,
+    /// HTTP 状态码，网络失败或被跳过时为空
+    pub status: Option<u16>,
     pub error: Option<String>,
 }
 
+impl From<crate::ping::PingResult> for PingEndpointResult {
+    fn from(result: crate::ping::PingResult) -> Self {
+        Self {
+            endpoint: result.endpoint,
+            status: result.status,
+            error: result.error,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct NotifySearchEnginesResponse {
+    pub success: bool,
+    pub url: Option<String>,
+    /// 各端点的提交结果，单个端点失败不影响 success
+    pub results: Vec<PingEndpointResult>,
+    #[serde(flatten)]
+    pub error: ErrorFields,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchEngine {
+    Google,
+    Bing,
+}
+
+impl From<SearchEngine> for crate::ping::SearchEngine {
+    fn from(engine: SearchEngine) -> Self {
+        match engine {
+            SearchEngine::Google => Self::Google,
+            SearchEngine::Bing => Self::Bing,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PingSearchEnginesRequest {
+    /// 为空时 ping 全部支持的搜索引擎
+    pub engines: Option<Vec<SearchEngine>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct EnginePingResult {
+    pub engine: SearchEngine,
+    pub success: bool,
+    /// HTTP 状态码，网络失败或被限流时为空
+    pub status: Option<u16>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PingResponse {
+    pub results: Vec<EnginePingResult>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SitemapFormat {
+    Xml,
+    Json,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetSitemapRequest {
+    pub format: SitemapFormat,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SitemapEntry {
+    pub url: String,
+    pub lastmod: String,
+    pub page_uid: String,
+    pub title: String,
+    /// 正文图片的完整 URL，与 `/sitemap.xml` 中的 `<image:loc>` 一致
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<String>,
+}
+
+impl From<crate::web::SitemapEntry> for SitemapEntry {
+    fn from(entry: crate::web::SitemapEntry) -> Self {
+        Self {
+            url: entry.url,
+            lastmod: entry.lastmod,
+            page_uid: entry.page_uid,
+            title: entry.title,
+            images: entry.images,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetSitemapResponse {
+    pub format: SitemapFormat,
+    /// `format: xml` 时为与 `/sitemap.xml` 相同的内容
+    pub xml: Option<String>,
+    /// `format: json` 时的页面列表
+    pub entries: Option<Vec<SitemapEntry>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RevisionSummary {
+    pub revision_id: u64,
+    pub created_at: i64,
+    pub html_bytes: usize,
+    /// 纯 HTML 页面的版本没有 Markdown 源
+    pub markdown_bytes: Option<usize>,
+}
+
+impl From<crate::store::RevisionInfo> for RevisionSummary {
+    fn from(info: crate::store::RevisionInfo) -> Self {
+        Self {
+            revision_id: info.revision_id,
+            created_at: info.created_at,
+            html_bytes: info.html_bytes,
+            markdown_bytes: info.markdown_bytes,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ListRevisionsResponse {
+    pub success: bool,
+    /// 按版本号升序，最旧的在前
+    pub revisions: Vec<RevisionSummary>,
+    #[serde(flatten)]
+    pub error: ErrorFields,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DiffRevisionsRequest {
+    pub page_id: String,
+    pub from_revision: u64,
+    /// 为空时与页面当前内容比较
+    pub to_revision: Option<u64>,
+    /// 为 true 时比较 Markdown 源，默认比较 HTML
+    pub markdown: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DiffRevisionsResponse {
+    pub success: bool,
+    /// 按行的 unified diff，内容相同时为空字符串
+    pub diff: Option<String>,
+    pub added_lines: usize,
+    pub removed_lines: usize,
+    /// diff 超过大小上限被截断
+    pub truncated: bool,
+    #[serde(flatten)]
+    pub error: ErrorFields,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RestoreRevisionRequest {
+    pub page_id: String,
+    pub revision_id: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RestoreRevisionResponse {
+    pub success: bool,
+    pub restored_revision: Option<u64>,
+    /// 恢复前为当前内容保存的版本号
+    pub backup_revision: Option<u64>,
+    #[serde(flatten)]
+    pub error: ErrorFields,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PrunePageVersionsRequest {
+    pub page_id: String,
+    /// 保留最新的版本数，至少为 1
+    pub keep: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PrunePageVersionsResponse {
+    pub success: bool,
+    /// 删除的版本数
+    pub deleted: usize,
+    #[serde(flatten)]
+    pub error: ErrorFields,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetPageMetaResponse {
+    pub success: bool,
+    pub pages: Vec<PageWithMeta>,
+    /// 不存在的 page_id
+    pub not_found: Vec<String>,
+    /// 读取失败（IO / 解析错误），不包含 not_found 中的 id
+    #[serde(flatten)]
+    pub error: ErrorFields,
+}
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct PageWithHtml {
     pub page_id: String,
     pub url: String,
-    pub meta: PageMetaResponse,
-    pub html: String,
+    /// 请求带 `fields` 时省略
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<PageMetaResponse>,
+    /// 按 `fields` 选出的元数据字段
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<serde_json::Map<String, serde_json::Value>>,
+    /// `include_html: false` 时省略
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub html: Option<String>,
+    /// html 被 `max_html_bytes` 截断
+    pub truncated: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct DeletePageResponse {
     pub success: bool,
-    pub error: Option<String>,
+    #[serde(flatten)]
+    pub error: ErrorFields,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ListDeletedPagesRequest {
+    /// 预留参数，保持 schema 的 properties 非空
+    pub reserved: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DeletedPageInfo {
+    /// 回收站条目 ID，同一 page_uid 被多次删除时用它区分
+    pub trash_id: String,
+    pub page_uid: String,
+    /// 删除前的标题
+    pub title: String,
+    pub deleted_at: i64,
+}
+
+impl From<crate::store::DeletedPage> for DeletedPageInfo {
+    fn from(page: crate::store::DeletedPage) -> Self {
+        Self {
+            trash_id: page.trash_id,
+            page_uid: page.page_uid,
+            title: page.title,
+            deleted_at: page.deleted_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ListDeletedPagesResponse {
+    pub success: bool,
+    /// 按删除时间倒序
+    pub pages: Vec<DeletedPageInfo>,
+    #[serde(flatten)]
+    pub error: ErrorFields,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PurgePageRequest {
+    /// 回收站中页面的 page_uid，需与 `list_deleted_pages` 返回的完全一致
+    pub page_uid: String,
+    /// 同一 page_uid 在回收站中有多条时必填
+    pub trash_id: Option<String>,
+    /// 永久删除无法撤销，必须传 true
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PurgePageResponse {
+    pub success: bool,
+    /// 被永久删除的条目
+    pub page: Option<DeletedPageInfo>,
+    #[serde(flatten)]
+    pub error: ErrorFields,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -118,7 +928,17 @@ pub struct UpdatePageRequest {
     pub seo_title: Option<String>,
     pub description: Option<String>,
     pub keywords: Option<Vec<String>>,
+    /// 结构化标签，传空数组清除
+    pub tags: Option<Vec<String>>,
     pub html: Option<String>,
+    /// 追加到 `<head>` 末尾的自定义片段（如 preload、站点验证 meta），传空字符串清除
+    pub head_html: Option<String>,
+    /// 为 true 时页面输出 robots noindex 并从 sitemap 中移除，false 清除标记
+    pub noindex: Option<bool>,
+    /// 读取页面时拿到的 `meta.updated_at`；页面此后被修改过则返回 conflict 错误，不覆盖他人的修改
+    pub expected_updated_at: Option<i64>,
+    /// 同 `PushPageRequest::structured_data`，传空对象 `{}` 清除
+    pub structured_data: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -127,7 +947,13 @@ pub struct UpdateMarkdownPageRequest {
     pub seo_title: Option<String>,
     pub description: Option<String>,
     pub keywords: Option<Vec<String>>,
+    /// 同 `UpdatePageRequest::tags`
+    pub tags: Option<Vec<String>>,
     pub markdown: Option<String>,
+    /// 同 `UpdatePageRequest::noindex`
+    pub noindex: Option<bool>,
+    /// 同 `UpdatePageRequest::expected_updated_at`
+    pub expected_updated_at: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -135,29 +961,311 @@ pub struct UpdatePageResponse {
     pub success: bool,
     pub url: Option<String>,
     pub meta: Option<PageMetaResponse>,
-    pub error: Option<String>,
+    #[serde(flatten)]
+    pub error: ErrorFields,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum BlogStyle {
-    PplxStyle,
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PatchOperation {
+    /// 要查找的原文，按字节精确匹配
+    pub find: String,
+    pub replace: String,
+    /// 只替换第几处匹配（从 1 开始），不填时替换全部
+    pub occurrence: Option<usize>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum HtmlStyleType {
-    Default,
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PatchPageHtmlRequest {
+    pub page_id: String,
+    /// 按顺序依次应用，后一个操作作用于前一个操作的结果
+    pub operations: Vec<PatchOperation>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PatchPageHtmlResponse {
+    pub success: bool,
+    pub url: Option<String>,
+    /// 每个操作实际替换的次数，与 `operations` 顺序一致
+    pub replacements: Vec<usize>,
+    #[serde(flatten)]
+    pub error: ErrorFields,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AppendPosition {
+    /// 插入到 `</body>` 之前（默认）
+    BeforeEndOfBody,
+    /// 插入到 `selector` 指定元素的闭合标签之后
+    AfterElement,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct AppendSectionRequest {
+    pub page_id: String,
+    /// 与 `markdown` 二选一
+    pub html: Option<String>,
+    /// 渲染为 HTML 片段后插入，与 `html` 二选一
+    pub markdown: Option<String>,
+    pub position: Option<AppendPosition>,
+    /// `after_element` 时必填，id 选择器，如 `#updates`
+    pub selector: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct AppendSectionResponse {
+    pub success: bool,
+    pub url: Option<String>,
+    pub updated_at: Option<i64>,
+    #[serde(flatten)]
+    pub error: ErrorFields,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RenderMarkdownRequest {
+    pub markdown: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RenderMarkdownResponse {
+    /// `markdown_to_html` 的结果，即文章正文片段，不含页面模板
+    pub html: String,
+    /// 统计渲染结果的可见文本，中文按字、西文按词，规则同 `/pages/{slug}/stats`
+    pub word_count: u32,
+    pub estimated_reading_minutes: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PreviewMarkdownRequest {
+    pub markdown: String,
+    /// 只返回文章正文片段，不套用 `front/markdown.html` 模板，默认 false
+    pub body_only: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PreviewMarkdownResponse {
+    pub html: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct CheckSeoRequest {
+    pub page_id: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SeoSeverity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl From<crate::web::SeoSeverity> for SeoSeverity {
+    fn from(severity: crate::web::SeoSeverity) -> Self {
+        match severity {
+            crate::web::SeoSeverity::Error => Self::Error,
+            crate::web::SeoSeverity::Warning => Self::Warning,
+            crate::web::SeoSeverity::Info => Self::Info,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SeoIssue {
+    pub severity: SeoSeverity,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct CheckSeoResponse {
+    /// 0–100，满分按问题级别扣减：error 20、warning 10、info 3
+    pub score: u8,
+    pub issues: Vec<SeoIssue>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SuggestSeoRequest {
+    /// 草稿标题
+    pub title: String,
+    /// 草稿 HTML，与 `markdown` 二选一
+    pub html: Option<String>,
+    /// 草稿 Markdown，与 `html` 二选一
+    pub markdown: Option<String>,
+    /// 返回的关键词数量，默认 10
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SuggestedKeyword {
+    pub keyword: String,
+    /// 加权出现次数：标题按 3 倍计，全站已有关键词再乘 2
+    pub score: usize,
+    /// 已使用该关键词的页面数，0 表示全站尚未使用
+    pub site_pages: usize,
+}
+
+/// 与草稿主题相近的已有公开页面，可作为内链
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SimilarPage {
+    pub page_id: String,
+    pub url: String,
+    pub title: String,
+    /// 与建议关键词重合的关键词
+    pub shared_keywords: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SuggestSeoResponse {
+    pub success: bool,
+    /// 按得分降序
+    pub keywords: Vec<SuggestedKeyword>,
+    /// 由正文截取的描述候选，超过 160 字时截断到 150–160 字
+    pub description: String,
+    pub similar_pages: Vec<SimilarPage>,
+    #[serde(flatten)]
+    pub error: ErrorFields,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ValidateHtmlRequest {
+    pub html: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ValidatePageHtmlResponse {
+    pub valid: bool,
+    pub size_bytes: usize,
+    pub max_bytes: usize,
+    pub error: Option<HtmlValidationError>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ValidateHtmlResponse {
+    /// 结构校验是否通过，与推送时的 `validate_html` 一致
+    pub valid: bool,
+    pub errors: Vec<String>,
+    /// 安全与 SEO 提示，不影响推送
+    pub warnings: Vec<String>,
+    pub word_count: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetServerInfoRequest {
+    /// 预留参数，保持 schema 的 properties 非空
+    pub reserved: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct StoreHealthResponse {
+    /// `fs` / `s3` / `memory`
+    pub backend: String,
+    /// 本地数据目录或 `s3://{bucket}`
+    pub data_dir: Option<String>,
+    /// 仅文件系统后端统计
+    pub disk_usage_bytes: Option<u64>,
+    pub index_ok: bool,
+    /// 仅文件系统后端探测
+    pub readable: Option<bool>,
+    /// S3 后端不写入探测对象，始终为空
+    pub writable: Option<bool>,
+    pub errors: Vec<String>,
+}
+
+impl From<crate::store::StoreHealth> for StoreHealthResponse {
+    fn from(health: crate::store::StoreHealth) -> Self {
+        Self {
+            backend: health.backend,
+            data_dir: health.data_dir,
+            disk_usage_bytes: health.disk_usage_bytes,
+            index_ok: health.index_ok,
+            readable: health.readable,
+            writable: health.writable,
+            errors: health.errors,
+        }
+    }
+}
+
+/// 不包含 MCP token 等敏感配置
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetServerInfoResponse {
+    pub version: String,
+    /// 未设置 `SITE_URL` 时为空
+    pub site_url: Option<String>,
+    /// 读取索引失败时为空
+    pub page_count: Option<usize>,
+    /// 当前入口的 token 权限：`rw` 或 `ro`
+    pub scope: String,
+    /// 当前版本固定为 `disk`：模板在每次渲染时从 `front/` 读取
+    pub template_source: String,
+    pub missing_templates: Vec<String>,
+    /// 存储探测通过且模板齐全
+    pub healthy: bool,
+    pub store: StoreHealthResponse,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct GetBlogStyleRequest {
-    /// 博文风格类型
-    pub style: BlogStyle,
+    /// 博文风格名称，不区分大小写，可用值见 `list_styles`
+    pub style: String,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct GetHtmlStyleRequest {
-    /// HTML 风格类型
-    pub style: HtmlStyleType,
+    /// HTML 风格名称，不区分大小写，可用值见 `list_styles`
+    pub style: String,
+}
+
+/// `write_post` prompt 的参数，MCP prompt 参数均为字符串
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct WritePostArgs {
+    /// 文章主题
+    pub topic: String,
+    /// 期望篇幅，例如「800 字」或 short / medium / long，默认 medium
+    pub length: Option<String>,
+    /// 博文风格名称，可用值见 `list_styles`，不传时不附带写作风格指南
+    pub blog_style: Option<String>,
+    /// HTML 风格名称，可用值见 `list_styles`，默认 default
+    pub html_style: Option<String>,
+}
+
+/// `improve_post` prompt 的参数
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ImprovePostArgs {
+    /// 要修改的页面 page_uid
+    pub page_id: String,
+    /// 修改要求，不传时做通读润色
+    pub instructions: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ListStylesRequest {
+    /// 预留参数，保持 schema 的 properties 非空
+    pub reserved: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct StyleSummary {
+    pub name: String,
+    /// 取自风格文件开头的 `<!-- ... -->` 注释
+    pub description: Option<String>,
+}
+
+impl From<crate::style::StyleInfo> for StyleSummary {
+    fn from(info: crate::style::StyleInfo) -> Self {
+        Self {
+            name: info.name,
+            description: info.description,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ListStylesResponse {
+    pub success: bool,
+    /// `get_blog_style` 可用的风格
+    pub blog_styles: Vec<StyleSummary>,
+    /// `get_html_style` 可用的风格
+    pub html_styles: Vec<StyleSummary>,
+    #[serde(flatten)]
+    pub error: ErrorFields,
 }
@@ -1,4 +1,6 @@
 pub mod dto;
+pub mod prompts;
+pub mod resources;
 pub mod server;
 pub mod tools;
 
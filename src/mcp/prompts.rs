@@ -0,0 +1,116 @@
+use rmcp::{
+    ErrorData as McpError,
+    handler::server::{router::prompt::PromptRouter, wrapper::Parameters},
+    model::{PromptMessage, PromptMessageRole},
+    prompt, prompt_router,
+};
+
+use crate::{
+    mcp::{
+        dto::{ErrorCode, ImprovePostArgs, WritePostArgs},
+        resources::store_error,
+        server::BlogMcpServer,
+        tools::style_error,
+    },
+    style::{load_blog_style, load_html_style},
+};
+
+/// `write_post` 未指定 `html_style` 时使用的样板
+const DEFAULT_HTML_STYLE: &str = "default";
+
+/// `write_post` 未指定 `length` 时的篇幅
+const DEFAULT_POST_LENGTH: &str = "medium";
+
+#[prompt_router]
+impl BlogMcpServer {
+    pub(crate) fn build_prompt_router() -> PromptRouter<BlogMcpServer> {
+        Self::prompt_router()
+    }
+
+    /// 风格指南与样板和 `get_blog_style` / `get_html_style` 一样按名称从磁盘加载
+    #[prompt(
+        name = "write_post",
+        description = "Write a new blog post on a topic, bundled with the selected blog style guide and HTML template, then publish it with push_page"
+    )]
+    async fn write_post(
+        &self,
+        Parameters(args): Parameters<WritePostArgs>,
+    ) -> Result<Vec<PromptMessage>, McpError> {
+        let length = non_empty(args.length.as_deref()).unwrap_or(DEFAULT_POST_LENGTH);
+        let html_style = non_empty(args.html_style.as_deref()).unwrap_or(DEFAULT_HTML_STYLE);
+        let html_template = load_html_style(html_style).map_err(style_error)?;
+
+        let mut text = format!(
+            "请以「{}」为主题撰写一篇博客文章，篇幅：{}。\n\
+             按下面的 HTML 样板生成完整的 index.html（包含 <title> 与 description、keywords 等 SEO meta），\
+             完成后调用 push_page 发布，并把返回的页面地址告诉我。\n",
+            args.topic.trim(),
+            length
+        );
+        if let Some(blog_style) = non_empty(args.blog_style.as_deref()) {
+            let guide = load_blog_style(blog_style).map_err(style_error)?;
+            text.push_str(&format!(
+                "\n<blog_style name=\"{}\">\n{}\n</blog_style>\n",
+                blog_style, guide
+            ));
+        }
+        text.push_str(&format!(
+            "\n<html_style name=\"{}\">\n{}\n</html_style>\n",
+            html_style, html_template
+        ));
+        Ok(vec![PromptMessage::new_text(PromptMessageRole::User, text)])
+    }
+
+    /// 页面与工具一样按 page_uid 解析；有 Markdown 源文件时嵌入 Markdown，否则嵌入 HTML
+    #[prompt(
+        name = "improve_post",
+        description = "Revise an existing blog post: embeds the page's current markdown or html by page_uid, with instructions to save it back via update_markdown_page or update_page"
+    )]
+    async fn improve_post(
+        &self,
+        Parameters(args): Parameters<ImprovePostArgs>,
+    ) -> Result<Vec<PromptMessage>, McpError> {
+        let page_id = match self.store.resolve_page_id_by_uid(args.page_id.trim()).await {
+            Ok(Some(id)) => id,
+            Ok(None) => {
+                let data = serde_json::json!({ "code": ErrorCode::NotFound });
+                let message = format!("page not found: {}", args.page_id);
+                return Err(McpError::invalid_params(message, Some(data)));
+            }
+            Err(err) => return Err(store_error(err)),
+        };
+        let (meta, html) = self.store.load_page(&page_id).await.map_err(store_error)?;
+        let markdown = self
+            .store
+            .load_page_markdown(&page_id)
+            .await
+            .map_err(store_error)?;
+        let instructions = non_empty(args.instructions.as_deref())
+            .unwrap_or("通读全文，修正错别字与不通顺的句子，保持原有结构与观点");
+        let (format, save_tool, content) = match markdown.as_deref() {
+            Some(markdown) => ("markdown", "update_markdown_page", markdown),
+            None => ("html", "update_page", html.as_str()),
+        };
+
+        let text = format!(
+            "请按以下要求修改博客文章「{}」：{}\n\
+             修改完成后调用 {} 保存（page_id: {}，expected_updated_at: {}），\
+             若返回 conflict 说明页面已被他人修改，请先重新读取。\n\
+             \n<current_{} page_id=\"{}\">\n{}\n</current_{}>\n",
+            meta.seo.title,
+            instructions,
+            save_tool,
+            meta.page_uid,
+            meta.updated_at,
+            format,
+            meta.page_uid,
+            content,
+            format
+        );
+        Ok(vec![PromptMessage::new_text(PromptMessageRole::User, text)])
+    }
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|value| !value.is_empty())
+}
@@ -0,0 +1,172 @@
+use rmcp::{
+    ErrorData as McpError,
+    model::{
+        AnnotateAble, ListResourcesResult, RawResource, ReadResourceResult, Resource,
+        ResourceContents,
+    },
+};
+use serde::Serialize;
+
+use crate::{
+    config::resolve_site_url_from_env,
+    mcp::{dto::ErrorCode, server::BlogMcpServer, tools::build_page_full_url},
+    store::PageIndexEntry,
+};
+
+/// 全部页面的索引资源
+pub const INDEX_RESOURCE_URI: &str = "blog://index";
+
+/// 单个页面资源：`blog://pages/{page_uid}`
+pub const PAGE_RESOURCE_PREFIX: &str = "blog://pages/";
+
+/// `resources/list` 每页返回的资源数，cursor 为下一页的起始偏移
+const RESOURCES_PER_PAGE: usize = 50;
+
+/// `blog://index` 的单个条目
+#[derive(Debug, Serialize)]
+struct IndexResourceItem {
+    uri: String,
+    page_uid: String,
+    title: String,
+    url: String,
+}
+
+impl BlogMcpServer {
+    /// 第一项为 `blog://index`，其后按索引顺序列出页面
+    pub(crate) async fn list_page_resources(
+        &self,
+        cursor: Option<String>,
+    ) -> Result<ListResourcesResult, McpError> {
+        let offset = match cursor.as_deref() {
+            Some(cursor) => cursor.parse::<usize>().map_err(|_| {
+                McpError::invalid_params(format!("invalid cursor: {}", cursor), None)
+            })?,
+            None => 0,
+        };
+        let entries = self.store.list_page_entries().await.map_err(store_error)?;
+
+        let mut resources = vec![index_resource(entries.len())];
+        resources.extend(entries.iter().map(page_resource));
+        let total = resources.len();
+        let page: Vec<Resource> = resources
+            .into_iter()
+            .skip(offset)
+            .take(RESOURCES_PER_PAGE)
+            .collect();
+        let next_offset = offset.saturating_add(RESOURCES_PER_PAGE);
+
+        let mut result = ListResourcesResult::with_all_items(page);
+        result.next_cursor = (next_offset < total).then(|| next_offset.to_string());
+        Ok(result)
+    }
+
+    /// 页面资源与工具一样按 page_uid 解析；有 Markdown 源文件时返回 Markdown，否则返回 HTML
+    pub(crate) async fn read_page_resource(
+        &self,
+        uri: &str,
+    ) -> Result<ReadResourceResult, McpError> {
+        if uri == INDEX_RESOURCE_URI {
+            return self.read_index_resource().await;
+        }
+        let Some(page_uid) = uri.strip_prefix(PAGE_RESOURCE_PREFIX) else {
+            return Err(resource_not_found(uri));
+        };
+        let page_id = match self.store.resolve_page_id_by_uid(page_uid).await {
+            Ok(Some(id)) => id,
+            Ok(None) => return Err(resource_not_found(uri)),
+            Err(err) => return Err(store_error(err)),
+        };
+        let (_, html) = self.store.load_page(&page_id).await.map_err(store_error)?;
+        let markdown = self
+            .store
+            .load_page_markdown(&page_id)
+            .await
+            .map_err(store_error)?;
+        let contents = match markdown {
+            Some(markdown) => text_contents(uri, "text/markdown", markdown),
+            None => text_contents(uri, "text/html", html),
+        };
+        Ok(ReadResourceResult {
+            contents: vec![contents],
+        })
+    }
+
+    async fn read_index_resource(&self) -> Result<ReadResourceResult, McpError> {
+        let entries = self.store.list_page_entries().await.map_err(store_error)?;
+        let base_url = resolve_site_url_from_env();
+        let items: Vec<IndexResourceItem> = entries
+            .iter()
+            .map(|entry| {
+                let page_uid = entry_page_uid(entry);
+                IndexResourceItem {
+                    uri: format!("{}{}", PAGE_RESOURCE_PREFIX, page_uid),
+                    page_uid: page_uid.to_string(),
+                    title: entry_title(entry).to_string(),
+                    url: build_page_full_url(&base_url, page_uid, &entry.seo.seo_title),
+                }
+            })
+            .collect();
+        let json = serde_json::to_string_pretty(&items)
+            .map_err(|err| McpError::internal_error(err.to_string(), None))?;
+        Ok(ReadResourceResult {
+            contents: vec![text_contents(INDEX_RESOURCE_URI, "application/json", json)],
+        })
+    }
+}
+
+fn index_resource(page_count: usize) -> Resource {
+    let mut raw = RawResource::new(INDEX_RESOURCE_URI, "Blog index");
+    raw.description = Some(format!(
+        "All {} pages: uri, page_uid, title and url",
+        page_count
+    ));
+    raw.mime_type = Some("application/json".to_string());
+    raw.no_annotation()
+}
+
+fn page_resource(entry: &PageIndexEntry) -> Resource {
+    let uri = format!("{}{}", PAGE_RESOURCE_PREFIX, entry_page_uid(entry));
+    let mut raw = RawResource::new(uri, entry_title(entry));
+    raw.description = Some(entry.seo.description.clone()).filter(|value| !value.is_empty());
+    raw.no_annotation()
+}
+
+/// 旧索引条目可能没有 page_uid，此时 page_id 即为 page_uid
+fn entry_page_uid(entry: &PageIndexEntry) -> &str {
+    if entry.page_uid.is_empty() {
+        &entry.page_id
+    } else {
+        &entry.page_uid
+    }
+}
+
+fn entry_title(entry: &PageIndexEntry) -> &str {
+    if entry.seo.title.is_empty() {
+        &entry.seo.seo_title
+    } else {
+        &entry.seo.title
+    }
+}
+
+fn text_contents(uri: &str, mime_type: &str, text: String) -> ResourceContents {
+    let mut contents = ResourceContents::text(text, uri);
+    if let ResourceContents::TextResourceContents {
+        mime_type: slot, ..
+    } = &mut contents
+    {
+        *slot = Some(mime_type.to_string());
+    }
+    contents
+}
+
+/// `data.code` 与响应 DTO 的 `error_info.code` 取值一致
+fn resource_not_found(uri: &str) -> McpError {
+    let data = serde_json::json!({ "code": ErrorCode::NotFound });
+    McpError::resource_not_found(format!("resource not found: {}", uri), Some(data))
+}
+
+/// 读取存储失败，prompt 与资源共用
+pub(crate) fn store_error(err: anyhow::Error) -> McpError {
+    let data = serde_json::json!({ "code": ErrorCode::Internal });
+    McpError::internal_error(format!("{err:#}"), Some(data))
+}
@@ -1,40 +1,178 @@
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use rmcp::{
-    ServerHandler,
-    handler::server::router::tool::ToolRouter,
-    model::{Implementation, ProtocolVersion, ServerCapabilities, ServerInfo},
-    tool_handler,
+    ErrorData as McpError, RoleServer, ServerHandler,
+    handler::server::{
+        router::{prompt::PromptRouter, tool::ToolRouter},
+        tool::ToolCallContext,
+    },
+    model::{
+        CallToolRequestParams, CallToolResult, GetPromptRequestParams, GetPromptResult,
+        Implementation, ListPromptsResult, ListResourcesResult, ListToolsResult,
+        PaginatedRequestParams, ProtocolVersion, ReadResourceRequestParams, ReadResourceResult,
+        ServerCapabilities, ServerInfo,
+    },
+    prompt_handler,
+    service::RequestContext,
+};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::{
+    config::{McpScope, resolve_request_timeout_from_env},
+    mcp::dto::ToolError,
+    ping::SearchEngine,
+    store::PageStoreTrait,
 };
 
-use crate::store::PageStore;
+/// 只读 token 不能调用的工具：会改写页面、资源、索引或对外发送通知
+const WRITE_TOOLS: &[&str] = &[
+    "push_page",
+    "push_markdown",
+    "push_pages",
+    "update_page",
+    "patch_page_html",
+    "append_section",
+    "update_markdown_page",
+    "delete_page",
+    "purge_page",
+    "set_page_visibility",
+    "set_page_extra",
+    "set_tags",
+    "set_page_order",
+    "reorder_pages",
+    "bulk_update_seo",
+    "rerender_markdown_pages",
+    "upload_asset",
+    "delete_asset",
+    "restore_revision",
+    "prune_page_versions",
+    "notify_search_engines",
+    "ping_search_engines",
+];
+
+/// 各 MCP 客户端支持的连接方式，附在 `instructions` 末尾
+const MCP_CLIENT_AUTH_NOTES: &str = " Connecting: POST /mcp with header `Authorization: Bearer <token>` is the default. Clients that can set headers use it: Claude Code (`claude mcp add --transport http <name> <url>/mcp --header \"Authorization: Bearer <token>\"`), Cursor and VS Code (`headers` in mcp.json), Claude Desktop through `npx mcp-remote <url>/mcp --header \"Authorization: Bearer <token>\"`. Clients that only accept a bare URL (Claude.ai and Claude Desktop custom connectors, other URL-only clients) need the legacy /{token}/mcp endpoint, which the server only mounts when MCP_LEGACY_PATH=1.";
 
 #[derive(Clone)]
 pub struct BlogMcpServer {
-    pub(crate) store: Arc<PageStore>,
+    pub(crate) store: Arc<dyn PageStoreTrait>,
     pub(crate) tool_router: ToolRouter<BlogMcpServer>,
+    pub(crate) prompt_router: PromptRouter<BlogMcpServer>,
+    /// 各搜索引擎上次 sitemap ping 的时间，所有会话共享
+    pub(crate) ping_history: Arc<Mutex<HashMap<SearchEngine, Instant>>>,
+    /// 挂载入口的 token 权限，每个入口一个实例
+    pub(crate) scope: McpScope,
+    /// 单次工具调用的时限，取自 `REQUEST_TIMEOUT_SECS`；`None` 时不限时
+    pub(crate) tool_timeout: Option<Duration>,
 }
 
 impl BlogMcpServer {
-    pub fn new(store: Arc<PageStore>) -> Self {
+    pub fn new(store: Arc<dyn PageStoreTrait>) -> Self {
+        Self::with_scope(store, McpScope::ReadWrite)
+    }
+
+    pub fn with_scope(store: Arc<dyn PageStoreTrait>, scope: McpScope) -> Self {
         Self {
             store,
             tool_router: Self::build_tool_router(),
+            prompt_router: Self::build_prompt_router(),
+            ping_history: Arc::new(Mutex::new(HashMap::new())),
+            scope,
+            tool_timeout: resolve_request_timeout_from_env(),
         }
     }
 }
 
-#[tool_handler(router = self.tool_router)]
+fn tool_error_result(error: ToolError) -> CallToolResult {
+    CallToolResult::structured_error(serde_json::json!({
+        "success": false,
+        "error": error.message,
+        "error_info": error,
+    }))
+}
+
+#[prompt_handler(router = self.prompt_router)]
 impl ServerHandler for BlogMcpServer {
+    /// 按入口权限拦截写入类工具，其余与 `tool_handler` 生成的分发一致；
+    /// 超过时限的调用返回 `timeout` 错误，已经交给存储线程的写入仍会完成
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("mcp tool {} (scope: {})", request.name, self.scope.as_str());
+        if self.scope == McpScope::ReadOnly && WRITE_TOOLS.iter().any(|tool| request.name == *tool)
+        {
+            let error = ToolError::permission_denied(format!(
+                "tool {} requires a read-write token",
+                request.name
+            ));
+            return Ok(tool_error_result(error));
+        }
+        let name = request.name.clone();
+        let context = ToolCallContext::new(self, request, context);
+        let Some(timeout) = self.tool_timeout else {
+            return self.tool_router.call(context).await;
+        };
+        match tokio::time::timeout(timeout, self.tool_router.call(context)).await {
+            Ok(result) => result,
+            Err(_) => {
+                warn!("mcp tool {name} timed out after {}s", timeout.as_secs());
+                Ok(tool_error_result(ToolError::timeout(format!(
+                    "tool {name} did not finish within {}s (REQUEST_TIMEOUT_SECS), \
+                     split the work into smaller calls",
+                    timeout.as_secs()
+                ))))
+            }
+        }
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        Ok(ListToolsResult::with_all_items(self.tool_router.list_all()))
+    }
+
     fn get_info(&self) -> ServerInfo {
+        let mut instructions = "This server provides tools: push_page, push_markdown, get_all_page, get_page_by_id, delete_page, update_page, update_markdown_page, get_blog_style, get_html_style. Resources: blog://index lists all pages, blog://pages/{page_uid} returns a page's markdown source or html. Prompts: write_post, improve_post."
+            .to_string();
+        instructions.push_str(MCP_CLIENT_AUTH_NOTES);
+        if self.scope == McpScope::ReadOnly {
+            instructions.push_str(" This endpoint is read-only: write tools are denied.");
+        }
         ServerInfo {
             protocol_version: ProtocolVersion::V_2024_11_05,
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_prompts()
+                .enable_resources()
+                .build(),
             server_info: Implementation::from_build_env(),
-            instructions: Some(
-                "This server provides tools: push_page, push_markdown, get_all_page, get_page_by_id, delete_page, update_page, update_markdown_page, get_blog_style, get_html_style."
-                    .to_string(),
-            ),
+            instructions: Some(instructions),
         }
     }
+
+    async fn list_resources(
+        &self,
+        request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        let cursor = request.and_then(|request| request.cursor);
+        self.list_page_resources(cursor).await
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        self.read_page_resource(&request.uri).await
+    }
 }
@@ -1,82 +1,154 @@
 use rmcp::{
     ErrorData as McpError,
-    handler::server::{router::tool::ToolRouter, wrapper::{Json, Parameters}},
+    handler::server::{
+        router::tool::ToolRouter,
+        wrapper::{Json, Parameters},
+    },
     model::{CallToolResult, Content},
     tool, tool_router,
 };
 
+use std::collections::BTreeSet;
+use tokio::sync::Mutex;
+use tracing::warn;
+
 use crate::{
-    config::resolve_site_url_from_env,
+    config::{resolve_idempotency_ttl_from_env, resolve_site_url_from_env},
+    diff::unified_diff,
     mcp::{
         dto::{
-            BlogStyle, DeletePageResponse, GetAllPageRequest, GetAllPageResponse,
-            GetBlogStyleRequest, GetHtmlStyleRequest, GetPageByIdRequest, GetPageByIdResponse,
-            HtmlStyleType, PageIdRequest, PageWithHtml, PageWithMeta, PushMarkdownRequest,
-            PushPageRequest, PushPageResponse, UpdateMarkdownPageRequest, UpdatePageRequest,
-            UpdatePageResponse,
+            AppendPosition, AppendSectionRequest, AppendSectionResponse, AssetInfo,
+            BulkUpdateSeoRequest, BulkUpdateSeoResponse, CheckSeoRequest, CheckSeoResponse,
+            DeleteAssetRequest, DeleteAssetResponse, DeletePageResponse, DiffRevisionsRequest,
+            DiffRevisionsResponse, EnginePingResult, ErrorCode, ErrorFields, GetAllPageRequest,
+            GetAllPageResponse, GetBlogStyleRequest, GetHtmlStyleRequest,
+            GetMostPopularPagesRequest, GetPageByIdRequest, GetPageByIdResponse,
+            GetPageByUrlRequest, GetPageExtraRequest, GetPageMetaResponse, GetPageUrlRequest,
+            GetPageUrlResponse, GetRecentUpdatesRequest, GetRelatedPagesRequest,
+            GetServerInfoRequest, GetServerInfoResponse, GetSitemapRequest, GetSitemapResponse,
+            HtmlValidationError, ListAssetsRequest, ListAssetsResponse, ListDeletedPagesRequest,
+            ListDeletedPagesResponse, ListRevisionsResponse, ListStylesRequest, ListStylesResponse,
+            ListTagsRequest, ListTagsResponse, NotifySearchEnginesRequest,
+            NotifySearchEnginesResponse, PageExtraResponse, PageIdRequest, PageMetaResponse,
+            PageOrder, PageVisibilityResponse, PageWithHtml, PageWithMeta, PatchOperation,
+            PatchPageHtmlRequest, PatchPageHtmlResponse, PingResponse, PingSearchEnginesRequest,
+            PreviewMarkdownRequest, PreviewMarkdownResponse, PrunePageVersionsRequest,
+            PrunePageVersionsResponse, PurgePageRequest, PurgePageResponse, PushMarkdownRequest,
+            PushPageRequest, PushPageResponse, PushPagesItem, PushPagesRequest, PushPagesResponse,
+            RenderMarkdownRequest, RenderMarkdownResponse, ReorderPagesRequest,
+            ReorderPagesResponse, RerenderMarkdownPagesRequest, RerenderMarkdownPagesResponse,
+            RestoreRevisionRequest, RestoreRevisionResponse, SearchEngine, SearchField, SearchHit,
+            SearchPagesRequest, SearchPagesResponse, SeoIssue, SetPageExtraRequest,
+            SetPageOrderRequest, SetPageOrderResponse, SetPageVisibilityRequest, SetTagsRequest,
+            SetTagsResponse, SimilarPage, SitemapFormat, SuggestSeoRequest, SuggestSeoResponse,
+            SuggestedKeyword, TagCount, TagsMode, ToolError, UpdateMarkdownPageRequest,
+            UpdatePageRequest, UpdatePageResponse, UploadAssetRequest, UploadAssetResponse,
+            ValidateHtmlRequest, ValidateHtmlResponse, ValidatePageHtmlResponse,
         },
         server::BlogMcpServer,
     },
-    store::{PageMeta, SeoMeta, validate_html},
-    web::{build_page_url, render_markdown_page},
+    ping::{notify_search_engines, ping_sitemap, reserve_sitemap_ping, spawn_auto_ping},
+    store::{
+        HtmlError, NewPage, PageMeta, PageStoreTrait, SeoMeta, StoreHealth,
+        check_expected_updated_at, entries_sharing_keywords, estimate_reading_time,
+        html_visible_text, normalize_page_tags, normalize_tag, now_unix_seconds,
+        site_keyword_counts, validate_html,
+    },
+    style::{UnknownStyle, list_blog_styles, list_html_styles, load_blog_style, load_html_style},
+    upload::{
+        decode_base64_content, delete_upload, list_uploads, save_upload, validate_upload_filename,
+    },
+    web::{
+        EXTRA_HEAD_HTML_KEY, EXTRA_STATUS_KEY, PageUrlError, RerenderOutcome, STATUS_UNPUBLISHED,
+        SectionPosition, append_extra_head_html, build_page_url, check_page_seo,
+        draft_visible_text, extract_seo_meta, inject_seo_meta, insert_html_section, is_page_public,
+        list_sitemap_entries, load_public_pages, markdown_to_html, page_slug_from_url,
+        parse_page_id_from_slug, render_markdown_page, render_sitemap_xml, rerender_markdown_page,
+        sanitize_extra_head_html, scan_html_security, set_page_noindex, suggest_description,
+        suggest_keywords, validate_seo_meta,
+    },
+    webhook::{PageEvent, dispatch_page_event},
 };
 
+/// 渲染时从磁盘读取的模板，`get_server_info` 逐个检查是否存在
+const TEMPLATE_FILES: &[&str] = &[
+    "front/index.html",
+    "front/header.html",
+    "front/markdown.html",
+    "front/404.html",
+    "front/breadcrumb.html",
+    "front/related.html",
+    "front/share.html",
+    "front/admin/list.html",
+    "front/admin/edit.html",
+];
+
+/// `get_most_popular_pages` 未指定 `limit` 时返回的页面数量
+const DEFAULT_POPULAR_PAGES_LIMIT: usize = 10;
+
+/// `get_related_pages` 未指定 `limit` 时返回的页面数量
+const DEFAULT_RELATED_PAGES_LIMIT: usize = 5;
+
+/// `get_recent_updates` 未指定 `limit` 时返回的页面数量
+const DEFAULT_RECENT_UPDATES_LIMIT: usize = 10;
+
+/// `suggest_seo` 未指定 `limit` 时返回的关键词数量
+const DEFAULT_SUGGESTED_KEYWORDS_LIMIT: usize = 10;
+
+/// `suggest_seo` 返回的相似页面数量
+const SIMILAR_PAGES_LIMIT: usize = 5;
+
+/// `push_pages` 单次允许创建的页面数量上限
+const MAX_PUSH_PAGES_BATCH: usize = 20;
+
+/// 单个页面 HTML 的大小上限，`push_page` 与 `validate_page_html` 共用
+const MAX_PAGE_HTML_BYTES: usize = 5 * 1024 * 1024;
+
+/// Markdown 源文件的大小上限，`push_markdown` 与 `preview_markdown` 共用
+const MAX_MARKDOWN_BYTES: usize = 1024 * 1024;
+
+/// `HtmlValidationError::snippet` 在出错位置前后各截取的字符数
+const HTML_ERROR_SNIPPET_CHARS: usize = 80;
+
+/// `search_pages` 未指定 `limit` 时返回的结果数量
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+/// `diff_revisions` 返回的 diff 文本大小上限
+const MAX_REVISION_DIFF_BYTES: usize = 64 * 1024;
+
+/// 带幂等键的推送串行执行，同一键并发推送（MCP 与 REST API 之间也一样）时只会创建一个页面
+static IDEMPOTENCY_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// `get_page_by_id` 的 `fields` 可选值：`PageMetaResponse` 的字段，`seo` 内的字段可单独选取
+const PAGE_META_FIELDS: &[&str] = &[
+    "seo",
+    "seo_title",
+    "description",
+    "keywords",
+    "page_uid",
+    "created_at",
+    "updated_at",
+    "view_count",
+    "unique_view_count",
+    "tags",
+    "sort_order",
+    "published_at",
+];
+
 #[tool_router(router = tool_router)]
 impl BlogMcpServer {
     pub(crate) fn build_tool_router() -> ToolRouter<BlogMcpServer> {
         Self::tool_router()
     }
 
-    #[tool(description = "Create a new blog page and return its page_id (page_uid)")]
+    #[tool(
+        description = "Create a new blog page and return its page_id (page_uid). Optional structured_data (a JSON object, e.g. FAQPage) is emitted as an extra JSON-LD script"
+    )]
     async fn push_page(
         &self,
         Parameters(params): Parameters<PushPageRequest>,
     ) -> Result<Json<PushPageResponse>, String> {
-        let meta = PageMeta {
-            seo: SeoMeta {
-                title: params.seo_title.clone(),
-                seo_title: params.seo_title,
-                description: params.description,
-                keywords: params.keywords,
-                extra: Default::default(),
-            },
-            page_uid: String::new(),
-            created_at: 0,
-            updated_at: 0,
-            view_count: 0,
-            extra: Default::default(),
-        };
-
-        if let Err(err) = validate_html(&params.html) {
-            return Ok(Json(PushPageResponse {
-                success: false,
-                page_id: None,
-                url: None,
-                meta: None,
-                error: Some(err.to_string()),
-            }));
-        }
-
-        match self.store.create_page_auto_uid(&meta, &params.html) {
-            Ok(saved_meta) => Ok(Json(PushPageResponse {
-                url: Some(build_page_full_url(
-                    &resolve_site_url_from_env(),
-                    &saved_meta.page_uid,
-                    &saved_meta.seo.seo_title,
-                )),
-                success: true,
-                page_id: Some(saved_meta.page_uid.clone()),
-                meta: Some(saved_meta.into()),
-                error: None,
-            })),
-            Err(err) => Ok(Json(PushPageResponse {
-                success: false,
-                page_id: None,
-                url: None,
-                meta: None,
-                error: Some(err.to_string()),
-            })),
-        }
+        Ok(Json(push_html_page(self.store.as_ref(), params).await))
     }
 
     #[tool(description = "推送一篇 Markdown 格式的博客文章")]
@@ -84,6 +156,22 @@ impl BlogMcpServer {
         &self,
         Parameters(req): Parameters<PushMarkdownRequest>,
     ) -> Result<Json<PushPageResponse>, String> {
+        let idempotency_key = normalize_idempotency_key(req.idempotency_key.as_deref());
+        let _idempotency_guard = match &idempotency_key {
+            Some(key) => {
+                let guard = IDEMPOTENCY_LOCK.lock().await;
+                match find_idempotent_page(self.store.as_ref(), key).await {
+                    Ok(Some(meta)) => return Ok(Json(deduplicated_push_response(meta))),
+                    Ok(None) => Some(guard),
+                    Err(err) => return Ok(Json(failed_push_response(ToolError::from_store(&err)))),
+                }
+            }
+            None => None,
+        };
+
+        if let Err(error) = check_markdown_size(&req.markdown) {
+            return Ok(Json(failed_push_response(ToolError::too_large(error))));
+        }
         let html = match render_markdown_page(&req.markdown) {
             Ok(rendered) => rendered,
             Err(err) => {
@@ -92,7 +180,9 @@ impl BlogMcpServer {
                     page_id: None,
                     url: None,
                     meta: None,
-                    error: Some(err.to_string()),
+                    error: ToolError::from_store(&err).into(),
+                    html_error: None,
+                    deduplicated: false,
                 }));
             }
         };
@@ -103,7 +193,9 @@ impl BlogMcpServer {
                 page_id: None,
                 url: None,
                 meta: None,
-                error: Some(err.to_string()),
+                error: ToolError::from_store(&err).into(),
+                html_error: None,
+                deduplicated: false,
             }));
         }
 
@@ -114,51 +206,309 @@ impl BlogMcpServer {
                 description: req.description,
                 keywords: req.keywords,
                 extra: Default::default(),
+                structured_data: None,
             },
             page_uid: String::new(),
             created_at: 0,
             updated_at: 0,
             view_count: 0,
+            unique_view_count: 0,
+            tags: req.tags,
+            sort_order: None,
+            published_at: None,
             extra: Default::default(),
         };
 
         match self
             .store
             .create_page_auto_uid_with_markdown(&meta, &html, Some(&req.markdown))
+            .await
         {
-            Ok(saved_meta) => Ok(Json(PushPageResponse {
-                url: Some(build_page_full_url(
-                    &resolve_site_url_from_env(),
-                    &saved_meta.page_uid,
-                    &saved_meta.seo.seo_title,
-                )),
-                success: true,
-                page_id: Some(saved_meta.page_uid.clone()),
-                meta: Some(saved_meta.into()),
-                error: None,
-            })),
+            Ok(saved_meta) => {
+                if let Some(key) = &idempotency_key {
+                    record_idempotency_key(self.store.as_ref(), key, &saved_meta.page_uid).await;
+                }
+                Ok(Json(created_push_response(saved_meta)))
+            }
             Err(err) => Ok(Json(PushPageResponse {
                 success: false,
                 page_id: None,
                 url: None,
                 meta: None,
-                error: Some(err.to_string()),
+                error: ToolError::from_store(&err).into(),
+                html_error: None,
+                deduplicated: false,
             })),
         }
     }
 
+    #[tool(
+        description = "Render markdown to html without saving anything, to preview the result before push_markdown. Returns the html fragment, word count and estimated reading minutes"
+    )]
+    async fn render_markdown(
+        &self,
+        Parameters(params): Parameters<RenderMarkdownRequest>,
+    ) -> Result<Json<RenderMarkdownResponse>, String> {
+        let html = markdown_to_html(&params.markdown);
+        let (word_count, reading_minutes) = estimate_reading_time(&html_visible_text(&html));
+        Ok(Json(RenderMarkdownResponse {
+            html,
+            word_count: u32::try_from(word_count).unwrap_or(u32::MAX),
+            estimated_reading_minutes: u32::try_from(reading_minutes).unwrap_or(u32::MAX),
+        }))
+    }
+
+    #[tool(
+        description = "Preview markdown as it would be served by push_markdown, without creating a page. Returns the full rendered page html, or only the article body when body_only is true"
+    )]
+    async fn preview_markdown(
+        &self,
+        Parameters(params): Parameters<PreviewMarkdownRequest>,
+    ) -> Result<Json<PreviewMarkdownResponse>, String> {
+        check_markdown_size(&params.markdown)?;
+        let html = if params.body_only.unwrap_or(false) {
+            markdown_to_html(&params.markdown)
+        } else {
+            render_markdown_page(&params.markdown).map_err(|err| format!("{err:#}"))?
+        };
+        Ok(Json(PreviewMarkdownResponse { html }))
+    }
+
+    #[tool(
+        description = "Score the SEO quality of a saved page (0-100) and list issues with severity error/warning/info: title and description length, keywords, canonical url, og:image, word count, <h1> structure and image alt text"
+    )]
+    async fn check_seo(
+        &self,
+        Parameters(params): Parameters<CheckSeoRequest>,
+    ) -> Result<Json<CheckSeoResponse>, String> {
+        let resolved_id = match self.store.resolve_page_id_by_uid(&params.page_id).await {
+            Ok(Some(id)) => id,
+            Ok(None) => return Err("page not found".to_string()),
+            Err(err) => return Err(err.to_string()),
+        };
+        let (meta, html) = self
+            .store
+            .load_page(&resolved_id)
+            .await
+            .map_err(|err| err.to_string())?;
+        let (score, issues) = check_page_seo(&meta, &html);
+        Ok(Json(CheckSeoResponse {
+            score,
+            issues: issues
+                .into_iter()
+                .map(|(severity, message)| SeoIssue {
+                    severity: severity.into(),
+                    message,
+                })
+                .collect(),
+        }))
+    }
+
+    #[tool(
+        description = "Suggest SEO keywords and a 150-160 character description for a draft (title plus html or markdown) without saving. Keywords are ranked by term frequency, preferring keywords already used on the site; also lists existing public pages on similar topics as internal link candidates"
+    )]
+    async fn suggest_seo(
+        &self,
+        Parameters(params): Parameters<SuggestSeoRequest>,
+    ) -> Result<Json<SuggestSeoResponse>, String> {
+        let failed = |error: ToolError| {
+            Ok(Json(SuggestSeoResponse {
+                success: false,
+                keywords: Vec::new(),
+                description: String::new(),
+                similar_pages: Vec::new(),
+                error: error.into(),
+            }))
+        };
+        let text = match (params.html.as_deref(), params.markdown.as_deref()) {
+            (Some(html), None) => draft_visible_text(html),
+            (None, Some(markdown)) => html_visible_text(&markdown_to_html(markdown)),
+            _ => {
+                return failed(ToolError::validation(
+                    "provide exactly one of html or markdown",
+                ));
+            }
+        };
+        let entries = match self.store.list_page_entries().await {
+            Ok(entries) => entries,
+            Err(err) => return failed(ToolError::from_store(&err)),
+        };
+
+        let limit = params.limit.unwrap_or(DEFAULT_SUGGESTED_KEYWORDS_LIMIT);
+        let site_keywords = site_keyword_counts(&entries);
+        let suggestions = suggest_keywords(&params.title, &text, &site_keywords, limit);
+        let keyword_set: BTreeSet<String> = suggestions
+            .iter()
+            .map(|suggestion| normalize_tag(&suggestion.keyword))
+            .collect();
+        // 多取一倍候选，过滤掉未公开页面后尽量凑满
+        let candidates = entries_sharing_keywords(entries, &keyword_set, SIMILAR_PAGES_LIMIT * 2);
+        let candidate_entries = candidates.iter().map(|(entry, _)| entry.clone()).collect();
+        let public_pages = match load_public_pages(self.store.as_ref(), candidate_entries).await {
+            Ok(pages) => pages,
+            Err(err) => return failed(ToolError::from_store(&err)),
+        };
+
+        let base_url = resolve_site_url_from_env();
+        let similar_pages = public_pages
+            .into_iter()
+            .take(SIMILAR_PAGES_LIMIT)
+            .map(|(entry, meta)| {
+                let shared_keywords = candidates
+                    .iter()
+                    .find(|(candidate, _)| candidate.page_id == entry.page_id)
+                    .map(|(_, shared)| shared.clone())
+                    .unwrap_or_default();
+                let title = if meta.seo.title.is_empty() {
+                    meta.seo.seo_title.clone()
+                } else {
+                    meta.seo.title.clone()
+                };
+                SimilarPage {
+                    url: build_page_full_url(&base_url, &meta.page_uid, &meta.seo.seo_title),
+                    page_id: meta.page_uid,
+                    title,
+                    shared_keywords,
+                }
+            })
+            .collect();
+
+        Ok(Json(SuggestSeoResponse {
+            success: true,
+            keywords: suggestions
+                .into_iter()
+                .map(|suggestion| SuggestedKeyword {
+                    keyword: suggestion.keyword,
+                    score: suggestion.score,
+                    site_pages: suggestion.site_pages,
+                })
+                .collect(),
+            description: suggest_description(&text),
+            similar_pages,
+            error: Default::default(),
+        }))
+    }
+
+    #[tool(
+        name = "validate_html",
+        description = "Lint html without saving: structure errors (the same check push_page applies), plus security and SEO warnings from the page's own <title> and meta tags"
+    )]
+    async fn validate_html_tool(
+        &self,
+        Parameters(params): Parameters<ValidateHtmlRequest>,
+    ) -> Result<Json<ValidateHtmlResponse>, String> {
+        let errors = match validate_html(&params.html) {
+            Ok(()) => Vec::new(),
+            Err(err) => vec![err.to_string()],
+        };
+        let mut warnings = scan_html_security(&params.html);
+        warnings.extend(validate_seo_meta(&extract_seo_meta(&params.html)));
+        let (word_count, _) = estimate_reading_time(&html_visible_text(&params.html));
+        Ok(Json(ValidateHtmlResponse {
+            valid: errors.is_empty(),
+            errors,
+            warnings,
+            word_count: u32::try_from(word_count).unwrap_or(u32::MAX),
+        }))
+    }
+
+    #[tool(
+        description = "Dry-run the checks push_page applies (size limit and strict tag matching) without saving. On failure returns the message, byte offset, line/column and a snippet around the problem"
+    )]
+    async fn validate_page_html(
+        &self,
+        Parameters(params): Parameters<ValidateHtmlRequest>,
+    ) -> Result<Json<ValidatePageHtmlResponse>, String> {
+        let error = check_page_html(&params.html).err();
+        Ok(Json(ValidatePageHtmlResponse {
+            valid: error.is_none(),
+            size_bytes: params.html.len(),
+            max_bytes: MAX_PAGE_HTML_BYTES,
+            error,
+        }))
+    }
+
+    #[tool(
+        description = "Create multiple blog pages in one call (at most 20, each item has format html or markdown). Items are validated independently; a failed item does not abort the others. Returns per-item results in request order"
+    )]
+    async fn push_pages(
+        &self,
+        Parameters(params): Parameters<PushPagesRequest>,
+    ) -> Result<Json<PushPagesResponse>, String> {
+        if params.pages.is_empty() || params.pages.len() > MAX_PUSH_PAGES_BATCH {
+            return Ok(Json(PushPagesResponse {
+                success: false,
+                results: Vec::new(),
+                error: ToolError::validation(format!(
+                    "pages must contain 1 to {} items, got {}",
+                    MAX_PUSH_PAGES_BATCH,
+                    params.pages.len()
+                ))
+                .into(),
+            }));
+        }
+
+        // 校验失败的条目直接记录结果，其余交给存储层批量创建
+        let mut results: Vec<Option<PushPageResponse>> = Vec::new();
+        let mut new_pages = Vec::new();
+        for item in params.pages {
+            match new_page_from_push_item(item) {
+                Ok(page) => {
+                    new_pages.push(page);
+                    results.push(None);
+                }
+                Err(err) => results.push(Some(failed_push_response(err))),
+            }
+        }
+
+        let created = match self.store.create_pages_batch(&new_pages).await {
+            Ok(created) => created,
+            Err(err) => {
+                let results = results
+                    .into_iter()
+                    .map(|result| {
+                        result.unwrap_or_else(|| failed_push_response(ToolError::from_store(&err)))
+                    })
+                    .collect();
+                return Ok(Json(PushPagesResponse {
+                    success: false,
+                    results,
+                    error: ToolError::from_store(&err).into(),
+                }));
+            }
+        };
+
+        let mut created = created.into_iter();
+        let results: Vec<PushPageResponse> = results
+            .into_iter()
+            .map(|result| {
+                result.unwrap_or_else(|| match created.next() {
+                    Some(Ok(saved_meta)) => created_push_response(saved_meta),
+                    Some(Err(err)) => failed_push_response(ToolError::from_store(&err)),
+                    None => failed_push_response(ToolError::internal("page not created")),
+                })
+            })
+            .collect();
+
+        Ok(Json(PushPagesResponse {
+            success: results.iter().all(|result| result.success),
+            results,
+            error: Default::default(),
+        }))
+    }
+
     #[tool(description = "List all blog page metadata")]
     async fn get_all_page(
         &self,
         Parameters(_params): Parameters<GetAllPageRequest>,
     ) -> Result<Json<GetAllPageResponse>, String> {
-        let entries = match self.store.list_page_entries() {
+        let entries = match self.store.list_page_entries().await {
             Ok(entries) => entries,
             Err(err) => {
                 return Ok(Json(GetAllPageResponse {
                     success: false,
                     pages: Vec::new(),
-                    error: Some(err.to_string()),
+                    error: ToolError::from_store(&err).into(),
                 }));
             }
         };
@@ -166,7 +516,7 @@ impl BlogMcpServer {
         let base_url = resolve_site_url_from_env();
         let mut pages = Vec::new();
         for entry in entries {
-            let meta = self.store.get_page_meta(&entry.page_id).ok();
+            let meta = self.store.get_page_meta(&entry.page_id).await.ok();
             if let Some(meta) = meta {
                 let url = build_page_full_url(&base_url, &meta.page_uid, &meta.seo.seo_title);
                 pages.push(PageWithMeta {
@@ -180,345 +530,2597 @@ impl BlogMcpServer {
         Ok(Json(GetAllPageResponse {
             success: true,
             pages,
-            error: None,
+            error: Default::default(),
         }))
     }
 
-    #[tool(
-        description = "Get blog pages by page_id list (page_uid). Supports single page_id for backward compatibility"
-    )]
-    async fn get_page_by_id(
+    #[tool(description = "List the most viewed blog pages, sorted by view_count descending")]
+    async fn get_most_popular_pages(
         &self,
-        Parameters(params): Parameters<GetPageByIdRequest>,
-    ) -> Result<Json<GetPageByIdResponse>, String> {
-        let mut ids = Vec::new();
-        if let Some(single_id) = params.page_id {
-            if !single_id.trim().is_empty() {
-                ids.push(single_id);
+        Parameters(params): Parameters<GetMostPopularPagesRequest>,
+    ) -> Result<Json<GetAllPageResponse>, String> {
+        let limit = params.limit.unwrap_or(DEFAULT_POPULAR_PAGES_LIMIT);
+        let entries = match self.store.get_most_popular_pages(limit).await {
+            Ok(entries) => entries,
+            Err(err) => {
+                return Ok(Json(GetAllPageResponse {
+                    success: false,
+                    pages: Vec::new(),
+                    error: ToolError::from_store(&err).into(),
+                }));
             }
-        }
-        if let Some(more_ids) = params.ids {
-            ids.extend(more_ids.into_iter().filter(|id| !id.trim().is_empty()));
-        }
-
-        if ids.is_empty() {
-            return Ok(Json(GetPageByIdResponse {
-                success: false,
-                pages: Vec::new(),
-                error: Some("ids is empty".to_string()),
-            }));
-        }
+        };
 
         let base_url = resolve_site_url_from_env();
         let mut pages = Vec::new();
-        let mut errors = Vec::new();
-
-        for page_id in ids {
-            let resolved_id = match self.store.resolve_page_id_by_uid(&page_id) {
-                Ok(Some(id)) => id,
-                Ok(None) => {
-                    errors.push(format!("page not found: {page_id}"));
-                    continue;
-                }
-                Err(err) => {
-                    errors.push(format!("resolve page failed: {page_id}: {err}"));
-                    continue;
-                }
-            };
-
-            match self.store.load_page(&resolved_id) {
-                Ok((meta, html)) => pages.push(PageWithHtml {
+        for entry in entries {
+            let meta = self.store.get_page_meta(&entry.page_id).await.ok();
+            if let Some(meta) = meta {
+                let url = build_page_full_url(&base_url, &meta.page_uid, &meta.seo.seo_title);
+                pages.push(PageWithMeta {
                     page_id: meta.page_uid.clone(),
-                    url: build_page_full_url(&base_url, &meta.page_uid, &meta.seo.seo_title),
+                    url,
                     meta: meta.into(),
-                    html,
-                }),
-                Err(err) => errors.push(format!("load page failed: {page_id}: {err}")),
+                });
             }
         }
 
-        Ok(Json(GetPageByIdResponse {
-            success: errors.is_empty(),
+        Ok(Json(GetAllPageResponse {
+            success: true,
             pages,
-            error: if errors.is_empty() {
-                None
-            } else {
-                Some(errors.join("; "))
-            },
+            error: Default::default(),
         }))
     }
 
-    #[tool(description = "Delete blog page by page_id (page_uid)")]
-    async fn delete_page(
+    #[tool(
+        description = "List pages related to the given page, ranked by keyword overlap (Jaccard similarity)"
+    )]
+    async fn get_related_pages(
         &self,
-        Parameters(params): Parameters<PageIdRequest>,
-    ) -> Result<Json<DeletePageResponse>, String> {
-        let resolved_id = match self.store.resolve_page_id_by_uid(&params.page_id) {
-            Ok(Some(id)) => id,
-            Ok(None) => {
-                return Ok(Json(DeletePageResponse {
-                    success: false,
-                    error: Some("page not found".to_string()),
-                }));
-            }
+        Parameters(params): Parameters<GetRelatedPagesRequest>,
+    ) -> Result<Json<GetAllPageResponse>, String> {
+        let limit = params.limit.unwrap_or(DEFAULT_RELATED_PAGES_LIMIT);
+        let entries = match self.store.get_related_pages(&params.page_id, limit).await {
+            Ok(entries) => entries,
             Err(err) => {
-                return Ok(Json(DeletePageResponse {
+                return Ok(Json(GetAllPageResponse {
                     success: false,
-                    error: Some(err.to_string()),
+                    pages: Vec::new(),
+                    error: ToolError::from_store(&err).into(),
                 }));
             }
         };
 
-        match self.store.delete_page(&resolved_id) {
-            Ok(_) => Ok(Json(DeletePageResponse {
-                success: true,
-                error: None,
-            })),
-            Err(err) => Ok(Json(DeletePageResponse {
-                success: false,
-                error: Some(err.to_string()),
-            })),
+        let base_url = resolve_site_url_from_env();
+        let mut pages = Vec::new();
+        for entry in entries {
+            let meta = self.store.get_page_meta(&entry.page_id).await.ok();
+            if let Some(meta) = meta {
+                let url = build_page_full_url(&base_url, &meta.page_uid, &meta.seo.seo_title);
+                pages.push(PageWithMeta {
+                    page_id: meta.page_uid.clone(),
+                    url,
+                    meta: meta.into(),
+                });
+            }
         }
+
+        Ok(Json(GetAllPageResponse {
+            success: true,
+            pages,
+            error: Default::default(),
+        }))
     }
 
-    #[tool(description = "Update blog page by page_id (page_uid)")]
-    async fn update_page(
+    #[tool(
+        description = "List the most recently updated blog pages, sorted by updated_at descending"
+    )]
+    async fn get_recent_updates(
         &self,
-        Parameters(params): Parameters<UpdatePageRequest>,
-    ) -> Result<Json<UpdatePageResponse>, String> {
-        let resolved_id = match self.store.resolve_page_id_by_uid(&params.page_id) {
-            Ok(Some(id)) => id,
-            Ok(None) => {
-                return Ok(Json(UpdatePageResponse {
-                    success: false,
-                    url: None,
-                    meta: None,
-                    error: Some("page not found".to_string()),
-                }));
-            }
-            Err(err) => {
-                return Ok(Json(UpdatePageResponse {
-                    success: false,
-                    url: None,
-                    meta: None,
-                    error: Some(err.to_string()),
-                }));
-            }
-        };
-
-        let (mut meta, mut html) = match self.store.load_page(&resolved_id) {
-            Ok(data) => data,
+        Parameters(params): Parameters<GetRecentUpdatesRequest>,
+    ) -> Result<Json<GetAllPageResponse>, String> {
+        let limit = params.limit.unwrap_or(DEFAULT_RECENT_UPDATES_LIMIT);
+        let entries = match self.store.list_recent_updates(limit).await {
+            Ok(entries) => entries,
             Err(err) => {
-                return Ok(Json(UpdatePageResponse {
+                return Ok(Json(GetAllPageResponse {
                     success: false,
-                    url: None,
-                    meta: None,
-                    error: Some(err.to_string()),
+                    pages: Vec::new(),
+                    error: ToolError::from_store(&err).into(),
                 }));
             }
         };
 
-        if let Some(seo_title) = params.seo_title {
-            meta.seo.seo_title = seo_title;
-        }
-        if let Some(description) = params.description {
-            meta.seo.description = description;
+        let base_url = resolve_site_url_from_env();
+        let mut pages = Vec::new();
+        for entry in entries {
+            let meta = self.store.get_page_meta(&entry.page_id).await.ok();
+            if let Some(meta) = meta {
+                let url = build_page_full_url(&base_url, &meta.page_uid, &meta.seo.seo_title);
+                pages.push(PageWithMeta {
+                    page_id: meta.page_uid.clone(),
+                    url,
+                    meta: meta.into(),
+                });
+            }
         }
-        if let Some(keywords) = params.keywords {
-            meta.seo.keywords = Some(keywords);
+
+        Ok(Json(GetAllPageResponse {
+            success: true,
+            pages,
+            error: Default::default(),
+        }))
+    }
+
+    #[tool(
+        description = "Search blog pages by title, content or keywords. Markdown pages are searched by their markdown source; returns which fields matched"
+    )]
+    async fn search_pages(
+        &self,
+        Parameters(params): Parameters<SearchPagesRequest>,
+    ) -> Result<Json<SearchPagesResponse>, String> {
+        let terms: Vec<String> = params
+            .query
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect();
+        if terms.is_empty() {
+            return Ok(Json(SearchPagesResponse {
+                success: false,
+                hits: Vec::new(),
+                error: ToolError::validation("query is empty").into(),
+            }));
         }
-        if let Some(new_html) = params.html {
-            if let Err(err) = validate_html(&new_html) {
-                return Ok(Json(UpdatePageResponse {
+        let fields = params
+            .search_in
+            .filter(|fields| !fields.is_empty())
+            .unwrap_or_else(|| {
+                vec![
+                    SearchField::Title,
+                    SearchField::Content,
+                    SearchField::Keywords,
+                ]
+            });
+        let entries = match self.store.list_page_entries().await {
+            Ok(entries) => entries,
+            Err(err) => {
+                return Ok(Json(SearchPagesResponse {
                     success: false,
-                    url: None,
-                    meta: None,
-                    error: Some(err.to_string()),
+                    hits: Vec::new(),
+                    error: ToolError::from_store(&err).into(),
                 }));
             }
-            html = new_html.to_string();
-        }
+        };
 
-        match self.store.update_page(&resolved_id, &meta, &html) {
-            Ok(_) => {
-                let (saved_meta, _) = match self.store.load_page(&resolved_id) {
-                    Ok(data) => data,
-                    Err(err) => {
-                        return Ok(Json(UpdatePageResponse {
-                            success: false,
-                            url: None,
-                            meta: None,
-                            error: Some(err.to_string()),
-                        }));
+        let base_url = resolve_site_url_from_env();
+        let mut hits = Vec::new();
+        for entry in entries {
+            let mut matched_terms = vec![false; terms.len()];
+            let mut matched_fields = Vec::new();
+            let mut score = 0;
+            for field in &fields {
+                // 标题与关键词直接取自索引，只有搜索正文时才读取页面文件
+                let text = match field {
+                    SearchField::Title => format!("{} {}", entry.seo.title, entry.seo.seo_title),
+                    SearchField::Keywords => entry
+                        .seo
+                        .keywords
+                        .as_ref()
+                        .map(|keywords| keywords.join(" "))
+                        .unwrap_or_default(),
+                    SearchField::Content => {
+                        load_search_content(self.store.as_ref(), &entry.page_id).await
                     }
                 };
-                Ok(Json(UpdatePageResponse {
-                    success: true,
-                    url: Some(build_page_full_url(
-                        &resolve_site_url_from_env(),
-                        &saved_meta.page_uid,
-                        &saved_meta.seo.seo_title,
-                    )),
-                    meta: Some(saved_meta.into()),
-                    error: None,
-                }))
+                let text = text.to_lowercase();
+                let mut field_hits = 0;
+                for (term, matched) in terms.iter().zip(matched_terms.iter_mut()) {
+                    let count = text.matches(term.as_str()).count();
+                    if count > 0 {
+                        *matched = true;
+                        field_hits += count;
+                    }
+                }
+                if field_hits > 0 {
+                    matched_fields.push(*field);
+                    score += field_hits;
+                }
             }
-            Err(err) => Ok(Json(UpdatePageResponse {
+            if !matched_terms.iter().all(|matched| *matched) {
+                continue;
+            }
+            let page_id = if entry.page_uid.is_empty() {
+                entry.page_id.clone()
+            } else {
+                entry.page_uid.clone()
+            };
+            let title = if entry.seo.title.is_empty() {
+                entry.seo.seo_title.clone()
+            } else {
+                entry.seo.title.clone()
+            };
+            hits.push(SearchHit {
+                url: build_page_full_url(&base_url, &page_id, &entry.seo.seo_title),
+                page_id,
+                title,
+                matched_fields,
+                score,
+            });
+        }
+        hits.sort_by(|left, right| {
+            right
+                .score
+                .cmp(&left.score)
+                .then_with(|| left.page_id.cmp(&right.page_id))
+        });
+        hits.truncate(params.limit.unwrap_or(DEFAULT_SEARCH_LIMIT));
+
+        Ok(Json(SearchPagesResponse {
+            success: true,
+            hits,
+            error: Default::default(),
+        }))
+    }
+
+    #[tool(
+        description = "Resolve the public url of a page by page_id (page_uid). Reads only the metadata, much cheaper than get_page_by_id"
+    )]
+    async fn get_page_url(
+        &self,
+        Parameters(params): Parameters<GetPageUrlRequest>,
+    ) -> Result<Json<GetPageUrlResponse>, String> {
+        let failed = |error: ToolError| {
+            Ok(Json(GetPageUrlResponse {
                 success: false,
                 url: None,
-                meta: None,
-                error: Some(err.to_string()),
+                error: error.into(),
+            }))
+        };
+        let resolved_id = match self.store.resolve_page_id_by_uid(&params.page_id).await {
+            Ok(Some(id)) => id,
+            Ok(None) => return failed(ToolError::not_found("page not found")),
+            Err(err) => return failed(ToolError::from_store(&err)),
+        };
+        match self.store.get_page_meta(&resolved_id).await {
+            Ok(meta) => Ok(Json(GetPageUrlResponse {
+                success: true,
+                url: Some(build_page_full_url(
+                    &resolve_site_url_from_env(),
+                    &meta.page_uid,
+                    &meta.seo.seo_title,
+                )),
+                error: Default::default(),
             })),
+            Err(err) => failed(ToolError::from_store(&err)),
         }
     }
 
-    #[tool(description = "Update markdown blog page by page_id (page_uid)")]
-    async fn update_markdown_page(
+    #[tool(
+        description = "Get the sitemap as served at /sitemap.xml (format: xml) or as a json list of url, lastmod, page_uid and title (format: json). Only public, indexable pages are included"
+    )]
+    async fn get_sitemap(
         &self,
-        Parameters(params): Parameters<UpdateMarkdownPageRequest>,
-    ) -> Result<Json<UpdatePageResponse>, String> {
-        let resolved_id = match self.store.resolve_page_id_by_uid(&params.page_id) {
-            Ok(Some(id)) => id,
-            Ok(None) => {
-                return Ok(Json(UpdatePageResponse {
-                    success: false,
-                    url: None,
-                    meta: None,
-                    error: Some("page not found".to_string()),
-                }));
+        Parameters(params): Parameters<GetSitemapRequest>,
+    ) -> Result<Json<GetSitemapResponse>, String> {
+        let base_url = resolve_site_url_from_env();
+        let store = self.store.as_ref();
+        let response = match params.format {
+            SitemapFormat::Xml => {
+                let xml = render_sitemap_xml(store, &base_url)
+                    .await
+                    .map_err(|err| format!("{err:#}"))?;
+                GetSitemapResponse {
+                    format: params.format,
+                    xml: Some(xml),
+                    entries: None,
+                }
             }
-            Err(err) => {
-                return Ok(Json(UpdatePageResponse {
-                    success: false,
-                    url: None,
-                    meta: None,
-                    error: Some(err.to_string()),
-                }));
+            SitemapFormat::Json => {
+                let entries = list_sitemap_entries(store, &base_url)
+                    .await
+                    .map_err(|err| format!("{err:#}"))?;
+                GetSitemapResponse {
+                    format: params.format,
+                    xml: None,
+                    entries: Some(entries.into_iter().map(Into::into).collect()),
+                }
             }
         };
+        Ok(Json(response))
+    }
 
-        let (mut meta, mut html) = match self.store.load_page(&resolved_id) {
-            Ok(data) => data,
-            Err(err) => {
-                return Ok(Json(UpdatePageResponse {
+    #[tool(
+        description = "Submit a public page url to IndexNow and ping the Google/Bing sitemap endpoints. Reports the status code of each endpoint; requires SITE_URL, IndexNow also requires INDEXNOW_KEY"
+    )]
+    async fn notify_search_engines(
+        &self,
+        Parameters(params): Parameters<NotifySearchEnginesRequest>,
+    ) -> Result<Json<NotifySearchEnginesResponse>, String> {
+        let failed = |error: ToolError| {
+            Ok(Json(NotifySearchEnginesResponse {
+                success: false,
+                url: None,
+                results: Vec::new(),
+                error: error.into(),
+            }))
+        };
+        let resolved_id = match self.store.resolve_page_id_by_uid(&params.page_id).await {
+            Ok(Some(id)) => id,
+            Ok(None) => return failed(ToolError::not_found("page not found")),
+            Err(err) => return failed(ToolError::from_store(&err)),
+        };
+        let meta = match self.store.get_page_meta(&resolved_id).await {
+            Ok(meta) => meta,
+            Err(err) => return failed(ToolError::from_store(&err)),
+        };
+        // 草稿与取消发布的页面不应提交给搜索引擎
+        if !is_page_public(&meta) {
+            return failed(ToolError::validation("page is not public"));
+        }
+        let url = build_page_full_url(
+            &resolve_site_url_from_env(),
+            &meta.page_uid,
+            &meta.seo.seo_title,
+        );
+        let results = notify_search_engines(std::slice::from_ref(&url)).await;
+        Ok(Json(NotifySearchEnginesResponse {
+            success: true,
+            url: Some(url),
+            results: results.into_iter().map(Into::into).collect(),
+            error: Default::default(),
+        }))
+    }
+
+    #[tool(
+        description = "Ping Google/Bing with the site sitemap url after publishing or updating pages. engines defaults to all; each engine is pinged at most once per 5 minutes. Requires SITE_URL"
+    )]
+    async fn ping_search_engines(
+        &self,
+        Parameters(params): Parameters<PingSearchEnginesRequest>,
+    ) -> Result<Json<PingResponse>, String> {
+        let mut engines = params
+            .engines
+            .unwrap_or_else(|| vec![SearchEngine::Google, SearchEngine::Bing]);
+        let mut seen = Vec::new();
+        engines.retain(|engine| {
+            let first = !seen.contains(engine);
+            seen.push(*engine);
+            first
+        });
+        let mut results = Vec::new();
+        for engine in engines {
+            if let Err(wait) = reserve_sitemap_ping(&self.ping_history, engine.into()).await {
+                results.push(EnginePingResult {
+                    engine,
                     success: false,
-                    url: None,
-                    meta: None,
-                    error: Some(err.to_string()),
-                }));
+                    status: None,
+                    error: Some(format!("rate limited, retry in {}s", wait.as_secs() + 1)),
+                });
+                continue;
             }
-        };
+            let result = ping_sitemap(engine.into()).await;
+            results.push(EnginePingResult {
+                engine,
+                success: result.error.is_none(),
+                status: result.status,
+                error: result.error,
+            });
+        }
+        Ok(Json(PingResponse { results }))
+    }
 
-        if let Some(seo_title) = params.seo_title {
-            meta.seo.seo_title = seo_title;
+    #[tool(
+        description = "Get blog page metadata (seo, tags, timestamps, view counts, url) by page_id list (page_uid) without the HTML body"
+    )]
+    async fn get_page_meta(
+        &self,
+        Parameters(params): Parameters<GetPageByIdRequest>,
+    ) -> Result<Json<GetPageMetaResponse>, String> {
+        let mut ids = Vec::new();
+        if let Some(single_id) = params.page_id
+            && !single_id.trim().is_empty()
+        {
+            ids.push(single_id);
         }
-        if let Some(description) = params.description {
-            meta.seo.description = description;
+        if let Some(more_ids) = params.ids {
+            ids.extend(more_ids.into_iter().filter(|id| !id.trim().is_empty()));
         }
-        if let Some(keywords) = params.keywords {
-            meta.seo.keywords = Some(keywords);
+
+        if ids.is_empty() {
+            return Ok(Json(GetPageMetaResponse {
+                success: false,
+                pages: Vec::new(),
+                not_found: Vec::new(),
+                error: ToolError::validation("ids is empty").into(),
+            }));
         }
-        let mut markdown_source: Option<String> = None;
-        if let Some(markdown) = params.markdown {
-            let rendered = match render_markdown_page(&markdown) {
-                Ok(rendered) => rendered,
+
+        let base_url = resolve_site_url_from_env();
+        let mut pages = Vec::new();
+        let mut not_found = Vec::new();
+        let mut errors = Vec::new();
+
+        for page_id in ids {
+            let resolved_id = match self.store.resolve_page_id_by_uid(&page_id).await {
+                Ok(Some(id)) => id,
+                Ok(None) => {
+                    not_found.push(page_id);
+                    continue;
+                }
                 Err(err) => {
-                    return Ok(Json(UpdatePageResponse {
-                        success: false,
-                        url: None,
-                        meta: None,
-                        error: Some(err.to_string()),
-                    }));
+                    let context = format!("resolve page failed: {page_id}");
+                    errors.push(ToolError::from_store(&err).with_context(context));
+                    continue;
                 }
             };
-            if let Err(err) = validate_html(&rendered) {
-                return Ok(Json(UpdatePageResponse {
-                    success: false,
-                    url: None,
-                    meta: None,
-                    error: Some(err.to_string()),
-                }));
-            }
-            html = rendered;
-            markdown_source = Some(markdown);
-        }
-
-        match self.store.update_page_with_markdown(
-            &resolved_id,
-            &meta,
-            &html,
-            markdown_source.as_deref(),
-        ) {
-            Ok(_) => {
-                let (saved_meta, _) = match self.store.load_page(&resolved_id) {
-                    Ok(data) => data,
-                    Err(err) => {
-                        return Ok(Json(UpdatePageResponse {
-                            success: false,
-                            url: None,
-                            meta: None,
-                            error: Some(err.to_string()),
-                        }));
-                    }
-                };
-                Ok(Json(UpdatePageResponse {
-                    success: true,
-                    url: Some(build_page_full_url(
-                        &resolve_site_url_from_env(),
-                        &saved_meta.page_uid,
-                        &saved_meta.seo.seo_title,
-                    )),
-                    meta: Some(saved_meta.into()),
-                    error: None,
-                }))
+
+            match self.store.get_page_meta(&resolved_id).await {
+                Ok(meta) => pages.push(PageWithMeta {
+                    page_id: meta.page_uid.clone(),
+                    url: build_page_full_url(&base_url, &meta.page_uid, &meta.seo.seo_title),
+                    meta: meta.into(),
+                }),
+                Err(err) => {
+                    let context = format!("load page meta failed: {page_id}");
+                    errors.push(ToolError::from_store(&err).with_context(context));
+                }
             }
-            Err(err) => Ok(Json(UpdatePageResponse {
+        }
+
+        Ok(Json(GetPageMetaResponse {
+            success: not_found.is_empty() && errors.is_empty(),
+            pages,
+            not_found,
+            error: ErrorFields::from_errors(errors),
+        }))
+    }
+
+    #[tool(
+        description = "Get blog pages with full HTML by page_id list (page_uid). Supports single page_id for backward compatibility. Set include_html false to skip reading html, fields to return only selected meta fields (e.g. seo_title, tags), and max_html_bytes to truncate long bodies (marked truncated: true)"
+    )]
+    async fn get_page_by_id(
+        &self,
+        Parameters(params): Parameters<GetPageByIdRequest>,
+    ) -> Result<Json<GetPageByIdResponse>, String> {
+        let mut ids = Vec::new();
+        if let Some(single_id) = params.page_id
+            && !single_id.trim().is_empty()
+        {
+            ids.push(single_id);
+        }
+        if let Some(more_ids) = params.ids {
+            ids.extend(more_ids.into_iter().filter(|id| !id.trim().is_empty()));
+        }
+
+        if ids.is_empty() {
+            return Ok(Json(GetPageByIdResponse {
                 success: false,
-                url: None,
-                meta: None,
-                error: Some(err.to_string()),
-            })),
+                pages: Vec::new(),
+                error: ToolError::validation("ids is empty").into(),
+            }));
+        }
+        if let Some(unknown) = params
+            .fields
+            .iter()
+            .flatten()
+            .find(|field| !PAGE_META_FIELDS.contains(&field.as_str()))
+        {
+            return Ok(Json(GetPageByIdResponse {
+                success: false,
+                pages: Vec::new(),
+                error: ToolError::validation(format!(
+                    "unknown field: {}, available: {}",
+                    unknown,
+                    PAGE_META_FIELDS.join(", ")
+                ))
+                .into(),
+            }));
+        }
+
+        let include_html = params.include_html.unwrap_or(true);
+        let base_url = resolve_site_url_from_env();
+        let mut pages = Vec::new();
+        let mut errors = Vec::new();
+
+        for page_id in ids {
+            let resolved_id = match self.store.resolve_page_id_by_uid(&page_id).await {
+                Ok(Some(id)) => id,
+                Ok(None) => {
+                    errors.push(ToolError::not_found(format!("page not found: {page_id}")));
+                    continue;
+                }
+                Err(err) => {
+                    let context = format!("resolve page failed: {page_id}");
+                    errors.push(ToolError::from_store(&err).with_context(context));
+                    continue;
+                }
+            };
+
+            // 不要 html 时走只读 meta.json 的路径
+            let loaded = if include_html {
+                self.store
+                    .load_page(&resolved_id)
+                    .await
+                    .map(|(meta, html)| (meta, Some(html)))
+            } else {
+                self.store
+                    .get_page_meta(&resolved_id)
+                    .await
+                    .map(|meta| (meta, None))
+            };
+            match loaded {
+                Ok((meta, html)) => {
+                    let url = build_page_full_url(&base_url, &meta.page_uid, &meta.seo.seo_title);
+                    let page_id = meta.page_uid.clone();
+                    let (meta, fields) = match &params.fields {
+                        Some(fields) => (None, Some(select_meta_fields(meta.into(), fields))),
+                        None => (Some(meta.into()), None),
+                    };
+                    let (html, truncated) = match (html, params.max_html_bytes) {
+                        (Some(html), Some(max_bytes)) => {
+                            let (html, truncated) = truncate_html(html, max_bytes);
+                            (Some(html), truncated)
+                        }
+                        (html, _) => (html, false),
+                    };
+                    pages.push(PageWithHtml {
+                        page_id,
+                        url,
+                        meta,
+                        fields,
+                        html,
+                        truncated,
+                    });
+                }
+                Err(err) => {
+                    let context = format!("load page failed: {page_id}");
+                    errors.push(ToolError::from_store(&err).with_context(context));
+                }
+            }
         }
+
+        Ok(Json(GetPageByIdResponse {
+            success: errors.is_empty(),
+            pages,
+            error: ErrorFields::from_errors(errors),
+        }))
     }
 
-    #[tool(name = "get_blog_style", description = "获取指定的博文写作风格指南")]
-    async fn get_blog_style(
+    #[tool(
+        description = "Get a blog page with full HTML by its url, absolute or relative (e.g. https://blog.example.com/pages/title+uid). Query strings, trailing slashes and percent-encoded slugs are accepted. Returns the same payload as get_page_by_id; urls on other hosts fail with \"foreign url\""
+    )]
+    async fn get_page_by_url(
         &self,
-        Parameters(params): Parameters<GetBlogStyleRequest>,
-    ) -> Result<CallToolResult, McpError> {
-        let style = &params.style;
-        let content = match style {
-            BlogStyle::PplxStyle => std::fs::read_to_string("public/prompt/PPLX.xml")
-                .map_err(|err| McpError::internal_error(format!("读取文件失败: {err}"), None))?,
+        Parameters(params): Parameters<GetPageByUrlRequest>,
+    ) -> Result<Json<GetPageByIdResponse>, String> {
+        let failed = |error: ToolError| {
+            Ok(Json(GetPageByIdResponse {
+                success: false,
+                pages: Vec::new(),
+                error: error.into(),
+            }))
         };
-        Ok(CallToolResult::success(vec![Content::text(content)]))
+        let base_url = resolve_site_url_from_env();
+        let slug = match page_slug_from_url(&params.url, &base_url) {
+            Ok(slug) => slug,
+            Err(err) => return failed(ToolError::validation(err.to_string())),
+        };
+        let Some(page_id) = parse_page_id_from_slug(&slug) else {
+            return failed(ToolError::validation(PageUrlError::NotPageUrl.to_string()));
+        };
+        let resolved_id = match self.store.resolve_page_id_by_uid(&page_id).await {
+            Ok(Some(id)) => id,
+            Ok(None) => return failed(ToolError::not_found(format!("page not found: {page_id}"))),
+            Err(err) => {
+                let context = format!("resolve page failed: {page_id}");
+                return failed(ToolError::from_store(&err).with_context(context));
+            }
+        };
+        match self.store.load_page(&resolved_id).await {
+            Ok((meta, html)) => Ok(Json(GetPageByIdResponse {
+                success: true,
+                pages: vec![PageWithHtml {
+                    page_id: meta.page_uid.clone(),
+                    url: build_page_full_url(&base_url, &meta.page_uid, &meta.seo.seo_title),
+                    meta: Some(meta.into()),
+                    fields: None,
+                    html: Some(html),
+                    truncated: false,
+                }],
+                error: Default::default(),
+            })),
+            Err(err) => {
+                let context = format!("load page failed: {page_id}");
+                failed(ToolError::from_store(&err).with_context(context))
+            }
+        }
     }
 
     #[tool(
-        name = "get_html_style",
-        description = "获取 HTML 风格参考，1. 用户未指定样式，则默认为default。2. 在制作HTML博文时需先获得参考样式"
+        description = "Set a custom annotation in page meta.extra by key. value_json is a JSON literal; null removes the key. Does not change updated_at, except for head_html, which must be an HTML string that validates against the page and bumps updated_at"
     )]
-    async fn get_html_style(
+    async fn set_page_extra(
         &self,
-        Parameters(params): Parameters<GetHtmlStyleRequest>,
-    ) -> Result<CallToolResult, McpError> {
-        let style = &params.style;
-        let template = match style {
-            HtmlStyleType::Default => std::fs::read_to_string("public/prompt/HTML.xml")
-                .map_err(|err| McpError::internal_error(format!("读取文件失败: {err}"), None))?,
-        };
-        let example_css = std::fs::read_to_string("front/example.css")
-            .map_err(|err| McpError::internal_error(format!("读取文件失败: {err}"), None))?;
-        let example_html = std::fs::read_to_string("front/index.html")
-            .map_err(|err| McpError::internal_error(format!("读取文件失败: {err}"), None))?;
-        let content = template
-            .replace("{{EXAMPLE_CSS}}", &example_css)
-            .replace("{{EXAMPLE_HTML}}", &example_html);
-        Ok(CallToolResult::success(vec![Content::text(content)]))
-    }
-}
+        Parameters(params): Parameters<SetPageExtraRequest>,
+    ) -> Result<Json<PageExtraResponse>, String> {
+        let value = match serde_json::from_str::<serde_json::Value>(&params.value_json) {
+            Ok(value) => value,
+            Err(err) => {
+                return Ok(Json(PageExtraResponse {
+                    success: false,
+                    key: params.key,
+                    value: None,
+                    error: ToolError::validation(format!("invalid value_json: {err}")).into(),
+                }));
+            }
+        };
+        let resolved_id = match self.store.resolve_page_id_by_uid(&params.page_id).await {
+            Ok(Some(id)) => id,
+            Ok(None) => {
+                return Ok(Json(PageExtraResponse {
+                    success: false,
+                    key: params.key,
+                    value: None,
+                    error: ToolError::not_found("page not found").into(),
+                }));
+            }
+            Err(err) => {
+                return Ok(Json(PageExtraResponse {
+                    success: false,
+                    key: params.key,
+                    value: None,
+                    error: ToolError::from_store(&err).into(),
+                }));
+            }
+        };
 
-pub(crate) fn build_page_full_url(base_url: &str, page_id: &str, seo_title: &str) -> String {
-    let path = build_page_url(page_id, seo_title);
-    format!("{}{}", base_url.trim_end_matches('/'), path)
+        let saved_value = Some(value.clone()).filter(|value| !value.is_null());
+        match self
+            .store
+            .set_page_extra(&resolved_id, &params.key, value)
+            .await
+        {
+            Ok(()) => Ok(Json(PageExtraResponse {
+                success: true,
+                key: params.key,
+                value: saved_value,
+                error: Default::default(),
+            })),
+            Err(err) => Ok(Json(PageExtraResponse {
+                success: false,
+                key: params.key,
+                value: None,
+                error: ToolError::from_store(&err).into(),
+            })),
+        }
+    }
+
+    #[tool(description = "Get a custom annotation from page meta.extra by key")]
+    async fn get_page_extra(
+        &self,
+        Parameters(params): Parameters<GetPageExtraRequest>,
+    ) -> Result<Json<PageExtraResponse>, String> {
+        let resolved_id = match self.store.resolve_page_id_by_uid(&params.page_id).await {
+            Ok(Some(id)) => id,
+            Ok(None) => {
+                return Ok(Json(PageExtraResponse {
+                    success: false,
+                    key: params.key,
+                    value: None,
+                    error: ToolError::not_found("page not found").into(),
+                }));
+            }
+            Err(err) => {
+                return Ok(Json(PageExtraResponse {
+                    success: false,
+                    key: params.key,
+                    value: None,
+                    error: ToolError::from_store(&err).into(),
+                }));
+            }
+        };
+
+        match self.store.get_page_extra(&resolved_id, &params.key).await {
+            Ok(value) => Ok(Json(PageExtraResponse {
+                success: true,
+                key: params.key,
+                value,
+                error: Default::default(),
+            })),
+            Err(err) => Ok(Json(PageExtraResponse {
+                success: false,
+                key: params.key,
+                value: None,
+                error: ToolError::from_store(&err).into(),
+            })),
+        }
+    }
+
+    #[tool(
+        description = "Set the tags of a page without touching its content. mode: replace (default), add or remove. Tags are trimmed, lowercased and deduped; at most 20 per page"
+    )]
+    async fn set_tags(
+        &self,
+        Parameters(params): Parameters<SetTagsRequest>,
+    ) -> Result<Json<SetTagsResponse>, String> {
+        let failed = |error: ToolError| {
+            Ok(Json(SetTagsResponse {
+                success: false,
+                tags: Vec::new(),
+                error: error.into(),
+            }))
+        };
+        let requested = match normalize_page_tags(&params.tags) {
+            Ok(tags) => tags,
+            Err(err) => return failed(ToolError::validation(err.to_string())),
+        };
+        let resolved_id = match self.store.resolve_page_id_by_uid(&params.page_id).await {
+            Ok(Some(id)) => id,
+            Ok(None) => return failed(ToolError::not_found("page not found")),
+            Err(err) => return failed(ToolError::from_store(&err)),
+        };
+
+        let tags = match params.mode.unwrap_or(TagsMode::Replace) {
+            TagsMode::Replace => requested,
+            mode => {
+                let current = match self.store.get_page_meta(&resolved_id).await {
+                    Ok(meta) => meta.tags.unwrap_or_default(),
+                    Err(err) => return failed(ToolError::from_store(&err)),
+                };
+                if matches!(mode, TagsMode::Add) {
+                    current.into_iter().chain(requested).collect()
+                } else {
+                    current
+                        .into_iter()
+                        .filter(|tag| !requested.contains(&normalize_tag(tag)))
+                        .collect()
+                }
+            }
+        };
+
+        match self.store.set_page_tags(&resolved_id, &tags).await {
+            Ok(meta) => {
+                let url = build_page_full_url(
+                    &resolve_site_url_from_env(),
+                    &meta.page_uid,
+                    &meta.seo.seo_title,
+                );
+                dispatch_page_event(PageEvent::Updated, &meta.page_uid, &url);
+                Ok(Json(SetTagsResponse {
+                    success: true,
+                    tags: meta.tags.unwrap_or_default(),
+                    error: Default::default(),
+                }))
+            }
+            Err(err) => failed(ToolError::from_store(&err)),
+        }
+    }
+
+    #[tool(description = "List every tag in the tag index with its page count")]
+    async fn list_tags(
+        &self,
+        Parameters(_params): Parameters<ListTagsRequest>,
+    ) -> Result<Json<ListTagsResponse>, String> {
+        match self.store.list_tags().await {
+            Ok(tags) => Ok(Json(ListTagsResponse {
+                success: true,
+                tags: tags
+                    .into_iter()
+                    .map(|(tag, page_count)| TagCount { tag, page_count })
+                    .collect(),
+                error: Default::default(),
+            })),
+            Err(err) => Ok(Json(ListTagsResponse {
+                success: false,
+                tags: Vec::new(),
+                error: ToolError::from_store(&err).into(),
+            })),
+        }
+    }
+
+    #[tool(
+        description = "Apply the same SEO change to several pages: append a title suffix, prefix the description and/or add keywords. Pages are processed in order; returns updated page_ids and per-page errors"
+    )]
+    async fn bulk_update_seo(
+        &self,
+        Parameters(params): Parameters<BulkUpdateSeoRequest>,
+    ) -> Result<Json<BulkUpdateSeoResponse>, String> {
+        let mut updated = Vec::new();
+        let mut errors = Vec::new();
+        let base_url = resolve_site_url_from_env();
+        for page_id in params.page_ids {
+            let resolved_id = match self.store.resolve_page_id_by_uid(&page_id).await {
+                Ok(Some(id)) => id,
+                Ok(None) => {
+                    errors.push((page_id, "page not found".to_string()));
+                    continue;
+                }
+                Err(err) => {
+                    errors.push((page_id, err.to_string()));
+                    continue;
+                }
+            };
+            let mut meta = match self.store.get_page_meta(&resolved_id).await {
+                Ok(meta) => meta,
+                Err(err) => {
+                    errors.push((page_id, err.to_string()));
+                    continue;
+                }
+            };
+            let changed = apply_seo_changes(
+                &mut meta.seo,
+                params.seo_title_suffix.as_deref(),
+                params.description_prefix.as_deref(),
+                params.add_keywords.as_deref(),
+            );
+            if changed {
+                if let Err(err) = self.store.update_page_meta(&resolved_id, &meta).await {
+                    errors.push((page_id, err.to_string()));
+                    continue;
+                }
+                let url = build_page_full_url(&base_url, &meta.page_uid, &meta.seo.seo_title);
+                dispatch_page_event(PageEvent::Updated, &meta.page_uid, &url);
+            }
+            updated.push(page_id);
+        }
+        Ok(Json(BulkUpdateSeoResponse { updated, errors }))
+    }
+
+    #[tool(
+        description = "Re-render markdown pages with the current front/markdown.html and front/header.html after a template change. Only the stored html is replaced; created_at, page_uid and updated_at are kept. Pages that fail to render or validate are left unchanged and reported in errors"
+    )]
+    async fn rerender_markdown_pages(
+        &self,
+        Parameters(params): Parameters<RerenderMarkdownPagesRequest>,
+    ) -> Result<Json<RerenderMarkdownPagesResponse>, String> {
+        let mut updated = Vec::new();
+        let mut unchanged = Vec::new();
+        let mut errors = Vec::new();
+        // 未指定页面时遍历全部页面，没有 Markdown 源文件的页面直接跳过
+        let explicit = params.page_ids.is_some();
+        let page_ids = match params.page_ids {
+            Some(page_ids) => page_ids,
+            None => match self.store.list_page_entries().await {
+                Ok(entries) => entries
+                    .into_iter()
+                    .map(|entry| {
+                        // 旧索引条目可能没有 page_uid，此时 page_id 即为 page_uid
+                        if entry.page_uid.is_empty() {
+                            entry.page_id
+                        } else {
+                            entry.page_uid
+                        }
+                    })
+                    .collect(),
+                Err(err) => return Err(err.to_string()),
+            },
+        };
+        for page_id in page_ids {
+            let resolved_id = match self.store.resolve_page_id_by_uid(&page_id).await {
+                Ok(Some(id)) => id,
+                Ok(None) => {
+                    errors.push((page_id, "page not found".to_string()));
+                    continue;
+                }
+                Err(err) => {
+                    errors.push((page_id, err.to_string()));
+                    continue;
+                }
+            };
+            match rerender_markdown_page(self.store.as_ref(), &resolved_id).await {
+                Ok(RerenderOutcome::Updated) => updated.push(page_id),
+                Ok(RerenderOutcome::Unchanged) => unchanged.push(page_id),
+                Ok(RerenderOutcome::NoMarkdown) if explicit => {
+                    errors.push((page_id, "page has no markdown source".to_string()));
+                }
+                Ok(RerenderOutcome::NoMarkdown) => {}
+                Err(err) => errors.push((page_id, format!("{err:#}"))),
+            }
+        }
+        Ok(Json(RerenderMarkdownPagesResponse {
+            updated,
+            unchanged,
+            errors,
+        }))
+    }
+
+    #[tool(
+        description = "Set the manual sort order of a page on the index. Lower values appear first; pages without sort_order follow, sorted by updated_at"
+    )]
+    async fn set_page_order(
+        &self,
+        Parameters(params): Parameters<SetPageOrderRequest>,
+    ) -> Result<Json<SetPageOrderResponse>, String> {
+        let failed = |error: ToolError| {
+            Ok(Json(SetPageOrderResponse {
+                success: false,
+                sort_order: None,
+                error: error.into(),
+            }))
+        };
+        let resolved_id = match self.store.resolve_page_id_by_uid(&params.page_id).await {
+            Ok(Some(id)) => id,
+            Ok(None) => return failed(ToolError::not_found("page not found")),
+            Err(err) => return failed(ToolError::from_store(&err)),
+        };
+        match self
+            .store
+            .set_page_sort_order(&resolved_id, Some(params.sort_order))
+            .await
+        {
+            Ok(meta) => Ok(Json(SetPageOrderResponse {
+                success: true,
+                sort_order: meta.sort_order,
+                error: Default::default(),
+            })),
+            Err(err) => failed(ToolError::from_store(&err)),
+        }
+    }
+
+    #[tool(
+        description = "Reorder pages on the index: assigns sort_order 1, 2, 3... following ordered_page_ids. Pages not listed keep their current sort_order"
+    )]
+    async fn reorder_pages(
+        &self,
+        Parameters(params): Parameters<ReorderPagesRequest>,
+    ) -> Result<Json<ReorderPagesResponse>, String> {
+        let failed = |orders: Vec<PageOrder>, error: ToolError| {
+            Ok(Json(ReorderPagesResponse {
+                success: false,
+                orders,
+                error: error.into(),
+            }))
+        };
+        if params.ordered_page_ids.is_empty() {
+            return failed(
+                Vec::new(),
+                ToolError::validation("ordered_page_ids is empty"),
+            );
+        }
+        // 先解析全部页面，任一页面不存在或重复时不做任何修改
+        let mut resolved = Vec::with_capacity(params.ordered_page_ids.len());
+        for page_id in &params.ordered_page_ids {
+            let resolved_id = match self.store.resolve_page_id_by_uid(page_id).await {
+                Ok(Some(id)) => id,
+                Ok(None) => {
+                    return failed(
+                        Vec::new(),
+                        ToolError::not_found(format!("page not found: {}", page_id)),
+                    );
+                }
+                Err(err) => return failed(Vec::new(), ToolError::from_store(&err)),
+            };
+            if resolved.contains(&resolved_id) {
+                return failed(
+                    Vec::new(),
+                    ToolError::conflict(format!("duplicate page: {}", page_id)),
+                );
+            }
+            resolved.push(resolved_id);
+        }
+
+        let mut orders = Vec::with_capacity(resolved.len());
+        for (sort_order, resolved_id) in (1..).zip(resolved) {
+            match self
+                .store
+                .set_page_sort_order(&resolved_id, Some(sort_order))
+                .await
+            {
+                Ok(meta) => orders.push(PageOrder {
+                    page_id: meta.page_uid,
+                    sort_order,
+                }),
+                Err(err) => return failed(orders, ToolError::from_store(&err)),
+            }
+        }
+
+        Ok(Json(ReorderPagesResponse {
+            success: true,
+            orders,
+            error: Default::default(),
+        }))
+    }
+
+    #[tool(
+        description = "Upload a base64 encoded asset (image, pdf, audio, video) for a page or as a global asset and return its public url. Optionally replace a placeholder string in the page html with the asset path in the same call"
+    )]
+    async fn upload_asset(
+        &self,
+        Parameters(params): Parameters<UploadAssetRequest>,
+    ) -> Result<Json<UploadAssetResponse>, String> {
+        let failed = |error: ToolError| {
+            Ok(Json(UploadAssetResponse {
+                success: false,
+                url: None,
+                path: None,
+                replacements: None,
+                error: error.into(),
+            }))
+        };
+        if params.replace_in_page.is_some() && params.page_id.is_none() {
+            return failed(ToolError::validation("replace_in_page requires page_id"));
+        }
+        if let Some(replace) = &params.replace_in_page
+            && replace.placeholder.is_empty()
+        {
+            return failed(ToolError::validation("placeholder is empty"));
+        }
+        let filename = match validate_upload_filename(&params.filename, &params.mime_type) {
+            Ok(filename) => filename,
+            Err(err) => return failed(ToolError::validation(err.to_string())),
+        };
+        let data = match decode_base64_content(&params.content_base64) {
+            Ok(data) => data,
+            Err(err) => return failed(ToolError::validation(err.to_string())),
+        };
+
+        let page = match &params.page_id {
+            Some(page_id) => {
+                let resolved_id = match self.store.resolve_page_id_by_uid(page_id).await {
+                    Ok(Some(id)) => id,
+                    Ok(None) => return failed(ToolError::not_found("page not found")),
+                    Err(err) => return failed(ToolError::from_store(&err)),
+                };
+                match self.store.load_page(&resolved_id).await {
+                    Ok((meta, html)) => Some((resolved_id, meta, html)),
+                    Err(err) => return failed(ToolError::from_store(&err)),
+                }
+            }
+            None => None,
+        };
+
+        let page_uid = page.as_ref().map(|(_, meta, _)| meta.page_uid.as_str());
+        let path = match save_upload(page_uid, &filename, &data) {
+            Ok(path) => path,
+            Err(err) => return failed(ToolError::from_store(&err)),
+        };
+        let url = format!("{}{}", resolve_site_url_from_env(), path);
+
+        let mut replacements = None;
+        if let (Some(replace), Some((resolved_id, meta, html))) = (&params.replace_in_page, &page) {
+            let count = html.matches(&replace.placeholder).count();
+            if count > 0 {
+                let patched = html.replace(&replace.placeholder, &path);
+                if let Err(err) = validate_html(&patched) {
+                    return failed(ToolError::validation(format!(
+                        "asset saved but page html is invalid: {err}"
+                    )));
+                }
+                save_revision_before_update(self.store.as_ref(), resolved_id).await;
+                if let Err(err) = self
+                    .store
+                    .update_page_html(resolved_id, &patched, true)
+                    .await
+                {
+                    let error = ToolError::from_store(&err);
+                    return failed(error.with_context("asset saved but page update failed"));
+                }
+                let page_url = build_page_full_url(
+                    &resolve_site_url_from_env(),
+                    &meta.page_uid,
+                    &meta.seo.seo_title,
+                );
+                dispatch_page_event(PageEvent::Updated, &meta.page_uid, &page_url);
+            }
+            replacements = Some(count);
+        }
+
+        Ok(Json(UploadAssetResponse {
+            success: true,
+            url: Some(url),
+            path: Some(path),
+            replacements,
+            error: Default::default(),
+        }))
+    }
+
+    #[tool(
+        description = "List uploaded assets of a page (or global assets when page_id is omitted) with name, size, mime type and public url"
+    )]
+    async fn list_assets(
+        &self,
+        Parameters(params): Parameters<ListAssetsRequest>,
+    ) -> Result<Json<ListAssetsResponse>, String> {
+        let failed = |error: ToolError| {
+            Ok(Json(ListAssetsResponse {
+                success: false,
+                assets: Vec::new(),
+                error: error.into(),
+            }))
+        };
+        let mut scopes = Vec::new();
+        if let Some(page_id) = &params.page_id {
+            match resolve_asset_page_uid(self.store.as_ref(), page_id).await {
+                Ok(page_uid) => scopes.push(Some(page_uid)),
+                Err(err) => return failed(err),
+            }
+        }
+        if params.page_id.is_none() || params.include_global.unwrap_or(false) {
+            scopes.push(None);
+        }
+
+        let site_url = resolve_site_url_from_env();
+        let mut assets = Vec::new();
+        for page_uid in scopes {
+            let uploads = match list_uploads(page_uid.as_deref()) {
+                Ok(uploads) => uploads,
+                Err(err) => return failed(ToolError::from_store(&err)),
+            };
+            assets.extend(uploads.into_iter().map(|asset| AssetInfo {
+                url: format!("{}{}", site_url, asset.path),
+                name: asset.name,
+                page_id: asset.page_uid,
+                size: asset.size,
+                mime_type: asset.mime_type,
+                path: asset.path,
+            }));
+        }
+
+        Ok(Json(ListAssetsResponse {
+            success: true,
+            assets,
+            error: Default::default(),
+        }))
+    }
+
+    #[tool(
+        description = "Delete an uploaded asset by name. Deleting an asset still referenced by page html is allowed; the response lists the referencing pages as a warning"
+    )]
+    async fn delete_asset(
+        &self,
+        Parameters(params): Parameters<DeleteAssetRequest>,
+    ) -> Result<Json<DeleteAssetResponse>, String> {
+        let failed = |error: ToolError| {
+            Ok(Json(DeleteAssetResponse {
+                success: false,
+                path: None,
+                referenced_by: Vec::new(),
+                warning: None,
+                error: error.into(),
+            }))
+        };
+        let page_uid = match &params.page_id {
+            Some(page_id) => match resolve_asset_page_uid(self.store.as_ref(), page_id).await {
+                Ok(page_uid) => Some(page_uid),
+                Err(err) => return failed(err),
+            },
+            None => None,
+        };
+        let path = match delete_upload(page_uid.as_deref(), &params.name) {
+            Ok(path) => path,
+            Err(err) => return failed(ToolError::from_store(&err)),
+        };
+
+        // 简单的子串扫描，删除已完成，扫描失败只影响提示
+        let mut referenced_by = Vec::new();
+        match self.store.list_page_entries().await {
+            Ok(entries) => {
+                for entry in entries {
+                    match self.store.get_page_html(&entry.page_id).await {
+                        Ok(html) if html.contains(&path) => referenced_by.push(entry.page_uid),
+                        Ok(_) => {}
+                        Err(err) => {
+                            warn!("scan asset references in {} failed: {err}", entry.page_id)
+                        }
+                    }
+                }
+            }
+            Err(err) => warn!("scan asset references failed: {err}"),
+        }
+        let warning = (!referenced_by.is_empty()).then(|| {
+            format!(
+                "asset is still referenced by {} page(s): {}",
+                referenced_by.len(),
+                referenced_by.join(", ")
+            )
+        });
+
+        Ok(Json(DeleteAssetResponse {
+            success: true,
+            path: Some(path),
+            referenced_by,
+            warning,
+            error: Default::default(),
+        }))
+    }
+
+    #[tool(
+        description = "Delete blog page by page_id (page_uid). The page is moved to the trash; use list_deleted_pages to see it and purge_page to remove it permanently"
+    )]
+    async fn delete_page(
+        &self,
+        Parameters(params): Parameters<PageIdRequest>,
+    ) -> Result<Json<DeletePageResponse>, String> {
+        Ok(Json(delete_page_by_uid(self.store.as_ref(), params).await))
+    }
+
+    #[tool(
+        description = "List pages in the trash (newest deletion first) with trash_id, page_uid, original title and deleted_at"
+    )]
+    async fn list_deleted_pages(
+        &self,
+        Parameters(_params): Parameters<ListDeletedPagesRequest>,
+    ) -> Result<Json<ListDeletedPagesResponse>, String> {
+        match self.store.list_deleted_pages().await {
+            Ok(pages) => Ok(Json(ListDeletedPagesResponse {
+                success: true,
+                pages: pages.into_iter().map(Into::into).collect(),
+                error: Default::default(),
+            })),
+            Err(err) => Ok(Json(ListDeletedPagesResponse {
+                success: false,
+                pages: Vec::new(),
+                error: ToolError::from_store(&err).into(),
+            })),
+        }
+    }
+
+    #[tool(
+        description = "Permanently remove one trashed page. Requires confirm: true and the exact page_uid from list_deleted_pages; pass trash_id when the same page_uid was trashed more than once. Never touches live pages"
+    )]
+    async fn purge_page(
+        &self,
+        Parameters(params): Parameters<PurgePageRequest>,
+    ) -> Result<Json<PurgePageResponse>, String> {
+        let failed = |error: ToolError| {
+            Ok(Json(PurgePageResponse {
+                success: false,
+                page: None,
+                error: error.into(),
+            }))
+        };
+        if !params.confirm {
+            return failed(ToolError::validation(
+                "purge_page cannot be undone, pass confirm: true",
+            ));
+        }
+        let page_uid = params.page_uid.trim();
+        if page_uid.is_empty() {
+            return failed(ToolError::validation("page_uid is empty"));
+        }
+        let trashed = match self.store.list_deleted_pages().await {
+            Ok(pages) => pages,
+            Err(err) => return failed(ToolError::from_store(&err)),
+        };
+        let mut candidates: Vec<_> = trashed
+            .into_iter()
+            .filter(|page| page.page_uid == page_uid)
+            .collect();
+        if let Some(trash_id) = params.trash_id.as_deref().map(str::trim) {
+            candidates.retain(|page| page.trash_id == trash_id);
+        }
+        let entry = match candidates.as_slice() {
+            [entry] => entry,
+            [] => {
+                return failed(ToolError::not_found(format!(
+                    "page {} not found in trash",
+                    page_uid
+                )));
+            }
+            _ => {
+                let trash_ids: Vec<&str> = candidates
+                    .iter()
+                    .map(|page| page.trash_id.as_str())
+                    .collect();
+                return failed(ToolError::conflict(format!(
+                    "page {} is in the trash {} times, pass trash_id: {}",
+                    page_uid,
+                    candidates.len(),
+                    trash_ids.join(", ")
+                )));
+            }
+        };
+        match self.store.purge_deleted_page(&entry.trash_id).await {
+            Ok(page) => Ok(Json(PurgePageResponse {
+                success: true,
+                page: Some(page.into()),
+                error: Default::default(),
+            })),
+            Err(err) => failed(ToolError::from_store(&err)),
+        }
+    }
+
+    #[tool(
+        description = "Publish or unpublish a page without resending its content. Idempotent; returns the resulting state and url. Publishing is refused while the stored html fails validate_html"
+    )]
+    async fn set_page_visibility(
+        &self,
+        Parameters(params): Parameters<SetPageVisibilityRequest>,
+    ) -> Result<Json<PageVisibilityResponse>, String> {
+        let failed = |error: ToolError| {
+            Ok(Json(PageVisibilityResponse {
+                success: false,
+                published: None,
+                changed: false,
+                url: None,
+                error: error.into(),
+            }))
+        };
+        let resolved_id = match self.store.resolve_page_id_by_uid(&params.page_id).await {
+            Ok(Some(id)) => id,
+            Ok(None) => return failed(ToolError::not_found("page not found")),
+            Err(err) => return failed(ToolError::from_store(&err)),
+        };
+        let (meta, html) = match self.store.load_page(&resolved_id).await {
+            Ok(data) => data,
+            Err(err) => return failed(ToolError::from_store(&err)),
+        };
+        let url = build_page_full_url(
+            &resolve_site_url_from_env(),
+            &meta.page_uid,
+            &meta.seo.seo_title,
+        );
+
+        let changed = is_page_public(&meta) != params.published;
+        if changed {
+            // 早于校验逻辑写入的旧数据可能不合法，修复前不允许公开
+            if params.published
+                && let Err(err) = validate_html(&html)
+            {
+                return failed(ToolError::validation(format!(
+                    "page html is invalid, fix it before publishing: {err}"
+                )));
+            }
+            let status = if params.published {
+                serde_json::Value::Null
+            } else {
+                STATUS_UNPUBLISHED.into()
+            };
+            if let Err(err) = self
+                .store
+                .set_page_extra(&resolved_id, EXTRA_STATUS_KEY, status)
+                .await
+            {
+                return failed(ToolError::from_store(&err));
+            }
+            // 首次从草稿或取消发布状态公开时记录发布时间，再次公开沿用原值
+            if params.published
+                && let Err(err) = self.store.mark_page_published(&resolved_id).await
+            {
+                return failed(ToolError::from_store(&err));
+            }
+            dispatch_page_event(PageEvent::Updated, &meta.page_uid, &url);
+        }
+
+        Ok(Json(PageVisibilityResponse {
+            success: true,
+            published: Some(params.published),
+            changed,
+            url: Some(url),
+            error: Default::default(),
+        }))
+    }
+
+    #[tool(
+        description = "Update blog page by page_id (page_uid). Pass expected_updated_at (meta.updated_at from your last read) to get a conflict error instead of overwriting someone else's edits. structured_data replaces the custom JSON-LD object; pass {} to clear it"
+    )]
+    async fn update_page(
+        &self,
+        Parameters(params): Parameters<UpdatePageRequest>,
+    ) -> Result<Json<UpdatePageResponse>, String> {
+        Ok(Json(update_html_page(self.store.as_ref(), params).await))
+    }
+
+    #[tool(
+        description = "Patch the stored HTML of a page with find/replace operations instead of resending the whole document. Fails without writing if any find string is missing or the result is invalid HTML. Markdown source is left unchanged"
+    )]
+    async fn patch_page_html(
+        &self,
+        Parameters(params): Parameters<PatchPageHtmlRequest>,
+    ) -> Result<Json<PatchPageHtmlResponse>, String> {
+        let failed = |error: ToolError| Ok(Json(failed_patch_response(error)));
+        if params.operations.is_empty() {
+            return failed(ToolError::validation("operations is empty"));
+        }
+        let resolved_id = match self.store.resolve_page_id_by_uid(&params.page_id).await {
+            Ok(Some(id)) => id,
+            Ok(None) => return failed(ToolError::not_found("page not found")),
+            Err(err) => return failed(ToolError::from_store(&err)),
+        };
+        let (meta, html) = match self.store.load_page(&resolved_id).await {
+            Ok(data) => data,
+            Err(err) => return failed(ToolError::from_store(&err)),
+        };
+
+        let (patched, replacements) = match apply_html_patches(&html, &params.operations) {
+            Ok(result) => result,
+            Err(err) => return failed(ToolError::validation(err)),
+        };
+        if let Err(err) = validate_html(&patched) {
+            return failed(ToolError::validation(format!(
+                "patched html is invalid: {err}"
+            )));
+        }
+        save_revision_before_update(self.store.as_ref(), &resolved_id).await;
+        if let Err(err) = self
+            .store
+            .update_page_html(&resolved_id, &patched, true)
+            .await
+        {
+            return failed(ToolError::from_store(&err));
+        }
+
+        let url = build_page_full_url(
+            &resolve_site_url_from_env(),
+            &meta.page_uid,
+            &meta.seo.seo_title,
+        );
+        dispatch_page_event(PageEvent::Updated, &meta.page_uid, &url);
+        Ok(Json(PatchPageHtmlResponse {
+            success: true,
+            url: Some(url),
+            replacements,
+            error: Default::default(),
+        }))
+    }
+
+    #[tool(
+        description = "Append an html or markdown fragment to an existing page, before </body> by default or after the element matched by an id selector (position: after_element, selector: \"#id\"). Returns the page url and new updated_at"
+    )]
+    async fn append_section(
+        &self,
+        Parameters(params): Parameters<AppendSectionRequest>,
+    ) -> Result<Json<AppendSectionResponse>, String> {
+        let failed = |error: ToolError| Ok(Json(failed_append_response(error)));
+        let fragment = match (params.html, params.markdown) {
+            (Some(html), None) => html,
+            (None, Some(markdown)) => markdown_to_html(&markdown),
+            _ => {
+                return failed(ToolError::validation(
+                    "exactly one of html or markdown must be set",
+                ));
+            }
+        };
+        if let Err(err) = validate_html(&fragment) {
+            return failed(ToolError::validation(format!("invalid fragment: {err}")));
+        }
+        let element_id = match params.position.unwrap_or(AppendPosition::BeforeEndOfBody) {
+            AppendPosition::BeforeEndOfBody => None,
+            AppendPosition::AfterElement => {
+                let selector = params.selector.unwrap_or_default();
+                match selector.trim().strip_prefix('#') {
+                    Some(id) if !id.is_empty() => Some(id.to_string()),
+                    _ => return failed(ToolError::validation("selector must be an id like #id")),
+                }
+            }
+        };
+        let position = match &element_id {
+            Some(id) => SectionPosition::AfterElement(id),
+            None => SectionPosition::BeforeEndOfBody,
+        };
+
+        let resolved_id = match self.store.resolve_page_id_by_uid(&params.page_id).await {
+            Ok(Some(id)) => id,
+            Ok(None) => return failed(ToolError::not_found("page not found")),
+            Err(err) => return failed(ToolError::from_store(&err)),
+        };
+        let (_, html) = match self.store.load_page(&resolved_id).await {
+            Ok(data) => data,
+            Err(err) => return failed(ToolError::from_store(&err)),
+        };
+        let appended = match insert_html_section(&html, &fragment, position) {
+            Ok(appended) => appended,
+            Err(err) => return failed(ToolError::from_store(&err)),
+        };
+        if let Err(err) = validate_html(&appended) {
+            return failed(ToolError::validation(format!(
+                "appended html is invalid: {err}"
+            )));
+        }
+        save_revision_before_update(self.store.as_ref(), &resolved_id).await;
+        if let Err(err) = self
+            .store
+            .update_page_html(&resolved_id, &appended, true)
+            .await
+        {
+            return failed(ToolError::from_store(&err));
+        }
+
+        let meta = match self.store.get_page_meta(&resolved_id).await {
+            Ok(meta) => meta,
+            Err(err) => return failed(ToolError::from_store(&err)),
+        };
+        let url = build_page_full_url(
+            &resolve_site_url_from_env(),
+            &meta.page_uid,
+            &meta.seo.seo_title,
+        );
+        dispatch_page_event(PageEvent::Updated, &meta.page_uid, &url);
+        Ok(Json(AppendSectionResponse {
+            success: true,
+            url: Some(url),
+            updated_at: Some(meta.updated_at),
+            error: Default::default(),
+        }))
+    }
+
+    #[tool(
+        description = "Update markdown blog page by page_id (page_uid). Pass expected_updated_at (meta.updated_at from your last read) to get a conflict error instead of overwriting someone else's edits"
+    )]
+    async fn update_markdown_page(
+        &self,
+        Parameters(params): Parameters<UpdateMarkdownPageRequest>,
+    ) -> Result<Json<UpdatePageResponse>, String> {
+        Ok(Json(
+            update_markdown_page_by_uid(self.store.as_ref(), params).await,
+        ))
+    }
+
+    #[tool(
+        description = "List saved revisions of a page (oldest first) with timestamps and html / markdown sizes. A revision of the previous content is saved before every update, patch, append and restore"
+    )]
+    async fn list_revisions(
+        &self,
+        Parameters(params): Parameters<PageIdRequest>,
+    ) -> Result<Json<ListRevisionsResponse>, String> {
+        let failed = |error: ToolError| {
+            Ok(Json(ListRevisionsResponse {
+                success: false,
+                revisions: Vec::new(),
+                error: error.into(),
+            }))
+        };
+        let resolved_id = match self.store.resolve_page_id_by_uid(&params.page_id).await {
+            Ok(Some(id)) => id,
+            Ok(None) => return failed(ToolError::not_found("page not found")),
+            Err(err) => return failed(ToolError::from_store(&err)),
+        };
+        match self.store.list_revisions(&resolved_id).await {
+            Ok(revisions) => Ok(Json(ListRevisionsResponse {
+                success: true,
+                revisions: revisions.into_iter().map(Into::into).collect(),
+                error: Default::default(),
+            })),
+            Err(err) => failed(ToolError::from_store(&err)),
+        }
+    }
+
+    #[tool(
+        description = "Line-based unified diff of a page between two revisions, or between a revision and the current content when to_revision is omitted. Compares html by default, set markdown=true for the markdown source. Large diffs are truncated"
+    )]
+    async fn diff_revisions(
+        &self,
+        Parameters(params): Parameters<DiffRevisionsRequest>,
+    ) -> Result<Json<DiffRevisionsResponse>, String> {
+        let failed = |error: ToolError| {
+            Ok(Json(DiffRevisionsResponse {
+                success: false,
+                diff: None,
+                added_lines: 0,
+                removed_lines: 0,
+                truncated: false,
+                error: error.into(),
+            }))
+        };
+        let resolved_id = match self.store.resolve_page_id_by_uid(&params.page_id).await {
+            Ok(Some(id)) => id,
+            Ok(None) => return failed(ToolError::not_found("page not found")),
+            Err(err) => return failed(ToolError::from_store(&err)),
+        };
+        let use_markdown = params.markdown.unwrap_or(false);
+        let old = match self
+            .store
+            .load_revision(&resolved_id, params.from_revision)
+            .await
+        {
+            Ok(revision) if use_markdown => revision.markdown,
+            Ok(revision) => Some(revision.html),
+            Err(err) => return failed(ToolError::from_store(&err)),
+        };
+        let old_label = format!("revision {}", params.from_revision);
+        let new_label = params
+            .to_revision
+            .map_or_else(|| "current".to_string(), |id| format!("revision {id}"));
+        let new = match params.to_revision {
+            Some(to_revision) => match self.store.load_revision(&resolved_id, to_revision).await {
+                Ok(revision) if use_markdown => revision.markdown,
+                Ok(revision) => Some(revision.html),
+                Err(err) => return failed(ToolError::from_store(&err)),
+            },
+            None => {
+                let current = if use_markdown {
+                    self.store.load_page_markdown(&resolved_id).await
+                } else {
+                    self.store.get_page_html(&resolved_id).await.map(Some)
+                };
+                match current {
+                    Ok(content) => content,
+                    Err(err) => return failed(ToolError::from_store(&err)),
+                }
+            }
+        };
+        let (Some(old), Some(new)) = (old, new) else {
+            return failed(ToolError::validation(
+                "markdown source is not available for both sides",
+            ));
+        };
+        let diff = unified_diff(&old, &new, &old_label, &new_label, MAX_REVISION_DIFF_BYTES);
+        Ok(Json(DiffRevisionsResponse {
+            success: true,
+            diff: Some(diff.text),
+            added_lines: diff.added_lines,
+            removed_lines: diff.removed_lines,
+            truncated: diff.truncated,
+            error: Default::default(),
+        }))
+    }
+
+    #[tool(
+        description = "Restore the html (and markdown source) of a page to a saved revision. The current content is saved as a new revision first, so a restore can itself be undone; seo and other metadata are kept"
+    )]
+    async fn restore_revision(
+        &self,
+        Parameters(params): Parameters<RestoreRevisionRequest>,
+    ) -> Result<Json<RestoreRevisionResponse>, String> {
+        let failed = |error: ToolError| {
+            Ok(Json(RestoreRevisionResponse {
+                success: false,
+                restored_revision: None,
+                backup_revision: None,
+                error: error.into(),
+            }))
+        };
+        let resolved_id = match self.store.resolve_page_id_by_uid(&params.page_id).await {
+            Ok(Some(id)) => id,
+            Ok(None) => return failed(ToolError::not_found("page not found")),
+            Err(err) => return failed(ToolError::from_store(&err)),
+        };
+        let backup = match self
+            .store
+            .restore_revision(&resolved_id, params.revision_id)
+            .await
+        {
+            Ok(backup) => backup,
+            Err(err) => return failed(ToolError::from_store(&err)),
+        };
+        if let Ok(meta) = self.store.get_page_meta(&resolved_id).await {
+            let url = build_page_full_url(
+                &resolve_site_url_from_env(),
+                &meta.page_uid,
+                &meta.seo.seo_title,
+            );
+            dispatch_page_event(PageEvent::Updated, &meta.page_uid, &url);
+        }
+        Ok(Json(RestoreRevisionResponse {
+            success: true,
+            restored_revision: Some(params.revision_id),
+            backup_revision: Some(backup.revision_id),
+            error: Default::default(),
+        }))
+    }
+
+    #[tool(
+        description = "Delete old revisions of a page, keeping only the newest `keep` (at least 1). Returns how many revisions were deleted"
+    )]
+    async fn prune_page_versions(
+        &self,
+        Parameters(params): Parameters<PrunePageVersionsRequest>,
+    ) -> Result<Json<PrunePageVersionsResponse>, String> {
+        let failed = |error: ToolError| {
+            Ok(Json(PrunePageVersionsResponse {
+                success: false,
+                deleted: 0,
+                error: error.into(),
+            }))
+        };
+        if params.keep == 0 {
+            return failed(ToolError::validation("keep must be at least 1"));
+        }
+        let resolved_id = match self.store.resolve_page_id_by_uid(&params.page_id).await {
+            Ok(Some(id)) => id,
+            Ok(None) => return failed(ToolError::not_found("page not found")),
+            Err(err) => return failed(ToolError::from_store(&err)),
+        };
+        match self.store.prune_revisions(&resolved_id, params.keep).await {
+            Ok(deleted) => Ok(Json(PrunePageVersionsResponse {
+                success: true,
+                deleted,
+                error: Default::default(),
+            })),
+            Err(err) => failed(ToolError::from_store(&err)),
+        }
+    }
+
+    #[tool(
+        description = "Report the server version, SITE_URL, token scope (rw or ro), store backend and data dir, page count, disk usage, template source and a quick store health probe (index parses, data dir writable). Cheap way to confirm connectivity before doing work; secrets such as the MCP token are never included"
+    )]
+    async fn get_server_info(
+        &self,
+        Parameters(_params): Parameters<GetServerInfoRequest>,
+    ) -> Result<Json<GetServerInfoResponse>, String> {
+        let health = match self.store.probe_health().await {
+            Ok(health) => health,
+            Err(err) => StoreHealth {
+                backend: "unknown".to_string(),
+                errors: vec![format!("probe store health failed: {:#}", err)],
+                ..Default::default()
+            },
+        };
+        let page_count = match self.store.list_page_entries().await {
+            Ok(entries) => Some(entries.len()),
+            Err(err) => {
+                warn!("server info: list page entries failed: {err}");
+                None
+            }
+        };
+        let site_url = Some(resolve_site_url_from_env()).filter(|url| !url.is_empty());
+        let missing_templates: Vec<String> = TEMPLATE_FILES
+            .iter()
+            .filter(|path| !std::path::Path::new(path).is_file())
+            .map(|path| path.to_string())
+            .collect();
+        Ok(Json(GetServerInfoResponse {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            site_url,
+            page_count,
+            scope: self.scope.as_str().to_string(),
+            template_source: "disk".to_string(),
+            healthy: health.is_healthy() && missing_templates.is_empty(),
+            missing_templates,
+            store: health.into(),
+        }))
+    }
+
+    #[tool(
+        description = "List the styles accepted by get_blog_style and get_html_style, with a short description of each"
+    )]
+    async fn list_styles(
+        &self,
+        Parameters(_params): Parameters<ListStylesRequest>,
+    ) -> Result<Json<ListStylesResponse>, String> {
+        match (list_blog_styles(), list_html_styles()) {
+            (Ok(blog_styles), Ok(html_styles)) => Ok(Json(ListStylesResponse {
+                success: true,
+                blog_styles: blog_styles.into_iter().map(Into::into).collect(),
+                html_styles: html_styles.into_iter().map(Into::into).collect(),
+                error: Default::default(),
+            })),
+            (Err(err), _) | (_, Err(err)) => Ok(Json(ListStylesResponse {
+                success: false,
+                blog_styles: Vec::new(),
+                html_styles: Vec::new(),
+                error: ToolError::from_store(&err).into(),
+            })),
+        }
+    }
+
+    #[tool(
+        name = "get_blog_style",
+        description = "获取指定的博文写作风格指南，可用风格见 list_styles"
+    )]
+    async fn get_blog_style(
+        &self,
+        Parameters(params): Parameters<GetBlogStyleRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let content = load_blog_style(&params.style).map_err(style_error)?;
+        Ok(CallToolResult::success(vec![Content::text(content)]))
+    }
+
+    #[tool(
+        name = "get_html_style",
+        description = "获取 HTML 风格参考，可用风格见 list_styles。1. 用户未指定样式，则默认为default。2. 在制作HTML博文时需先获得参考样式"
+    )]
+    async fn get_html_style(
+        &self,
+        Parameters(params): Parameters<GetHtmlStyleRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let content = load_html_style(&params.style).map_err(style_error)?;
+        Ok(CallToolResult::success(vec![Content::text(content)]))
+    }
+}
+
+/// 未知风格属于参数错误，错误信息中列出可用风格；其余为读取文件失败
+/// `data.code` 与响应 DTO 的 `error_info.code` 取值一致
+pub(crate) fn style_error(err: anyhow::Error) -> McpError {
+    if err.downcast_ref::<UnknownStyle>().is_some() {
+        let data = serde_json::json!({ "code": ErrorCode::NotFound });
+        McpError::invalid_params(err.to_string(), Some(data))
+    } else {
+        let data = serde_json::json!({ "code": ErrorCode::Io });
+        McpError::internal_error(format!("读取文件失败: {err:#}"), Some(data))
+    }
+}
+
+pub(crate) fn build_page_full_url(base_url: &str, page_id: &str, seo_title: &str) -> String {
+    let path = build_page_url(page_id, seo_title);
+    format!("{}{}", base_url.trim_end_matches('/'), path)
+}
+
+/// 自定义 JSON-LD 只接受对象，数组或标量无法作为独立的结构化数据节点
+fn check_structured_data(value: Option<&serde_json::Value>) -> Result<(), ToolError> {
+    match value {
+        Some(value) if !value.is_object() => Err(ToolError::validation(
+            "structured_data must be a JSON object",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// `push_page` 工具与 `POST /api/pages` 共用的创建流程
+pub(crate) async fn push_html_page(
+    store: &dyn PageStoreTrait,
+    params: PushPageRequest,
+) -> PushPageResponse {
+    let idempotency_key = normalize_idempotency_key(params.idempotency_key.as_deref());
+    let _idempotency_guard = match &idempotency_key {
+        Some(key) => {
+            let guard = IDEMPOTENCY_LOCK.lock().await;
+            match find_idempotent_page(store, key).await {
+                Ok(Some(meta)) => return deduplicated_push_response(meta),
+                Ok(None) => Some(guard),
+                Err(err) => return failed_push_response(ToolError::from_store(&err)),
+            }
+        }
+        None => None,
+    };
+    if let Err(err) = check_structured_data(params.structured_data.as_ref()) {
+        return failed_push_response(err);
+    }
+
+    let meta = PageMeta {
+        seo: SeoMeta {
+            title: params.seo_title.clone(),
+            seo_title: params.seo_title,
+            description: params.description,
+            keywords: params.keywords,
+            extra: Default::default(),
+            structured_data: params.structured_data,
+        },
+        page_uid: String::new(),
+        created_at: 0,
+        updated_at: 0,
+        view_count: 0,
+        unique_view_count: 0,
+        tags: params.tags,
+        sort_order: None,
+        published_at: None,
+        extra: Default::default(),
+    };
+
+    if let Err(html_error) = check_page_html(&params.html) {
+        return PushPageResponse {
+            success: false,
+            page_id: None,
+            url: None,
+            meta: None,
+            error: ToolError::validation(html_error.message.clone()).into(),
+            html_error: Some(html_error),
+            deduplicated: false,
+        };
+    }
+
+    match store.create_page_auto_uid(&meta, &params.html).await {
+        Ok(saved_meta) => {
+            if let Some(key) = &idempotency_key {
+                record_idempotency_key(store, key, &saved_meta.page_uid).await;
+            }
+            created_push_response(saved_meta)
+        }
+        Err(err) => PushPageResponse {
+            success: false,
+            page_id: None,
+            url: None,
+            meta: None,
+            error: ToolError::from_store(&err).into(),
+            html_error: None,
+            deduplicated: false,
+        },
+    }
+}
+
+/// `delete_page` 工具与 `DELETE /api/pages/{page_uid}` 共用
+pub(crate) async fn delete_page_by_uid(
+    store: &dyn PageStoreTrait,
+    params: PageIdRequest,
+) -> DeletePageResponse {
+    let resolved_id = match store.resolve_page_id_by_uid(&params.page_id).await {
+        Ok(Some(id)) => id,
+        Ok(None) => {
+            return DeletePageResponse {
+                success: false,
+                error: ToolError::not_found("page not found").into(),
+            };
+        }
+        Err(err) => {
+            return DeletePageResponse {
+                success: false,
+                error: ToolError::from_store(&err).into(),
+            };
+        }
+    };
+
+    // 删除后无法再读取元数据，先取出存储中的 page_uid 并拼好 Webhook 中的页面 URL
+    let (page_uid, url) = match store.get_page_meta(&resolved_id).await {
+        Ok(meta) => {
+            let url = build_page_full_url(
+                &resolve_site_url_from_env(),
+                &meta.page_uid,
+                &meta.seo.seo_title,
+            );
+            (meta.page_uid, url)
+        }
+        Err(_) => (params.page_id.clone(), String::new()),
+    };
+    match store.delete_page(&resolved_id).await {
+        Ok(_) => {
+            dispatch_page_event(PageEvent::Deleted, &page_uid, &url);
+            DeletePageResponse {
+                success: true,
+                error: Default::default(),
+            }
+        }
+        Err(err) => DeletePageResponse {
+            success: false,
+            error: ToolError::from_store(&err).into(),
+        },
+    }
+}
+
+/// `update_markdown_page` 工具与管理后台共用
+pub(crate) async fn update_markdown_page_by_uid(
+    store: &dyn PageStoreTrait,
+    params: UpdateMarkdownPageRequest,
+) -> UpdatePageResponse {
+    let resolved_id = match store.resolve_page_id_by_uid(&params.page_id).await {
+        Ok(Some(id)) => id,
+        Ok(None) => {
+            return UpdatePageResponse {
+                success: false,
+                url: None,
+                meta: None,
+                error: ToolError::not_found("page not found").into(),
+            };
+        }
+        Err(err) => {
+            return UpdatePageResponse {
+                success: false,
+                url: None,
+                meta: None,
+                error: ToolError::from_store(&err).into(),
+            };
+        }
+    };
+
+    let (mut meta, mut html) = match store.load_page(&resolved_id).await {
+        Ok(data) => data,
+        Err(err) => {
+            return UpdatePageResponse {
+                success: false,
+                url: None,
+                meta: None,
+                error: ToolError::from_store(&err).into(),
+            };
+        }
+    };
+    let expected = params.expected_updated_at;
+    if let Err(err) = check_expected_updated_at(&resolved_id, Some(&meta), expected) {
+        return UpdatePageResponse {
+            success: false,
+            url: None,
+            meta: None,
+            error: ToolError::from_store(&err).into(),
+        };
+    }
+
+    if let Some(seo_title) = params.seo_title {
+        meta.seo.seo_title = seo_title;
+    }
+    if let Some(description) = params.description {
+        meta.seo.description = description;
+    }
+    if let Some(keywords) = params.keywords {
+        meta.seo.keywords = Some(keywords);
+    }
+    if let Some(tags) = params.tags {
+        meta.tags = Some(tags).filter(|tags| !tags.is_empty());
+    }
+    if let Some(noindex) = params.noindex {
+        set_page_noindex(&mut meta, noindex);
+    }
+    let mut markdown_source: Option<String> = None;
+    if let Some(markdown) = params.markdown {
+        let rendered = match render_markdown_page(&markdown) {
+            Ok(rendered) => rendered,
+            Err(err) => {
+                return UpdatePageResponse {
+                    success: false,
+                    url: None,
+                    meta: None,
+                    error: ToolError::from_store(&err).into(),
+                };
+            }
+        };
+        if let Err(err) = validate_html(&rendered) {
+            return UpdatePageResponse {
+                success: false,
+                url: None,
+                meta: None,
+                error: ToolError::from_store(&err).into(),
+            };
+        }
+        html = rendered;
+        markdown_source = Some(markdown);
+    }
+
+    save_revision_before_update(store, &resolved_id).await;
+    let markdown = markdown_source.as_deref();
+    let saved = match expected {
+        Some(updated_at) => {
+            store
+                .update_page_if_unmodified(&resolved_id, &meta, &html, markdown, updated_at)
+                .await
+        }
+        None => {
+            store
+                .update_page_with_markdown(&resolved_id, &meta, &html, markdown)
+                .await
+        }
+    };
+    match saved {
+        Ok(_) => {
+            let (saved_meta, _) = match store.load_page(&resolved_id).await {
+                Ok(data) => data,
+                Err(err) => {
+                    return UpdatePageResponse {
+                        success: false,
+                        url: None,
+                        meta: None,
+                        error: ToolError::from_store(&err).into(),
+                    };
+                }
+            };
+            let url = build_page_full_url(
+                &resolve_site_url_from_env(),
+                &saved_meta.page_uid,
+                &saved_meta.seo.seo_title,
+            );
+            dispatch_page_event(PageEvent::Updated, &saved_meta.page_uid, &url);
+            UpdatePageResponse {
+                success: true,
+                url: Some(url),
+                meta: Some(saved_meta.into()),
+                error: Default::default(),
+            }
+        }
+        Err(err) => UpdatePageResponse {
+            success: false,
+            url: None,
+            meta: None,
+            error: ToolError::from_store(&err).into(),
+        },
+    }
+}
+
+/// `update_page` 工具与 `PUT /api/pages/{page_uid}` 共用
+pub(crate) async fn update_html_page(
+    store: &dyn PageStoreTrait,
+    params: UpdatePageRequest,
+) -> UpdatePageResponse {
+    let resolved_id = match store.resolve_page_id_by_uid(&params.page_id).await {
+        Ok(Some(id)) => id,
+        Ok(None) => {
+            return UpdatePageResponse {
+                success: false,
+                url: None,
+                meta: None,
+                error: ToolError::not_found("page not found").into(),
+            };
+        }
+        Err(err) => {
+            return UpdatePageResponse {
+                success: false,
+                url: None,
+                meta: None,
+                error: ToolError::from_store(&err).into(),
+            };
+        }
+    };
+
+    let (mut meta, mut html) = match store.load_page(&resolved_id).await {
+        Ok(data) => data,
+        Err(err) => {
+            return UpdatePageResponse {
+                success: false,
+                url: None,
+                meta: None,
+                error: ToolError::from_store(&err).into(),
+            };
+        }
+    };
+    // 提前比较一次，已过期的更新不生成历史版本；保存时存储层在写锁内再次比较
+    let expected = params.expected_updated_at;
+    if let Err(err) = check_expected_updated_at(&resolved_id, Some(&meta), expected) {
+        return UpdatePageResponse {
+            success: false,
+            url: None,
+            meta: None,
+            error: ToolError::from_store(&err).into(),
+        };
+    }
+
+    if let Some(seo_title) = params.seo_title {
+        meta.seo.seo_title = seo_title;
+    }
+    if let Some(description) = params.description {
+        meta.seo.description = description;
+    }
+    if let Some(keywords) = params.keywords {
+        meta.seo.keywords = Some(keywords);
+    }
+    if let Some(tags) = params.tags {
+        meta.tags = Some(tags).filter(|tags| !tags.is_empty());
+    }
+    if let Some(noindex) = params.noindex {
+        set_page_noindex(&mut meta, noindex);
+    }
+    if let Some(structured_data) = params.structured_data {
+        if let Err(err) = check_structured_data(Some(&structured_data)) {
+            return UpdatePageResponse {
+                success: false,
+                url: None,
+                meta: None,
+                error: err.into(),
+            };
+        }
+        // 传空对象表示清除
+        let cleared = structured_data
+            .as_object()
+            .is_some_and(|object| object.is_empty());
+        meta.seo.structured_data = (!cleared).then_some(structured_data);
+    }
+    if let Some(new_html) = params.html {
+        if let Err(err) = validate_html(&new_html) {
+            return UpdatePageResponse {
+                success: false,
+                url: None,
+                meta: None,
+                error: ToolError::from_store(&err).into(),
+            };
+        }
+        html = new_html.to_string();
+    }
+    if let Some(head_html) = params.head_html {
+        if head_html.trim().is_empty() {
+            meta.extra.remove(EXTRA_HEAD_HTML_KEY);
+        } else {
+            let title = if meta.seo.title.is_empty() {
+                &meta.seo.seo_title
+            } else {
+                &meta.seo.title
+            };
+            let checked = sanitize_extra_head_html(&head_html).and_then(|snippet| {
+                append_extra_head_html(&inject_seo_meta(&html, title, &meta.seo), &snippet)
+                    .map(|_| snippet)
+            });
+            match checked {
+                Ok(snippet) => {
+                    meta.extra
+                        .insert(EXTRA_HEAD_HTML_KEY.to_string(), snippet.into());
+                }
+                Err(err) => {
+                    return UpdatePageResponse {
+                        success: false,
+                        url: None,
+                        meta: None,
+                        error: ToolError::validation(format!("invalid head_html: {err:#}")).into(),
+                    };
+                }
+            }
+        }
+    }
+
+    save_revision_before_update(store, &resolved_id).await;
+    let saved = match expected {
+        Some(updated_at) => {
+            store
+                .update_page_if_unmodified(&resolved_id, &meta, &html, None, updated_at)
+                .await
+        }
+        None => store.update_page(&resolved_id, &meta, &html).await,
+    };
+    match saved {
+        Ok(_) => {
+            let (saved_meta, _) = match store.load_page(&resolved_id).await {
+                Ok(data) => data,
+                Err(err) => {
+                    return UpdatePageResponse {
+                        success: false,
+                        url: None,
+                        meta: None,
+                        error: ToolError::from_store(&err).into(),
+                    };
+                }
+            };
+            let url = build_page_full_url(
+                &resolve_site_url_from_env(),
+                &saved_meta.page_uid,
+                &saved_meta.seo.seo_title,
+            );
+            dispatch_page_event(PageEvent::Updated, &saved_meta.page_uid, &url);
+            UpdatePageResponse {
+                success: true,
+                url: Some(url),
+                meta: Some(saved_meta.into()),
+                error: Default::default(),
+            }
+        }
+        Err(err) => UpdatePageResponse {
+            success: false,
+            url: None,
+            meta: None,
+            error: ToolError::from_store(&err).into(),
+        },
+    }
+}
+
+/// 覆盖正文前保存当前版本；保存失败只记录日志，不阻止本次更新
+async fn save_revision_before_update(store: &dyn PageStoreTrait, page_id: &str) {
+    if let Err(err) = store.create_revision(page_id).await {
+        warn!("save revision for page {page_id} failed: {err:#}");
+    }
+}
+
+/// 有 Markdown 源时直接搜索源文件，避免 HTML 标签与样式稀释词频；否则退回 HTML 可见文本
+async fn load_search_content(store: &dyn PageStoreTrait, page_id: &str) -> String {
+    if let Ok(Some(markdown)) = store.load_page_markdown(page_id).await {
+        return markdown;
+    }
+    store
+        .get_page_html(page_id)
+        .await
+        .map(|html| html_visible_text(&html))
+        .unwrap_or_default()
+}
+
+/// 资源目录按 page_uid 命名，`page_id` 可以是旧 ID 或 page_uid
+async fn resolve_asset_page_uid(
+    store: &dyn PageStoreTrait,
+    page_id: &str,
+) -> Result<String, ToolError> {
+    let resolved_id = match store.resolve_page_id_by_uid(page_id).await {
+        Ok(Some(id)) => id,
+        Ok(None) => return Err(ToolError::not_found("page not found")),
+        Err(err) => return Err(ToolError::from_store(&err)),
+    };
+    store
+        .get_page_meta(&resolved_id)
+        .await
+        .map(|meta| meta.page_uid)
+        .map_err(|err| ToolError::from_store(&err))
+}
+
+/// `bulk_update_seo` 的变换，重复执行结果不变；返回是否有字段被修改
+fn apply_seo_changes(
+    seo: &mut SeoMeta,
+    title_suffix: Option<&str>,
+    description_prefix: Option<&str>,
+    add_keywords: Option<&[String]>,
+) -> bool {
+    let mut changed = false;
+    if let Some(suffix) = title_suffix.filter(|value| !value.is_empty()) {
+        if seo.title.is_empty() {
+            seo.title = seo.seo_title.clone();
+        }
+        if !seo.title.ends_with(suffix) {
+            seo.title.push_str(suffix);
+            changed = true;
+        }
+    }
+    if let Some(prefix) = description_prefix.filter(|value| !value.is_empty())
+        && !seo.description.starts_with(prefix)
+    {
+        seo.description = format!("{}{}", prefix, seo.description);
+        changed = true;
+    }
+    for keyword in add_keywords.into_iter().flatten() {
+        let keyword = keyword.trim();
+        if keyword.is_empty() {
+            continue;
+        }
+        let keywords = seo.keywords.get_or_insert_with(Vec::new);
+        if !keywords
+            .iter()
+            .any(|value| value.eq_ignore_ascii_case(keyword))
+        {
+            keywords.push(keyword.to_string());
+            changed = true;
+        }
+    }
+    changed
+}
+
+fn failed_append_response(error: ToolError) -> AppendSectionResponse {
+    AppendSectionResponse {
+        success: false,
+        url: None,
+        updated_at: None,
+        error: error.into(),
+    }
+}
+
+fn failed_patch_response(error: ToolError) -> PatchPageHtmlResponse {
+    PatchPageHtmlResponse {
+        success: false,
+        url: None,
+        replacements: Vec::new(),
+        error: error.into(),
+    }
+}
+
+/// 依次应用替换操作，任一 `find` 未命中即整体失败，返回每个操作的替换次数
+fn apply_html_patches(
+    html: &str,
+    operations: &[PatchOperation],
+) -> Result<(String, Vec<usize>), String> {
+    let mut html = html.to_string();
+    let mut replacements = Vec::with_capacity(operations.len());
+    for (index, operation) in operations.iter().enumerate() {
+        if operation.find.is_empty() {
+            return Err(format!("operation {index}: find is empty"));
+        }
+        let count = match operation.occurrence {
+            None => {
+                let count = html.matches(&operation.find).count();
+                html = html.replace(&operation.find, &operation.replace);
+                count
+            }
+            Some(0) => return Err(format!("operation {index}: occurrence starts at 1")),
+            Some(nth) => match html.match_indices(&operation.find).nth(nth - 1) {
+                Some((start, _)) => {
+                    let end = start + operation.find.len();
+                    html.replace_range(start..end, &operation.replace);
+                    1
+                }
+                None => 0,
+            },
+        };
+        if count == 0 {
+            return Err(format!("operation {index}: find string not found"));
+        }
+        replacements.push(count);
+    }
+    Ok((html, replacements))
+}
+
+/// 创建成功的响应，同时触发 `page.created` Webhook
+fn created_push_response(saved_meta: PageMeta) -> PushPageResponse {
+    let url = build_page_full_url(
+        &resolve_site_url_from_env(),
+        &saved_meta.page_uid,
+        &saved_meta.seo.seo_title,
+    );
+    dispatch_page_event(PageEvent::Created, &saved_meta.page_uid, &url);
+    if is_page_public(&saved_meta) {
+        spawn_auto_ping(&url);
+    }
+    PushPageResponse {
+        url: Some(url),
+        success: true,
+        page_id: Some(saved_meta.page_uid.clone()),
+        meta: Some(saved_meta.into()),
+        error: Default::default(),
+        html_error: None,
+        deduplicated: false,
+    }
+}
+
+/// 幂等键命中时的响应：不再触发 Webhook 与自动 ping
+fn deduplicated_push_response(saved_meta: PageMeta) -> PushPageResponse {
+    let url = build_page_full_url(
+        &resolve_site_url_from_env(),
+        &saved_meta.page_uid,
+        &saved_meta.seo.seo_title,
+    );
+    PushPageResponse {
+        url: Some(url),
+        success: true,
+        page_id: Some(saved_meta.page_uid.clone()),
+        meta: Some(saved_meta.into()),
+        error: Default::default(),
+        html_error: None,
+        deduplicated: true,
+    }
+}
+
+/// 去掉首尾空白，空字符串视为未传
+fn normalize_idempotency_key(key: Option<&str>) -> Option<String> {
+    key.map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(str::to_string)
+}
+
+/// 有效期内记录过且页面仍存在时返回其 meta；页面已被删除时重新创建
+async fn find_idempotent_page(
+    store: &dyn PageStoreTrait,
+    key: &str,
+) -> anyhow::Result<Option<PageMeta>> {
+    let since = now_unix_seconds()? - resolve_idempotency_ttl_from_env();
+    let Some(page_uid) = store.find_idempotency_key(key, since).await? else {
+        return Ok(None);
+    };
+    match store.resolve_page_id_by_uid(&page_uid).await? {
+        Some(page_id) => Ok(Some(store.get_page_meta(&page_id).await?)),
+        None => Ok(None),
+    }
+}
+
+/// 页面已经创建成功，记录失败只打印日志，重试时会再创建一个页面
+async fn record_idempotency_key(store: &dyn PageStoreTrait, key: &str, page_uid: &str) {
+    let since = match now_unix_seconds() {
+        Ok(now) => now - resolve_idempotency_ttl_from_env(),
+        Err(err) => {
+            warn!("record idempotency key failed: {err:#}");
+            return;
+        }
+    };
+    if let Err(err) = store.record_idempotency_key(key, page_uid, since).await {
+        warn!("record idempotency key failed: {err:#}");
+    }
+}
+
+fn check_markdown_size(markdown: &str) -> Result<(), String> {
+    if markdown.len() > MAX_MARKDOWN_BYTES {
+        return Err(format!(
+            "markdown is {} bytes, exceeds the {} byte limit",
+            markdown.len(),
+            MAX_MARKDOWN_BYTES
+        ));
+    }
+    Ok(())
+}
+
+/// 大小限制与 `validate_html` 的组合检查
+fn check_page_html(html: &str) -> Result<(), HtmlValidationError> {
+    if html.len() > MAX_PAGE_HTML_BYTES {
+        return Err(HtmlValidationError {
+            message: format!(
+                "html is {} bytes, exceeds the {} byte limit",
+                html.len(),
+                MAX_PAGE_HTML_BYTES
+            ),
+            offset: None,
+            line: None,
+            column: None,
+            snippet: None,
+        });
+    }
+    validate_html(html).map_err(|err| html_validation_error(html, &err))
+}
+
+/// 从 `HtmlError` 的字节偏移推算行列号与上下文片段
+fn html_validation_error(html: &str, err: &anyhow::Error) -> HtmlValidationError {
+    let Some(mut offset) = err.downcast_ref::<HtmlError>().and_then(|err| err.offset) else {
+        return HtmlValidationError {
+            message: err.to_string(),
+            offset: None,
+            line: None,
+            column: None,
+            snippet: None,
+        };
+    };
+    offset = offset.min(html.len());
+    while !html.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let (before, after) = html.split_at(offset);
+    let line_start = before.rfind('\n').map_or(0, |pos| pos + 1);
+    let snippet_start = before
+        .char_indices()
+        .rev()
+        .nth(HTML_ERROR_SNIPPET_CHARS - 1)
+        .map_or(0, |(pos, _)| pos);
+    let snippet_end = after
+        .char_indices()
+        .nth(HTML_ERROR_SNIPPET_CHARS)
+        .map_or(html.len(), |(pos, _)| offset + pos);
+    HtmlValidationError {
+        message: err.to_string(),
+        offset: Some(offset),
+        line: Some(before.matches('\n').count() + 1),
+        column: Some(before[line_start..].chars().count() + 1),
+        snippet: Some(html[snippet_start..snippet_end].to_string()),
+    }
+}
+
+/// 按 `PAGE_META_FIELDS` 中的名称取字段，调用前已校验名称
+fn select_meta_fields(
+    meta: PageMetaResponse,
+    fields: &[String],
+) -> serde_json::Map<String, serde_json::Value> {
+    let serde_json::Value::Object(mut meta) = serde_json::to_value(meta).unwrap_or_default() else {
+        return serde_json::Map::new();
+    };
+    let mut seo = match meta.get("seo") {
+        Some(serde_json::Value::Object(seo)) => seo.clone(),
+        _ => serde_json::Map::new(),
+    };
+    let mut selected = serde_json::Map::new();
+    for field in fields {
+        let value = meta.remove(field).or_else(|| seo.remove(field));
+        if let Some(value) = value {
+            selected.insert(field.clone(), value);
+        }
+    }
+    selected
+}
+
+/// 超过 `max_bytes` 时退到最近的字符边界截断，返回 (html, 是否截断)
+fn truncate_html(mut html: String, max_bytes: usize) -> (String, bool) {
+    if html.len() <= max_bytes {
+        return (html, false);
+    }
+    let mut end = max_bytes;
+    while !html.is_char_boundary(end) {
+        end -= 1;
+    }
+    html.truncate(end);
+    (html, true)
+}
+
+fn failed_push_response(error: ToolError) -> PushPageResponse {
+    PushPageResponse {
+        success: false,
+        page_id: None,
+        url: None,
+        meta: None,
+        error: error.into(),
+        html_error: None,
+        deduplicated: false,
+    }
+}
+
+/// 与 `push_page` / `push_markdown` 相同的校验与元数据构造
+fn new_page_from_push_item(item: PushPagesItem) -> Result<NewPage, ToolError> {
+    let (seo_title, description, keywords, tags, html, markdown, structured_data) = match item {
+        PushPagesItem::Html(req) => {
+            check_structured_data(req.structured_data.as_ref())?;
+            (
+                req.seo_title,
+                req.description,
+                req.keywords,
+                req.tags,
+                req.html,
+                None,
+                req.structured_data,
+            )
+        }
+        PushPagesItem::Markdown(req) => {
+            let html =
+                render_markdown_page(&req.markdown).map_err(|err| ToolError::from_store(&err))?;
+            (
+                req.seo_title,
+                req.description,
+                req.keywords,
+                req.tags,
+                html,
+                Some(req.markdown),
+                None,
+            )
+        }
+    };
+    validate_html(&html).map_err(|err| ToolError::from_store(&err))?;
+    let meta = PageMeta {
+        seo: SeoMeta {
+            title: seo_title.clone(),
+            seo_title,
+            description,
+            keywords,
+            extra: Default::default(),
+            structured_data,
+        },
+        page_uid: String::new(),
+        created_at: 0,
+        updated_at: 0,
+        view_count: 0,
+        unique_view_count: 0,
+        tags,
+        sort_order: None,
+        published_at: None,
+        extra: Default::default(),
+    };
+    Ok(NewPage {
+        meta,
+        html,
+        markdown,
+    })
 }
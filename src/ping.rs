@@ -0,0 +1,183 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
+use reqwest::{Client, RequestBuilder};
+use serde::Serialize;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::{
+    config::{
+        resolve_auto_ping_enabled_from_env, resolve_indexnow_key_from_env,
+        resolve_site_url_from_env,
+    },
+    web::url_host,
+};
+
+const PING_TIMEOUT: Duration = Duration::from_secs(10);
+const INDEXNOW_ENDPOINT: &str = "https://api.indexnow.org/indexnow";
+/// `ping_search_engines` 对同一搜索引擎的最小 ping 间隔
+pub const SITEMAP_PING_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// 支持 sitemap ping 的搜索引擎
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SearchEngine {
+    Google,
+    Bing,
+}
+
+impl SearchEngine {
+    pub const ALL: [SearchEngine; 2] = [SearchEngine::Google, SearchEngine::Bing];
+
+    /// sitemap ping 地址；Google 已停用该接口并可能返回 404，这里只做尽力通知
+    pub fn ping_endpoint(self) -> &'static str {
+        match self {
+            SearchEngine::Google => "https://www.google.com/ping",
+            SearchEngine::Bing => "https://www.bing.com/ping",
+        }
+    }
+}
+
+/// 单个端点的通知结果，`status` 为空表示请求没有发出或网络失败
+#[derive(Debug, Clone)]
+pub struct PingResult {
+    pub endpoint: String,
+    pub status: Option<u16>,
+    pub error: Option<String>,
+}
+
+impl PingResult {
+    fn skipped(endpoint: &str, reason: &str) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            status: None,
+            error: Some(reason.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexNowPayload<'a> {
+    host: &'a str,
+    key: &'a str,
+    key_location: String,
+    url_list: &'a [String],
+}
+
+/// 依次提交 IndexNow 与各 sitemap ping，单个端点失败只记录在结果中，不返回错误
+/// `page_urls` 必须是完整地址；未设置 `SITE_URL` 时所有端点都会跳过
+pub async fn notify_search_engines(page_urls: &[String]) -> Vec<PingResult> {
+    let site_url = resolve_site_url_from_env();
+    let Some(host) = url_host(&site_url) else {
+        return std::iter::once(INDEXNOW_ENDPOINT)
+            .chain(SearchEngine::ALL.map(SearchEngine::ping_endpoint))
+            .map(|endpoint| PingResult::skipped(endpoint, "SITE_URL is not set"))
+            .collect();
+    };
+    let client = Client::new();
+    let mut results = Vec::new();
+    match resolve_indexnow_key_from_env() {
+        Some(key) => {
+            let payload = IndexNowPayload {
+                host,
+                key: &key,
+                key_location: format!("{}/{}.txt", site_url, key),
+                url_list: page_urls,
+            };
+            let request = client
+                .post(INDEXNOW_ENDPOINT)
+                .timeout(PING_TIMEOUT)
+                .json(&payload);
+            results.push(send_ping(INDEXNOW_ENDPOINT, request).await);
+        }
+        None => results.push(PingResult::skipped(
+            INDEXNOW_ENDPOINT,
+            "INDEXNOW_KEY is not set",
+        )),
+    }
+    for engine in SearchEngine::ALL {
+        results.push(send_sitemap_ping(&client, engine, &site_url).await);
+    }
+    results
+}
+
+/// 只向单个搜索引擎 ping 站点 sitemap；未设置 `SITE_URL` 时跳过
+pub async fn ping_sitemap(engine: SearchEngine) -> PingResult {
+    let site_url = resolve_site_url_from_env();
+    if url_host(&site_url).is_none() {
+        return PingResult::skipped(engine.ping_endpoint(), "SITE_URL is not set");
+    }
+    send_sitemap_ping(&Client::new(), engine, &site_url).await
+}
+
+/// 未开启 `AUTO_PING` 时直接返回；否则在后台任务中通知，结果只写日志，不影响发布
+pub fn spawn_auto_ping(page_url: &str) {
+    if !resolve_auto_ping_enabled_from_env() {
+        return;
+    }
+    let page_urls = vec![page_url.to_string()];
+    tokio::spawn(async move {
+        for result in notify_search_engines(&page_urls).await {
+            match (result.status, result.error) {
+                (Some(status), None) => info!(
+                    "search engine ping {} ok (status {status})",
+                    result.endpoint
+                ),
+                (_, error) => warn!(
+                    "search engine ping {} failed: {}",
+                    result.endpoint,
+                    error.unwrap_or_default()
+                ),
+            }
+        }
+    });
+}
+
+/// 记录本次 ping 时间；距上次 ping 不足 `SITEMAP_PING_INTERVAL` 时返回剩余等待时间
+pub async fn reserve_sitemap_ping(
+    history: &Mutex<HashMap<SearchEngine, Instant>>,
+    engine: SearchEngine,
+) -> Result<(), Duration> {
+    let mut history = history.lock().await;
+    let now = Instant::now();
+    if let Some(last) = history.get(&engine) {
+        let elapsed = now.duration_since(*last);
+        if elapsed < SITEMAP_PING_INTERVAL {
+            return Err(SITEMAP_PING_INTERVAL - elapsed);
+        }
+    }
+    history.insert(engine, now);
+    Ok(())
+}
+
+async fn send_sitemap_ping(client: &Client, engine: SearchEngine, site_url: &str) -> PingResult {
+    let sitemap_url = format!("{}/sitemap.xml", site_url);
+    let sitemap_url = utf8_percent_encode(&sitemap_url, NON_ALPHANUMERIC);
+    let endpoint = engine.ping_endpoint();
+    let request = client
+        .get(format!("{}?sitemap={}", endpoint, sitemap_url))
+        .timeout(PING_TIMEOUT);
+    send_ping(endpoint, request).await
+}
+
+async fn send_ping(endpoint: &str, request: RequestBuilder) -> PingResult {
+    match request.send().await {
+        Ok(response) => {
+            let status = response.status();
+            PingResult {
+                endpoint: endpoint.to_string(),
+                status: Some(status.as_u16()),
+                error: (!status.is_success()).then(|| format!("status {}", status)),
+            }
+        }
+        Err(err) => PingResult {
+            endpoint: endpoint.to_string(),
+            status: None,
+            error: Some(err.to_string()),
+        },
+    }
+}
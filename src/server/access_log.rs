@@ -0,0 +1,263 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, ErrorKind, Write},
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+        mpsc::{Receiver, SyncSender, TrySendError, sync_channel},
+    },
+    time::Instant,
+};
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{
+        HeaderMap, Request,
+        header::{CONTENT_LENGTH, REFERER, USER_AGENT},
+    },
+    middleware::Next,
+    response::Response,
+};
+use chrono::Utc;
+use tracing::{info, warn};
+
+use crate::{
+    config::resolve_trust_proxy_from_env,
+    server::{
+        health::is_health_path,
+        middleware::{RedactedUri, request_client_ip},
+    },
+};
+
+const ACCESS_LOG_PATH: &str = "logs/access.log";
+
+/// 写入线程处理不过来时最多积压的日志行数，超出的行直接丢弃，不阻塞请求
+const ACCESS_LOG_QUEUE_CAPACITY: usize = 4096;
+
+/// 访问日志配置，启动时从环境变量读取
+/// - `ACCESS_LOG`：设为 `off` 时关闭，默认开启，写入 `logs/access.log`
+/// - `ACCESS_LOG_MAX_SIZE_MB`：单个文件大小上限，超出后轮转，默认 `10`
+/// - `ACCESS_LOG_MAX_FILES`：保留的已轮转文件数（`access.log.1` 最新），默认 `7`
+///
+/// 每行为 combined 格式并在末尾追加耗时毫秒数；客户端 IP 取连接的对端地址，
+/// `TRUST_PROXY=true` 时优先取 `X-Real-IP` / `X-Forwarded-For`
+#[derive(Debug, Clone)]
+pub struct AccessLog {
+    sender: SyncSender<String>,
+    trust_proxy: bool,
+    dropped: Arc<AtomicU64>,
+}
+
+impl AccessLog {
+    /// 在独立线程中写文件；`max_files` 为 0 时轮转直接丢弃旧内容
+    pub fn open(path: impl Into<PathBuf>, max_bytes: u64, max_files: usize) -> io::Result<Self> {
+        let mut writer = RotatingFile::open(path.into(), max_bytes, max_files)?;
+        let (sender, receiver) = sync_channel(ACCESS_LOG_QUEUE_CAPACITY);
+        std::thread::Builder::new()
+            .name("access-log".to_string())
+            .spawn(move || writer.run(receiver))?;
+        Ok(Self {
+            sender,
+            trust_proxy: false,
+            dropped: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    /// `ACCESS_LOG=off` 或日志文件无法打开时返回 `None`，不挂载中间件
+    pub fn from_env() -> Option<Self> {
+        let switch = std::env::var("ACCESS_LOG").unwrap_or_default();
+        if switch.trim().eq_ignore_ascii_case("off") {
+            return None;
+        }
+        let max_size_mb = read_access_log_env("ACCESS_LOG_MAX_SIZE_MB", 10);
+        let max_files = read_access_log_env("ACCESS_LOG_MAX_FILES", 7);
+        match Self::open(
+            ACCESS_LOG_PATH,
+            max_size_mb.max(1) * 1024 * 1024,
+            max_files as usize,
+        ) {
+            Ok(mut log) => {
+                log.trust_proxy = resolve_trust_proxy_from_env();
+                info!("access log enabled: {ACCESS_LOG_PATH}");
+                Some(log)
+            }
+            Err(err) => {
+                warn!("open {ACCESS_LOG_PATH} failed, access log disabled: {err}");
+                None
+            }
+        }
+    }
+
+    fn push(&self, line: String) {
+        match self.sender.try_send(line) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                // 只在第一次及之后每 1000 行提示一次，避免丢日志时刷屏
+                let dropped = self.dropped.fetch_add(1, Ordering::Relaxed);
+                if dropped.is_multiple_of(1000) {
+                    warn!("access log queue full, {} lines dropped", dropped + 1);
+                }
+            }
+            Err(TrySendError::Disconnected(_)) => {}
+        }
+    }
+}
+
+/// 数值不合法时打印警告并使用默认值
+fn read_access_log_env(key: &str, default_value: u64) -> u64 {
+    let value = std::env::var(key).unwrap_or_default();
+    let value = value.trim();
+    if value.is_empty() {
+        return default_value;
+    }
+    match value.parse::<u64>() {
+        Ok(parsed) => parsed,
+        Err(_) => {
+            warn!("{key} must be a non-negative integer, using {default_value}");
+            default_value
+        }
+    }
+}
+
+/// 记录一行访问日志（健康检查探针除外）；日志只进入内存队列，由写入线程落盘
+pub async fn access_log(State(log): State<AccessLog>, req: Request<Body>, next: Next) -> Response {
+    if is_health_path(req.uri().path()) {
+        return next.run(req).await;
+    }
+    let started = Instant::now();
+    let ip = request_client_ip(&req, log.trust_proxy)
+        .map(|ip| ip.to_string())
+        .unwrap_or_else(|| "-".to_string());
+    // 请求日志中间件已把路径中的 token 脱敏
+    let uri = req
+        .extensions()
+        .get::<RedactedUri>()
+        .map_or_else(|| req.uri().to_string(), |RedactedUri(uri)| uri.clone());
+    let request_line = format!("{} {uri} {:?}", req.method(), req.version());
+    let referer = quoted_header(req.headers(), REFERER.as_str());
+    let user_agent = quoted_header(req.headers(), USER_AGENT.as_str());
+    let response = next.run(req).await;
+    let size = response
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("-");
+    log.push(format!(
+        "{ip} - - [{}] \"{}\" {} {size} \"{referer}\" \"{user_agent}\" {}\n",
+        Utc::now().format("%d/%b/%Y:%H:%M:%S %z"),
+        escape_log_field(&request_line),
+        response.status().as_u16(),
+        started.elapsed().as_millis()
+    ));
+    response
+}
+
+fn quoted_header(headers: &HeaderMap, name: &str) -> String {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.is_empty())
+        .map(escape_log_field)
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// 转义双引号与反斜杠并去掉控制字符，防止伪造日志行
+fn escape_log_field(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            ch if ch.is_control() => {}
+            ch => out.push(ch),
+        }
+    }
+    out
+}
+
+/// 按大小轮转的日志文件：`access.log` 写满后依次改名为 `access.log.1`、`access.log.2`……
+struct RotatingFile {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    file: File,
+    written: u64,
+}
+
+impl RotatingFile {
+    fn open(path: PathBuf, max_bytes: u64, max_files: usize) -> io::Result<Self> {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)?;
+        }
+        let file = open_append(&path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            path,
+            max_bytes,
+            max_files,
+            file,
+            written,
+        })
+    }
+
+    fn run(&mut self, receiver: Receiver<String>) {
+        while let Ok(line) = receiver.recv() {
+            if let Err(err) = self.write_line(&line) {
+                warn!("write access log failed: {err}");
+            }
+        }
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        if self.written > 0 && self.written + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.written += line.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        if self.max_files == 0 {
+            self.file.set_len(0)?;
+        } else {
+            remove_if_exists(&self.rotated_path(self.max_files))?;
+            for index in (1..self.max_files).rev() {
+                rename_if_exists(&self.rotated_path(index), &self.rotated_path(index + 1))?;
+            }
+            fs::rename(&self.path, self.rotated_path(1))?;
+            self.file = open_append(&self.path)?;
+        }
+        self.written = 0;
+        Ok(())
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut name = self.path.as_os_str().to_os_string();
+        name.push(format!(".{index}"));
+        PathBuf::from(name)
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+fn rename_if_exists(from: &Path, to: &Path) -> io::Result<()> {
+    match fs::rename(from, to) {
+        Err(err) if err.kind() != ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
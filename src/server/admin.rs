@@ -0,0 +1,100 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::{
+    Json,
+    extract::State,
+    http::{HeaderMap, StatusCode, header::AUTHORIZATION},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use tracing::{error, info};
+
+use crate::store::PageStoreTrait;
+
+#[derive(Debug, Serialize)]
+struct RebuildIndexResponse {
+    success: bool,
+    page_count: Option<usize>,
+    page_ids: Option<Vec<String>>,
+    elapsed_ms: Option<u128>,
+    error: Option<String>,
+}
+
+/// `POST /admin/rebuild-index`：异步重建 index.json，需携带 `Authorization: Bearer {ADMIN_TOKEN}`
+pub async fn rebuild_index_handler(
+    State(store): State<Arc<dyn PageStoreTrait>>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(response) = authorize_admin(&headers) {
+        return *response;
+    }
+    let started = Instant::now();
+    match store.rebuild_index().await {
+        Ok(index) => {
+            info!("admin rebuild index: {} pages", index.pages.len());
+            Json(RebuildIndexResponse {
+                success: true,
+                page_count: Some(index.pages.len()),
+                page_ids: Some(index.pages.into_keys().collect()),
+                elapsed_ms: Some(started.elapsed().as_millis()),
+                error: None,
+            })
+            .into_response()
+        }
+        Err(err) => {
+            error!("admin rebuild index failed: {err:#}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(RebuildIndexResponse {
+                    success: false,
+                    page_count: None,
+                    page_ids: None,
+                    elapsed_ms: Some(started.elapsed().as_millis()),
+                    error: Some(format!("rebuild index failed: {err:#}")),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// 未配置 `ADMIN_TOKEN` 时管理接口整体关闭，返回 404
+fn authorize_admin(headers: &HeaderMap) -> Result<(), Box<Response>> {
+    authorize_bearer(headers, "ADMIN_TOKEN", "invalid admin token")
+}
+
+/// 校验 `Authorization: Bearer {token}`，令牌取自环境变量 `token_env`，未配置时返回 404
+pub(crate) fn authorize_bearer(
+    headers: &HeaderMap,
+    token_env: &str,
+    invalid_message: &'static str,
+) -> Result<(), Box<Response>> {
+    let expected = std::env::var(token_env).unwrap_or_default();
+    let expected = expected.trim();
+    if expected.is_empty() {
+        return Err(Box::new(StatusCode::NOT_FOUND.into_response()));
+    }
+    let provided = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().strip_prefix("Bearer "))
+        .map(str::trim)
+        .unwrap_or_default();
+    if !constant_time_eq(provided.as_bytes(), expected.as_bytes()) {
+        return Err(Box::new(
+            (StatusCode::UNAUTHORIZED, invalid_message).into_response(),
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    if left.len() != right.len() {
+        return false;
+    }
+    left.iter()
+        .zip(right)
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+        == 0
+}
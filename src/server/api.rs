@@ -0,0 +1,512 @@
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, LazyLock, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    Extension, Json,
+    body::Bytes,
+    extract::{ConnectInfo, Path, Query, State, rejection::JsonRejection},
+    http::{
+        HeaderMap, HeaderValue, StatusCode,
+        header::{
+            ACCESS_CONTROL_ALLOW_ORIGIN, CACHE_CONTROL, CONTENT_TYPE, ETAG, IF_NONE_MATCH,
+            RETRY_AFTER,
+        },
+    },
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::resolve_trust_proxy_from_env,
+    mcp::{
+        dto::{
+            DeletePageResponse, ErrorCode, ErrorFields, GetPageByIdResponse, PageIdRequest,
+            PageListResponse, PageWithHtml, PageWithMeta, PushPageRequest, ToolError,
+            UpdatePageRequest,
+        },
+        tools::{build_page_full_url, delete_page_by_uid, push_html_page, update_html_page},
+    },
+    server::{
+        admin::authorize_bearer,
+        handlers::resolve_base_url,
+        middleware::{ClientLru, resolve_client_ip},
+    },
+    store::{PageStoreTrait, validate_html},
+    web::{is_page_public, render_page_html, scan_html_security},
+};
+
+const DEFAULT_PER_PAGE: usize = 20;
+const MAX_PER_PAGE: usize = 100;
+const LIST_CACHE_CONTROL: &str = "public, max-age=60";
+const PAGE_CACHE_CONTROL: &str = "public, max-age=300";
+
+/// `POST /api/pages/{page_uid}/validate` 每个客户端 IP 每分钟的请求上限
+const VALIDATE_RATE_LIMIT: u32 = 30;
+const VALIDATE_RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// 校验限流最多跟踪的客户端 IP 数，超出时淘汰最久未访问的 IP
+const VALIDATE_RATE_MAX_CLIENTS: usize = 10_000;
+
+/// 固定窗口计数：IP → (窗口开始时间, 请求数)
+static VALIDATE_RATE: LazyLock<Mutex<ClientLru<IpAddr, (Instant, u32)>>> =
+    LazyLock::new(|| Mutex::new(ClientLru::new(VALIDATE_RATE_MAX_CLIENTS)));
+
+#[derive(Debug, Deserialize)]
+pub struct PageListQuery {
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PageDetailQuery {
+    /// rendered（默认，含 SEO 注入）或 raw（推送时的原始 HTML）
+    pub format: Option<String>,
+}
+
+/// `GET /api/pages`：按 `updated_at` 倒序分页列出公开页面
+pub async fn api_page_list_handler(
+    State(store): State<Arc<dyn PageStoreTrait>>,
+    Query(query): Query<PageListQuery>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query
+        .per_page
+        .unwrap_or(DEFAULT_PER_PAGE)
+        .clamp(1, MAX_PER_PAGE);
+    let entries = match store.list_page_entries().await {
+        Ok(entries) => entries,
+        Err(err) => {
+            return api_json_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                PageListResponse {
+                    success: false,
+                    pages: Vec::new(),
+                    page,
+                    per_page,
+                    total: 0,
+                    error: ToolError::from_store(&err).into(),
+                },
+                None,
+            );
+        }
+    };
+
+    let mut metas = Vec::new();
+    for entry in entries {
+        if let Ok(meta) = store.get_page_meta(&entry.page_id).await
+            && is_page_public(&meta)
+        {
+            metas.push(meta);
+        }
+    }
+    metas.sort_by(|left, right| {
+        right
+            .updated_at
+            .cmp(&left.updated_at)
+            .then_with(|| right.created_at.cmp(&left.created_at))
+            .then_with(|| right.page_uid.cmp(&left.page_uid))
+    });
+
+    let total = metas.len();
+    let base_url = resolve_base_url(&headers);
+    let pages = metas
+        .into_iter()
+        .skip((page - 1).saturating_mul(per_page))
+        .take(per_page)
+        .map(|meta| PageWithMeta {
+            page_id: meta.page_uid.clone(),
+            url: build_page_full_url(&base_url, &meta.page_uid, &meta.seo.seo_title),
+            meta: meta.into(),
+        })
+        .collect();
+
+    api_json_response(
+        StatusCode::OK,
+        PageListResponse {
+            success: true,
+            pages,
+            page,
+            per_page,
+            total,
+            error: Default::default(),
+        },
+        Some(LIST_CACHE_CONTROL),
+    )
+}
+
+/// `GET /api/pages/{page_uid}`：返回页面 meta 与 HTML，`?format=raw` 返回原始 HTML
+pub async fn api_page_detail_handler(
+    State(store): State<Arc<dyn PageStoreTrait>>,
+    Path(page_uid): Path<String>,
+    Query(query): Query<PageDetailQuery>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let raw = match query.format.as_deref().map(str::trim) {
+        None | Some("") | Some("rendered") => false,
+        Some("raw") => true,
+        Some(other) => {
+            return api_page_error(
+                StatusCode::BAD_REQUEST,
+                ToolError::validation(format!(
+                    "unsupported format: {} (expected rendered|raw)",
+                    other
+                )),
+            );
+        }
+    };
+
+    let page_id = match store.resolve_page_id_by_uid(&page_uid).await {
+        Ok(Some(page_id)) => page_id,
+        Ok(None) => return page_not_found_error(),
+        Err(err) => {
+            return api_page_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ToolError::from_store(&err),
+            );
+        }
+    };
+    let (meta, html) = match store.load_page(&page_id).await {
+        Ok(page) => page,
+        Err(_err) => return page_not_found_error(),
+    };
+    // 未公开页面与不存在的页面返回相同的 404，避免泄露其存在
+    if !is_page_public(&meta) {
+        return page_not_found_error();
+    }
+
+    let html = if raw {
+        html
+    } else {
+        render_page_html(&meta, &html)
+    };
+    let base_url = resolve_base_url(&headers);
+    let page = PageWithHtml {
+        page_id: meta.page_uid.clone(),
+        url: build_page_full_url(&base_url, &meta.page_uid, &meta.seo.seo_title),
+        meta: Some(meta.into()),
+        fields: None,
+        html: Some(html),
+        truncated: false,
+    };
+    api_json_response(
+        StatusCode::OK,
+        GetPageByIdResponse {
+            success: true,
+            pages: vec![page],
+            error: Default::default(),
+        },
+        Some(PAGE_CACHE_CONTROL),
+    )
+}
+
+/// `GET /api/pages/{page_uid}/html`：原样返回存储的 index.html（不注入 SEO），需携带 API token；
+/// ETag 取自 `updated_at`，`If-None-Match` 命中时返回 304。草稿与取消发布的页面同样可读
+pub async fn page_html_api_handler(
+    State(store): State<Arc<dyn PageStoreTrait>>,
+    Path(page_uid): Path<String>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(response) = authorize_api(&headers) {
+        return *response;
+    }
+    let page_id = match store.resolve_page_id_by_uid(&page_uid).await {
+        Ok(Some(page_id)) => page_id,
+        Ok(None) => return page_not_found_error(),
+        Err(err) => {
+            return api_page_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ToolError::from_store(&err),
+            );
+        }
+    };
+    let (meta, html) = match store.load_page(&page_id).await {
+        Ok(page) => page,
+        Err(_err) => return page_not_found_error(),
+    };
+
+    let etag = format!("\"{}\"", meta.updated_at);
+    let mut response = if if_none_match(&headers, &etag) {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        ([(CONTENT_TYPE, "text/html; charset=utf-8")], html).into_response()
+    };
+    let response_headers = response.headers_mut();
+    response_headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, HeaderValue::from_static("*"));
+    response_headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        response_headers.insert(ETAG, value);
+    }
+    response
+}
+
+/// `If-None-Match` 可为逗号分隔的多个 ETag 或 `*`，弱校验前缀 `W/` 视为相同
+fn if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    let Some(value) = headers
+        .get(IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return false;
+    };
+    value
+        .split(',')
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ValidateHtmlBody {
+    pub html: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ValidateHtmlResult {
+    pub valid: bool,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// `POST /api/pages/{page_uid}/validate`：校验请求体中的 HTML，不读写页面，`page_uid` 不必存在；
+/// 请求体为原始 HTML，`Content-Type: application/json` 时为 `{ "html": "..." }`
+pub async fn validate_html_api_handler(
+    Path(_page_uid): Path<String>,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    let peer = connect_info.map(|Extension(ConnectInfo(addr))| addr);
+    // 取不到客户端 IP（未使用 ConnectInfo）时不限流
+    let client = resolve_client_ip(&headers, peer, resolve_trust_proxy_from_env());
+    let limited = client.and_then(|client| check_validate_rate(client, Instant::now()));
+    if let Some(retry_after) = limited {
+        let mut response = api_json_response(
+            StatusCode::TOO_MANY_REQUESTS,
+            ValidateHtmlResult {
+                valid: false,
+                errors: vec!["too many requests, limit is 30 per minute".to_string()],
+                warnings: Vec::new(),
+            },
+            None,
+        );
+        response
+            .headers_mut()
+            .insert(RETRY_AFTER, retry_after.into());
+        return response;
+    }
+
+    let is_json = headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.trim().starts_with("application/json"));
+    let html = if is_json {
+        serde_json::from_slice::<ValidateHtmlBody>(&body)
+            .map(|body| body.html)
+            .map_err(|err| format!("invalid json body: {err}"))
+    } else {
+        String::from_utf8(body.to_vec()).map_err(|_| "body is not valid utf-8".to_string())
+    };
+    let html = match html {
+        Ok(html) => html,
+        Err(message) => {
+            return api_json_response(
+                StatusCode::BAD_REQUEST,
+                ValidateHtmlResult {
+                    valid: false,
+                    errors: vec![message],
+                    warnings: Vec::new(),
+                },
+                None,
+            );
+        }
+    };
+
+    let errors = match validate_html(&html) {
+        Ok(()) => Vec::new(),
+        Err(err) => vec![err.to_string()],
+    };
+    let result = ValidateHtmlResult {
+        valid: errors.is_empty(),
+        errors,
+        warnings: scan_html_security(&html),
+    };
+    api_json_response(StatusCode::OK, result, None)
+}
+
+/// `POST /api/pages/{page_uid}/validate` 的限流，超限时返回距窗口结束的秒数；
+/// 过期窗口在再次访问时重置，不做整表清理
+pub fn check_validate_rate(client: IpAddr, now: Instant) -> Option<u64> {
+    let mut windows = VALIDATE_RATE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let (started, count) = windows.get_or_insert_with(client, || (now, 0));
+    if now.saturating_duration_since(*started) >= VALIDATE_RATE_WINDOW {
+        *started = now;
+        *count = 0;
+    }
+    if *count >= VALIDATE_RATE_LIMIT {
+        let remaining =
+            VALIDATE_RATE_WINDOW.saturating_sub(now.saturating_duration_since(*started));
+        return Some(remaining.as_secs().max(1));
+    }
+    *count += 1;
+    None
+}
+
+/// `PUT /api/pages/{page_uid}` 的请求体，字段与 `update_page` 工具一致，页面 ID 取自路径
+#[derive(Debug, Deserialize)]
+pub struct UpdatePageBody {
+    pub seo_title: Option<String>,
+    pub description: Option<String>,
+    pub keywords: Option<Vec<String>>,
+    pub tags: Option<Vec<String>>,
+    pub html: Option<String>,
+    pub head_html: Option<String>,
+    pub noindex: Option<bool>,
+    pub expected_updated_at: Option<i64>,
+    pub structured_data: Option<serde_json::Value>,
+}
+
+/// `POST /api/pages`：创建 HTML 页面，需携带 `Authorization: Bearer {API_TOKEN}`，
+/// 请求体与 `push_page` 工具一致；新建返回 201，幂等键命中返回 200
+pub async fn create_page_api_handler(
+    State(store): State<Arc<dyn PageStoreTrait>>,
+    headers: HeaderMap,
+    body: Result<Json<PushPageRequest>, JsonRejection>,
+) -> impl IntoResponse {
+    if let Err(response) = authorize_api(&headers) {
+        return *response;
+    }
+    let Json(params) = match body {
+        Ok(body) => body,
+        Err(rejection) => return invalid_body_response(rejection),
+    };
+    let response = push_html_page(store.as_ref(), params).await;
+    let status = match (response.success, response.deduplicated) {
+        (true, true) => StatusCode::OK,
+        (true, false) => StatusCode::CREATED,
+        (false, _) => error_status(&response.error),
+    };
+    api_json_response(status, response, None)
+}
+
+/// `PUT /api/pages/{page_uid}`：更新页面，未传的字段保持不变
+pub async fn update_page_api_handler(
+    State(store): State<Arc<dyn PageStoreTrait>>,
+    Path(page_uid): Path<String>,
+    headers: HeaderMap,
+    body: Result<Json<UpdatePageBody>, JsonRejection>,
+) -> impl IntoResponse {
+    if let Err(response) = authorize_api(&headers) {
+        return *response;
+    }
+    let Json(body) = match body {
+        Ok(body) => body,
+        Err(rejection) => return invalid_body_response(rejection),
+    };
+    let params = UpdatePageRequest {
+        page_id: page_uid,
+        seo_title: body.seo_title,
+        description: body.description,
+        keywords: body.keywords,
+        tags: body.tags,
+        html: body.html,
+        head_html: body.head_html,
+        noindex: body.noindex,
+        expected_updated_at: body.expected_updated_at,
+        structured_data: body.structured_data,
+    };
+    let response = update_html_page(store.as_ref(), params).await;
+    let status = if response.success {
+        StatusCode::OK
+    } else {
+        error_status(&response.error)
+    };
+    api_json_response(status, response, None)
+}
+
+/// `DELETE /api/pages/{page_uid}`：删除页面及其资源
+pub async fn delete_page_api_handler(
+    State(store): State<Arc<dyn PageStoreTrait>>,
+    Path(page_uid): Path<String>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(response) = authorize_api(&headers) {
+        return *response;
+    }
+    let params = PageIdRequest { page_id: page_uid };
+    let response = delete_page_by_uid(store.as_ref(), params).await;
+    let status = if response.success {
+        StatusCode::OK
+    } else {
+        error_status(&response.error)
+    };
+    api_json_response(status, response, None)
+}
+
+/// 未配置 `API_TOKEN` 时写接口整体关闭，返回 404
+fn authorize_api(headers: &HeaderMap) -> Result<(), Box<Response>> {
+    authorize_bearer(headers, "API_TOKEN", "invalid api token")
+}
+
+/// 请求体不是合法 JSON 或缺少必填字段，响应只含 `success` 与错误字段
+fn invalid_body_response(rejection: JsonRejection) -> Response {
+    let error = ToolError::validation(format!("invalid request body: {}", rejection.body_text()));
+    api_json_response(
+        StatusCode::BAD_REQUEST,
+        DeletePageResponse {
+            success: false,
+            error: error.into(),
+        },
+        None,
+    )
+}
+
+pub(crate) fn error_status(error: &ErrorFields) -> StatusCode {
+    match error.error_info.as_ref().map(|info| info.code) {
+        Some(ErrorCode::NotFound) => StatusCode::NOT_FOUND,
+        Some(ErrorCode::ValidationFailed) => StatusCode::BAD_REQUEST,
+        Some(ErrorCode::Conflict) => StatusCode::CONFLICT,
+        Some(ErrorCode::TooLarge) => StatusCode::PAYLOAD_TOO_LARGE,
+        Some(ErrorCode::PermissionDenied) => StatusCode::FORBIDDEN,
+        Some(ErrorCode::Timeout) => StatusCode::REQUEST_TIMEOUT,
+        Some(ErrorCode::Io) | Some(ErrorCode::Internal) | None => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+fn api_page_error(status: StatusCode, error: ToolError) -> Response {
+    api_json_response(
+        status,
+        GetPageByIdResponse {
+            success: false,
+            pages: Vec::new(),
+            error: error.into(),
+        },
+        None,
+    )
+}
+
+fn page_not_found_error() -> Response {
+    api_page_error(
+        StatusCode::NOT_FOUND,
+        ToolError::not_found("page not found"),
+    )
+}
+
+/// 公开 API 统一加上宽松 CORS；错误响应使用 no-store 避免 404 被缓存
+fn api_json_response<T: Serialize>(
+    status: StatusCode,
+    body: T,
+    cache_control: Option<&'static str>,
+) -> Response {
+    let mut response = (status, Json(body)).into_response();
+    let headers = response.headers_mut();
+    headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, HeaderValue::from_static("*"));
+    headers.insert(
+        CACHE_CONTROL,
+        HeaderValue::from_static(cache_control.unwrap_or("no-store")),
+    );
+    response
+}
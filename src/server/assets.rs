@@ -1,15 +1,29 @@
-use std::path::{Component, Path as FsPath, PathBuf};
+use std::{
+    collections::BTreeMap,
+    path::{Component, Path as FsPath, PathBuf},
+};
 
 use axum::{
-    extract::Path,
-    http::{StatusCode, header::CONTENT_TYPE},
-    response::{Html, IntoResponse},
+    extract::{Path, RawQuery},
+    http::{
+        HeaderMap, HeaderValue, StatusCode,
+        header::{ACCEPT, CACHE_CONTROL, CONTENT_TYPE, VARY},
+    },
+    response::{Html, IntoResponse, Response},
 };
 use mime_guess::MimeGuess;
 
-use crate::web::render_404_html;
+use crate::web::{
+    file_fingerprint, is_fingerprinted_asset_name, load_asset_manifest, render_404_html,
+    render_web_manifest,
+};
 
-pub async fn public_asset_handler(Path(path): Path<String>) -> impl IntoResponse {
+/// `/public/{path}`：文件名带指纹或 `?v=` 与当前内容指纹一致时长期缓存，其余只短期缓存
+pub async fn public_asset_handler(
+    Path(path): Path<String>,
+    RawQuery(query): RawQuery,
+    headers: HeaderMap,
+) -> Response {
     if path.is_empty() {
         return match render_404_html() {
             Ok(html) => (StatusCode::NOT_FOUND, Html(html)).into_response(),
@@ -20,7 +34,7 @@ pub async fn public_asset_handler(Path(path): Path<String>) -> impl IntoResponse
                 .into_response(),
         };
     }
-    let Ok(safe_path) = sanitize_public_path(&path) else {
+    let Some(safe_path) = sanitize_public_path(&path) else {
         return match render_404_html() {
             Ok(html) => (StatusCode::NOT_FOUND, Html(html)).into_response(),
             Err(err) => (
@@ -30,7 +44,22 @@ pub async fn public_asset_handler(Path(path): Path<String>) -> impl IntoResponse
                 .into_response(),
         };
     };
-    let full_path = PathBuf::from("public").join(&safe_path);
+    let manifest = load_asset_manifest();
+    let mut full_path = PathBuf::from("public").join(&safe_path);
+    if !full_path.is_file()
+        && let Some(front_path) = resolve_front_asset(&safe_path, &manifest)
+    {
+        full_path = front_path;
+    }
+    // 旧页面中的 `?v=` 可能已过期，只有与当前内容一致时才按不可变资源缓存
+    let versioned = requested_version(query.as_deref()).is_some_and(|version| {
+        file_fingerprint(&full_path).is_some_and(|current| current == version)
+    });
+    // 特殊图片的 PNG 地址按 `Accept` 协商，支持 WebP 的浏览器拿到 build.rs 生成的 `.webp`
+    let webp = webp_variant(&full_path);
+    if let Some(webp) = webp.as_ref().filter(|_| accepts_webp(&headers)) {
+        full_path = webp.clone();
+    }
     let data = match std::fs::read(&full_path) {
         Ok(data) => data,
         Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
@@ -52,30 +81,265 @@ pub async fn public_asset_handler(Path(path): Path<String>) -> impl IntoResponse
         }
     };
     let mime = guess_mime_type(&full_path);
-    ([(CONTENT_TYPE, mime.as_ref())], data).into_response()
+    let mut response = ([(CONTENT_TYPE, mime.as_ref())], data).into_response();
+    let cache_control = if versioned || is_fingerprinted_path(&safe_path, &manifest) {
+        FINGERPRINTED_CACHE_CONTROL
+    } else {
+        UNVERSIONED_CACHE_CONTROL
+    };
+    response
+        .headers_mut()
+        .insert(CACHE_CONTROL, HeaderValue::from_static(cache_control));
+    if webp.is_some() {
+        response
+            .headers_mut()
+            .insert(VARY, HeaderValue::from_static("Accept"));
+    }
+    response
+}
+
+/// build.rs 为这些特殊图片生成了同名 `.webp`，与 build.rs 的 `SPECIAL_IMAGES` 保持一致
+const WEBP_VARIANT_IMAGES: [&str; 3] = ["icon", "light", "night"];
+
+/// `public/{特殊图片}.png` 存在对应 `.webp` 时返回 WebP 的路径
+fn webp_variant(png_path: &FsPath) -> Option<PathBuf> {
+    let stem = png_path.file_stem()?.to_str()?;
+    let is_special_png = png_path.parent() == Some(FsPath::new("public"))
+        && png_path.extension().is_some_and(|ext| ext == "png")
+        && WEBP_VARIANT_IMAGES.contains(&stem);
+    let webp = png_path.with_extension("webp");
+    (is_special_png && webp.is_file()).then_some(webp)
+}
+
+/// `Accept` 中列出了 `image/webp` 且 q 值不为 0
+fn accepts_webp(headers: &HeaderMap) -> bool {
+    let Some(accept) = headers.get(ACCEPT).and_then(|value| value.to_str().ok()) else {
+        return false;
+    };
+    accept.split(',').any(|item| {
+        let mut params = item.split(';');
+        let is_webp = params
+            .next()
+            .is_some_and(|media| media.trim().eq_ignore_ascii_case("image/webp"));
+        is_webp
+            && !params.any(|param| {
+                param
+                    .trim()
+                    .strip_prefix("q=")
+                    .and_then(|q| q.trim().parse::<f32>().ok())
+                    .is_some_and(|q| q <= 0.0)
+            })
+    })
+}
+
+/// 文件名带内容哈希，内容变化时地址随之变化，允许浏览器长期缓存
+const FINGERPRINTED_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+/// 不带指纹的地址，文件替换后最多 5 分钟内生效
+const UNVERSIONED_CACHE_CONTROL: &str = "public, max-age=300";
+
+/// 查询参数中的 `v`，由 `web::asset_url` 生成
+fn requested_version(query: Option<&str>) -> Option<&str> {
+    query?
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("v="))
+        .filter(|version| !version.is_empty())
+}
+
+/// `assets/{name}` 中的文件名，其余路径返回 `None`
+fn assets_file_name(safe_path: &FsPath) -> Option<&str> {
+    let mut components = safe_path.components();
+    if components.next()? != Component::Normal("assets".as_ref()) {
+        return None;
+    }
+    let name = components.next()?.as_os_str().to_str()?;
+    components.next().is_none().then_some(name)
+}
+
+fn is_fingerprinted_path(safe_path: &FsPath, manifest: &BTreeMap<String, String>) -> bool {
+    assets_file_name(safe_path).is_some_and(|name| manifest.values().any(|hashed| hashed == name))
+}
+
+/// build.rs 复制到 `public/assets/` 的指纹文件不存在时（例如挂载了宿主机的 `public/`），
+/// 按 `front/manifest.json` 把指纹文件名映射回 `front/` 下的原文件；不带指纹的原文件名同样映射
+fn resolve_front_asset(safe_path: &FsPath, manifest: &BTreeMap<String, String>) -> Option<PathBuf> {
+    let name = assets_file_name(safe_path)?;
+    let original = manifest
+        .iter()
+        .find(|(_, hashed)| hashed.as_str() == name)
+        .map_or(name, |(original, _)| original.as_str());
+    is_fingerprinted_asset_name(original).then(|| PathBuf::from("front").join(original))
+}
+
+/// 图标文件极少变化，允许浏览器缓存 7 天
+const ICON_CACHE_CONTROL: &str = "public, max-age=604800";
+const ICON_PNG_PATH: &str = "public/icon.png";
+const ICON_WEBP_PATH: &str = "public/icon.webp";
+const APPLE_TOUCH_ICON_PATH: &str = "public/apple-touch-icon.png";
+
+pub async fn favicon_handler() -> impl IntoResponse {
+    let Some(png) = read_icon_file(ICON_PNG_PATH) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let Some(ico) = png_to_ico(&png) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    (
+        [
+            (CONTENT_TYPE, "image/x-icon"),
+            (CACHE_CONTROL, ICON_CACHE_CONTROL),
+        ],
+        ico,
+    )
+        .into_response()
 }
 
-pub fn sanitize_public_path(raw: &str) -> Result<PathBuf, ()> {
+/// 支持 WebP 的浏览器优先拿到 `public/icon.webp`，其余返回 PNG
+pub async fn icon_png_handler(headers: HeaderMap) -> impl IntoResponse {
+    let webp = accepts_webp(&headers)
+        .then(|| read_icon_file(ICON_WEBP_PATH))
+        .flatten();
+    let mut response = match webp {
+        Some(data) => icon_response(Some(data), "image/webp"),
+        None => icon_png_response(read_icon_file(ICON_PNG_PATH)),
+    };
+    if FsPath::new(ICON_WEBP_PATH).is_file() {
+        response
+            .headers_mut()
+            .insert(VARY, HeaderValue::from_static("Accept"));
+    }
+    response
+}
+
+pub async fn icon_webp_handler() -> impl IntoResponse {
+    icon_response(read_icon_file(ICON_WEBP_PATH), "image/webp")
+}
+
+pub async fn apple_touch_icon_handler() -> impl IntoResponse {
+    let data = read_icon_file(APPLE_TOUCH_ICON_PATH).or_else(|| read_icon_file(ICON_PNG_PATH));
+    icon_png_response(data)
+}
+
+fn icon_png_response(data: Option<Vec<u8>>) -> Response {
+    icon_response(data, "image/png")
+}
+
+fn icon_response(data: Option<Vec<u8>>, content_type: &'static str) -> Response {
+    match data {
+        Some(data) => (
+            [
+                (CONTENT_TYPE, content_type),
+                (CACHE_CONTROL, ICON_CACHE_CONTROL),
+            ],
+            data,
+        )
+            .into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// 图标缺失时静默返回 None，避免浏览器每次访问都刷错误日志
+fn read_icon_file(path: &str) -> Option<Vec<u8>> {
+    std::fs::read(path).ok()
+}
+
+/// 将 PNG 原样封装为单图 ICO（Vista 起支持 PNG 编码的 ICO 条目）
+fn png_to_ico(png: &[u8]) -> Option<Vec<u8>> {
+    const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+    if png.len() < 24 || !png.starts_with(PNG_SIGNATURE) || &png[12..16] != b"IHDR" {
+        return None;
+    }
+    let width = u32::from_be_bytes([png[16], png[17], png[18], png[19]]);
+    let height = u32::from_be_bytes([png[20], png[21], png[22], png[23]]);
+    // ICO 中 0 表示 256 及以上
+    let dimension = |value: u32| if value >= 256 { 0u8 } else { value as u8 };
+    let size = u32::try_from(png.len()).ok()?;
+
+    let mut out = Vec::with_capacity(22 + png.len());
+    out.extend_from_slice(&[0, 0, 1, 0, 1, 0]);
+    out.push(dimension(width));
+    out.push(dimension(height));
+    out.push(0); // 调色板数量
+    out.push(0); // 保留
+    out.extend_from_slice(&1u16.to_le_bytes()); // 色彩平面数
+    out.extend_from_slice(&32u16.to_le_bytes()); // 位深
+    out.extend_from_slice(&size.to_le_bytes());
+    out.extend_from_slice(&22u32.to_le_bytes());
+    out.extend_from_slice(png);
+    Some(out)
+}
+
+/// `/manifest.webmanifest`：由环境变量实时生成，不缓存以便配置修改立即生效
+pub async fn web_manifest_handler() -> impl IntoResponse {
+    (
+        [
+            (CONTENT_TYPE, "application/manifest+json"),
+            (CACHE_CONTROL, "no-cache"),
+        ],
+        render_web_manifest(),
+    )
+}
+
+/// `/sw.js`：service worker 必须位于站点根路径才能覆盖全站作用域
+pub async fn service_worker_handler() -> impl IntoResponse {
+    match std::fs::read_to_string("front/sw.js") {
+        Ok(script) => (
+            [
+                (CONTENT_TYPE, "application/javascript; charset=utf-8"),
+                (CACHE_CONTROL, "no-cache"),
+            ],
+            script,
+        )
+            .into_response(),
+        Err(_) => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// 拒绝 `..`、绝对路径等路径段，并解析符号链接：解析后的路径必须仍位于 `public/` 内。
+/// 目标文件（或 `public/` 本身）尚不存在时，检查其最近一层已存在的上级目录
+pub fn sanitize_public_path(raw: &str) -> Option<PathBuf> {
     let mut cleaned = PathBuf::new();
     for segment in raw.split('/') {
         if segment.is_empty() || segment == "." {
             continue;
         }
         if segment == ".." {
-            return Err(());
+            return None;
         }
         let segment_path = FsPath::new(segment);
         let mut segment_components = segment_path.components();
         match segment_components.next() {
             Some(Component::Normal(_)) if segment_components.next().is_none() => {}
-            _ => return Err(()),
+            _ => return None,
         }
         cleaned.push(segment);
     }
     if cleaned.as_os_str().is_empty() {
-        return Err(());
+        return None;
+    }
+    ensure_within_public_dir(&cleaned).ok()?;
+    Some(cleaned)
+}
+
+fn ensure_within_public_dir(relative: &FsPath) -> Result<(), ()> {
+    let root = FsPath::new("public");
+    let canonical_root = match std::fs::canonicalize(root) {
+        Ok(path) => path,
+        // `public/` 尚未创建时其中不可能有符号链接
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(_) => return Err(()),
+    };
+    let full_path = root.join(relative);
+    let existing = full_path
+        .ancestors()
+        .find(|path| path.symlink_metadata().is_ok())
+        .ok_or(())?;
+    let canonical = std::fs::canonicalize(existing).map_err(|_| ())?;
+    if canonical.starts_with(&canonical_root) {
+        Ok(())
+    } else {
+        Err(())
     }
-    Ok(cleaned)
 }
 
 fn guess_mime_type(path: &FsPath) -> mime_guess::Mime {
@@ -0,0 +1,494 @@
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use axum::{
+    Form,
+    extract::{Path, Query, State},
+    http::{HeaderValue, StatusCode, header::CACHE_CONTROL},
+    response::{Html, IntoResponse, Redirect, Response},
+};
+use serde::Deserialize;
+use tracing::error;
+
+use crate::{
+    config::generate_mcp_token,
+    mcp::{
+        dto::{ErrorFields, PageIdRequest, UpdateMarkdownPageRequest, UpdatePageRequest},
+        tools::{delete_page_by_uid, update_html_page, update_markdown_page_by_uid},
+    },
+    server::{admin::constant_time_eq, api::error_status},
+    store::{PageMeta, PageStoreTrait, recent_update_entries},
+    web::{
+        build_page_url, escape_html, escape_html_attr, format_display_timestamp, markdown_to_html,
+        replace_template,
+    },
+};
+
+/// 管理后台挂在 `/{token}/admin` 下，与 MCP 入口共用 token 校验（只接受 read-write token）
+#[derive(Clone)]
+pub struct DashboardState {
+    store: Arc<dyn PageStoreTrait>,
+    /// 启动时随机生成，所有表单共用；重启后旧页面上的表单需要刷新后再提交
+    csrf_token: String,
+}
+
+impl DashboardState {
+    pub fn new(store: Arc<dyn PageStoreTrait>) -> Self {
+        Self {
+            store,
+            csrf_token: generate_mcp_token(),
+        }
+    }
+
+    fn check_csrf(&self, provided: &str) -> Result<(), Box<Response>> {
+        if constant_time_eq(provided.as_bytes(), self.csrf_token.as_bytes()) {
+            Ok(())
+        } else {
+            Err(Box::new(
+                (StatusCode::FORBIDDEN, "invalid csrf token").into_response(),
+            ))
+        }
+    }
+}
+
+/// `/{token}/admin`，用于生成链接与表单地址
+fn dashboard_base_path(token: &str) -> String {
+    format!("/{token}/admin")
+}
+
+fn edit_url(base_path: &str, page_uid: &str) -> String {
+    format!("{base_path}/pages/{page_uid}")
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DashboardQuery {
+    /// 操作成功后重定向回来时的提示：`saved` / `deleted`
+    pub done: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DashboardPageForm {
+    pub csrf_token: String,
+    pub seo_title: String,
+    #[serde(default)]
+    pub description: String,
+    /// 逗号分隔，中英文逗号均可
+    #[serde(default)]
+    pub keywords: String,
+    /// 只有 Markdown 页面的表单带有该字段
+    pub markdown: Option<String>,
+    pub expected_updated_at: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DashboardCsrfForm {
+    pub csrf_token: String,
+}
+
+/// 页面上方的操作结果提示
+struct Notice {
+    success: bool,
+    message: String,
+}
+
+impl Notice {
+    fn success(message: &str) -> Self {
+        Self {
+            success: true,
+            message: message.to_string(),
+        }
+    }
+
+    fn error(error: &ErrorFields) -> Self {
+        let message = error
+            .error
+            .clone()
+            .unwrap_or_else(|| "操作失败".to_string());
+        Self {
+            success: false,
+            message,
+        }
+    }
+
+    fn render(notice: Option<&Notice>) -> String {
+        match notice {
+            Some(notice) => format!(
+                "<p class=\"notice {}\">{}</p>",
+                if notice.success { "success" } else { "error" },
+                escape_html(&notice.message)
+            ),
+            None => String::new(),
+        }
+    }
+}
+
+/// `GET /{token}/admin`：按更新时间倒序列出全部页面，包括草稿与取消发布的页面
+pub async fn dashboard_list_handler(
+    State(state): State<DashboardState>,
+    Path(token): Path<String>,
+    Query(query): Query<DashboardQuery>,
+) -> Response {
+    let notice = match query.done.as_deref() {
+        Some("deleted") => Some(Notice::success("页面已删除，可在回收站中恢复")),
+        _ => None,
+    };
+    list_response(
+        &state,
+        &dashboard_base_path(&token),
+        StatusCode::OK,
+        notice.as_ref(),
+    )
+    .await
+}
+
+/// `GET /{token}/admin/pages/{page_uid}`：编辑标题、描述、关键词，Markdown 页面还可编辑正文
+pub async fn dashboard_edit_handler(
+    State(state): State<DashboardState>,
+    Path((token, page_uid)): Path<(String, String)>,
+    Query(query): Query<DashboardQuery>,
+) -> Response {
+    let (meta, markdown) = match load_dashboard_page(state.store.as_ref(), &page_uid).await {
+        Ok(Some(page)) => page,
+        Ok(None) => return (StatusCode::NOT_FOUND, "page not found").into_response(),
+        Err(err) => return internal_error(err),
+    };
+    let notice = match query.done.as_deref() {
+        Some("saved") => Some(Notice::success("已保存")),
+        _ => None,
+    };
+    let form = DashboardPageForm {
+        csrf_token: String::new(),
+        seo_title: meta.seo.seo_title.clone(),
+        description: meta.seo.description.clone(),
+        keywords: meta.seo.keywords.as_deref().unwrap_or_default().join(", "),
+        markdown,
+        expected_updated_at: Some(meta.updated_at),
+    };
+    let base_path = dashboard_base_path(&token);
+    edit_response(
+        &state,
+        &base_path,
+        &meta,
+        &form,
+        StatusCode::OK,
+        notice.as_ref(),
+        None,
+    )
+}
+
+/// `POST /{token}/admin/pages/{page_uid}`：与 `update_markdown_page` / `update_page` 工具走同一流程，
+/// 带上读取时的 `updated_at`，页面已被他人修改时返回 409 并保留表单内容
+pub async fn dashboard_save_handler(
+    State(state): State<DashboardState>,
+    Path((token, page_uid)): Path<(String, String)>,
+    Form(form): Form<DashboardPageForm>,
+) -> Response {
+    if let Err(response) = state.check_csrf(&form.csrf_token) {
+        return *response;
+    }
+    let keywords = parse_keywords(&form.keywords);
+    let response = match form.markdown.clone() {
+        Some(markdown) => {
+            let params = UpdateMarkdownPageRequest {
+                page_id: page_uid.clone(),
+                seo_title: Some(form.seo_title.clone()),
+                description: Some(form.description.clone()),
+                keywords: Some(keywords),
+                tags: None,
+                markdown: Some(markdown),
+                noindex: None,
+                expected_updated_at: form.expected_updated_at,
+            };
+            update_markdown_page_by_uid(state.store.as_ref(), params).await
+        }
+        None => {
+            let params = UpdatePageRequest {
+                page_id: page_uid.clone(),
+                seo_title: Some(form.seo_title.clone()),
+                description: Some(form.description.clone()),
+                keywords: Some(keywords),
+                tags: None,
+                html: None,
+                head_html: None,
+                noindex: None,
+                expected_updated_at: form.expected_updated_at,
+                structured_data: None,
+            };
+            update_html_page(state.store.as_ref(), params).await
+        }
+    };
+    let base_path = dashboard_base_path(&token);
+    if response.success {
+        let location = format!("{}?done=saved", edit_url(&base_path, &page_uid));
+        return Redirect::to(&location).into_response();
+    }
+
+    let status = error_status(&response.error);
+    let meta = match load_dashboard_page(state.store.as_ref(), &page_uid).await {
+        Ok(Some((meta, _))) => meta,
+        Ok(None) => return (StatusCode::NOT_FOUND, "page not found").into_response(),
+        Err(err) => return internal_error(err),
+    };
+    let notice = Notice::error(&response.error);
+    edit_response(
+        &state,
+        &base_path,
+        &meta,
+        &form,
+        status,
+        Some(&notice),
+        None,
+    )
+}
+
+/// `POST /{token}/admin/pages/{page_uid}/preview`：按提交的 Markdown 渲染预览，不保存
+pub async fn dashboard_preview_handler(
+    State(state): State<DashboardState>,
+    Path((token, page_uid)): Path<(String, String)>,
+    Form(form): Form<DashboardPageForm>,
+) -> Response {
+    if let Err(response) = state.check_csrf(&form.csrf_token) {
+        return *response;
+    }
+    let meta = match load_dashboard_page(state.store.as_ref(), &page_uid).await {
+        Ok(Some((meta, _))) => meta,
+        Ok(None) => return (StatusCode::NOT_FOUND, "page not found").into_response(),
+        Err(err) => return internal_error(err),
+    };
+    let preview = form.markdown.as_deref().map(markdown_to_html);
+    let base_path = dashboard_base_path(&token);
+    edit_response(
+        &state,
+        &base_path,
+        &meta,
+        &form,
+        StatusCode::OK,
+        None,
+        preview.as_deref(),
+    )
+}
+
+/// `POST /{token}/admin/pages/{page_uid}/delete`：与 `delete_page` 工具一样移入回收站
+pub async fn dashboard_delete_handler(
+    State(state): State<DashboardState>,
+    Path((token, page_uid)): Path<(String, String)>,
+    Form(form): Form<DashboardCsrfForm>,
+) -> Response {
+    if let Err(response) = state.check_csrf(&form.csrf_token) {
+        return *response;
+    }
+    let params = PageIdRequest { page_id: page_uid };
+    let response = delete_page_by_uid(state.store.as_ref(), params).await;
+    let base_path = dashboard_base_path(&token);
+    if response.success {
+        let location = format!("{base_path}?done=deleted");
+        return Redirect::to(&location).into_response();
+    }
+    let notice = Notice::error(&response.error);
+    list_response(
+        &state,
+        &base_path,
+        error_status(&response.error),
+        Some(&notice),
+    )
+    .await
+}
+
+/// 按 page_uid 读取 meta 与 Markdown 源，页面不存在时返回 `None`
+async fn load_dashboard_page(
+    store: &dyn PageStoreTrait,
+    page_uid: &str,
+) -> Result<Option<(PageMeta, Option<String>)>> {
+    let Some(page_id) = store.resolve_page_id_by_uid(page_uid).await? else {
+        return Ok(None);
+    };
+    let meta = store.get_page_meta(&page_id).await?;
+    let markdown = store.load_page_markdown(&page_id).await?;
+    Ok(Some((meta, markdown)))
+}
+
+async fn list_response(
+    state: &DashboardState,
+    base_path: &str,
+    status: StatusCode,
+    notice: Option<&Notice>,
+) -> Response {
+    match render_list_html(state, base_path, notice).await {
+        Ok(html) => dashboard_html(status, html),
+        Err(err) => internal_error(err),
+    }
+}
+
+async fn render_list_html(
+    state: &DashboardState,
+    base_path: &str,
+    notice: Option<&Notice>,
+) -> Result<String> {
+    let template = std::fs::read_to_string("front/admin/list.html")
+        .context("read front/admin/list.html template")?;
+    let entries = state.store.list_page_entries().await?;
+    let page_count = entries.len();
+    let entries = recent_update_entries(entries, page_count);
+
+    let mut rows = String::new();
+    for entry in &entries {
+        let page_uid = if entry.page_uid.is_empty() {
+            &entry.page_id
+        } else {
+            &entry.page_uid
+        };
+        let title = if entry.seo.title.is_empty() {
+            &entry.seo.seo_title
+        } else {
+            &entry.seo.title
+        };
+        let edit_url = edit_url(base_path, page_uid);
+        rows.push_str(&format!(
+            concat!(
+                "<tr><td><a href=\"{edit}\">{title}</a></td><td><code>{uid}</code></td>",
+                "<td class=\"number\">{views}</td><td>{updated}</td><td class=\"actions\">",
+                "<a href=\"{edit}\">编辑</a>",
+                "<a href=\"{view}\" target=\"_blank\" rel=\"noopener noreferrer\">查看</a>",
+                "<form method=\"post\" action=\"{edit}/delete\" ",
+                "onsubmit=\"return confirm('确定删除该页面？')\">",
+                "<input type=\"hidden\" name=\"csrf_token\" value=\"{csrf}\">",
+                "<button type=\"submit\">删除</button></form></td></tr>"
+            ),
+            edit = escape_html_attr(&edit_url),
+            title = escape_html(title),
+            uid = escape_html(page_uid),
+            views = entry.view_count,
+            updated = escape_html(&format_display_timestamp(entry.updated_at)),
+            view = escape_html_attr(&build_page_url(page_uid, &entry.seo.seo_title)),
+            csrf = escape_html_attr(&state.csrf_token),
+        ));
+    }
+    if rows.is_empty() {
+        rows.push_str("<tr><td class=\"empty\" colspan=\"5\">还没有页面</td></tr>");
+    }
+
+    replace_template(
+        &template,
+        &[
+            ("page_count", &page_count.to_string()),
+            ("notice", &Notice::render(notice)),
+            ("rows", &rows),
+        ],
+    )
+}
+
+fn edit_response(
+    state: &DashboardState,
+    base_path: &str,
+    meta: &PageMeta,
+    form: &DashboardPageForm,
+    status: StatusCode,
+    notice: Option<&Notice>,
+    preview: Option<&str>,
+) -> Response {
+    match render_edit_html(state, base_path, meta, form, notice, preview) {
+        Ok(html) => dashboard_html(status, html),
+        Err(err) => internal_error(err),
+    }
+}
+
+/// 表单内容取自 `form`，保存失败或预览时保留用户输入
+fn render_edit_html(
+    state: &DashboardState,
+    base_path: &str,
+    meta: &PageMeta,
+    form: &DashboardPageForm,
+    notice: Option<&Notice>,
+    preview: Option<&str>,
+) -> Result<String> {
+    let template = std::fs::read_to_string("front/admin/edit.html")
+        .context("read front/admin/edit.html template")?;
+    let title = if meta.seo.title.is_empty() {
+        &meta.seo.seo_title
+    } else {
+        &meta.seo.title
+    };
+    let edit_url = edit_url(base_path, &meta.page_uid);
+    let (markdown_field, preview_button) = match form.markdown.as_deref() {
+        Some(markdown) => (
+            format!(
+                "<label for=\"markdown\">Markdown 正文</label>\
+                 <textarea class=\"markdown\" id=\"markdown\" name=\"markdown\">{}</textarea>",
+                escape_html(markdown)
+            ),
+            format!(
+                "<button type=\"submit\" class=\"secondary\" formaction=\"{}/preview\">\
+                 预览</button>",
+                escape_html_attr(&edit_url)
+            ),
+        ),
+        None => (
+            "<p class=\"hint\">该页面由 HTML 推送，正文请通过 MCP 工具 update_page 修改</p>"
+                .to_string(),
+            String::new(),
+        ),
+    };
+    let preview = match preview {
+        Some(html) => format!(
+            "<section class=\"preview\" aria-label=\"预览\"><h2>预览</h2>{}</section>",
+            html
+        ),
+        None => String::new(),
+    };
+    let expected_updated_at = form
+        .expected_updated_at
+        .unwrap_or(meta.updated_at)
+        .to_string();
+    let page_url = build_page_url(&meta.page_uid, &meta.seo.seo_title);
+
+    replace_template(
+        &template,
+        &[
+            ("title", &escape_html(title)),
+            ("list_url", &escape_html_attr(base_path)),
+            ("page_uid", &escape_html(&meta.page_uid)),
+            ("view_count", &meta.view_count.to_string()),
+            (
+                "updated_at",
+                &escape_html(&format_display_timestamp(meta.updated_at)),
+            ),
+            ("page_url", &escape_html_attr(&page_url)),
+            ("notice", &Notice::render(notice)),
+            ("save_url", &escape_html_attr(&edit_url)),
+            ("csrf_token", &escape_html_attr(&state.csrf_token)),
+            ("expected_updated_at", &expected_updated_at),
+            ("seo_title", &escape_html_attr(&form.seo_title)),
+            ("description", &escape_html(&form.description)),
+            ("keywords", &escape_html_attr(&form.keywords)),
+            ("markdown_field", &markdown_field),
+            ("preview_button", &preview_button),
+            ("preview", &preview),
+        ],
+    )
+}
+
+fn parse_keywords(raw: &str) -> Vec<String> {
+    raw.split([',', '，'])
+        .map(str::trim)
+        .filter(|keyword| !keyword.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// 管理页面含 CSRF token，禁止缓存
+fn dashboard_html(status: StatusCode, html: String) -> Response {
+    let mut response = (status, Html(html)).into_response();
+    response
+        .headers_mut()
+        .insert(CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    response
+}
+
+fn internal_error(err: anyhow::Error) -> Response {
+    error!("admin dashboard failed: {err:#}");
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        format!("admin dashboard failed: {err:#}"),
+    )
+        .into_response()
+}
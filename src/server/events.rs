@@ -0,0 +1,101 @@
+use std::convert::Infallible;
+
+use axum::{
+    extract::State,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use serde::Serialize;
+use tokio::sync::{broadcast, mpsc};
+use tokio_stream::{Stream, wrappers::ReceiverStream};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    store::{PageMeta, PageStoreBuilder, now_unix_seconds},
+    web::{build_page_url, is_page_public},
+    webhook::PageEvent,
+};
+
+/// 每个订阅者最多积压的事件数，消费过慢时跳过最旧的事件
+const PAGE_EVENTS_CAPACITY: usize = 64;
+
+/// `GET /events` 推送的事件，序列化为 SSE 的 `data` 字段
+#[derive(Debug, Clone, Serialize)]
+pub struct LivePageEvent {
+    /// `page.created` / `page.updated` / `page.deleted`，与 Webhook 事件名一致
+    pub event_type: PageEvent,
+    pub page_uid: String,
+    /// 站内相对路径
+    pub url: String,
+    pub timestamp: i64,
+}
+
+/// 页面变更广播，通过 `attach` 接到 `PageStore` 的写入与删除钩子上；
+/// 只推送公开页面，草稿与未公开页面的变更不会广播。`shutdown` 取消后所有 SSE 连接随之结束，
+/// 不会拖住服务的优雅退出
+#[derive(Debug, Clone)]
+pub struct PageEvents {
+    sender: broadcast::Sender<LivePageEvent>,
+    shutdown: CancellationToken,
+}
+
+impl PageEvents {
+    pub fn new(shutdown: CancellationToken) -> Self {
+        let (sender, _) = broadcast::channel(PAGE_EVENTS_CAPACITY);
+        Self { sender, shutdown }
+    }
+
+    pub fn attach(&self, builder: PageStoreBuilder) -> PageStoreBuilder {
+        let (created, updated, deleted) = (self.clone(), self.clone(), self.clone());
+        builder
+            .on_create(move |meta| created.publish(PageEvent::Created, meta))
+            .on_update(move |meta| updated.publish(PageEvent::Updated, meta))
+            .on_delete(move |meta| deleted.publish(PageEvent::Deleted, meta))
+    }
+
+    /// 没有订阅者时直接丢弃
+    pub fn publish(&self, event_type: PageEvent, meta: &PageMeta) {
+        if !is_page_public(meta) {
+            return;
+        }
+        let _ = self.sender.send(LivePageEvent {
+            event_type,
+            page_uid: meta.page_uid.clone(),
+            url: build_page_url(&meta.page_uid, &meta.seo.seo_title),
+            timestamp: now_unix_seconds().unwrap_or_default(),
+        });
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<LivePageEvent> {
+        self.sender.subscribe()
+    }
+}
+
+/// `GET /events`：以 SSE 推送页面创建、更新与删除事件，不设置 `event:` 字段，
+/// 浏览器端用 `EventSource.onmessage` 接收
+pub async fn events_handler(
+    State(events): State<PageEvents>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let mut receiver = events.subscribe();
+    let (sender, stream) = mpsc::channel(PAGE_EVENTS_CAPACITY);
+    tokio::spawn(async move {
+        loop {
+            let event = tokio::select! {
+                _ = events.shutdown.cancelled() => break,
+                _ = sender.closed() => break,
+                received = receiver.recv() => match received {
+                    Ok(event) => event,
+                    // 消费过慢被跳过的事件直接忽略，客户端收到下一条事件时仍会刷新
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+            };
+            let Ok(data) = serde_json::to_string(&event) else {
+                continue;
+            };
+            if sender.send(Ok(Event::default().data(data))).await.is_err() {
+                break;
+            }
+        }
+    });
+    Sse::new(ReceiverStream::new(stream)).keep_alive(KeepAlive::default())
+}
@@ -1,24 +1,33 @@
-use std::sync::Arc;
+use std::{net::SocketAddr, sync::Arc};
 
 use axum::{
-    extract::{Path, State},
-    http::{HeaderMap, StatusCode, header::CONTENT_TYPE},
-    response::{Html, IntoResponse},
+    Extension, Json,
+    extract::{ConnectInfo, Path, State},
+    http::{
+        HeaderMap, HeaderValue, StatusCode,
+        header::{ACCEPT, ACCESS_CONTROL_ALLOW_ORIGIN, CACHE_CONTROL, CONTENT_TYPE, VARY},
+    },
+    response::{Html, IntoResponse, Response},
 };
+use tracing::{error, warn};
 
 use crate::{
-    store::PageStore,
+    config::{find_site_url_from_env, resolve_indexnow_key_from_env, resolve_trust_proxy_from_env},
+    mcp::{dto::PageWithHtml, tools::build_page_full_url},
+    server::middleware::resolve_client_ip,
+    store::{PageMeta, PageStoreTrait},
     web::{
-        parse_page_id_from_slug, render_404_html, render_index_html, render_page_html,
-        render_sitemap_xml,
+        html_to_markdown, inject_robots_noindex, is_page_indexable, is_page_public,
+        is_page_unpublished, load_related_pages, parse_page_id_from_slug, render_404_html,
+        render_index_html, render_served_page, render_sitemap_xml, render_tag_html,
     },
 };
 
 pub async fn index_handler(
-    State(store): State<Arc<PageStore>>,
+    State(store): State<Arc<dyn PageStoreTrait>>,
     _headers: HeaderMap,
 ) -> impl IntoResponse {
-    match render_index_html(&store) {
+    match render_index_html(store.as_ref()).await {
         Ok(html) => Html(html).into_response(),
         Err(err) => (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -28,12 +37,27 @@ pub async fn index_handler(
     }
 }
 
+pub async fn tag_handler(
+    State(store): State<Arc<dyn PageStoreTrait>>,
+    Path(tag): Path<String>,
+) -> impl IntoResponse {
+    match render_tag_html(store.as_ref(), &tag).await {
+        Ok(Some(html)) => Html(html).into_response(),
+        Ok(None) => not_found_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("render tag page failed: {err}"),
+        )
+            .into_response(),
+    }
+}
+
 pub async fn sitemap_handler(
-    State(store): State<Arc<PageStore>>,
+    State(store): State<Arc<dyn PageStoreTrait>>,
     headers: HeaderMap,
 ) -> impl IntoResponse {
     let base_url = resolve_base_url(&headers);
-    match render_sitemap_xml(&store, &base_url) {
+    match render_sitemap_xml(store.as_ref(), &base_url).await {
         Ok(xml) => ([(CONTENT_TYPE, "application/xml")], xml).into_response(),
         Err(err) => (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -44,9 +68,15 @@ pub async fn sitemap_handler(
 }
 
 pub async fn page_handler(
-    State(store): State<Arc<PageStore>>,
+    State(store): State<Arc<dyn PageStoreTrait>>,
     Path(slug): Path<String>,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
+    // `/pages/{slug}.md` 与 `/pages/{slug}` 共用同一路由段，在这里分流
+    if let Some(slug) = slug.strip_suffix(".md") {
+        return page_markdown_response(store.as_ref(), slug).await;
+    }
     let Some(page_id) = parse_page_id_from_slug(&slug) else {
         return match render_404_html() {
             Ok(html) => (StatusCode::NOT_FOUND, Html(html)).into_response(),
@@ -57,13 +87,38 @@ pub async fn page_handler(
                 .into_response(),
         };
     };
-    match store.load_page(&page_id) {
+    match store.load_page(&page_id).await {
+        // 取消发布的页面与不存在的页面返回相同的 404，草稿仍可直链预览
+        Ok((meta, _)) if is_page_unpublished(&meta) => not_found_response(),
         Ok((meta, html)) => {
-            let rendered = render_page_html(&meta, &html);
-            if let Err(err) = store.increment_view_count(&page_id) {
-                eprintln!("[solin-blog] increment view count failed: {err}");
-            }
-            Html(rendered).into_response()
+            let mut response = match negotiate_page_format(&headers) {
+                PageFormat::Json => page_json_response(meta, html, &headers),
+                PageFormat::Markdown => {
+                    page_markdown_or_converted(store.as_ref(), &page_id, &html).await
+                }
+                PageFormat::Html => {
+                    let related = load_related_pages(store.as_ref(), &meta.page_uid).await;
+                    let rendered =
+                        render_served_page(&meta, &html, &resolve_base_url(&headers), &related);
+                    // 与限流一致：只有 `TRUST_PROXY=true` 时才采用转发头，否则按连接地址去重
+                    let peer = connect_info.map(|Extension(ConnectInfo(addr))| addr);
+                    let client_ip =
+                        resolve_client_ip(&headers, peer, resolve_trust_proxy_from_env())
+                            .map(|ip| ip.to_string());
+                    if let Err(err) = store
+                        .increment_view_count(&page_id, client_ip.as_deref())
+                        .await
+                    {
+                        warn!("increment view count failed: {err}");
+                    }
+                    Html(rendered).into_response()
+                }
+            };
+            // 同一 URL 按 Accept 返回不同内容，缓存需区分
+            response
+                .headers_mut()
+                .insert(VARY, HeaderValue::from_static("Accept"));
+            response
         }
         Err(_err) => match render_404_html() {
             Ok(html) => (StatusCode::NOT_FOUND, Html(html)).into_response(),
@@ -76,9 +131,171 @@ pub async fn page_handler(
     }
 }
 
+/// 原样返回推送时存储的 index.html：不注入 SEO、不计浏览量
+pub async fn page_raw_handler(
+    State(store): State<Arc<dyn PageStoreTrait>>,
+    Path(slug): Path<String>,
+) -> impl IntoResponse {
+    let Some(page_id) = resolve_page_id_from_slug(store.as_ref(), &slug).await else {
+        return not_found_response();
+    };
+    match store.load_page(&page_id).await {
+        Ok((meta, _)) if is_page_unpublished(&meta) => not_found_response(),
+        Ok((meta, html)) => {
+            let mut response = ([(CONTENT_TYPE, "text/html; charset=utf-8")], html).into_response();
+            // 原始 HTML 不做注入，改用响应头声明禁止收录
+            if !is_page_indexable(&meta) {
+                response.headers_mut().insert(
+                    "x-robots-tag",
+                    HeaderValue::from_static("noindex, nofollow"),
+                );
+            }
+            response
+        }
+        Err(_err) => not_found_response(),
+    }
+}
+
+/// `GET /pages/{slug}/stats`：公开的单页统计，供第三方组件展示浏览量与阅读时长
+pub async fn page_stats_handler(
+    State(store): State<Arc<dyn PageStoreTrait>>,
+    Path(slug): Path<String>,
+) -> impl IntoResponse {
+    let stats = match resolve_page_id_from_slug(store.as_ref(), &slug).await {
+        Some(page_id) => match store.get_page_meta(&page_id).await {
+            // 未公开页面与不存在的页面返回相同的 404
+            Ok(meta) if is_page_public(&meta) => store.get_page_stats(&page_id).await.ok(),
+            _ => None,
+        },
+        None => None,
+    };
+    let (mut response, cache_control) = match stats {
+        Some(stats) => (Json(stats).into_response(), "public, max-age=60"),
+        None => (
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({ "error": "page not found" })),
+            )
+                .into_response(),
+            "no-store",
+        ),
+    };
+    let headers = response.headers_mut();
+    headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, HeaderValue::from_static("*"));
+    headers.insert(CACHE_CONTROL, HeaderValue::from_static(cache_control));
+    response
+}
+
+/// 返回 Markdown 源文件，纯 HTML 推送的页面与取消发布的页面返回 404
+async fn page_markdown_response(store: &dyn PageStoreTrait, slug: &str) -> Response {
+    let Some(page_id) = resolve_page_id_from_slug(store, slug).await else {
+        return not_found_response();
+    };
+    if !matches!(store.get_page_meta(&page_id).await, Ok(meta) if !is_page_unpublished(&meta)) {
+        return not_found_response();
+    }
+    match store.load_page_markdown(&page_id).await {
+        Ok(Some(markdown)) => {
+            ([(CONTENT_TYPE, "text/markdown; charset=utf-8")], markdown).into_response()
+        }
+        Ok(None) | Err(_) => not_found_response(),
+    }
+}
+
+/// `GET /pages/{slug}` 按 `Accept` 协商的响应格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PageFormat {
+    Html,
+    Json,
+    Markdown,
+}
+
+/// 取 q 值最高的可用类型，q 值相同时按出现顺序；`text/html`、`*/*` 或缺少 `Accept` 时返回 HTML
+fn negotiate_page_format(headers: &HeaderMap) -> PageFormat {
+    let Some(accept) = headers.get(ACCEPT).and_then(|value| value.to_str().ok()) else {
+        return PageFormat::Html;
+    };
+    let mut best: Option<(PageFormat, f32)> = None;
+    for range in accept.split(',') {
+        let mut parts = range.split(';');
+        let media_type = parts.next().unwrap_or_default().trim().to_ascii_lowercase();
+        let format = match media_type.as_str() {
+            "text/html" | "application/xhtml+xml" | "text/*" | "*/*" => PageFormat::Html,
+            "application/json" => PageFormat::Json,
+            "text/markdown" | "text/x-markdown" => PageFormat::Markdown,
+            _ => continue,
+        };
+        let quality = parts
+            .filter_map(|param| param.trim().strip_prefix("q="))
+            .find_map(|value| value.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+        if quality > 0.0 && best.is_none_or(|(_, best_quality)| quality > best_quality) {
+            best = Some((format, quality));
+        }
+    }
+    best.map_or(PageFormat::Html, |(format, _)| format)
+}
+
+/// `Accept: application/json`：返回与 `/api/pages/{page_uid}` 相同的 `PageWithHtml`，`html` 为存储的原始 HTML
+fn page_json_response(meta: PageMeta, html: String, headers: &HeaderMap) -> Response {
+    let page = PageWithHtml {
+        page_id: meta.page_uid.clone(),
+        url: build_page_full_url(
+            &resolve_base_url(headers),
+            &meta.page_uid,
+            &meta.seo.seo_title,
+        ),
+        meta: Some(meta.into()),
+        fields: None,
+        html: Some(html),
+        truncated: false,
+    };
+    Json(page).into_response()
+}
+
+/// `Accept: text/markdown`：优先返回推送时的 `content.md`，纯 HTML 页面即时转换
+async fn page_markdown_or_converted(
+    store: &dyn PageStoreTrait,
+    page_id: &str,
+    html: &str,
+) -> Response {
+    let markdown = match store.load_page_markdown(page_id).await {
+        Ok(Some(markdown)) => markdown,
+        Ok(None) => html_to_markdown(html),
+        Err(err) => {
+            warn!("load page markdown for {page_id} failed: {err:#}");
+            html_to_markdown(html)
+        }
+    };
+    ([(CONTENT_TYPE, "text/markdown; charset=utf-8")], markdown).into_response()
+}
+
+async fn resolve_page_id_from_slug(store: &dyn PageStoreTrait, slug: &str) -> Option<String> {
+    let page_id = parse_page_id_from_slug(slug)?;
+    match store.resolve_page_id_by_uid(&page_id).await {
+        Ok(Some(resolved)) => Some(resolved),
+        Ok(None) => None,
+        Err(err) => {
+            error!("resolve page id failed: {err}");
+            None
+        }
+    }
+}
+
+fn not_found_response() -> Response {
+    match render_404_html() {
+        Ok(html) => (StatusCode::NOT_FOUND, Html(html)).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("render 404 failed: {err}"),
+        )
+            .into_response(),
+    }
+}
+
 pub async fn token_generator_handler() -> impl IntoResponse {
     match std::fs::read_to_string("front/token-generator.html") {
-        Ok(html) => Html(html).into_response(),
+        Ok(html) => Html(inject_robots_noindex(&html)).into_response(),
         Err(err) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             format!("read token generator html failed: {err}"),
@@ -87,31 +304,70 @@ pub async fn token_generator_handler() -> impl IntoResponse {
     }
 }
 
-fn resolve_base_url(headers: &HeaderMap) -> String {
-    if let Some(host) = headers
-        .get("host")
+/// `GET /{key}.txt`：IndexNow 密钥文件，内容为密钥本身
+pub async fn indexnow_key_handler() -> impl IntoResponse {
+    match resolve_indexnow_key_from_env() {
+        Some(key) => ([(CONTENT_TYPE, "text/plain; charset=utf-8")], key).into_response(),
+        None => not_found_response(),
+    }
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)
         .and_then(|value| value.to_str().ok())
         .map(str::trim)
         .filter(|value| !value.is_empty())
-    {
-        let scheme = headers
-            .get("x-forwarded-proto")
-            .and_then(|value| value.to_str().ok())
-            .map(str::trim)
-            .filter(|value| !value.is_empty())
-            .unwrap_or("http");
-        return format!("{}://{}", scheme, host)
-            .trim_end_matches('/')
-            .to_string();
+}
+
+/// 生成完整 URL 的站点根地址，请求头无法确定时回退为 `SITE_URL`
+pub(crate) fn resolve_base_url(headers: &HeaderMap) -> String {
+    if let Some(base_url) = base_url_from_headers(headers, resolve_trust_proxy_from_env()) {
+        return base_url;
     }
 
-    let value = std::env::var("SITE_URL").unwrap_or_default();
-    let trimmed = value.trim().trim_end_matches('/');
-    if trimmed.is_empty() {
-        eprintln!(
-            "[solin-blog] WARNING: SITE_URL is not set and request headers missing host, sitemap URLs will be relative"
-        );
-        return String::new();
+    match find_site_url_from_env() {
+        Some(url) => url,
+        None => {
+            warn!(
+                "SITE_URL is not set and request headers missing host, sitemap URLs will be relative"
+            );
+            String::new()
+        }
     }
-    trimmed.to_string()
+}
+
+/// 协议取 `X-Forwarded-Proto`（缺省 http），主机取 `Host`；
+/// `trust_proxy` 时优先 `X-Forwarded-Host`，并追加 `X-Forwarded-Prefix` 子路径
+fn base_url_from_headers(headers: &HeaderMap, trust_proxy: bool) -> Option<String> {
+    let forwarded_host = if trust_proxy {
+        first_forwarded_value(headers, "x-forwarded-host")
+    } else {
+        None
+    };
+    let host = forwarded_host.or_else(|| header_str(headers, "host"))?;
+    let scheme = header_str(headers, "x-forwarded-proto").unwrap_or("http");
+    let prefix = if trust_proxy {
+        first_forwarded_value(headers, "x-forwarded-prefix")
+            .map(|value| value.trim_matches('/'))
+            .filter(|value| !value.is_empty())
+            .map(|value| format!("/{}", value))
+            .unwrap_or_default()
+    } else {
+        String::new()
+    };
+    Some(
+        format!("{}://{}{}", scheme, host, prefix)
+            .trim_end_matches('/')
+            .to_string(),
+    )
+}
+
+/// 经过多层代理时转发头为逗号分隔的列表，第一项为客户端请求的原始值
+fn first_forwarded_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    header_str(headers, name)?
+        .split(',')
+        .next()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
 }
@@ -0,0 +1,111 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use tracing::warn;
+
+use crate::store::{PageStoreTrait, StoreHealth};
+
+/// 探针路径：不计入限流与访问日志，请求日志降为 debug 级别
+pub(crate) const HEALTH_PATHS: &[&str] = &["/healthz", "/readyz"];
+
+pub(crate) fn is_health_path(path: &str) -> bool {
+    HEALTH_PATHS.contains(&path)
+}
+
+/// `/healthz` 的状态，记录进程启动时间
+#[derive(Debug, Clone, Copy)]
+pub struct HealthState {
+    started_at: Instant,
+}
+
+impl HealthState {
+    pub fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+        }
+    }
+}
+
+impl Default for HealthState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct HealthzResponse {
+    status: &'static str,
+    uptime_seconds: u64,
+    version: &'static str,
+}
+
+/// `GET /healthz`：存活探针，进程能处理请求即返回 200，不访问存储
+pub async fn healthz_handler(State(state): State<HealthState>) -> impl IntoResponse {
+    Json(HealthzResponse {
+        status: "ok",
+        uptime_seconds: state.started_at.elapsed().as_secs(),
+        version: env!("CARGO_PKG_VERSION"),
+    })
+}
+
+#[derive(Debug, Serialize)]
+struct ReadyzResponse {
+    status: &'static str,
+    /// 第一项失败的检查：`store` / `data_dir_readable` / `data_dir_writable` / `index`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failed_check: Option<&'static str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<String>,
+}
+
+/// `GET /readyz`：就绪探针，数据目录可读写且 index.json 可以解析时返回 200，否则返回 503
+pub async fn readyz_handler(State(store): State<Arc<dyn PageStoreTrait>>) -> Response {
+    let (failed_check, errors) = match store.probe_health().await {
+        Ok(health) => (readiness_failed_check(&health), health.errors),
+        Err(err) => (
+            Some("store"),
+            vec![format!("probe store health failed: {err:#}")],
+        ),
+    };
+    let Some(failed_check) = failed_check else {
+        return Json(ReadyzResponse {
+            status: "ready",
+            failed_check: None,
+            errors: Vec::new(),
+        })
+        .into_response();
+    };
+    warn!(
+        "readiness check {failed_check} failed: {}",
+        errors.join("; ")
+    );
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(ReadyzResponse {
+            status: "unavailable",
+            failed_check: Some(failed_check),
+            errors,
+        }),
+    )
+        .into_response()
+}
+
+/// `/readyz` 中第一项失败的检查名，全部通过时为 `None`
+pub fn readiness_failed_check(health: &StoreHealth) -> Option<&'static str> {
+    if health.readable == Some(false) {
+        Some("data_dir_readable")
+    } else if health.writable == Some(false) {
+        Some("data_dir_writable")
+    } else if !health.index_ok {
+        Some("index")
+    } else {
+        None
+    }
+}
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    body::Body,
+    extract::{Request, State},
+    response::Response,
+};
+use rmcp::transport::streamable_http_server::{
+    StreamableHttpServerConfig, StreamableHttpService, session::local::LocalSessionManager,
+};
+use tokio_util::sync::CancellationToken;
+
+use crate::{config::McpScope, mcp::BlogMcpServer, store::PageStoreTrait};
+
+type McpService = StreamableHttpService<BlogMcpServer, LocalSessionManager>;
+
+/// read-write 与 read-only 各一个 MCP 服务实例，所有 token 共用；
+/// 请求经 `mcp_token_auth` 校验后按 token 的权限范围分发
+#[derive(Clone)]
+pub struct McpEndpoints {
+    read_write: McpService,
+    read_only: McpService,
+}
+
+impl McpEndpoints {
+    /// `shutdown` 取消后 MCP 的 SSE 长连接随之结束
+    pub fn new(store: Arc<dyn PageStoreTrait>, shutdown: &CancellationToken) -> Self {
+        Self {
+            read_write: mcp_service(Arc::clone(&store), McpScope::ReadWrite, shutdown),
+            read_only: mcp_service(store, McpScope::ReadOnly, shutdown),
+        }
+    }
+}
+
+fn mcp_service(
+    store: Arc<dyn PageStoreTrait>,
+    scope: McpScope,
+    shutdown: &CancellationToken,
+) -> McpService {
+    let server = BlogMcpServer::with_scope(store, scope);
+    StreamableHttpService::new(
+        move || Ok(server.clone()),
+        LocalSessionManager::default().into(),
+        StreamableHttpServerConfig {
+            cancellation_token: shutdown.child_token(),
+            ..Default::default()
+        },
+    )
+}
+
+/// `/{token}/mcp`：Streamable HTTP 的 POST / GET / DELETE 都由同一个路由处理
+pub async fn mcp_endpoint_handler(
+    State(endpoints): State<McpEndpoints>,
+    Extension(scope): Extension<McpScope>,
+    req: Request,
+) -> Response {
+    let service = match scope {
+        McpScope::ReadWrite => &endpoints.read_write,
+        McpScope::ReadOnly => &endpoints.read_only,
+    };
+    service.handle(req).await.map(Body::new)
+}
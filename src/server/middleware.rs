@@ -1,29 +1,862 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
 use axum::{
     body::Body,
-    http::Request,
+    extract::{ConnectInfo, OriginalUri, State},
+    http::{
+        HeaderMap, HeaderName, HeaderValue, Method, Request, StatusCode, Uri,
+        header::{
+            ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS,
+            ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_EXPOSE_HEADERS, ACCESS_CONTROL_MAX_AGE,
+            ACCESS_CONTROL_REQUEST_HEADERS, ACCESS_CONTROL_REQUEST_METHOD, AUTHORIZATION,
+            CONTENT_LENGTH, CONTENT_SECURITY_POLICY, CONTENT_TYPE, ORIGIN, REFERRER_POLICY,
+            RETRY_AFTER, VARY, WWW_AUTHENTICATE, X_CONTENT_TYPE_OPTIONS, X_FRAME_OPTIONS,
+        },
+    },
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
+};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use tracing::{Instrument, debug, info, info_span, warn};
+
+use crate::{
+    config::{
+        AdminBasicAuth, IpCidr, McpScope, TokenFormat, generate_token,
+        resolve_mcp_allowed_ips_from_env, resolve_mcp_max_body_bytes_from_env,
+        resolve_request_timeout_from_env, resolve_trust_proxy_from_env,
+    },
+    server::{admin::constant_time_eq, health::is_health_path},
+    tokens::TokenStore,
 };
 
-pub async fn log_request(req: Request<Body>, next: Next) -> Response {
-    let upgrade = req
+/// 请求 ID 响应头，同一请求内的日志都带有该 ID
+const X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+/// 请求日志与访问日志中记录的 URI，路径中与已存 token 相同的段已替换为 `***`
+#[derive(Debug, Clone)]
+pub struct RedactedUri(pub String);
+
+/// 为每个请求分配请求 ID 并写入 `X-Request-Id` 响应头，请求内的日志都归入同一个 span；
+/// `TRUST_PROXY=true` 时沿用反向代理传入的合法 `X-Request-Id`。
+/// 日志中的 URI 经过脱敏，放入请求扩展 `RedactedUri` 供访问日志复用
+pub async fn log_request(
+    State(tokens): State<Arc<TokenStore>>,
+    mut req: Request<Body>,
+    next: Next,
+) -> Response {
+    let request_id = incoming_request_id(&req).unwrap_or_else(|| {
+        generate_token(16, TokenFormat::Hex).unwrap_or_else(|_| "-".to_string())
+    });
+    let uri = redact_uri(req.uri(), &tokens);
+    let span = info_span!(
+        "request",
+        request_id = %request_id,
+        method = %req.method(),
+        uri = %uri
+    );
+    req.extensions_mut().insert(RedactedUri(uri));
+    async move {
+        {
+            let header = |name: &str| {
+                req.headers()
+                    .get(name)
+                    .and_then(|value| value.to_str().ok())
+                    .unwrap_or("-")
+                    .to_string()
+            };
+            debug!(
+                upgrade = %header("upgrade"),
+                connection = %header("connection"),
+                "request started"
+            );
+        }
+        let started = Instant::now();
+        let health_probe = is_health_path(req.uri().path());
+        let mut response = next.run(req).await;
+        let status = response.status().as_u16();
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+        // 编排平台的探针请求频繁，只在 debug 级别记录
+        if health_probe {
+            debug!(status, elapsed_ms, "request finished");
+        } else {
+            info!(status, elapsed_ms, "request finished");
+        }
+        if let Ok(value) = HeaderValue::from_str(&request_id) {
+            response.headers_mut().insert(X_REQUEST_ID, value);
+        }
+        response
+    }
+    .instrument(span)
+    .await
+}
+
+/// 查询参数原样保留，token 只会出现在路径中
+fn redact_uri(uri: &Uri, tokens: &TokenStore) -> String {
+    let path = tokens.redact_path(uri.path());
+    match uri.query() {
+        Some(query) => format!("{path}?{query}"),
+        None => path,
+    }
+}
+
+fn incoming_request_id(req: &Request<Body>) -> Option<String> {
+    if !resolve_trust_proxy_from_env() {
+        return None;
+    }
+    req.headers()
+        .get(&X_REQUEST_ID)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| {
+            (1..=64).contains(&value.len())
+                && value
+                    .bytes()
+                    .all(|byte| byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.'))
+        })
+        .map(str::to_string)
+}
+
+/// 推送的页面大量使用内联样式与脚本，默认 CSP 只限制 frame 嵌入与插件，不限制内联内容
+const DEFAULT_CONTENT_SECURITY_POLICY: &str = "default-src 'self' https: data: blob: 'unsafe-inline' 'unsafe-eval'; img-src * data: blob:; object-src 'none'; base-uri 'self'; frame-ancestors 'self'";
+
+/// 安全响应头配置，启动时从环境变量读取；变量设为 `off` 可关闭对应响应头
+/// - `SECURITY_X_CONTENT_TYPE_OPTIONS`：默认 `nosniff`
+/// - `SECURITY_REFERRER_POLICY`：默认 `strict-origin-when-cross-origin`
+/// - `SECURITY_X_FRAME_OPTIONS`：默认 `SAMEORIGIN`，仅 HTML 响应
+/// - `SECURITY_CSP`：默认 `DEFAULT_CONTENT_SECURITY_POLICY`，仅 HTML 响应
+#[derive(Debug, Clone)]
+pub struct SecurityHeaders {
+    content_type_options: Option<HeaderValue>,
+    referrer_policy: Option<HeaderValue>,
+    frame_options: Option<HeaderValue>,
+    content_security_policy: Option<HeaderValue>,
+}
+
+impl SecurityHeaders {
+    pub fn from_env() -> Self {
+        Self {
+            content_type_options: read_header_env("SECURITY_X_CONTENT_TYPE_OPTIONS", "nosniff"),
+            referrer_policy: read_header_env(
+                "SECURITY_REFERRER_POLICY",
+                "strict-origin-when-cross-origin",
+            ),
+            frame_options: read_header_env("SECURITY_X_FRAME_OPTIONS", "SAMEORIGIN"),
+            content_security_policy: read_header_env(
+                "SECURITY_CSP",
+                DEFAULT_CONTENT_SECURITY_POLICY,
+            ),
+        }
+    }
+}
+
+fn read_header_env(key: &str, default_value: &str) -> Option<HeaderValue> {
+    let value = std::env::var(key).unwrap_or_else(|_| default_value.to_string());
+    let value = value.trim();
+    if value.is_empty() || value.eq_ignore_ascii_case("off") {
+        return None;
+    }
+    match HeaderValue::from_str(value) {
+        Ok(header) => Some(header),
+        Err(err) => {
+            warn!("invalid {key}, header disabled: {err}");
+            None
+        }
+    }
+}
+
+pub async fn security_headers(
+    State(config): State<SecurityHeaders>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    // MCP 入口不添加安全头
+    let excluded = is_mcp_path(req.uri().path());
+    let mut response = next.run(req).await;
+    if excluded {
+        return response;
+    }
+
+    let is_html = response
         .headers()
-        .get("upgrade")
+        .get(CONTENT_TYPE)
         .and_then(|value| value.to_str().ok())
-        .unwrap_or("-");
-    let connection = req
+        .is_some_and(|value| value.trim_start().starts_with("text/html"));
+    let mut headers: Vec<(HeaderName, &Option<HeaderValue>)> = vec![
+        (X_CONTENT_TYPE_OPTIONS, &config.content_type_options),
+        (REFERRER_POLICY, &config.referrer_policy),
+    ];
+    if is_html {
+        headers.push((X_FRAME_OPTIONS, &config.frame_options));
+        headers.push((CONTENT_SECURITY_POLICY, &config.content_security_policy));
+    }
+    let response_headers = response.headers_mut();
+    for (name, value) in headers {
+        if let Some(value) = value {
+            // handler 自行设置的同名头优先
+            response_headers
+                .entry(name)
+                .or_insert_with(|| value.clone());
+        }
+    }
+    response
+}
+
+const CORS_ALLOW_METHODS: &str = "GET, HEAD, POST, PUT, DELETE, OPTIONS";
+/// 预检请求没有带 `Access-Control-Request-Headers` 时允许的请求头
+const CORS_DEFAULT_ALLOW_HEADERS: &str = "Accept, Authorization, Content-Type, If-None-Match";
+const CORS_EXPOSE_HEADERS: &str = "ETag, Retry-After, X-Request-Id";
+/// 浏览器缓存预检结果的秒数
+const CORS_MAX_AGE_SECS: &str = "600";
+
+/// 跨域访问配置，启动时从 `CORS_ALLOWED_ORIGINS` 读取：逗号分隔的 origin 列表
+/// （如 `https://app.example.com`），或 `*` 允许任意 origin；不携带 Cookie 等凭据。
+/// 不在列表中的 origin 拿不到 CORS 头，请求本身照常处理
+#[derive(Debug, Clone)]
+pub struct Cors {
+    any_origin: bool,
+    allowed_origins: Vec<String>,
+}
+
+impl Cors {
+    /// `allowed_origins` 为空时返回 `None`，不挂载中间件
+    pub fn new(allowed_origins: &str) -> Option<Self> {
+        let origins: Vec<String> = allowed_origins
+            .split(',')
+            .map(|origin| origin.trim().trim_end_matches('/').to_ascii_lowercase())
+            .filter(|origin| !origin.is_empty())
+            .collect();
+        if origins.is_empty() {
+            return None;
+        }
+        Some(Self {
+            any_origin: origins.iter().any(|origin| origin == "*"),
+            allowed_origins: origins,
+        })
+    }
+
+    pub fn from_env() -> Option<Self> {
+        let value = std::env::var("CORS_ALLOWED_ORIGINS").unwrap_or_default();
+        Self::new(&value)
+    }
+
+    /// 允许时返回 `Access-Control-Allow-Origin` 的值：通配时为 `*`，否则原样回显请求的 origin
+    fn allow_origin(&self, origin: &HeaderValue) -> Option<HeaderValue> {
+        if self.any_origin {
+            return Some(HeaderValue::from_static("*"));
+        }
+        let normalized = origin.to_str().ok()?.trim().to_ascii_lowercase();
+        self.allowed_origins
+            .contains(&normalized)
+            .then(|| origin.clone())
+    }
+}
+
+/// 为允许的 origin 添加 CORS 响应头并直接应答预检 `OPTIONS` 请求（204）；
+/// 响应统一带 `Vary: Origin`，避免缓存把某个 origin 的结果返回给其他 origin；
+/// token 路径下的 MCP 入口与管理后台只供自己使用，不开放跨域
+pub async fn cors(State(config): State<Cors>, req: Request<Body>, next: Next) -> Response {
+    if is_mcp_path(req.uri().path()) || is_dashboard_path(req.uri().path()) {
+        return next.run(req).await;
+    }
+    let allow_origin = req
+        .headers()
+        .get(ORIGIN)
+        .and_then(|origin| config.allow_origin(origin));
+    let preflight = req.method() == Method::OPTIONS
+        && req.headers().contains_key(ACCESS_CONTROL_REQUEST_METHOD);
+    let mut response = if preflight {
+        let requested_headers = req.headers().get(ACCESS_CONTROL_REQUEST_HEADERS).cloned();
+        let mut response = StatusCode::NO_CONTENT.into_response();
+        if allow_origin.is_some() {
+            let headers = response.headers_mut();
+            headers.insert(
+                ACCESS_CONTROL_ALLOW_METHODS,
+                HeaderValue::from_static(CORS_ALLOW_METHODS),
+            );
+            headers.insert(
+                ACCESS_CONTROL_ALLOW_HEADERS,
+                requested_headers
+                    .unwrap_or_else(|| HeaderValue::from_static(CORS_DEFAULT_ALLOW_HEADERS)),
+            );
+            headers.insert(
+                ACCESS_CONTROL_MAX_AGE,
+                HeaderValue::from_static(CORS_MAX_AGE_SECS),
+            );
+        }
+        response
+    } else {
+        let mut response = next.run(req).await;
+        if allow_origin.is_some() {
+            response.headers_mut().insert(
+                ACCESS_CONTROL_EXPOSE_HEADERS,
+                HeaderValue::from_static(CORS_EXPOSE_HEADERS),
+            );
+        }
+        response
+    };
+    let headers = response.headers_mut();
+    if let Some(origin) = allow_origin {
+        headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+    }
+    headers.append(VARY, HeaderValue::from_static("Origin"));
+    response
+}
+
+/// 配置了 `ADMIN_USERNAME` / `ADMIN_PASSWORD` 时挂在首页与 `/api/*` 上，要求 HTTP Basic Auth；
+/// 客户端无法同时携带 Basic 与 Bearer，带有效 `API_TOKEN` 的 Bearer 请求同样放行
+pub async fn admin_auth_middleware(
+    State(auth): State<AdminBasicAuth>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let authorization = req
         .headers()
-        .get("connection")
+        .get(AUTHORIZATION)
         .and_then(|value| value.to_str().ok())
-        .unwrap_or("-");
-    println!(
-        "[solin-blog] {} {} upgrade={} connection={}",
-        req.method(),
-        req.uri(),
-        upgrade,
-        connection
+        .map(str::trim)
+        .unwrap_or_default();
+    if basic_auth_matches(authorization, &auth) || api_bearer_matches(authorization) {
+        return next.run(req).await;
+    }
+    (
+        StatusCode::UNAUTHORIZED,
+        [(WWW_AUTHENTICATE, "Basic realm=\"SolinBlog\"")],
+        "unauthorized",
+    )
+        .into_response()
+}
+
+fn basic_auth_matches(authorization: &str, auth: &AdminBasicAuth) -> bool {
+    let Some(encoded) = authorization.strip_prefix("Basic ") else {
+        return false;
+    };
+    let Some(decoded) = STANDARD
+        .decode(encoded.trim())
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+    else {
+        return false;
+    };
+    let Some((username, password)) = decoded.split_once(':') else {
+        return false;
+    };
+    // 用户名与密码都比较完再判断，耗时不随哪一项出错而变化
+    let username_ok = constant_time_eq(username.as_bytes(), auth.username.as_bytes());
+    let password_ok = constant_time_eq(password.as_bytes(), auth.password.as_bytes());
+    username_ok & password_ok
+}
+
+fn api_bearer_matches(authorization: &str) -> bool {
+    let expected = std::env::var("API_TOKEN").unwrap_or_default();
+    let expected = expected.trim();
+    match authorization.strip_prefix("Bearer ") {
+        Some(provided) if !expected.is_empty() => {
+            constant_time_eq(provided.trim().as_bytes(), expected.as_bytes())
+        }
+        _ => false,
+    }
+}
+
+/// 限流器最多跟踪的客户端 IP 数，超出时淘汰最久未访问的 IP
+const RATE_LIMIT_MAX_CLIENTS: usize = 10_000;
+
+/// 按最近访问淘汰的有界客户端表，供各限流器共用；访问与淘汰都是 O(log n)，不需要整表扫描
+#[derive(Debug)]
+pub struct ClientLru<K, V> {
+    capacity: usize,
+    /// 键 → (最近访问序号, 值)
+    entries: HashMap<K, (u64, V)>,
+    /// 最近访问序号 → 键，第一项即最久未访问的键
+    order: BTreeMap<u64, K>,
+    tick: u64,
+}
+
+impl<K: Copy + Eq + Hash, V> ClientLru<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    /// 取出键对应的值并记为最近访问，不存在时用 `init` 创建；表满时先淘汰最久未访问的键
+    pub fn get_or_insert_with(&mut self, key: K, init: impl FnOnce() -> V) -> &mut V {
+        if !self.entries.contains_key(&key)
+            && self.entries.len() >= self.capacity
+            && let Some((_, oldest)) = self.order.pop_first()
+        {
+            self.entries.remove(&oldest);
+        }
+        self.tick += 1;
+        let entry = self
+            .entries
+            .entry(key)
+            .or_insert_with(|| (self.tick, init()));
+        self.order.remove(&entry.0);
+        entry.0 = self.tick;
+        self.order.insert(self.tick, key);
+        &mut entry.1
+    }
+}
+
+/// 静态资源路由，其余非 MCP 路由都按页面路由限流
+const ASSET_PATHS: &[&str] = &[
+    "/favicon.ico",
+    "/icon.png",
+    "/icon.webp",
+    "/apple-touch-icon.png",
+    "/manifest.webmanifest",
+    "/sw.js",
+];
+
+/// 限流分组，每组独立配置，同一 IP 在不同分组各有一个令牌桶
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RateLimitClass {
+    Page,
+    Asset,
+    Mcp,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TokenBucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+/// 按客户端 IP 的令牌桶限流，桶容量为每分钟请求数，令牌按分钟匀速补充
+/// - `RATE_LIMIT_PAGES`：页面、标签页、API 等路由，默认 `0`（不限流）
+/// - `RATE_LIMIT_ASSETS`：`/public/*`、favicon、图标、manifest 与 Service Worker，默认 `0`
+/// - `RATE_LIMIT_MCP`：各 MCP 入口，默认 `0`
+///
+/// 客户端 IP 取连接的对端地址，`TRUST_PROXY=true` 时优先取 `X-Real-IP` / `X-Forwarded-For`；
+/// 回环地址不限流
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    page_limit: u32,
+    asset_limit: u32,
+    mcp_limit: u32,
+    trust_proxy: bool,
+    buckets: Arc<Mutex<ClientLru<(IpAddr, RateLimitClass), TokenBucket>>>,
+}
+
+impl RateLimiter {
+    pub fn new(page_limit: u32, asset_limit: u32, mcp_limit: u32) -> Self {
+        Self {
+            page_limit,
+            asset_limit,
+            mcp_limit,
+            trust_proxy: false,
+            buckets: Arc::new(Mutex::new(ClientLru::new(RATE_LIMIT_MAX_CLIENTS))),
+        }
+    }
+
+    /// 三组限额都为 0 时返回 `None`，不挂载中间件
+    pub fn from_env() -> Option<Self> {
+        let page_limit = read_rate_limit_env("RATE_LIMIT_PAGES");
+        let asset_limit = read_rate_limit_env("RATE_LIMIT_ASSETS");
+        let mcp_limit = read_rate_limit_env("RATE_LIMIT_MCP");
+        if page_limit == 0 && asset_limit == 0 && mcp_limit == 0 {
+            return None;
+        }
+        let mut limiter = Self::new(page_limit, asset_limit, mcp_limit);
+        limiter.trust_proxy = resolve_trust_proxy_from_env();
+        Some(limiter)
+    }
+
+    pub fn classify(&self, path: &str) -> RateLimitClass {
+        if is_mcp_path(path) {
+            RateLimitClass::Mcp
+        } else if path.starts_with("/public/") || ASSET_PATHS.contains(&path) {
+            RateLimitClass::Asset
+        } else {
+            RateLimitClass::Page
+        }
+    }
+
+    fn limit(&self, class: RateLimitClass) -> u32 {
+        match class {
+            RateLimitClass::Page => self.page_limit,
+            RateLimitClass::Asset => self.asset_limit,
+            RateLimitClass::Mcp => self.mcp_limit,
+        }
+    }
+
+    /// 消耗一个令牌；令牌不足时返回需要等待的时间
+    pub fn check(&self, ip: IpAddr, class: RateLimitClass, now: Instant) -> Result<(), Duration> {
+        let limit = self.limit(class);
+        if limit == 0 || ip.is_loopback() {
+            return Ok(());
+        }
+        let capacity = f64::from(limit);
+        let per_second = capacity / 60.0;
+        let mut buckets = self.buckets.lock().unwrap_or_else(|err| err.into_inner());
+        let bucket = buckets.get_or_insert_with((ip, class), || TokenBucket {
+            tokens: capacity,
+            updated_at: now,
+        });
+        let elapsed = now
+            .saturating_duration_since(bucket.updated_at)
+            .as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * per_second).min(capacity);
+        bucket.updated_at = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+        Err(Duration::from_secs_f64((1.0 - bucket.tokens) / per_second))
+    }
+}
+
+/// 客户端 IP：`trust_proxy` 时优先取 `X-Real-IP` / `X-Forwarded-For`，否则只取连接的对端地址，
+/// 不信任代理时伪造的转发头不生效
+pub fn resolve_client_ip(
+    headers: &HeaderMap,
+    peer: Option<SocketAddr>,
+    trust_proxy: bool,
+) -> Option<IpAddr> {
+    let forwarded = trust_proxy
+        .then(|| forwarded_client_ip(headers))
+        .flatten()
+        .and_then(|value| value.parse::<IpAddr>().ok());
+    forwarded.or_else(|| peer.map(|addr| addr.ip()))
+}
+
+/// 反向代理传入的客户端 IP：优先 `X-Real-IP`，其次 `X-Forwarded-For` 中的第一个地址
+fn forwarded_client_ip(headers: &HeaderMap) -> Option<&str> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    };
+    header("x-real-ip").or_else(|| {
+        header("x-forwarded-for")
+            .and_then(|value| value.split(',').next())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    })
+}
+
+/// 从请求中取客户端 IP，对端地址来自 `ConnectInfo`，限流、MCP 白名单与访问日志共用
+pub(crate) fn request_client_ip(req: &Request<Body>, trust_proxy: bool) -> Option<IpAddr> {
+    let peer = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| *addr);
+    resolve_client_ip(req.headers(), peer, trust_proxy)
+}
+
+/// 额度不合法时打印警告并按 0（不限流）处理
+fn read_rate_limit_env(key: &str) -> u32 {
+    let value = std::env::var(key).unwrap_or_default();
+    let value = value.trim();
+    if value.is_empty() {
+        return 0;
+    }
+    match value.parse::<u32>() {
+        Ok(limit) => limit,
+        Err(_) => {
+            warn!("{key} must be a non-negative integer, ignored");
+            0
+        }
+    }
+}
+
+/// 超出额度时返回 429，`Retry-After` 为补足一个令牌所需的秒数（向上取整）；`/healthz`、`/readyz` 不限流
+pub async fn rate_limit(
+    State(limiter): State<RateLimiter>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    if is_health_path(req.uri().path()) {
+        return next.run(req).await;
+    }
+    let class = limiter.classify(req.uri().path());
+    // 取不到客户端 IP（未使用 ConnectInfo）时不限流
+    let Some(ip) = request_client_ip(&req, limiter.trust_proxy) else {
+        return next.run(req).await;
+    };
+    match limiter.check(ip, class, Instant::now()) {
+        Ok(()) => next.run(req).await,
+        Err(wait) => {
+            let retry_after = wait.as_secs_f64().ceil().max(1.0) as u64;
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(RETRY_AFTER, retry_after.to_string())],
+                "too many requests",
+            )
+                .into_response()
+        }
+    }
+}
+
+/// 与 token 路径 `/{token}/...` 同样是两段的公开路由前缀，这些路径不按 token 路径处理
+const PUBLIC_ROUTE_SEGMENTS: &[&str] = &["admin", "api", "pages", "public", "tags", "tools"];
+
+/// `/{token}/mcp` 与 `/{token}/admin/...` 中的 token 与其后的路径段
+fn split_token_path(path: &str) -> Option<(&str, &str)> {
+    let mut segments = path.strip_prefix('/')?.splitn(3, '/');
+    let token = segments.next().filter(|token| !token.is_empty())?;
+    let section = segments.next()?;
+    (!PUBLIC_ROUTE_SEGMENTS.contains(&token)).then_some((token, section))
+}
+
+/// 通过 `Authorization: Bearer` 传递 token 的 MCP 入口
+const MCP_BEARER_PATH: &str = "/mcp";
+
+/// MCP 入口 `/mcp` 与旧的 `/{token}/mcp`；只看路径形状，不校验 token，未知 token 同样按 MCP 入口限流
+pub(crate) fn is_mcp_path(path: &str) -> bool {
+    path == MCP_BEARER_PATH || split_token_path(path).is_some_and(|(_, section)| section == "mcp")
+}
+
+/// 管理后台 `/{token}/admin`
+pub(crate) fn is_dashboard_path(path: &str) -> bool {
+    split_token_path(path).is_some_and(|(_, section)| section == "admin")
+}
+
+/// 校验 `/mcp` 的 `Authorization: Bearer` 或 token 路径 `/{token}/mcp`、`/{token}/admin` 中的 token，
+/// token 在 `data/tokens.json` 中维护，新增与吊销无需重启；`require_write` 时只接受 read-write token（管理后台）
+#[derive(Debug, Clone)]
+pub struct McpTokenAuth {
+    tokens: Arc<TokenStore>,
+    require_write: bool,
+}
+
+impl McpTokenAuth {
+    pub fn new(tokens: Arc<TokenStore>) -> Self {
+        Self {
+            tokens,
+            require_write: false,
+        }
+    }
+
+    pub fn read_write_only(tokens: Arc<TokenStore>) -> Self {
+        Self {
+            tokens,
+            require_write: true,
+        }
+    }
+}
+
+/// `/mcp` 上 Bearer token 缺失或无效时返回 401；token 路径上 token 不存在、已吊销或权限不足时
+/// 返回 404，与未挂载的路径无法区分。校验通过后把权限范围放入请求扩展，供 MCP 入口选择对应的服务实例
+pub async fn mcp_token_auth(
+    State(auth): State<McpTokenAuth>,
+    mut req: Request<Body>,
+    next: Next,
+) -> Response {
+    // 管理后台嵌套在 `/{token}/admin` 下，请求 URI 已去掉前缀，需从原始 URI 取 token
+    let path = req.extensions().get::<OriginalUri>().map_or_else(
+        || req.uri().path().to_string(),
+        |uri| uri.path().to_string(),
     );
-    let response = next.run(req).await;
-    println!("[solin-blog] -> {}", response.status());
-    response
+    let bearer = path == MCP_BEARER_PATH;
+    let token = if bearer {
+        req.headers()
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().strip_prefix("Bearer "))
+            .map(str::trim)
+    } else {
+        split_token_path(&path).map(|(token, _)| token)
+    };
+    let scope = token
+        .filter(|token| !token.is_empty())
+        .and_then(|token| auth.tokens.authenticate(token))
+        .map(|(_, scope)| scope)
+        .filter(|scope| !auth.require_write || *scope == McpScope::ReadWrite);
+    let Some(scope) = scope else {
+        if bearer {
+            return (
+                StatusCode::UNAUTHORIZED,
+                [(WWW_AUTHENTICATE, "Bearer realm=\"SolinBlog MCP\"")],
+                "unauthorized",
+            )
+                .into_response();
+        }
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    req.extensions_mut().insert(scope);
+    next.run(req).await
+}
+
+/// MCP 入口的请求体大小上限，取自 `MCP_MAX_BODY_BYTES`
+#[derive(Debug, Clone, Copy)]
+pub struct McpBodyLimit {
+    max_bytes: usize,
+}
+
+impl McpBodyLimit {
+    pub fn new(max_bytes: usize) -> Self {
+        Self { max_bytes }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(resolve_mcp_max_body_bytes_from_env())
+    }
+
+    /// 413 与 JSON-RPC 错误体，错误信息带有上限值，客户端可以据此拆分请求
+    fn too_large(&self) -> Response {
+        let message = format!(
+            "request body exceeds MCP_MAX_BODY_BYTES ({} bytes), \
+             split the content into smaller calls",
+            self.max_bytes
+        );
+        (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            axum::Json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": null,
+                "error": {
+                    "code": -32600,
+                    "message": message,
+                    "data": { "max_body_bytes": self.max_bytes },
+                },
+            })),
+        )
+            .into_response()
+    }
+}
+
+/// 按上限读取 MCP 请求体后再交给 MCP 服务：`Content-Length` 超限时直接拒绝，
+/// 分块传输的请求边读边计数，超出上限立即停止读取，不会整体缓冲超大的请求
+pub async fn mcp_body_limit(
+    State(limit): State<McpBodyLimit>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let declared = req
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok());
+    if let Some(len) = declared.filter(|len| *len > limit.max_bytes as u64) {
+        warn!("mcp request body of {len} bytes rejected");
+        return limit.too_large();
+    }
+    let (parts, body) = req.into_parts();
+    let Ok(bytes) = axum::body::to_bytes(body, limit.max_bytes).await else {
+        warn!(
+            "mcp request body exceeds {} bytes, rejected",
+            limit.max_bytes
+        );
+        return limit.too_large();
+    };
+    next.run(Request::from_parts(parts, Body::from(bytes)))
+        .await
+}
+
+/// MCP 入口的来源 IP 白名单，取自 `MCP_ALLOWED_IPS`，只挂在 MCP 子路由上，公开页面不受影响。
+/// 客户端 IP 取连接的对端地址，只有 `TRUST_PROXY=true` 时才采信 `X-Real-IP` / `X-Forwarded-For`，
+/// 否则伪造的代理头不会生效
+#[derive(Debug, Clone)]
+pub struct McpIpAllowlist {
+    allowed: Arc<Vec<IpCidr>>,
+    trust_proxy: bool,
+}
+
+impl McpIpAllowlist {
+    pub fn new(allowed: Vec<IpCidr>, trust_proxy: bool) -> Self {
+        Self {
+            allowed: Arc::new(allowed),
+            trust_proxy,
+        }
+    }
+
+    /// 未设置 `MCP_ALLOWED_IPS` 时返回 `Ok(None)`，不挂载中间件
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        Ok(resolve_mcp_allowed_ips_from_env()?
+            .map(|allowed| Self::new(allowed, resolve_trust_proxy_from_env())))
+    }
+
+    pub fn is_allowed(&self, ip: IpAddr) -> bool {
+        self.allowed.iter().any(|cidr| cidr.contains(ip))
+    }
+}
+
+/// 不在白名单内或取不到客户端 IP 时返回 403；日志只记录 IP，不记录路径，避免 token 写进日志
+pub async fn mcp_ip_allowlist(
+    State(allowlist): State<McpIpAllowlist>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    match request_client_ip(&req, allowlist.trust_proxy) {
+        Some(ip) if allowlist.is_allowed(ip) => next.run(req).await,
+        Some(ip) => {
+            warn!("mcp request from {ip} rejected by MCP_ALLOWED_IPS");
+            (StatusCode::FORBIDDEN, "forbidden").into_response()
+        }
+        None => {
+            warn!("mcp request without client ip rejected by MCP_ALLOWED_IPS");
+            (StatusCode::FORBIDDEN, "forbidden").into_response()
+        }
+    }
+}
+
+/// 普通路由的处理时限，取自 `REQUEST_TIMEOUT_SECS`；MCP 入口与 `/events` 是长连接，不受限制，
+/// MCP 工具调用在工具层单独限时
+#[derive(Debug, Clone)]
+pub struct RequestTimeout {
+    timeout: Duration,
+}
+
+impl RequestTimeout {
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout }
+    }
+
+    /// `REQUEST_TIMEOUT_SECS=0` 时返回 `None`，不挂载中间件
+    pub fn from_env() -> Option<Self> {
+        resolve_request_timeout_from_env().map(Self::new)
+    }
+
+    fn is_excluded(&self, path: &str) -> bool {
+        path == "/events" || is_mcp_path(path)
+    }
+}
+
+/// 超时返回 408，响应体说明时限；只限制生成响应的时间，不限制响应体的传输
+pub async fn request_timeout(
+    State(config): State<RequestTimeout>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    if config.is_excluded(req.uri().path()) {
+        return next.run(req).await;
+    }
+    match tokio::time::timeout(config.timeout, next.run(req)).await {
+        Ok(response) => response,
+        Err(_) => {
+            warn!("request timed out after {}s", config.timeout.as_secs());
+            (
+                StatusCode::REQUEST_TIMEOUT,
+                format!(
+                    "request did not finish within {}s (REQUEST_TIMEOUT_SECS)",
+                    config.timeout.as_secs()
+                ),
+            )
+                .into_response()
+        }
+    }
 }
@@ -1,7 +1,38 @@
+pub mod access_log;
+pub mod admin;
+pub mod api;
+pub mod assets;
+pub mod dashboard;
+pub mod events;
 pub mod handlers;
+pub mod health;
+pub mod mcp_endpoint;
 pub mod middleware;
-pub mod assets;
+pub mod tls;
 
+pub use access_log::{AccessLog, access_log};
+pub use admin::rebuild_index_handler;
+pub use api::{
+    api_page_detail_handler, api_page_list_handler, check_validate_rate, create_page_api_handler,
+    delete_page_api_handler, page_html_api_handler, update_page_api_handler,
+    validate_html_api_handler,
+};
+pub use assets::{
+    apple_touch_icon_handler, favicon_handler, icon_png_handler, icon_webp_handler,
+    public_asset_handler, sanitize_public_path, service_worker_handler, web_manifest_handler,
+};
+pub use dashboard::{
+    DashboardState, dashboard_delete_handler, dashboard_edit_handler, dashboard_list_handler,
+    dashboard_preview_handler, dashboard_save_handler,
+};
+pub use events::{LivePageEvent, PageEvents, events_handler};
 pub use handlers::*;
-pub use middleware::log_request;
-pub use assets::{public_asset_handler, sanitize_public_path};
+pub use health::{HealthState, healthz_handler, readiness_failed_check, readyz_handler};
+pub use mcp_endpoint::{McpEndpoints, mcp_endpoint_handler};
+pub use middleware::{
+    ClientLru, Cors, McpBodyLimit, McpIpAllowlist, McpTokenAuth, RateLimitClass, RateLimiter,
+    RedactedUri, RequestTimeout, SecurityHeaders, admin_auth_middleware, cors, log_request,
+    mcp_body_limit, mcp_ip_allowlist, mcp_token_auth, rate_limit, request_timeout,
+    resolve_client_ip, security_headers,
+};
+pub use tls::{load_rustls_config, reload_tls_on_sighup, spawn_https_redirect};
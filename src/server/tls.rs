@@ -0,0 +1,116 @@
+use std::{io, net::SocketAddr};
+
+use anyhow::{Context, Result};
+use axum::{
+    Router,
+    extract::{Request, State},
+    http::{
+        HeaderValue, StatusCode,
+        header::{HOST, LOCATION},
+    },
+    response::{IntoResponse, Response},
+};
+use axum_server::tls_rustls::RustlsConfig;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+use crate::config::TlsConfig;
+
+/// 读取证书链与私钥；文件不存在或 PEM 无法解析时返回带路径的错误
+pub async fn load_rustls_config(tls: &TlsConfig) -> Result<RustlsConfig> {
+    // reqwest 同样依赖 rustls，进程内只安装一次 ring 作为默认加密实现
+    let _ = rustls::crypto::ring::default_provider().install_default();
+    RustlsConfig::from_pem_file(&tls.cert_path, &tls.key_path)
+        .await
+        .with_context(|| {
+            format!(
+                "load TLS certificate {} and key {}",
+                tls.cert_path.display(),
+                tls.key_path.display()
+            )
+        })
+}
+
+/// 收到 SIGHUP 时重新读取证书与私钥，续期后无需重启；读取失败时继续使用旧证书
+#[cfg(unix)]
+pub fn reload_tls_on_sighup(config: RustlsConfig, tls: TlsConfig) {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(err) => {
+            error!("listen for SIGHUP failed, TLS certificate reload disabled: {err}");
+            return;
+        }
+    };
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            match config
+                .reload_from_pem_file(&tls.cert_path, &tls.key_path)
+                .await
+            {
+                Ok(()) => info!("TLS certificate reloaded from {}", tls.cert_path.display()),
+                Err(err) => warn!("reload TLS certificate failed, keeping the current one: {err}"),
+            }
+        }
+    });
+}
+
+#[cfg(not(unix))]
+pub fn reload_tls_on_sighup(_config: RustlsConfig, _tls: TlsConfig) {}
+
+/// 在 `addr` 上监听明文 HTTP，所有请求 301 跳转到 `https_port` 上的同名主机，保留路径与查询参数；
+/// 端口绑定失败时返回错误，`shutdown` 取消后停止监听
+pub async fn spawn_https_redirect(
+    addr: SocketAddr,
+    https_port: u16,
+    shutdown: CancellationToken,
+) -> io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    info!("http redirect listening on http://{addr}");
+    let app = Router::new()
+        .fallback(https_redirect_handler)
+        .with_state(https_port);
+    tokio::spawn(async move {
+        if let Err(err) = axum::serve(listener, app)
+            .with_graceful_shutdown(shutdown.cancelled_owned())
+            .await
+        {
+            error!("http redirect listener stopped: {err}");
+        }
+    });
+    Ok(())
+}
+
+async fn https_redirect_handler(State(https_port): State<u16>, req: Request) -> Response {
+    let Some(host) = req
+        .headers()
+        .get(HOST)
+        .and_then(|value| value.to_str().ok())
+        .map(host_without_port)
+        .filter(|host| !host.is_empty())
+    else {
+        return (StatusCode::BAD_REQUEST, "missing Host header").into_response();
+    };
+    let path = req
+        .uri()
+        .path_and_query()
+        .map_or("/", |path_and_query| path_and_query.as_str());
+    let location = if https_port == 443 {
+        format!("https://{host}{path}")
+    } else {
+        format!("https://{host}:{https_port}{path}")
+    };
+    match HeaderValue::try_from(location) {
+        Ok(location) => (StatusCode::MOVED_PERMANENTLY, [(LOCATION, location)]).into_response(),
+        Err(_) => (StatusCode::BAD_REQUEST, "invalid Host header").into_response(),
+    }
+}
+
+/// `example.com:80` → `example.com`，`[::1]:80` → `[::1]`
+fn host_without_port(host: &str) -> &str {
+    if host.starts_with('[') {
+        return host.find(']').map_or(host, |end| &host[..=end]);
+    }
+    host.split(':').next().unwrap_or(host)
+}
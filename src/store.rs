@@ -1,12 +1,22 @@
 use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use chrono::Utc;
 use getrandom::getrandom;
 use pinyin::ToPinyin;
 use serde::{Deserialize, Serialize};
 use serde_json::Map;
-use std::collections::BTreeMap;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
+use std::io::{ErrorKind, Write};
 use std::path::{Path, PathBuf};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{Span, error, info, instrument, warn};
+
+use crate::store_wal::{WalLog, WalOp, lock_wal};
+use crate::web::{EXTRA_HEAD_HTML_KEY, append_extra_head_html, sanitize_extra_head_html};
+use html_util::{find_bytes, find_bytes_case_insensitive};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SeoMeta {
@@ -17,6 +27,9 @@ pub struct SeoMeta {
     pub keywords: Option<Vec<String>>,
     #[serde(default)]
     pub extra: Map<String, serde_json::Value>,
+    /// 自定义 JSON-LD（如 `FAQPage`、`HowTo`），必须是 JSON 对象；与自动生成的 Article 一并输出
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured_data: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,6 +43,18 @@ pub struct PageMeta {
     pub updated_at: i64,
     #[serde(default)]
     pub view_count: u64,
+    /// 按 IP 哈希去重的访问数，同一访客每天只计一次
+    #[serde(default)]
+    pub unique_view_count: u64,
+    /// 结构化标签，支持 `language:rust` 这类命名空间写法，与只用于 SEO 的 `seo.keywords` 分开维护
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    /// 首页手动排序，值越小越靠前；未设置的页面排在最后
+    #[serde(default)]
+    pub sort_order: Option<i32>,
+    /// 首次从草稿发布的时间；为空时以 `created_at` 作为发布日期
+    #[serde(default)]
+    pub published_at: Option<i64>,
     #[serde(default)]
     pub extra: Map<String, serde_json::Value>,
 }
@@ -38,6 +63,104 @@ pub struct PageMeta {
 pub struct StoreIndex {
     #[serde(default)]
     pub pages: BTreeMap<String, PageIndexEntry>,
+    /// 标签 → page_id 列表（升序），随页面写入同步维护
+    #[serde(default)]
+    pub tag_index: BTreeMap<String, Vec<String>>,
+}
+
+impl StoreIndex {
+    /// 重新登记页面的标签，`tags` 为空时只移除旧标签
+    pub fn set_page_tags(&mut self, page_id: &str, tags: &[String]) {
+        self.remove_page_tags(page_id);
+        for tag in tags {
+            let page_ids = self.tag_index.entry(tag.clone()).or_default();
+            if let Err(pos) = page_ids.binary_search_by(|id| id.as_str().cmp(page_id)) {
+                page_ids.insert(pos, page_id.to_string());
+            }
+        }
+    }
+
+    pub fn remove_page_tags(&mut self, page_id: &str) {
+        self.tag_index.retain(|_, page_ids| {
+            page_ids.retain(|id| id != page_id);
+            !page_ids.is_empty()
+        });
+    }
+
+    /// 每个标签及其页面数，按标签名升序
+    pub fn tag_counts(&self) -> Vec<(String, usize)> {
+        self.tag_index
+            .iter()
+            .map(|(tag, page_ids)| (tag.clone(), page_ids.len()))
+            .collect()
+    }
+
+    /// 同时命中命名空间下的子标签，例如 `language` 可匹配 `language:rust`
+    pub fn entries_by_tag(&self, tag: &str) -> Vec<PageIndexEntry> {
+        let tag = normalize_tag(tag);
+        if tag.is_empty() {
+            return Vec::new();
+        }
+        let prefix = format!("{}:", tag);
+        let page_ids: BTreeSet<&String> = self
+            .tag_index
+            .iter()
+            .filter(|(key, _)| **key == tag || key.starts_with(&prefix))
+            .flat_map(|(_, page_ids)| page_ids)
+            .collect();
+        page_ids
+            .into_iter()
+            .filter_map(|page_id| self.pages.get(page_id).cloned())
+            .collect()
+    }
+}
+
+/// 标签去除首尾空白并转小写，`Rust` 与 `rust ` 视为同一标签
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
+/// 单个页面允许的标签数量上限
+pub const MAX_PAGE_TAGS: usize = 20;
+
+/// `set_page_tags` 写入前的规范化：逐个 `normalize_tag` 并按首次出现去重，拒绝空标签与超出上限
+pub fn normalize_page_tags(tags: &[String]) -> Result<Vec<String>> {
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = normalize_tag(tag);
+        if tag.is_empty() {
+            bail!("tag is empty");
+        }
+        if !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    if normalized.len() > MAX_PAGE_TAGS {
+        bail!(
+            "too many tags: {} (max {})",
+            normalized.len(),
+            MAX_PAGE_TAGS
+        );
+    }
+    Ok(normalized)
+}
+
+/// 写入标签索引的标签：`tags` 优先，未设置或为空时退回 `seo.keywords`
+pub fn page_index_tags(meta: &PageMeta) -> Vec<String> {
+    let source = meta
+        .tags
+        .as_ref()
+        .filter(|tags| !tags.is_empty())
+        .or(meta.seo.keywords.as_ref());
+    let mut tags: Vec<String> = source
+        .into_iter()
+        .flatten()
+        .map(|tag| normalize_tag(tag))
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,11 +170,680 @@ pub struct PageIndexEntry {
     #[serde(default)]
     pub page_uid: String,
     pub original_id: Option<String>,
+    /// 冗余自 meta.json，热门排行只需读取索引
+    #[serde(default)]
+    pub view_count: u64,
+    /// 冗余自 meta.json，与 `view_count` 一样随页面写入同步
+    #[serde(default)]
+    pub sort_order: Option<i32>,
+    /// 冗余自 meta.json，最近更新列表只需读取索引；旧索引中为 0，重建索引后补齐
+    #[serde(default)]
+    pub updated_at: i64,
 }
 
+/// `create_pages_batch` 的单个待创建页面，`page_uid` 由存储层分配
 #[derive(Debug, Clone)]
+pub struct NewPage {
+    pub meta: PageMeta,
+    pub html: String,
+    pub markdown: Option<String>,
+}
+
+/// 幂等键 → 页面的记录，FS 后端存为 `idempotency.json`，S3 后端存为同名对象
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IdempotencyRecords {
+    #[serde(default)]
+    pub keys: BTreeMap<String, IdempotencyRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdempotencyRecord {
+    pub page_uid: String,
+    pub created_at: i64,
+}
+
+impl IdempotencyRecords {
+    /// 只返回 `since` 之后记录的页面，过期的记录视为不存在
+    pub fn find(&self, key: &str, since: i64) -> Option<String> {
+        self.keys
+            .get(key)
+            .filter(|record| record.created_at >= since)
+            .map(|record| record.page_uid.clone())
+    }
+
+    /// 写入新记录，同时清理早于 `since` 的记录
+    pub fn insert(&mut self, key: &str, page_uid: &str, since: i64) -> Result<()> {
+        self.keys.retain(|_, record| record.created_at >= since);
+        self.keys.insert(
+            key.to_string(),
+            IdempotencyRecord {
+                page_uid: page_uid.to_string(),
+                created_at: now_unix_seconds()?,
+            },
+        );
+        Ok(())
+    }
+}
+
+/// 回收站中的页面，`trash_id` 为 `{page_id}-{deleted_at}`，同名冲突时追加 `-2`、`-3`…
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeletedPage {
+    pub trash_id: String,
+    pub page_id: String,
+    pub page_uid: String,
+    pub title: String,
+    pub deleted_at: i64,
+}
+
+impl DeletedPage {
+    /// 删除前的 meta 读取失败时，uid 与标题留空
+    pub(crate) fn new(
+        trash_id: String,
+        page_id: &str,
+        meta: Option<&PageMeta>,
+        deleted_at: i64,
+    ) -> Self {
+        let title = meta.map(|meta| {
+            if meta.seo.title.is_empty() {
+                meta.seo.seo_title.clone()
+            } else {
+                meta.seo.title.clone()
+            }
+        });
+        Self {
+            trash_id,
+            page_id: page_id.to_string(),
+            page_uid: meta.map(|meta| meta.page_uid.clone()).unwrap_or_default(),
+            title: title.unwrap_or_default(),
+            deleted_at,
+        }
+    }
+}
+
+/// 页面写入成功后的回调，收到的是落盘后的 meta；删除回调收到的是删除前的 meta
+pub type PageHook = Arc<dyn Fn(&PageMeta) + Send + Sync>;
+
+#[derive(Clone)]
 pub struct PageStore {
     pub base_dir: PathBuf,
+    /// 新页面写入后调用，见 `PageStoreBuilder::on_create`
+    post_create_hook: Option<PageHook>,
+    /// 已有页面的正文、meta 或 Markdown 写入后调用，见 `PageStoreBuilder::on_update`
+    post_update_hook: Option<PageHook>,
+    /// 页面移入回收站后调用，见 `PageStoreBuilder::on_delete`
+    post_delete_hook: Option<PageHook>,
+}
+
+impl std::fmt::Debug for PageStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PageStore")
+            .field("base_dir", &self.base_dir)
+            .field("post_create_hook", &self.post_create_hook.is_some())
+            .field("post_update_hook", &self.post_update_hook.is_some())
+            .field("post_delete_hook", &self.post_delete_hook.is_some())
+            .finish()
+    }
+}
+
+/// `PageStore::builder()` 返回的构造器，未设置 `base_dir` 时使用 `data`
+/// 钩子在 WAL 锁释放后同步调用，运行在存储所在的线程（`SyncPageStore` 中为 `spawn_blocking` 线程），
+/// 耗时操作（CDN 刷新、通知）应自行转交后台任务
+#[derive(Default)]
+pub struct PageStoreBuilder {
+    base_dir: Option<PathBuf>,
+    post_create_hook: Option<PageHook>,
+    post_update_hook: Option<PageHook>,
+    post_delete_hook: Option<PageHook>,
+}
+
+impl PageStoreBuilder {
+    pub fn base_dir(mut self, base_dir: impl Into<PathBuf>) -> Self {
+        self.base_dir = Some(base_dir.into());
+        self
+    }
+
+    /// 新页面（含批量创建）写入成功后调用
+    pub fn on_create(mut self, hook: impl Fn(&PageMeta) + Send + Sync + 'static) -> Self {
+        self.post_create_hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// 已有页面写入成功后调用；浏览计数、标签、排序等只改元数据的操作不触发
+    pub fn on_update(mut self, hook: impl Fn(&PageMeta) + Send + Sync + 'static) -> Self {
+        self.post_update_hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// 页面删除（移入回收站）成功后调用，收到删除前的 meta；永久删除回收站条目不触发
+    pub fn on_delete(mut self, hook: impl Fn(&PageMeta) + Send + Sync + 'static) -> Self {
+        self.post_delete_hook = Some(Arc::new(hook));
+        self
+    }
+
+    pub fn build(self) -> PageStore {
+        PageStore {
+            base_dir: self.base_dir.unwrap_or_else(|| PathBuf::from("data")),
+            post_create_hook: self.post_create_hook,
+            post_update_hook: self.post_update_hook,
+            post_delete_hook: self.post_delete_hook,
+        }
+    }
+}
+
+/// 页面存储后端接口，Web 路由与 MCP 工具只依赖该 trait
+/// - `SyncPageStore`：包装本地文件系统 `PageStore`（默认）
+/// - `S3PageStore`：S3 兼容对象存储（`s3` feature）
+#[async_trait]
+pub trait PageStoreTrait: Send + Sync {
+    async fn create_page_auto_uid(&self, meta: &PageMeta, html: &str) -> Result<PageMeta>;
+    async fn create_page_auto_uid_with_markdown(
+        &self,
+        meta: &PageMeta,
+        html: &str,
+        markdown: Option<&str>,
+    ) -> Result<PageMeta>;
+    async fn resolve_page_id_by_uid(&self, page_uid: &str) -> Result<Option<String>>;
+    async fn create_page(&self, page_id: &str, meta: &PageMeta, html: &str) -> Result<()>;
+    async fn save_page(&self, page_id: &str, meta: &PageMeta, html: &str) -> Result<()>;
+    async fn save_page_with_markdown(
+        &self,
+        page_id: &str,
+        meta: &PageMeta,
+        html: &str,
+        markdown: Option<&str>,
+    ) -> Result<()>;
+    async fn update_page(&self, page_id: &str, meta: &PageMeta, html: &str) -> Result<()>;
+    async fn update_page_with_markdown(
+        &self,
+        page_id: &str,
+        meta: &PageMeta,
+        html: &str,
+        markdown: Option<&str>,
+    ) -> Result<()>;
+    /// 乐观并发控制：比较与写入在同一把写锁内完成，页面已被修改时返回 `UpdateConflict`
+    async fn update_page_if_unmodified(
+        &self,
+        page_id: &str,
+        meta: &PageMeta,
+        html: &str,
+        markdown: Option<&str>,
+        expected_updated_at: i64,
+    ) -> Result<()>;
+    async fn load_page(&self, page_id: &str) -> Result<(PageMeta, String)>;
+    async fn load_page_markdown(&self, page_id: &str) -> Result<Option<String>>;
+    async fn get_page_meta(&self, page_id: &str) -> Result<PageMeta>;
+    async fn get_page_html(&self, page_id: &str) -> Result<String>;
+    async fn update_page_meta(&self, page_id: &str, meta: &PageMeta) -> Result<()>;
+    /// `bump_updated_at` 为 false 时保留 `updated_at`，用于模板变化后的重新渲染等非内容修改
+    async fn update_page_html(
+        &self,
+        page_id: &str,
+        html: &str,
+        bump_updated_at: bool,
+    ) -> Result<()>;
+    async fn update_page_markdown(&self, page_id: &str, markdown: &str) -> Result<()>;
+    /// `client_ip` 为空时无法去重，每次调用都累加 `view_count`
+    async fn increment_view_count(
+        &self,
+        page_id: &str,
+        client_ip: Option<&str>,
+    ) -> Result<PageMeta>;
+    /// 只改写 `extra` 中的单个键，`value` 为 null 时删除该键；不更新 `updated_at`。
+    /// `head_html` 会改变渲染结果：写入前按当前页面 HTML 校验，并更新 `updated_at`
+    async fn set_page_extra(
+        &self,
+        page_id: &str,
+        key: &str,
+        value: serde_json::Value,
+    ) -> Result<()>;
+    /// 页面移入回收站，之后不再出现在索引与各个列表中
+    async fn delete_page(&self, page_id: &str) -> Result<()>;
+    /// 回收站中的页面，按删除时间倒序
+    async fn list_deleted_pages(&self) -> Result<Vec<DeletedPage>>;
+    /// 永久删除一个回收站条目，`trash_id` 不在回收站中时报错，不会触及在用页面
+    async fn purge_deleted_page(&self, trash_id: &str) -> Result<DeletedPage>;
+    async fn page_exists(&self, page_id: &str) -> Result<bool>;
+    async fn list_pages(&self) -> Result<Vec<String>>;
+    async fn list_page_entries(&self) -> Result<Vec<PageIndexEntry>>;
+    /// 替换页面标签（经 `normalize_page_tags` 处理），只改写 meta.json 与标签索引，不更新 `updated_at`
+    async fn set_page_tags(&self, page_id: &str, tags: &[String]) -> Result<PageMeta>;
+    /// 设置首页手动排序，`None` 清除；与 `set_page_tags` 一样不更新 `updated_at`
+    async fn set_page_sort_order(&self, page_id: &str, sort_order: Option<i32>)
+    -> Result<PageMeta>;
+    /// `published_at` 为空时记录为当前时间，已有值保持不变；不更新 `updated_at`
+    async fn mark_page_published(&self, page_id: &str) -> Result<PageMeta>;
+    /// 按 `tag_index` 查询，匹配规则见 `StoreIndex::entries_by_tag`
+    async fn list_pages_by_tag(&self, tag: &str) -> Result<Vec<PageIndexEntry>>;
+    /// 所有标签及其页面数，见 `StoreIndex::tag_counts`
+    async fn list_tags(&self) -> Result<Vec<(String, usize)>>;
+    async fn rebuild_index(&self) -> Result<StoreIndex>;
+    /// 把页面当前的 HTML 与 Markdown 源保存为新版本，版本号从 1 开始递增
+    async fn create_revision(&self, page_id: &str) -> Result<RevisionInfo>;
+    /// 按版本号升序返回，最旧的版本在前
+    async fn list_revisions(&self, page_id: &str) -> Result<Vec<RevisionInfo>>;
+    async fn load_revision(&self, page_id: &str, revision_id: u64) -> Result<PageRevision>;
+    /// 只保留版本号最大的 `keep` 个版本，返回删除的版本数；`keep` 至少为 1，见 `check_revision_keep`
+    async fn prune_revisions(&self, page_id: &str, keep: usize) -> Result<usize>;
+    /// 幂等键在 `since`（unix 秒）之后记录的 page_uid
+    async fn find_idempotency_key(&self, key: &str, since: i64) -> Result<Option<String>>;
+    /// 记录幂等键对应的页面，同时清理早于 `since` 的记录
+    async fn record_idempotency_key(&self, key: &str, page_uid: &str, since: i64) -> Result<()>;
+
+    async fn get_page_extra(&self, page_id: &str, key: &str) -> Result<Option<serde_json::Value>> {
+        let meta = self.get_page_meta(page_id).await?;
+        Ok(meta.extra.get(key).cloned())
+    }
+
+    /// 逐个创建页面，返回与输入顺序一致的结果；单个页面失败不影响其余页面
+    /// 外层错误表示整批无法执行（如索引读写失败）
+    async fn create_pages_batch(&self, pages: &[NewPage]) -> Result<Vec<Result<PageMeta>>> {
+        let mut results = Vec::with_capacity(pages.len());
+        for page in pages {
+            let markdown = page.markdown.as_deref();
+            let result = self
+                .create_page_auto_uid_with_markdown(&page.meta, &page.html, markdown)
+                .await;
+            results.push(result);
+        }
+        Ok(results)
+    }
+
+    /// 字数优先按 Markdown 源统计，纯 HTML 页面按可见文本统计
+    async fn get_page_stats(&self, page_id: &str) -> Result<PageStats> {
+        let (meta, html) = self.load_page(page_id).await?;
+        let markdown = self.load_page_markdown(page_id).await?;
+        Ok(PageStats::from_page(&meta, &html, markdown.as_deref()))
+    }
+
+    /// 按索引中的 `view_count` 降序返回前 `limit` 个页面，不区分页面是否公开
+    async fn get_most_popular_pages(&self, limit: usize) -> Result<Vec<PageIndexEntry>> {
+        Ok(most_popular_entries(self.list_page_entries().await?, limit))
+    }
+
+    /// 按关键词重合度（Jaccard 相似度）返回相关页面，不区分页面是否公开
+    async fn get_related_pages(&self, page_id: &str, limit: usize) -> Result<Vec<PageIndexEntry>> {
+        related_entries(self.list_page_entries().await?, page_id, limit)
+    }
+
+    /// 按索引中的 `updated_at` 降序返回前 `limit` 个页面，不区分页面是否公开
+    async fn list_recent_updates(&self, limit: usize) -> Result<Vec<PageIndexEntry>> {
+        Ok(recent_update_entries(
+            self.list_page_entries().await?,
+            limit,
+        ))
+    }
+
+    /// 先把当前内容保存为新版本，再用指定版本覆盖正文，返回恢复前保存的版本
+    /// 只恢复 HTML 与 Markdown 源，SEO 等元数据保持不变
+    async fn restore_revision(&self, page_id: &str, revision_id: u64) -> Result<RevisionInfo> {
+        let revision = self.load_revision(page_id, revision_id).await?;
+        let backup = self.create_revision(page_id).await?;
+        let meta = self.get_page_meta(page_id).await?;
+        let markdown = revision.markdown.as_deref();
+        self.update_page_with_markdown(page_id, &meta, &revision.html, markdown)
+            .await?;
+        Ok(backup)
+    }
+
+    /// 对所有页面执行 `prune_revisions`，返回删除了版本的页面及删除数
+    async fn prune_all_revisions(&self, keep: usize) -> Result<BTreeMap<String, usize>> {
+        check_revision_keep(keep)?;
+        let mut pruned = BTreeMap::new();
+        for page_id in self.list_pages().await? {
+            let deleted = self
+                .prune_revisions(&page_id, keep)
+                .await
+                .with_context(|| format!("prune revisions of {}", page_id))?;
+            if deleted > 0 {
+                pruned.insert(page_id, deleted);
+            }
+        }
+        Ok(pruned)
+    }
+
+    /// 快速健康探测；默认只确认索引可以读取
+    async fn probe_health(&self) -> Result<StoreHealth> {
+        let mut health = StoreHealth {
+            backend: "unknown".to_string(),
+            ..Default::default()
+        };
+        match self.list_page_entries().await {
+            Ok(_) => health.index_ok = true,
+            Err(err) => health
+                .errors
+                .push(format!("list page entries failed: {:#}", err)),
+        }
+        Ok(health)
+    }
+
+    /// 一致性检查依赖本地目录结构，其它后端默认不支持
+    async fn validate_consistency(&self) -> Result<ConsistencyReport> {
+        bail!("validate_consistency is not supported by this store backend")
+    }
+
+    async fn repair_consistency(&self, _report: &ConsistencyReport) -> Result<Vec<String>> {
+        bail!("repair_consistency is not supported by this store backend")
+    }
+}
+
+/// 浏览量相同时按 page_id 排序，保证结果稳定
+pub(crate) fn most_popular_entries(
+    mut entries: Vec<PageIndexEntry>,
+    limit: usize,
+) -> Vec<PageIndexEntry> {
+    entries.sort_by(|left, right| {
+        right
+            .view_count
+            .cmp(&left.view_count)
+            .then_with(|| left.page_id.cmp(&right.page_id))
+    });
+    entries.truncate(limit);
+    entries
+}
+
+/// 更新时间相同时按 page_id 排序，保证结果稳定
+pub(crate) fn recent_update_entries(
+    mut entries: Vec<PageIndexEntry>,
+    limit: usize,
+) -> Vec<PageIndexEntry> {
+    entries.sort_by(|left, right| {
+        right
+            .updated_at
+            .cmp(&left.updated_at)
+            .then_with(|| left.page_id.cmp(&right.page_id))
+    });
+    entries.truncate(limit);
+    entries
+}
+
+/// 按关键词集合的 Jaccard 相似度降序返回前 `limit` 个页面，排除自身与没有共同关键词的页面；
+/// 相似度相同时按 page_id 排序
+pub(crate) fn related_entries(
+    entries: Vec<PageIndexEntry>,
+    page_id: &str,
+    limit: usize,
+) -> Result<Vec<PageIndexEntry>> {
+    let Some(target) = entries.iter().find(|entry| entry.page_id == page_id) else {
+        bail!("page not found: {}", page_id);
+    };
+    let target_keywords = entry_keyword_set(target);
+    let others = entries
+        .into_iter()
+        .filter(|entry| entry.page_id != page_id)
+        .collect();
+    Ok(entries_sharing_keywords(others, &target_keywords, limit)
+        .into_iter()
+        .map(|(entry, _)| entry)
+        .collect())
+}
+
+/// 按与 `keywords`（已 `normalize_tag`）的 Jaccard 相似度降序返回前 `limit` 个页面及共同关键词，
+/// 排除没有共同关键词的页面；相似度相同时按 page_id 排序
+pub(crate) fn entries_sharing_keywords(
+    entries: Vec<PageIndexEntry>,
+    keywords: &BTreeSet<String>,
+    limit: usize,
+) -> Vec<(PageIndexEntry, Vec<String>)> {
+    if keywords.is_empty() {
+        return Vec::new();
+    }
+    let mut scored: Vec<(f64, PageIndexEntry, Vec<String>)> = entries
+        .into_iter()
+        .filter_map(|entry| {
+            let entry_keywords = entry_keyword_set(&entry);
+            let shared: Vec<String> = entry_keywords.intersection(keywords).cloned().collect();
+            if shared.is_empty() {
+                return None;
+            }
+            let union = entry_keywords.union(keywords).count();
+            Some((shared.len() as f64 / union as f64, entry, shared))
+        })
+        .collect();
+    scored.sort_by(|(left_score, left, _), (right_score, right, _)| {
+        right_score
+            .total_cmp(left_score)
+            .then_with(|| left.page_id.cmp(&right.page_id))
+    });
+    scored.truncate(limit);
+    scored
+        .into_iter()
+        .map(|(_, entry, shared)| (entry, shared))
+        .collect()
+}
+
+/// 全站关键词（已 `normalize_tag`）及使用它的页面数
+pub(crate) fn site_keyword_counts(entries: &[PageIndexEntry]) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for keyword in entries.iter().flat_map(entry_keyword_set) {
+        *counts.entry(keyword).or_insert(0) += 1;
+    }
+    counts
+}
+
+fn entry_keyword_set(entry: &PageIndexEntry) -> BTreeSet<String> {
+    entry
+        .seo
+        .keywords
+        .iter()
+        .flatten()
+        .map(|keyword| normalize_tag(keyword))
+        .filter(|keyword| !keyword.is_empty())
+        .collect()
+}
+
+/// 页面正文的历史版本，保存时刻的 HTML 与 Markdown 源
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageRevision {
+    pub revision_id: u64,
+    pub created_at: i64,
+    pub html: String,
+    #[serde(default)]
+    pub markdown: Option<String>,
+}
+
+/// 版本列表中的摘要，不含正文
+#[derive(Debug, Clone)]
+pub struct RevisionInfo {
+    pub revision_id: u64,
+    pub created_at: i64,
+    pub html_bytes: usize,
+    pub markdown_bytes: Option<usize>,
+}
+
+impl From<&PageRevision> for RevisionInfo {
+    fn from(revision: &PageRevision) -> Self {
+        Self {
+            revision_id: revision.revision_id,
+            created_at: revision.created_at,
+            html_bytes: revision.html.len(),
+            markdown_bytes: revision.markdown.as_ref().map(String::len),
+        }
+    }
+}
+
+/// 单页统计数据，对应 `GET /pages/{slug}/stats`
+#[derive(Debug, Clone, Serialize)]
+pub struct PageStats {
+    pub page_uid: String,
+    pub view_count: u64,
+    pub unique_view_count: u64,
+    pub word_count: u64,
+    pub reading_time_minutes: u64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// 阅读速度：中文按字、西文按词分别估算
+const CJK_CHARS_PER_MINUTE: u64 = 400;
+const LATIN_WORDS_PER_MINUTE: u64 = 200;
+
+impl PageStats {
+    pub fn from_page(meta: &PageMeta, html: &str, markdown: Option<&str>) -> Self {
+        let text = match markdown {
+            Some(markdown) => markdown.to_string(),
+            None => html_visible_text(html),
+        };
+        let (word_count, reading_time_minutes) = estimate_reading_time(&text);
+        Self {
+            page_uid: meta.page_uid.clone(),
+            view_count: meta.view_count,
+            unique_view_count: meta.unique_view_count,
+            word_count,
+            reading_time_minutes,
+            created_at: meta.created_at,
+            updated_at: meta.updated_at,
+        }
+    }
+}
+
+/// 返回（字数，阅读分钟数），非空文本至少 1 分钟
+pub fn estimate_reading_time(text: &str) -> (u64, u64) {
+    let (cjk_chars, latin_words) = count_words(text);
+    let reading_time_minutes = if cjk_chars + latin_words == 0 {
+        0
+    } else {
+        (cjk_chars * LATIN_WORDS_PER_MINUTE + latin_words * CJK_CHARS_PER_MINUTE)
+            .div_ceil(CJK_CHARS_PER_MINUTE * LATIN_WORDS_PER_MINUTE)
+            .max(1)
+    };
+    (cjk_chars + latin_words, reading_time_minutes)
+}
+
+/// 返回（中日韩字符数，其它连续字母数字词数）
+fn count_words(text: &str) -> (u64, u64) {
+    let mut cjk_chars = 0u64;
+    let mut latin_words = 0u64;
+    let mut in_word = false;
+    for ch in text.chars() {
+        if is_cjk_char(ch) {
+            cjk_chars += 1;
+            in_word = false;
+        } else if ch.is_alphanumeric() {
+            if !in_word {
+                latin_words += 1;
+            }
+            in_word = true;
+        } else {
+            in_word = false;
+        }
+    }
+    (cjk_chars, latin_words)
+}
+
+pub(crate) fn is_cjk_char(ch: char) -> bool {
+    matches!(
+        ch as u32,
+        0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF
+    )
+}
+
+/// 去掉标签以及 `<script>` / `<style>` 内容，只保留可见文本
+pub fn html_visible_text(html: &str) -> String {
+    let bytes = html.as_bytes();
+    let mut text = String::with_capacity(html.len());
+    let mut index = 0usize;
+    let mut copy_from = 0usize;
+    while index < bytes.len() {
+        if bytes[index] != b'<' {
+            index += 1;
+            continue;
+        }
+        text.push_str(&html[copy_from..index]);
+        text.push(' ');
+        let Some(tag_end) = find_tag_end(bytes, index + 1) else {
+            copy_from = bytes.len();
+            break;
+        };
+        let tag_name = html[index + 1..tag_end]
+            .trim_start()
+            .chars()
+            .take_while(|ch| ch.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_lowercase();
+        index = tag_end + 1;
+        if tag_name == "script" || tag_name == "style" {
+            let close = format!("</{}", tag_name);
+            index = find_bytes_case_insensitive(bytes, index, close.as_bytes())
+                .and_then(|close_start| find_tag_end(bytes, close_start + 2))
+                .map_or(bytes.len(), |close_end| close_end + 1);
+        }
+        copy_from = index;
+    }
+    if copy_from < bytes.len() {
+        text.push_str(&html[copy_from..]);
+    }
+    text
+}
+
+/// 存储一致性检查结果，由 `PageStore::validate_consistency` 生成
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConsistencyReport {
+    /// 数据目录中含 meta.json 的页面数量
+    pub page_count: usize,
+    /// index.json 缺失或无法解析时的错误信息
+    pub index_error: Option<String>,
+    /// 索引中存在但页面目录已不存在的条目
+    pub orphan_index_entries: Vec<String>,
+    /// 页面目录存在但未被索引收录
+    pub unindexed_pages: Vec<String>,
+    /// 页面目录缺少 meta.json 或无法解析
+    pub missing_meta: Vec<String>,
+    /// 页面目录缺少 index.html
+    pub missing_html: Vec<String>,
+    /// 多个页面共用同一个 page_uid：(page_uid, page_ids)
+    pub uid_collisions: Vec<(String, Vec<String>)>,
+    /// index.html 未通过 `validate_html`：(page_id, error)
+    pub invalid_html: Vec<(String, String)>,
+    /// 原子写入中断后残留的 .tmp 文件
+    pub leftover_tmp_files: Vec<PathBuf>,
+}
+
+impl ConsistencyReport {
+    pub fn error_count(&self) -> usize {
+        self.index_error.iter().count()
+            + self.missing_meta.len()
+            + self.missing_html.len()
+            + self.uid_collisions.len()
+    }
+
+    pub fn warning_count(&self) -> usize {
+        self.orphan_index_entries.len()
+            + self.unindexed_pages.len()
+            + self.invalid_html.len()
+            + self.leftover_tmp_files.len()
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.error_count() == 0 && self.warning_count() == 0
+    }
+}
+
+/// 存储后端的快速健康探测结果，由 `PageStoreTrait::probe_health` 生成
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StoreHealth {
+    /// 后端名称：`fs` / `s3` / `memory`
+    pub backend: String,
+    /// 本地数据目录或 `s3://{bucket}`，内存后端为空
+    pub data_dir: Option<String>,
+    /// 数据目录下所有文件的字节数之和，非文件系统后端为空
+    pub disk_usage_bytes: Option<u64>,
+    /// 索引可以读取并解析；尚未生成索引视为正常
+    pub index_ok: bool,
+    /// 数据目录可以列出，未探测时为空
+    pub readable: Option<bool>,
+    /// 数据目录可以写入，未探测时为空
+    pub writable: Option<bool>,
+    /// 探测过程中遇到的错误
+    pub errors: Vec<String>,
+}
+
+impl StoreHealth {
+    pub fn is_healthy(&self) -> bool {
+        self.index_ok
+            && self.readable != Some(false)
+            && self.writable != Some(false)
+            && self.errors.is_empty()
+    }
 }
 
 /// 将标题转换为 URL 友好的 slug
@@ -96,8 +888,21 @@ impl Default for PageStore {
 
 impl PageStore {
     pub fn new(base_dir: impl Into<PathBuf>) -> Self {
-        Self {
-            base_dir: base_dir.into(),
+        Self::builder().base_dir(base_dir).build()
+    }
+
+    pub fn builder() -> PageStoreBuilder {
+        PageStoreBuilder::default()
+    }
+
+    /// 重新读取落盘后的 meta 再调用钩子；读取失败只记录日志，写入结果不受影响
+    fn run_page_hook(&self, hook: Option<&PageHook>, page_id: &str) {
+        let Some(hook) = hook else {
+            return;
+        };
+        match self.get_page_meta(page_id) {
+            Ok(meta) => hook(&meta),
+            Err(err) => warn!("skip page hook for {}: {:#}", page_id, err),
         }
     }
 
@@ -134,6 +939,89 @@ impl PageStore {
         Ok(saved_meta)
     }
 
+    /// 批量创建页面：先把所有页面写入预写日志，再依次写入页面目录，最后只保存一次索引
+    pub fn create_pages_batch(&self, pages: &[NewPage]) -> Result<Vec<Result<PageMeta>>> {
+        fs::create_dir_all(&self.base_dir)
+            .with_context(|| format!("create base dir {:?}", self.base_dir))?;
+
+        let _guard = lock_wal();
+        self.recover_wal()?;
+        let mut index = self.read_index()?;
+        let wal_path = self.wal_path();
+        let mut log = WalLog::load(&wal_path)?;
+        let mut prepared = Vec::with_capacity(pages.len());
+        let mut batch_uids = BTreeSet::new();
+        for page in pages {
+            let meta = validate_html(&page.html)
+                .context("validate html")
+                .and_then(|_| {
+                    let mut uid = generate_unique_page_uid(&index)?;
+                    while batch_uids.contains(&uid) {
+                        uid = generate_unique_page_uid(&index)?;
+                    }
+                    let mut meta = page.meta.clone();
+                    if meta.seo.title.is_empty() {
+                        meta.seo.title = meta.seo.seo_title.clone();
+                    }
+                    meta.seo.seo_title = to_url_slug(&meta.seo.seo_title);
+                    meta.page_uid = uid.clone();
+                    let meta = merge_page_meta(&meta, None, &index, &uid)?;
+                    batch_uids.insert(uid);
+                    Ok(meta)
+                });
+            prepared.push(match meta {
+                Ok(meta) => {
+                    let op = WalOp::CreatePage {
+                        page_id: meta.page_uid.clone(),
+                        meta: Box::new(meta.clone()),
+                        html: page.html.clone(),
+                        markdown: page.markdown.clone(),
+                    };
+                    Ok((log.append(op.clone())?, meta, op))
+                }
+                Err(err) => Err(err),
+            });
+        }
+        log.save(&wal_path)?;
+
+        let mut results = Vec::with_capacity(prepared.len());
+        let mut seqs = Vec::new();
+        for item in prepared {
+            let (seq, meta, op) = match item {
+                Ok(item) => item,
+                Err(err) => {
+                    results.push(Err(err));
+                    continue;
+                }
+            };
+            seqs.push(seq);
+            match self.apply_wal_op(&op, &mut index) {
+                Ok(()) => results.push(Ok(meta)),
+                Err(err) => {
+                    let _ = fs::remove_dir_all(self.base_dir.join(&meta.page_uid));
+                    index.pages.remove(&meta.page_uid);
+                    index.remove_page_tags(&meta.page_uid);
+                    results.push(Err(err));
+                }
+            }
+        }
+        self.save_index(&index)?;
+
+        let mut log = WalLog::load(&wal_path)?;
+        for seq in seqs {
+            log.append(WalOp::Commit { seq })?;
+        }
+        log.compact();
+        log.save(&wal_path)?;
+        drop(_guard);
+        if let Some(hook) = &self.post_create_hook {
+            for meta in results.iter().flatten() {
+                hook(meta);
+            }
+        }
+        Ok(results)
+    }
+
     pub fn resolve_page_id_by_uid(&self, page_uid: &str) -> Result<Option<String>> {
         let index = self.load_index()?;
         if index.pages.contains_key(page_uid) {
@@ -167,18 +1055,28 @@ impl PageStore {
         html: &str,
         markdown: Option<&str>,
     ) -> Result<()> {
-        fs::create_dir_all(&self.base_dir)
-            .with_context(|| format!("create base dir {:?}", self.base_dir))?;
+        self.save_page_checked(page_id, meta, html, markdown, None)
+    }
 
-        let safe_id = sanitize_page_id(page_id);
-        let page_dir = self.base_dir.join(&safe_id);
-        fs::create_dir_all(&page_dir).with_context(|| format!("create page dir {:?}", page_dir))?;
+    /// 在 WAL 锁内读取现有 meta 后校验 `expected_updated_at`，保证比较与写入之间没有其他写入
+    fn save_page_checked(
+        &self,
+        page_id: &str,
+        meta: &PageMeta,
+        html: &str,
+        markdown: Option<&str>,
+        expected_updated_at: Option<i64>,
+    ) -> Result<()> {
+        fs::create_dir_all(&self.base_dir)
+            .with_context(|| format!("create base dir {:?}", self.base_dir))?;
+        validate_html(html).context("validate html")?;
 
-        let meta_path = page_dir.join("meta.json");
-        let html_path = page_dir.join("index.html");
-        let markdown_path = page_dir.join("content.md");
+        let safe_id = sanitize_page_id(page_id);
+        let meta_path = self.base_dir.join(&safe_id).join("meta.json");
 
-        let mut index = self.load_index()?;
+        let _guard = lock_wal();
+        self.recover_wal()?;
+        let mut index = self.read_index()?;
         let existing_meta = if meta_path.exists() {
             let existing_raw = fs::read_to_string(&meta_path)
                 .with_context(|| format!("read meta.json {:?}", meta_path))?;
@@ -188,6 +1086,7 @@ impl PageStore {
         } else {
             None
         };
+        check_expected_updated_at(page_id, existing_meta.as_ref(), expected_updated_at)?;
         let existing_uid = existing_meta
             .as_ref()
             .map(|value| value.page_uid.clone())
@@ -219,45 +1118,39 @@ impl PageStore {
         let created_at = existing_created_at
             .or(fallback_created_at)
             .unwrap_or(now_ts);
-        let updated_at = now_ts;
+        let previous_updated_at = existing_meta.as_ref().map_or(0, |value| value.updated_at);
+        let updated_at = next_updated_at(previous_updated_at, now_ts);
         let mut meta_to_write = meta.clone();
         if meta_to_write.seo.title.is_empty() {
             meta_to_write.seo.title = meta_to_write.seo.seo_title.clone();
         }
-        meta_to_write.page_uid = page_uid.clone();
+        meta_to_write.page_uid = page_uid;
         meta_to_write.created_at = created_at;
         meta_to_write.updated_at = updated_at;
-
-        let meta_bytes =
-            serde_json::to_vec_pretty(&meta_to_write).context("serialize meta.json")?;
-        atomic_write(&meta_path, &meta_bytes).context("write meta.json")?;
-        if let Some(markdown) = markdown {
-            atomic_write(&markdown_path, markdown.as_bytes()).context("write content.md")?;
-        }
-        validate_html(html).context("validate html")?;
-        atomic_write(&html_path, html.as_bytes()).context("write index.html")?;
-        let original_id = index
-            .pages
-            .get(&safe_id)
-            .and_then(|entry| entry.original_id.clone())
-            .or_else(|| {
-                if safe_id == page_id {
-                    None
-                } else {
-                    Some(page_id.to_string())
-                }
-            });
-        index.pages.insert(
-            safe_id.clone(),
-            PageIndexEntry {
-                page_id: safe_id,
-                seo: meta_to_write.seo.clone(),
-                page_uid: page_uid.clone(),
-                original_id,
-            },
-        );
-        self.save_index(&index)?;
-
+        meta_to_write.published_at = meta
+            .published_at
+            .or_else(|| existing_meta.as_ref().and_then(|value| value.published_at));
+
+        let (op, hook) = if existing_meta.is_some() {
+            let op = WalOp::UpdatePage {
+                page_id: page_id.to_string(),
+                meta: Box::new(meta_to_write),
+                html: Some(html.to_string()),
+                markdown: markdown.map(str::to_string),
+            };
+            (op, self.post_update_hook.as_ref())
+        } else {
+            let op = WalOp::CreatePage {
+                page_id: page_id.to_string(),
+                meta: Box::new(meta_to_write),
+                html: html.to_string(),
+                markdown: markdown.map(str::to_string),
+            };
+            (op, self.post_create_hook.as_ref())
+        };
+        self.execute_wal_op(op, &mut index)?;
+        drop(_guard);
+        self.run_page_hook(hook, page_id);
         Ok(())
     }
 
@@ -271,6 +1164,29 @@ impl PageStore {
         meta: &PageMeta,
         html: &str,
         markdown: Option<&str>,
+    ) -> Result<()> {
+        self.update_page_checked(page_id, meta, html, markdown, None)
+    }
+
+    /// 页面当前的 updated_at 与 `expected_updated_at` 不一致时返回 `UpdateConflict`，不写入
+    pub fn update_page_if_unmodified(
+        &self,
+        page_id: &str,
+        meta: &PageMeta,
+        html: &str,
+        markdown: Option<&str>,
+        expected_updated_at: i64,
+    ) -> Result<()> {
+        self.update_page_checked(page_id, meta, html, markdown, Some(expected_updated_at))
+    }
+
+    fn update_page_checked(
+        &self,
+        page_id: &str,
+        meta: &PageMeta,
+        html: &str,
+        markdown: Option<&str>,
+        expected_updated_at: Option<i64>,
     ) -> Result<()> {
         if !self.page_exists(page_id)? {
             bail!("page not found: {}", page_id);
@@ -280,7 +1196,13 @@ impl PageStore {
             meta_to_update.seo.title = meta_to_update.seo.seo_title.clone();
         }
         meta_to_update.seo.seo_title = to_url_slug(&meta_to_update.seo.seo_title);
-        self.save_page_with_markdown(page_id, &meta_to_update, html, markdown)
+        self.save_page_checked(
+            page_id,
+            &meta_to_update,
+            html,
+            markdown,
+            expected_updated_at,
+        )
     }
 
     pub fn load_page(&self, page_id: &str) -> Result<(PageMeta, String)> {
@@ -311,9 +1233,16 @@ impl PageStore {
         Ok(Some(markdown))
     }
 
+    /// 只读取 meta.json，不加载 HTML；文件不存在时返回 `page not found`
     pub fn get_page_meta(&self, page_id: &str) -> Result<PageMeta> {
-        let (meta, _) = self.load_page(page_id)?;
-        Ok(meta)
+        let safe_id = sanitize_page_id(page_id);
+        let meta_path = self.base_dir.join(&safe_id).join("meta.json");
+        let meta_raw = match fs::read_to_string(&meta_path) {
+            Ok(raw) => raw,
+            Err(err) if err.kind() == ErrorKind::NotFound => bail!("page not found: {}", page_id),
+            Err(err) => return Err(err).with_context(|| format!("read meta.json {:?}", meta_path)),
+        };
+        serde_json::from_str(&meta_raw).context("parse meta.json")
     }
 
     pub fn get_page_html(&self, page_id: &str) -> Result<String> {
@@ -328,7 +1257,9 @@ impl PageStore {
 
         let safe_id = sanitize_page_id(page_id);
         let meta_path = self.base_dir.join(&safe_id).join("meta.json");
-        let mut index = self.load_index()?;
+        let _guard = lock_wal();
+        self.recover_wal()?;
+        let mut index = self.read_index()?;
         let existing_meta = if meta_path.exists() {
             let existing_raw = fs::read_to_string(&meta_path)
                 .with_context(|| format!("read meta.json {:?}", meta_path))?;
@@ -369,57 +1300,91 @@ impl PageStore {
         let created_at = existing_created_at
             .or(fallback_created_at)
             .unwrap_or(now_ts);
-        let updated_at = now_ts;
+        let previous_updated_at = existing_meta.as_ref().map_or(0, |value| value.updated_at);
+        let updated_at = next_updated_at(previous_updated_at, now_ts);
         let mut meta_to_write = meta.clone();
         if meta_to_write.seo.title.is_empty() {
             meta_to_write.seo.title = meta_to_write.seo.seo_title.clone();
         }
-        meta_to_write.page_uid = page_uid.clone();
+        meta_to_write.page_uid = page_uid;
         meta_to_write.created_at = created_at;
         meta_to_write.updated_at = updated_at;
-        let meta_bytes =
-            serde_json::to_vec_pretty(&meta_to_write).context("serialize meta.json")?;
-        atomic_write(&meta_path, &meta_bytes).context("write meta.json")?;
-        let original_id = index
+        meta_to_write.published_at = meta
+            .published_at
+            .or_else(|| existing_meta.as_ref().and_then(|value| value.published_at));
+        let op = WalOp::UpdatePage {
+            page_id: page_id.to_string(),
+            meta: Box::new(meta_to_write),
+            html: None,
+            markdown: None,
+        };
+        self.execute_wal_op(op, &mut index)?;
+        drop(_guard);
+        self.run_page_hook(self.post_update_hook.as_ref(), page_id);
+        Ok(())
+    }
+
+    pub fn update_page_html(&self, page_id: &str, html: &str, bump_updated_at: bool) -> Result<()> {
+        if !self.page_exists(page_id)? {
+            bail!("page not found: {}", page_id);
+        }
+        validate_html(html).context("validate html")?;
+
+        let safe_id = sanitize_page_id(page_id);
+        let _guard = lock_wal();
+        self.recover_wal()?;
+        let meta_path = self.base_dir.join(&safe_id).join("meta.json");
+        let meta_raw = fs::read_to_string(&meta_path)
+            .with_context(|| format!("read meta.json {:?}", meta_path))?;
+        let mut meta: PageMeta = serde_json::from_str(&meta_raw).context("parse meta.json")?;
+        let mut index = self.read_index()?;
+        let now_ts = now_unix_seconds()?;
+        let index_uid = index
             .pages
             .get(&safe_id)
-            .and_then(|entry| entry.original_id.clone())
-            .or_else(|| {
-                if safe_id == page_id {
-                    None
-                } else {
-                    Some(page_id.to_string())
-                }
-            });
-        index.pages.insert(
-            safe_id.clone(),
-            PageIndexEntry {
-                page_id: safe_id,
-                seo: meta_to_write.seo.clone(),
-                page_uid,
-                original_id,
-            },
-        );
-        self.save_index(&index)?;
-
+            .map(|entry| entry.page_uid.clone())
+            .filter(|uid| !uid.is_empty());
+        let meta_uid = if meta.page_uid.is_empty() {
+            None
+        } else {
+            Some(meta.page_uid.clone())
+        };
+        let page_uid = match meta_uid.or(index_uid) {
+            Some(uid) => uid,
+            None => generate_unique_page_uid(&index)?,
+        };
+        if meta.created_at <= 0 {
+            meta.created_at = now_ts;
+        }
+        if bump_updated_at {
+            meta.updated_at = next_updated_at(meta.updated_at, now_ts);
+        }
+        meta.page_uid = page_uid;
+        let op = WalOp::UpdatePage {
+            page_id: page_id.to_string(),
+            meta: Box::new(meta),
+            html: Some(html.to_string()),
+            markdown: None,
+        };
+        self.execute_wal_op(op, &mut index)?;
+        drop(_guard);
+        self.run_page_hook(self.post_update_hook.as_ref(), page_id);
         Ok(())
     }
 
-    pub fn update_page_html(&self, page_id: &str, html: &str) -> Result<()> {
+    pub fn update_page_markdown(&self, page_id: &str, markdown: &str) -> Result<()> {
         if !self.page_exists(page_id)? {
             bail!("page not found: {}", page_id);
         }
 
         let safe_id = sanitize_page_id(page_id);
-        let html_path = self.base_dir.join(&safe_id).join("index.html");
-        validate_html(html).context("validate html")?;
-        atomic_write(&html_path, html.as_bytes()).context("write index.html")?;
-
+        let _guard = lock_wal();
+        self.recover_wal()?;
         let meta_path = self.base_dir.join(&safe_id).join("meta.json");
         let meta_raw = fs::read_to_string(&meta_path)
             .with_context(|| format!("read meta.json {:?}", meta_path))?;
         let mut meta: PageMeta = serde_json::from_str(&meta_raw).context("parse meta.json")?;
-        let mut index = self.load_index()?;
+        let mut index = self.read_index()?;
         let now_ts = now_unix_seconds()?;
         let index_uid = index
             .pages
@@ -438,215 +1403,1267 @@ impl PageStore {
         if meta.created_at <= 0 {
             meta.created_at = now_ts;
         }
-        meta.updated_at = now_ts;
-        meta.page_uid = page_uid.clone();
+        meta.updated_at = next_updated_at(meta.updated_at, now_ts);
+        meta.page_uid = page_uid;
+        let op = WalOp::UpdatePage {
+            page_id: page_id.to_string(),
+            meta: Box::new(meta),
+            html: None,
+            markdown: Some(markdown.to_string()),
+        };
+        self.execute_wal_op(op, &mut index)?;
+        drop(_guard);
+        self.run_page_hook(self.post_update_hook.as_ref(), page_id);
+        Ok(())
+    }
+
+    /// 有客户端 IP 时同一访客当天只计一次，IP 哈希记录在 `visitors/{YYYY-MM-DD}.txt`
+    pub fn increment_view_count(&self, page_id: &str, client_ip: Option<&str>) -> Result<PageMeta> {
+        let safe_id = sanitize_page_id(page_id);
+        let page_dir = self.base_dir.join(&safe_id);
+        let meta_path = page_dir.join("meta.json");
+        let _guard = lock_wal();
+        let meta_raw = fs::read_to_string(&meta_path)
+            .with_context(|| format!("read meta.json {:?}", meta_path))?;
+        let mut meta: PageMeta = serde_json::from_str(&meta_raw).context("parse meta.json")?;
+        if let Some(client_ip) = client_ip {
+            let day = visitor_day();
+            let visitor_hash = hash_visitor_ip(client_ip, &day);
+            if !record_visitor(&page_dir.join(VISITORS_DIR), &day, &visitor_hash)? {
+                return Ok(meta);
+            }
+            meta.unique_view_count = meta.unique_view_count.saturating_add(1);
+        }
+        meta.view_count = meta.view_count.saturating_add(1);
         let meta_bytes = serde_json::to_vec_pretty(&meta).context("serialize meta.json")?;
         atomic_write(&meta_path, &meta_bytes).context("write meta.json")?;
+        let mut index = self.read_index()?;
+        if let Some(entry) = index.pages.get_mut(&safe_id) {
+            entry.view_count = meta.view_count;
+            self.save_index(&index)?;
+        }
+        Ok(meta)
+    }
 
-        let original_id = index
-            .pages
-            .get(&safe_id)
-            .and_then(|entry| entry.original_id.clone())
-            .or_else(|| {
-                if safe_id == page_id {
-                    None
-                } else {
-                    Some(page_id.to_string())
-                }
-            });
-        index.pages.insert(
-            safe_id.clone(),
-            PageIndexEntry {
-                page_id: safe_id,
-                seo: meta.seo.clone(),
-                page_uid,
-                original_id,
-            },
-        );
+    pub fn set_page_extra(&self, page_id: &str, key: &str, value: serde_json::Value) -> Result<()> {
+        let key = validate_extra_key(key)?;
+        let safe_id = sanitize_page_id(page_id);
+        let meta_path = self.base_dir.join(&safe_id).join("meta.json");
+        let _guard = lock_wal();
+        let mut meta = self.get_page_meta(&safe_id)?;
+        let head_html = key == EXTRA_HEAD_HTML_KEY;
+        let value = if head_html {
+            check_extra_head_html(value, &self.get_page_html(&safe_id)?)?
+        } else {
+            value
+        };
+        set_extra_value(&mut meta, key, value);
+        if head_html {
+            meta.updated_at = next_updated_at(meta.updated_at, now_unix_seconds()?);
+        }
+        let meta_bytes = serde_json::to_vec_pretty(&meta).context("serialize meta.json")?;
+        atomic_write(&meta_path, &meta_bytes).context("write meta.json")?;
+        if head_html {
+            let mut index = self.read_index()?;
+            if let Some(entry) = index.pages.get_mut(&safe_id) {
+                entry.updated_at = meta.updated_at;
+                self.save_index(&index)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_page_extra(&self, page_id: &str, key: &str) -> Result<Option<serde_json::Value>> {
+        let meta = self.get_page_meta(page_id)?;
+        Ok(meta.extra.get(key).cloned())
+    }
+
+    pub fn set_page_tags(&self, page_id: &str, tags: &[String]) -> Result<PageMeta> {
+        let tags = normalize_page_tags(tags)?;
+        let safe_id = sanitize_page_id(page_id);
+        let meta_path = self.base_dir.join(&safe_id).join("meta.json");
+        let _guard = lock_wal();
+        self.recover_wal()?;
+        let mut meta = self.get_page_meta(&safe_id)?;
+        meta.tags = Some(tags).filter(|tags| !tags.is_empty());
+        let meta_bytes = serde_json::to_vec_pretty(&meta).context("serialize meta.json")?;
+        atomic_write(&meta_path, &meta_bytes).context("write meta.json")?;
+        let mut index = self.read_index()?;
+        index.set_page_tags(&safe_id, &page_index_tags(&meta));
         self.save_index(&index)?;
+        Ok(meta)
+    }
+
+    pub fn set_page_sort_order(&self, page_id: &str, sort_order: Option<i32>) -> Result<PageMeta> {
+        let safe_id = sanitize_page_id(page_id);
+        let meta_path = self.base_dir.join(&safe_id).join("meta.json");
+        let _guard = lock_wal();
+        self.recover_wal()?;
+        let mut meta = self.get_page_meta(&safe_id)?;
+        meta.sort_order = sort_order;
+        let meta_bytes = serde_json::to_vec_pretty(&meta).context("serialize meta.json")?;
+        atomic_write(&meta_path, &meta_bytes).context("write meta.json")?;
+        let mut index = self.read_index()?;
+        if let Some(entry) = index.pages.get_mut(&safe_id) {
+            entry.sort_order = sort_order;
+            self.save_index(&index)?;
+        }
+        Ok(meta)
+    }
+
+    pub fn mark_page_published(&self, page_id: &str) -> Result<PageMeta> {
+        let safe_id = sanitize_page_id(page_id);
+        let meta_path = self.base_dir.join(&safe_id).join("meta.json");
+        let _guard = lock_wal();
+        self.recover_wal()?;
+        let mut meta = self.get_page_meta(&safe_id)?;
+        if meta.published_at.is_none() {
+            meta.published_at = Some(now_unix_seconds()?);
+            let meta_bytes = serde_json::to_vec_pretty(&meta).context("serialize meta.json")?;
+            atomic_write(&meta_path, &meta_bytes).context("write meta.json")?;
+        }
+        Ok(meta)
+    }
+
+    pub fn find_idempotency_key(&self, key: &str, since: i64) -> Result<Option<String>> {
+        Ok(self.read_idempotency_records()?.find(key, since))
+    }
+
+    pub fn record_idempotency_key(&self, key: &str, page_uid: &str, since: i64) -> Result<()> {
+        fs::create_dir_all(&self.base_dir)
+            .with_context(|| format!("create base dir {:?}", self.base_dir))?;
+        let _guard = lock_wal();
+        let mut records = self.read_idempotency_records()?;
+        records.insert(key, page_uid, since)?;
+        let bytes = serde_json::to_vec_pretty(&records).context("serialize idempotency.json")?;
+        atomic_write(&self.idempotency_path(), &bytes).context("write idempotency.json")
+    }
+
+    /// 文件不存在时视为没有记录
+    fn read_idempotency_records(&self) -> Result<IdempotencyRecords> {
+        let path = self.idempotency_path();
+        match fs::read_to_string(&path) {
+            Ok(raw) => serde_json::from_str(&raw).context("parse idempotency.json"),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Default::default()),
+            Err(err) => Err(err).with_context(|| format!("read {:?}", path)),
+        }
+    }
+
+    pub fn delete_page(&self, page_id: &str) -> Result<()> {
+        if !self.page_exists(page_id)? {
+            bail!("page not found: {}", page_id);
+        }
+
+        // 删除后无法再读取 meta，先读出留给删除钩子
+        let deleted_meta = self.post_delete_hook.as_ref().and_then(|_| {
+            self.get_page_meta(page_id)
+                .inspect_err(|err| warn!("skip page hook for {}: {:#}", page_id, err))
+                .ok()
+        });
+
+        let _guard = lock_wal();
+        self.recover_wal()?;
+        let mut index = self.read_index()?;
+        let op = WalOp::DeletePage {
+            page_id: page_id.to_string(),
+        };
+        self.execute_wal_op(op, &mut index)?;
+        drop(_guard);
+        if let (Some(hook), Some(meta)) = (&self.post_delete_hook, &deleted_meta) {
+            hook(meta);
+        }
+        Ok(())
+    }
+
+    /// 读取 `.trash/*/deleted.json`，缺少记录的目录跳过
+    pub fn list_deleted_pages(&self) -> Result<Vec<DeletedPage>> {
+        let trash_dir = self.trash_dir();
+        let entries = match fs::read_dir(&trash_dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err).with_context(|| format!("read trash dir {:?}", trash_dir)),
+        };
+        let mut pages = Vec::new();
+        for entry in entries {
+            let path = entry.context("read trash dir entry")?.path();
+            let Ok(raw) = fs::read_to_string(path.join(DELETED_RECORD_FILE)) else {
+                continue;
+            };
+            match serde_json::from_str::<DeletedPage>(&raw) {
+                Ok(page) => pages.push(page),
+                Err(err) => warn!("parse trash record {:?} failed: {err}", path),
+            }
+        }
+        sort_deleted_pages(&mut pages);
+        Ok(pages)
+    }
+
+    pub fn purge_deleted_page(&self, trash_id: &str) -> Result<DeletedPage> {
+        // trash_id 只允许页面 ID 的字符集，不会被拼成回收站之外的路径
+        if trash_id.is_empty() || sanitize_page_id(trash_id) != trash_id {
+            bail!("trash entry not found: {}", trash_id);
+        }
+        let _guard = lock_wal();
+        let entry_dir = self.trash_dir().join(trash_id);
+        let record_path = entry_dir.join(DELETED_RECORD_FILE);
+        let raw = match fs::read_to_string(&record_path) {
+            Ok(raw) => raw,
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                bail!("trash entry not found: {}", trash_id)
+            }
+            Err(err) => return Err(err).with_context(|| format!("read {:?}", record_path)),
+        };
+        let page: DeletedPage = serde_json::from_str(&raw).context("parse deleted.json")?;
+        fs::remove_dir_all(&entry_dir)
+            .with_context(|| format!("remove trash entry {:?}", entry_dir))?;
+        Ok(page)
+    }
+
+    /// 先在页面目录写入 `deleted.json` 再整体改名，改名是原子的，中断时页面仍留在原处
+    fn move_page_to_trash(&self, safe_id: &str, page_dir: &Path) -> Result<()> {
+        let meta = fs::read_to_string(page_dir.join("meta.json"))
+            .ok()
+            .and_then(|raw| serde_json::from_str::<PageMeta>(&raw).ok());
+        let trash_dir = self.trash_dir();
+        fs::create_dir_all(&trash_dir)
+            .with_context(|| format!("create trash dir {:?}", trash_dir))?;
+        let deleted_at = now_unix_seconds()?;
+        let trash_id = unique_trash_id(safe_id, deleted_at, |id| trash_dir.join(id).exists());
+        let page = DeletedPage::new(trash_id, safe_id, meta.as_ref(), deleted_at);
+        let bytes = serde_json::to_vec_pretty(&page).context("serialize deleted.json")?;
+        atomic_write(&page_dir.join(DELETED_RECORD_FILE), &bytes).context("write deleted.json")?;
+        let target = trash_dir.join(&page.trash_id);
+        fs::rename(page_dir, &target)
+            .with_context(|| format!("move page dir {:?} to {:?}", page_dir, target))
+    }
+
+    fn trash_dir(&self) -> PathBuf {
+        self.base_dir.join(TRASH_DIR)
+    }
+
+    pub fn page_exists(&self, page_id: &str) -> Result<bool> {
+        let safe_id = sanitize_page_id(page_id);
+        let index = self.load_index()?;
+        if index.pages.contains_key(&safe_id) {
+            return Ok(true);
+        }
+        let page_dir = self.base_dir.join(&safe_id);
+        Ok(page_dir.is_dir())
+    }
+
+    pub fn list_pages(&self) -> Result<Vec<String>> {
+        let index = self.load_index()?;
+        Ok(index.pages.keys().cloned().collect())
+    }
+
+    pub fn list_page_entries(&self) -> Result<Vec<PageIndexEntry>> {
+        let index = self.load_index()?;
+        Ok(index.pages.values().cloned().collect())
+    }
+
+    pub fn list_pages_by_tag(&self, tag: &str) -> Result<Vec<PageIndexEntry>> {
+        let index = self.load_index()?;
+        Ok(index.entries_by_tag(tag))
+    }
+
+    pub fn list_tags(&self) -> Result<Vec<(String, usize)>> {
+        let index = self.load_index()?;
+        Ok(index.tag_counts())
+    }
+
+    pub fn get_most_popular_pages(&self, limit: usize) -> Result<Vec<PageIndexEntry>> {
+        Ok(most_popular_entries(self.list_page_entries()?, limit))
+    }
+
+    pub fn get_related_pages(&self, page_id: &str, limit: usize) -> Result<Vec<PageIndexEntry>> {
+        related_entries(self.list_page_entries()?, page_id, limit)
+    }
+
+    pub fn list_recent_updates(&self, limit: usize) -> Result<Vec<PageIndexEntry>> {
+        Ok(recent_update_entries(self.list_page_entries()?, limit))
+    }
+
+    pub fn get_page_stats(&self, page_id: &str) -> Result<PageStats> {
+        let (meta, html) = self.load_page(page_id)?;
+        let markdown = self.load_page_markdown(page_id)?;
+        Ok(PageStats::from_page(&meta, &html, markdown.as_deref()))
+    }
+
+    pub fn create_revision(&self, page_id: &str) -> Result<RevisionInfo> {
+        let _guard = lock_wal();
+        self.recover_wal()?;
+        // 先读 meta，页面不存在时返回 `page not found`
+        self.get_page_meta(page_id)?;
+        let html = self.get_page_html(page_id)?;
+        let markdown = self.load_page_markdown(page_id)?;
+        let revisions_dir = self.revisions_dir(page_id);
+        fs::create_dir_all(&revisions_dir)
+            .with_context(|| format!("create revisions dir {:?}", revisions_dir))?;
+        let revision_id = read_revision_ids(&revisions_dir)?
+            .last()
+            .map_or(1, |id| id + 1);
+        let revision = PageRevision {
+            revision_id,
+            created_at: now_unix_seconds()?,
+            html,
+            markdown,
+        };
+        let bytes = serde_json::to_vec(&revision).context("serialize revision")?;
+        atomic_write(&revisions_dir.join(format!("{}.json", revision_id)), &bytes)
+            .context("write revision")?;
+        Ok(RevisionInfo::from(&revision))
+    }
+
+    pub fn list_revisions(&self, page_id: &str) -> Result<Vec<RevisionInfo>> {
+        self.get_page_meta(page_id)?;
+        let revisions_dir = self.revisions_dir(page_id);
+        read_revision_ids(&revisions_dir)?
+            .into_iter()
+            .map(|revision_id| {
+                let revision = self.load_revision(page_id, revision_id)?;
+                Ok(RevisionInfo::from(&revision))
+            })
+            .collect()
+    }
+
+    pub fn load_revision(&self, page_id: &str, revision_id: u64) -> Result<PageRevision> {
+        let path = self
+            .revisions_dir(page_id)
+            .join(format!("{}.json", revision_id));
+        let raw = match fs::read_to_string(&path) {
+            Ok(raw) => raw,
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                bail!("revision not found: {}", revision_id)
+            }
+            Err(err) => return Err(err).with_context(|| format!("read revision {:?}", path)),
+        };
+        serde_json::from_str(&raw).with_context(|| format!("parse revision {:?}", path))
+    }
+
+    pub fn prune_revisions(&self, page_id: &str, keep: usize) -> Result<usize> {
+        check_revision_keep(keep)?;
+        // 与 `create_revision` 互斥，避免删除正在分配版本号的目录
+        let _guard = lock_wal();
+        self.get_page_meta(page_id)?;
+        let revisions_dir = self.revisions_dir(page_id);
+        let revision_ids = read_revision_ids(&revisions_dir)?;
+        let excess = revision_ids.len().saturating_sub(keep);
+        for revision_id in &revision_ids[..excess] {
+            let path = revisions_dir.join(format!("{}.json", revision_id));
+            fs::remove_file(&path).with_context(|| format!("remove revision {:?}", path))?;
+        }
+        Ok(excess)
+    }
+
+    pub fn restore_revision(&self, page_id: &str, revision_id: u64) -> Result<RevisionInfo> {
+        let revision = self.load_revision(page_id, revision_id)?;
+        let backup = self.create_revision(page_id)?;
+        let meta = self.get_page_meta(page_id)?;
+        let markdown = revision.markdown.as_deref();
+        self.update_page_with_markdown(page_id, &meta, &revision.html, markdown)?;
+        Ok(backup)
+    }
+
+    pub fn rebuild_index(&self) -> Result<StoreIndex> {
+        fs::create_dir_all(&self.base_dir)
+            .with_context(|| format!("create base dir {:?}", self.base_dir))?;
+
+        let mut index = StoreIndex::default();
+        for entry in fs::read_dir(&self.base_dir)
+            .with_context(|| format!("read base dir {:?}", self.base_dir))?
+        {
+            let entry = entry.context("read dir entry")?;
+            let file_type = entry.file_type().context("read dir entry type")?;
+            if !file_type.is_dir() {
+                continue;
+            }
+            let page_id = entry.file_name().to_string_lossy().to_string();
+            let meta_path = entry.path().join("meta.json");
+            let meta_raw = match fs::read_to_string(&meta_path) {
+                Ok(raw) => raw,
+                Err(_) => continue,
+            };
+            let meta: PageMeta = match serde_json::from_str(&meta_raw) {
+                Ok(meta) => meta,
+                Err(_) => continue,
+            };
+            index.set_page_tags(&page_id, &page_index_tags(&meta));
+            index.pages.insert(
+                page_id.clone(),
+                PageIndexEntry {
+                    page_id,
+                    seo: meta.seo,
+                    page_uid: meta.page_uid,
+                    original_id: None,
+                    view_count: meta.view_count,
+                    sort_order: meta.sort_order,
+                    updated_at: meta.updated_at,
+                },
+            );
+        }
+
+        self.save_index(&index)?;
+        Ok(index)
+    }
+
+    /// 在阻塞线程池中重建索引，供异步上下文调用，避免阻塞 Tokio 运行时
+    pub async fn rebuild_index_async(&self) -> Result<StoreIndex> {
+        let store = self.clone();
+        tokio::task::spawn_blocking(move || store.rebuild_index())
+            .await
+            .context("join rebuild index task")?
+    }
+
+    /// 解析 index.json（不触发重建），在数据目录写入并删除探测文件，统计占用空间
+    pub fn probe_health(&self) -> Result<StoreHealth> {
+        let mut health = StoreHealth {
+            backend: "fs".to_string(),
+            data_dir: Some(self.base_dir.display().to_string()),
+            ..Default::default()
+        };
+        match fs::read_to_string(self.index_path()) {
+            Ok(raw) => match serde_json::from_str::<StoreIndex>(&raw) {
+                Ok(_) => health.index_ok = true,
+                Err(err) => health
+                    .errors
+                    .push(format!("parse index.json failed: {}", err)),
+            },
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => health.index_ok = true,
+            Err(err) => health
+                .errors
+                .push(format!("read index.json failed: {}", err)),
+        }
+
+        let probe_path = self.base_dir.join(".write-probe");
+        let probe = fs::create_dir_all(&self.base_dir)
+            .and_then(|_| fs::write(&probe_path, b"ok"))
+            .and_then(|_| fs::remove_file(&probe_path));
+        health.writable = Some(probe.is_ok());
+        if let Err(err) = probe {
+            health
+                .errors
+                .push(format!("data dir not writable: {}", err));
+        }
+        let listing = fs::read_dir(&self.base_dir);
+        health.readable = Some(listing.is_ok());
+        if let Err(err) = listing {
+            health
+                .errors
+                .push(format!("data dir not readable: {}", err));
+        }
+
+        match dir_size(&self.base_dir) {
+            Ok(bytes) => health.disk_usage_bytes = Some(bytes),
+            Err(err) => health
+                .errors
+                .push(format!("measure disk usage failed: {:#}", err)),
+        }
+        Ok(health)
+    }
+
+    /// 只读检查数据目录与 index.json 是否一致，不会触发索引重建
+    pub fn validate_consistency(&self) -> Result<ConsistencyReport> {
+        let mut report = ConsistencyReport::default();
+        if !self.base_dir.is_dir() {
+            report.index_error = Some(format!("data dir not found: {:?}", self.base_dir));
+            return Ok(report);
+        }
+
+        let index = match fs::read_to_string(self.index_path()) {
+            Ok(raw) => match serde_json::from_str::<StoreIndex>(&raw) {
+                Ok(index) => Some(index),
+                Err(err) => {
+                    report.index_error = Some(format!("parse index.json failed: {}", err));
+                    None
+                }
+            },
+            Err(err) => {
+                report.index_error = Some(format!("read index.json failed: {}", err));
+                None
+            }
+        };
+
+        let mut uid_owners: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut page_dirs: Vec<String> = Vec::new();
+        for entry in fs::read_dir(&self.base_dir)
+            .with_context(|| format!("read base dir {:?}", self.base_dir))?
+        {
+            let entry = entry.context("read dir entry")?;
+            let path = entry.path();
+            let file_type = entry.file_type().context("read dir entry type")?;
+            if !file_type.is_dir() {
+                if path.extension().is_some_and(|ext| ext == "tmp") {
+                    report.leftover_tmp_files.push(path);
+                }
+                continue;
+            }
+            let page_id = entry.file_name().to_string_lossy().to_string();
+            if page_id == TRASH_DIR {
+                continue;
+            }
+            page_dirs.push(page_id.clone());
+
+            for file in fs::read_dir(&path).with_context(|| format!("read page dir {:?}", path))? {
+                let file_path = file.context("read page dir entry")?.path();
+                if file_path.extension().is_some_and(|ext| ext == "tmp") {
+                    report.leftover_tmp_files.push(file_path);
+                }
+            }
+
+            let meta = fs::read_to_string(path.join("meta.json"))
+                .ok()
+                .and_then(|raw| serde_json::from_str::<PageMeta>(&raw).ok());
+            let Some(meta) = meta else {
+                report.missing_meta.push(page_id);
+                continue;
+            };
+            report.page_count += 1;
+            if !meta.page_uid.is_empty() {
+                uid_owners
+                    .entry(meta.page_uid.clone())
+                    .or_default()
+                    .push(page_id.clone());
+            }
+
+            match fs::read_to_string(path.join("index.html")) {
+                Ok(html) => {
+                    if let Err(err) = validate_html(&html) {
+                        report.invalid_html.push((page_id.clone(), err.to_string()));
+                    }
+                }
+                Err(_) => report.missing_html.push(page_id.clone()),
+            }
+
+            if index
+                .as_ref()
+                .is_some_and(|index| !index.pages.contains_key(&page_id))
+            {
+                report.unindexed_pages.push(page_id);
+            }
+        }
+
+        if let Some(index) = &index {
+            for page_id in index.pages.keys() {
+                if !page_dirs.iter().any(|dir| dir == page_id) {
+                    report.orphan_index_entries.push(page_id.clone());
+                }
+            }
+        }
+        report.uid_collisions = uid_owners
+            .into_iter()
+            .filter(|(_, owners)| owners.len() > 1)
+            .collect();
+
+        Ok(report)
+    }
+
+    /// 根据检查结果修复可自动修复的问题，返回执行过的修复动作
+    /// - 索引损坏：重建索引
+    /// - 孤儿索引条目 / 未收录页面：同步 index.json
+    /// - page_uid 冲突：保留最早创建的页面，其余重新生成 uid
+    /// - 残留 .tmp 文件：删除
+    ///
+    /// 缺失的 meta.json / index.html 与不合法的 HTML 需要人工处理
+    pub fn repair_consistency(&self, report: &ConsistencyReport) -> Result<Vec<String>> {
+        let mut actions = Vec::new();
+
+        for tmp_path in &report.leftover_tmp_files {
+            fs::remove_file(tmp_path).with_context(|| format!("remove tmp file {:?}", tmp_path))?;
+            actions.push(format!("removed leftover tmp file {:?}", tmp_path));
+        }
+
+        let mut index = if report.index_error.is_some() {
+            let index = self.rebuild_index()?;
+            actions.push(format!(
+                "rebuilt index.json with {} pages",
+                index.pages.len()
+            ));
+            index
+        } else {
+            let mut index = self.load_index()?;
+            let mut changed = false;
+            for page_id in &report.orphan_index_entries {
+                if index.pages.remove(page_id).is_some() {
+                    index.remove_page_tags(page_id);
+                    changed = true;
+                    actions.push(format!("removed orphan index entry {}", page_id));
+                }
+            }
+            for page_id in &report.unindexed_pages {
+                let meta_path = self.base_dir.join(page_id).join("meta.json");
+                let meta_raw = fs::read_to_string(&meta_path)
+                    .with_context(|| format!("read meta.json {:?}", meta_path))?;
+                let meta: PageMeta = serde_json::from_str(&meta_raw).context("parse meta.json")?;
+                index.set_page_tags(page_id, &page_index_tags(&meta));
+                index.pages.insert(
+                    page_id.clone(),
+                    PageIndexEntry {
+                        page_id: page_id.clone(),
+                        seo: meta.seo,
+                        page_uid: meta.page_uid,
+                        original_id: None,
+                        view_count: meta.view_count,
+                        sort_order: meta.sort_order,
+                        updated_at: meta.updated_at,
+                    },
+                );
+                changed = true;
+                actions.push(format!("added unindexed page {} to index", page_id));
+            }
+            if changed {
+                self.save_index(&index)?;
+            }
+            index
+        };
+
+        for (uid, owners) in &report.uid_collisions {
+            let mut metas = Vec::new();
+            for page_id in owners {
+                let meta_path = self.base_dir.join(page_id).join("meta.json");
+                let meta_raw = fs::read_to_string(&meta_path)
+                    .with_context(|| format!("read meta.json {:?}", meta_path))?;
+                let meta: PageMeta = serde_json::from_str(&meta_raw).context("parse meta.json")?;
+                metas.push((page_id.clone(), meta_path, meta));
+            }
+            metas.sort_by(|(left_id, _, left), (right_id, _, right)| {
+                left.created_at
+                    .cmp(&right.created_at)
+                    .then_with(|| left_id.cmp(right_id))
+            });
+            for (page_id, meta_path, mut meta) in metas.into_iter().skip(1) {
+                let new_uid = generate_unique_page_uid(&index)?;
+                meta.page_uid = new_uid.clone();
+                let meta_bytes = serde_json::to_vec_pretty(&meta).context("serialize meta.json")?;
+                atomic_write(&meta_path, &meta_bytes).context("write meta.json")?;
+                if let Some(entry) = index.pages.get_mut(&page_id) {
+                    entry.page_uid = new_uid.clone();
+                }
+                actions.push(format!(
+                    "reassigned page {} uid {} -> {}",
+                    page_id, uid, new_uid
+                ));
+            }
+        }
+        if !report.uid_collisions.is_empty() {
+            self.save_index(&index)?;
+        }
+
+        Ok(actions)
+    }
+
+    /// 读取索引前先处理 wal.json 中中断的写操作
+    fn load_index(&self) -> Result<StoreIndex> {
+        if self.wal_path().exists() {
+            let _guard = lock_wal();
+            self.recover_wal()?;
+        }
+        self.read_index()
+    }
+
+    fn read_index(&self) -> Result<StoreIndex> {
+        let index_path = self.index_path();
+        match fs::read_to_string(&index_path) {
+            Ok(raw) => match serde_json::from_str::<StoreIndex>(&raw) {
+                Ok(index) => Ok(index),
+                Err(_) => self.rebuild_index(),
+            },
+            Err(_) => self.rebuild_index(),
+        }
+    }
+
+    fn save_index(&self, index: &StoreIndex) -> Result<()> {
+        fs::create_dir_all(&self.base_dir)
+            .with_context(|| format!("create base dir {:?}", self.base_dir))?;
+        let index_path = self.index_path();
+        let bytes = serde_json::to_vec_pretty(index).context("serialize index.json")?;
+        atomic_write(&index_path, &bytes).context("write index.json")?;
+        Ok(())
+    }
+
+    fn revisions_dir(&self, page_id: &str) -> PathBuf {
+        self.base_dir
+            .join(sanitize_page_id(page_id))
+            .join(REVISIONS_DIR)
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.base_dir.join("index.json")
+    }
+
+    fn wal_path(&self) -> PathBuf {
+        self.base_dir.join("wal.json")
+    }
+
+    fn idempotency_path(&self) -> PathBuf {
+        self.base_dir.join("idempotency.json")
+    }
+
+    /// 需持有 `lock_wal()`：先写日志，再落盘页面与索引，最后提交并压缩日志
+    fn execute_wal_op(&self, op: WalOp, index: &mut StoreIndex) -> Result<()> {
+        let wal_path = self.wal_path();
+        let mut log = WalLog::load(&wal_path)?;
+        let seq = log.append(op.clone())?;
+        log.save(&wal_path)?;
+
+        self.apply_wal_op(&op, index)?;
+        self.save_index(index)?;
+
+        let mut log = WalLog::load(&wal_path)?;
+        log.append(WalOp::Commit { seq })?;
+        log.compact();
+        log.save(&wal_path)
+    }
+
+    /// 需持有 `lock_wal()`：重放未提交的操作；重放失败的新建页面回滚删除，其余丢弃
+    fn recover_wal(&self) -> Result<()> {
+        let wal_path = self.wal_path();
+        if !wal_path.exists() {
+            return Ok(());
+        }
+        let log = match WalLog::load(&wal_path) {
+            Ok(log) => log,
+            Err(err) => {
+                // 日志通过 atomic_write 写入，无法解析说明对应操作尚未开始执行
+                warn!("discard unreadable wal.json: {err:#}");
+                return WalLog::default().save(&wal_path);
+            }
+        };
+        let pending = log.pending();
+        if !pending.is_empty() {
+            let mut index = self.read_index()?;
+            for entry in pending {
+                let page_id = entry.op.page_id().unwrap_or_default();
+                match self.apply_wal_op(&entry.op, &mut index) {
+                    Ok(()) => info!("wal: replayed #{} for page {}", entry.seq, page_id),
+                    Err(err) => {
+                        error!(
+                            "wal: replay #{} for page {} failed, rolling back: {err:#}",
+                            entry.seq, page_id
+                        );
+                        if let WalOp::CreatePage { page_id, .. } = &entry.op {
+                            let safe_id = sanitize_page_id(page_id);
+                            let _ = fs::remove_dir_all(self.base_dir.join(&safe_id));
+                            index.pages.remove(&safe_id);
+                            index.remove_page_tags(&safe_id);
+                        }
+                    }
+                }
+            }
+            self.save_index(&index)?;
+        }
+        WalLog::default().save(&wal_path)
+    }
+
+    /// 将日志中记录的最终状态写入页面目录并同步内存中的索引，可重复执行
+    fn apply_wal_op(&self, op: &WalOp, index: &mut StoreIndex) -> Result<()> {
+        let (page_id, meta, html, markdown) = match op {
+            WalOp::CreatePage {
+                page_id,
+                meta,
+                html,
+                markdown,
+            } => (page_id, meta, Some(html.as_str()), markdown.as_deref()),
+            WalOp::UpdatePage {
+                page_id,
+                meta,
+                html,
+                markdown,
+            } => (page_id, meta, html.as_deref(), markdown.as_deref()),
+            WalOp::DeletePage { page_id } => {
+                let safe_id = sanitize_page_id(page_id);
+                let page_dir = self.base_dir.join(&safe_id);
+                if page_dir.exists() {
+                    self.move_page_to_trash(&safe_id, &page_dir)?;
+                }
+                index.pages.remove(&safe_id);
+                index.remove_page_tags(&safe_id);
+                return Ok(());
+            }
+            WalOp::Commit { .. } => return Ok(()),
+        };
+
+        let safe_id = sanitize_page_id(page_id);
+        let page_dir = self.base_dir.join(&safe_id);
+        fs::create_dir_all(&page_dir).with_context(|| format!("create page dir {:?}", page_dir))?;
+        let meta_bytes = serde_json::to_vec_pretty(meta).context("serialize meta.json")?;
+        atomic_write(&page_dir.join("meta.json"), &meta_bytes).context("write meta.json")?;
+        if let Some(markdown) = markdown {
+            atomic_write(&page_dir.join("content.md"), markdown.as_bytes())
+                .context("write content.md")?;
+        }
+        if let Some(html) = html {
+            atomic_write(&page_dir.join("index.html"), html.as_bytes())
+                .context("write index.html")?;
+        }
+
+        let original_id = index
+            .pages
+            .get(&safe_id)
+            .and_then(|entry| entry.original_id.clone())
+            .or_else(|| {
+                if safe_id == *page_id {
+                    None
+                } else {
+                    Some(page_id.to_string())
+                }
+            });
+        index.set_page_tags(&safe_id, &page_index_tags(meta));
+        index.pages.insert(
+            safe_id.clone(),
+            PageIndexEntry {
+                page_id: safe_id,
+                seo: meta.seo.clone(),
+                page_uid: meta.page_uid.clone(),
+                original_id,
+                view_count: meta.view_count,
+                sort_order: meta.sort_order,
+                updated_at: meta.updated_at,
+            },
+        );
+        Ok(())
+    }
+}
+
+/// 超过该耗时的存储操作记录一条 warn 日志，所在 span 带有页面 ID
+const SLOW_STORE_OP_THRESHOLD: Duration = Duration::from_millis(500);
+
+/// 文件系统存储的异步适配：所有 IO 在 `spawn_blocking` 线程池中执行，避免阻塞 Tokio 运行时
+#[derive(Debug, Clone)]
+pub struct SyncPageStore {
+    inner: PageStore,
+}
+
+impl SyncPageStore {
+    pub fn new(inner: PageStore) -> Self {
+        Self { inner }
+    }
+
+    pub fn inner(&self) -> &PageStore {
+        &self.inner
+    }
+
+    async fn run_blocking<T, F>(&self, task: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&PageStore) -> Result<T> + Send + 'static,
+    {
+        let store = self.inner.clone();
+        let span = Span::current();
+        let started = Instant::now();
+        let result = tokio::task::spawn_blocking(move || {
+            let _entered = span.enter();
+            task(&store)
+        })
+        .await
+        .context("join blocking store task")?;
+        let elapsed = started.elapsed();
+        if elapsed >= SLOW_STORE_OP_THRESHOLD {
+            warn!(
+                elapsed_ms = elapsed.as_millis() as u64,
+                "slow store operation"
+            );
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl PageStoreTrait for SyncPageStore {
+    #[instrument(skip_all)]
+    async fn create_page_auto_uid(&self, meta: &PageMeta, html: &str) -> Result<PageMeta> {
+        let (meta, html) = (meta.clone(), html.to_string());
+        self.run_blocking(move |store| store.create_page_auto_uid(&meta, &html))
+            .await
+    }
+
+    #[instrument(skip_all)]
+    async fn create_page_auto_uid_with_markdown(
+        &self,
+        meta: &PageMeta,
+        html: &str,
+        markdown: Option<&str>,
+    ) -> Result<PageMeta> {
+        let (meta, html) = (meta.clone(), html.to_string());
+        let markdown = markdown.map(str::to_string);
+        self.run_blocking(move |store| {
+            store.create_page_auto_uid_with_markdown(&meta, &html, markdown.as_deref())
+        })
+        .await
+    }
+
+    async fn create_pages_batch(&self, pages: &[NewPage]) -> Result<Vec<Result<PageMeta>>> {
+        let pages = pages.to_vec();
+        self.run_blocking(move |store| store.create_pages_batch(&pages))
+            .await
+    }
+
+    async fn resolve_page_id_by_uid(&self, page_uid: &str) -> Result<Option<String>> {
+        let page_uid = page_uid.to_string();
+        self.run_blocking(move |store| store.resolve_page_id_by_uid(&page_uid))
+            .await
+    }
+
+    #[instrument(skip_all, fields(page_id = %page_id))]
+    async fn create_page(&self, page_id: &str, meta: &PageMeta, html: &str) -> Result<()> {
+        let (page_id, meta, html) = (page_id.to_string(), meta.clone(), html.to_string());
+        self.run_blocking(move |store| store.create_page(&page_id, &meta, &html))
+            .await
+    }
+
+    #[instrument(skip_all, fields(page_id = %page_id))]
+    async fn save_page(&self, page_id: &str, meta: &PageMeta, html: &str) -> Result<()> {
+        let (page_id, meta, html) = (page_id.to_string(), meta.clone(), html.to_string());
+        self.run_blocking(move |store| store.save_page(&page_id, &meta, &html))
+            .await
+    }
+
+    #[instrument(skip_all, fields(page_id = %page_id))]
+    async fn save_page_with_markdown(
+        &self,
+        page_id: &str,
+        meta: &PageMeta,
+        html: &str,
+        markdown: Option<&str>,
+    ) -> Result<()> {
+        let (page_id, meta, html) = (page_id.to_string(), meta.clone(), html.to_string());
+        let markdown = markdown.map(str::to_string);
+        self.run_blocking(move |store| {
+            store.save_page_with_markdown(&page_id, &meta, &html, markdown.as_deref())
+        })
+        .await
+    }
+
+    #[instrument(skip_all, fields(page_id = %page_id))]
+    async fn update_page(&self, page_id: &str, meta: &PageMeta, html: &str) -> Result<()> {
+        let (page_id, meta, html) = (page_id.to_string(), meta.clone(), html.to_string());
+        self.run_blocking(move |store| store.update_page(&page_id, &meta, &html))
+            .await
+    }
+
+    #[instrument(skip_all, fields(page_id = %page_id))]
+    async fn update_page_with_markdown(
+        &self,
+        page_id: &str,
+        meta: &PageMeta,
+        html: &str,
+        markdown: Option<&str>,
+    ) -> Result<()> {
+        let (page_id, meta, html) = (page_id.to_string(), meta.clone(), html.to_string());
+        let markdown = markdown.map(str::to_string);
+        self.run_blocking(move |store| {
+            store.update_page_with_markdown(&page_id, &meta, &html, markdown.as_deref())
+        })
+        .await
+    }
+
+    #[instrument(skip_all, fields(page_id = %page_id))]
+    async fn update_page_if_unmodified(
+        &self,
+        page_id: &str,
+        meta: &PageMeta,
+        html: &str,
+        markdown: Option<&str>,
+        expected_updated_at: i64,
+    ) -> Result<()> {
+        let (page_id, meta, html) = (page_id.to_string(), meta.clone(), html.to_string());
+        let markdown = markdown.map(str::to_string);
+        self.run_blocking(move |store| {
+            let markdown = markdown.as_deref();
+            store.update_page_if_unmodified(&page_id, &meta, &html, markdown, expected_updated_at)
+        })
+        .await
+    }
+
+    #[instrument(skip_all, fields(page_id = %page_id))]
+    async fn load_page(&self, page_id: &str) -> Result<(PageMeta, String)> {
+        let page_id = page_id.to_string();
+        self.run_blocking(move |store| store.load_page(&page_id))
+            .await
+    }
+
+    #[instrument(skip_all, fields(page_id = %page_id))]
+    async fn load_page_markdown(&self, page_id: &str) -> Result<Option<String>> {
+        let page_id = page_id.to_string();
+        self.run_blocking(move |store| store.load_page_markdown(&page_id))
+            .await
+    }
+
+    #[instrument(skip_all, fields(page_id = %page_id))]
+    async fn get_page_meta(&self, page_id: &str) -> Result<PageMeta> {
+        let page_id = page_id.to_string();
+        self.run_blocking(move |store| store.get_page_meta(&page_id))
+            .await
+    }
+
+    #[instrument(skip_all, fields(page_id = %page_id))]
+    async fn get_page_html(&self, page_id: &str) -> Result<String> {
+        let page_id = page_id.to_string();
+        self.run_blocking(move |store| store.get_page_html(&page_id))
+            .await
+    }
+
+    #[instrument(skip_all, fields(page_id = %page_id))]
+    async fn update_page_meta(&self, page_id: &str, meta: &PageMeta) -> Result<()> {
+        let (page_id, meta) = (page_id.to_string(), meta.clone());
+        self.run_blocking(move |store| store.update_page_meta(&page_id, &meta))
+            .await
+    }
+
+    #[instrument(skip_all, fields(page_id = %page_id))]
+    async fn update_page_html(
+        &self,
+        page_id: &str,
+        html: &str,
+        bump_updated_at: bool,
+    ) -> Result<()> {
+        let (page_id, html) = (page_id.to_string(), html.to_string());
+        self.run_blocking(move |store| store.update_page_html(&page_id, &html, bump_updated_at))
+            .await
+    }
+
+    #[instrument(skip_all, fields(page_id = %page_id))]
+    async fn update_page_markdown(&self, page_id: &str, markdown: &str) -> Result<()> {
+        let (page_id, markdown) = (page_id.to_string(), markdown.to_string());
+        self.run_blocking(move |store| store.update_page_markdown(&page_id, &markdown))
+            .await
+    }
+
+    async fn increment_view_count(
+        &self,
+        page_id: &str,
+        client_ip: Option<&str>,
+    ) -> Result<PageMeta> {
+        let (page_id, client_ip) = (page_id.to_string(), client_ip.map(str::to_string));
+        self.run_blocking(move |store| store.increment_view_count(&page_id, client_ip.as_deref()))
+            .await
+    }
+
+    async fn set_page_extra(
+        &self,
+        page_id: &str,
+        key: &str,
+        value: serde_json::Value,
+    ) -> Result<()> {
+        let (page_id, key) = (page_id.to_string(), key.to_string());
+        self.run_blocking(move |store| store.set_page_extra(&page_id, &key, value))
+            .await
+    }
+
+    async fn get_page_extra(&self, page_id: &str, key: &str) -> Result<Option<serde_json::Value>> {
+        let (page_id, key) = (page_id.to_string(), key.to_string());
+        self.run_blocking(move |store| store.get_page_extra(&page_id, &key))
+            .await
+    }
+
+    async fn set_page_tags(&self, page_id: &str, tags: &[String]) -> Result<PageMeta> {
+        let (page_id, tags) = (page_id.to_string(), tags.to_vec());
+        self.run_blocking(move |store| store.set_page_tags(&page_id, &tags))
+            .await
+    }
+
+    async fn set_page_sort_order(
+        &self,
+        page_id: &str,
+        sort_order: Option<i32>,
+    ) -> Result<PageMeta> {
+        let page_id = page_id.to_string();
+        self.run_blocking(move |store| store.set_page_sort_order(&page_id, sort_order))
+            .await
+    }
+
+    async fn mark_page_published(&self, page_id: &str) -> Result<PageMeta> {
+        let page_id = page_id.to_string();
+        self.run_blocking(move |store| store.mark_page_published(&page_id))
+            .await
+    }
+
+    async fn find_idempotency_key(&self, key: &str, since: i64) -> Result<Option<String>> {
+        let key = key.to_string();
+        self.run_blocking(move |store| store.find_idempotency_key(&key, since))
+            .await
+    }
 
-        Ok(())
+    async fn record_idempotency_key(&self, key: &str, page_uid: &str, since: i64) -> Result<()> {
+        let key = key.to_string();
+        let page_uid = page_uid.to_string();
+        self.run_blocking(move |store| store.record_idempotency_key(&key, &page_uid, since))
+            .await
     }
 
-    pub fn update_page_markdown(&self, page_id: &str, markdown: &str) -> Result<()> {
-        if !self.page_exists(page_id)? {
-            bail!("page not found: {}", page_id);
-        }
+    #[instrument(skip_all, fields(page_id = %page_id))]
+    async fn delete_page(&self, page_id: &str) -> Result<()> {
+        let page_id = page_id.to_string();
+        self.run_blocking(move |store| store.delete_page(&page_id))
+            .await
+    }
 
-        let safe_id = sanitize_page_id(page_id);
-        let markdown_path = self.base_dir.join(&safe_id).join("content.md");
-        atomic_write(&markdown_path, markdown.as_bytes()).context("write content.md")?;
+    async fn list_deleted_pages(&self) -> Result<Vec<DeletedPage>> {
+        self.run_blocking(|store| store.list_deleted_pages()).await
+    }
 
-        let meta_path = self.base_dir.join(&safe_id).join("meta.json");
-        let meta_raw = fs::read_to_string(&meta_path)
-            .with_context(|| format!("read meta.json {:?}", meta_path))?;
-        let mut meta: PageMeta = serde_json::from_str(&meta_raw).context("parse meta.json")?;
-        let mut index = self.load_index()?;
-        let now_ts = now_unix_seconds()?;
-        let index_uid = index
-            .pages
-            .get(&safe_id)
-            .map(|entry| entry.page_uid.clone())
-            .filter(|uid| !uid.is_empty());
-        let meta_uid = if meta.page_uid.is_empty() {
-            None
-        } else {
-            Some(meta.page_uid.clone())
-        };
-        let page_uid = match meta_uid.or(index_uid) {
-            Some(uid) => uid,
-            None => generate_unique_page_uid(&index)?,
-        };
-        if meta.created_at <= 0 {
-            meta.created_at = now_ts;
-        }
-        meta.updated_at = now_ts;
-        meta.page_uid = page_uid.clone();
-        let meta_bytes = serde_json::to_vec_pretty(&meta).context("serialize meta.json")?;
-        atomic_write(&meta_path, &meta_bytes).context("write meta.json")?;
+    async fn purge_deleted_page(&self, trash_id: &str) -> Result<DeletedPage> {
+        let trash_id = trash_id.to_string();
+        self.run_blocking(move |store| store.purge_deleted_page(&trash_id))
+            .await
+    }
 
-        let original_id = index
-            .pages
-            .get(&safe_id)
-            .and_then(|entry| entry.original_id.clone())
-            .or_else(|| {
-                if safe_id == page_id {
-                    None
-                } else {
-                    Some(page_id.to_string())
-                }
-            });
-        index.pages.insert(
-            safe_id.clone(),
-            PageIndexEntry {
-                page_id: safe_id,
-                seo: meta.seo.clone(),
-                page_uid,
-                original_id,
-            },
-        );
-        self.save_index(&index)?;
+    async fn page_exists(&self, page_id: &str) -> Result<bool> {
+        let page_id = page_id.to_string();
+        self.run_blocking(move |store| store.page_exists(&page_id))
+            .await
+    }
 
-        Ok(())
+    async fn list_pages(&self) -> Result<Vec<String>> {
+        self.run_blocking(|store| store.list_pages()).await
     }
 
-    pub fn increment_view_count(&self, page_id: &str) -> Result<PageMeta> {
-        let safe_id = sanitize_page_id(page_id);
-        let meta_path = self.base_dir.join(&safe_id).join("meta.json");
-        let meta_raw = fs::read_to_string(&meta_path)
-            .with_context(|| format!("read meta.json {:?}", meta_path))?;
-        let mut meta: PageMeta = serde_json::from_str(&meta_raw).context("parse meta.json")?;
-        meta.view_count = meta.view_count.saturating_add(1);
-        let meta_bytes = serde_json::to_vec_pretty(&meta).context("serialize meta.json")?;
-        atomic_write(&meta_path, &meta_bytes).context("write meta.json")?;
-        Ok(meta)
+    async fn list_page_entries(&self) -> Result<Vec<PageIndexEntry>> {
+        self.run_blocking(|store| store.list_page_entries()).await
     }
 
-    pub fn delete_page(&self, page_id: &str) -> Result<()> {
-        let safe_id = sanitize_page_id(page_id);
-        if !self.page_exists(page_id)? {
-            bail!("page not found: {}", page_id);
-        }
+    async fn list_pages_by_tag(&self, tag: &str) -> Result<Vec<PageIndexEntry>> {
+        let tag = tag.to_string();
+        self.run_blocking(move |store| store.list_pages_by_tag(&tag))
+            .await
+    }
 
-        let page_dir = self.base_dir.join(&safe_id);
-        fs::remove_dir_all(&page_dir).with_context(|| format!("remove page dir {:?}", page_dir))?;
+    async fn list_tags(&self) -> Result<Vec<(String, usize)>> {
+        self.run_blocking(|store| store.list_tags()).await
+    }
 
-        let mut index = self.load_index()?;
-        index.pages.remove(&safe_id);
-        self.save_index(&index)?;
+    async fn rebuild_index(&self) -> Result<StoreIndex> {
+        self.run_blocking(|store| store.rebuild_index()).await
+    }
 
-        Ok(())
+    #[instrument(skip_all, fields(page_id = %page_id))]
+    async fn create_revision(&self, page_id: &str) -> Result<RevisionInfo> {
+        let page_id = page_id.to_string();
+        self.run_blocking(move |store| store.create_revision(&page_id))
+            .await
     }
 
-    pub fn page_exists(&self, page_id: &str) -> Result<bool> {
-        let safe_id = sanitize_page_id(page_id);
-        let index = self.load_index()?;
-        if index.pages.contains_key(&safe_id) {
-            return Ok(true);
-        }
-        let page_dir = self.base_dir.join(&safe_id);
-        Ok(page_dir.is_dir())
+    async fn list_revisions(&self, page_id: &str) -> Result<Vec<RevisionInfo>> {
+        let page_id = page_id.to_string();
+        self.run_blocking(move |store| store.list_revisions(&page_id))
+            .await
     }
 
-    pub fn list_pages(&self) -> Result<Vec<String>> {
-        let index = self.load_index()?;
-        Ok(index.pages.keys().cloned().collect())
+    async fn load_revision(&self, page_id: &str, revision_id: u64) -> Result<PageRevision> {
+        let page_id = page_id.to_string();
+        self.run_blocking(move |store| store.load_revision(&page_id, revision_id))
+            .await
     }
 
-    pub fn list_page_entries(&self) -> Result<Vec<PageIndexEntry>> {
-        let index = self.load_index()?;
-        Ok(index.pages.values().cloned().collect())
+    #[instrument(skip_all, fields(page_id = %page_id))]
+    async fn prune_revisions(&self, page_id: &str, keep: usize) -> Result<usize> {
+        let page_id = page_id.to_string();
+        self.run_blocking(move |store| store.prune_revisions(&page_id, keep))
+            .await
     }
 
-    pub fn rebuild_index(&self) -> Result<StoreIndex> {
-        fs::create_dir_all(&self.base_dir)
-            .with_context(|| format!("create base dir {:?}", self.base_dir))?;
+    #[instrument(skip_all, fields(page_id = %page_id))]
+    async fn restore_revision(&self, page_id: &str, revision_id: u64) -> Result<RevisionInfo> {
+        let page_id = page_id.to_string();
+        self.run_blocking(move |store| store.restore_revision(&page_id, revision_id))
+            .await
+    }
 
-        let mut index = StoreIndex::default();
-        for entry in fs::read_dir(&self.base_dir)
-            .with_context(|| format!("read base dir {:?}", self.base_dir))?
-        {
-            let entry = entry.context("read dir entry")?;
-            let file_type = entry.file_type().context("read dir entry type")?;
-            if !file_type.is_dir() {
-                continue;
-            }
-            let page_id = entry.file_name().to_string_lossy().to_string();
-            let meta_path = entry.path().join("meta.json");
-            let meta_raw = match fs::read_to_string(&meta_path) {
-                Ok(raw) => raw,
-                Err(_) => continue,
-            };
-            let meta: PageMeta = match serde_json::from_str(&meta_raw) {
-                Ok(meta) => meta,
-                Err(_) => continue,
-            };
-            index.pages.insert(
-                page_id.clone(),
-                PageIndexEntry {
-                    page_id,
-                    seo: meta.seo,
-                    page_uid: meta.page_uid,
-                    original_id: None,
-                },
-            );
-        }
+    async fn get_page_stats(&self, page_id: &str) -> Result<PageStats> {
+        let page_id = page_id.to_string();
+        self.run_blocking(move |store| store.get_page_stats(&page_id))
+            .await
+    }
 
-        self.save_index(&index)?;
-        Ok(index)
+    async fn get_most_popular_pages(&self, limit: usize) -> Result<Vec<PageIndexEntry>> {
+        self.run_blocking(move |store| store.get_most_popular_pages(limit))
+            .await
     }
 
-    fn load_index(&self) -> Result<StoreIndex> {
-        let index_path = self.index_path();
-        match fs::read_to_string(&index_path) {
-            Ok(raw) => match serde_json::from_str::<StoreIndex>(&raw) {
-                Ok(index) => Ok(index),
-                Err(_) => self.rebuild_index(),
-            },
-            Err(_) => self.rebuild_index(),
-        }
+    async fn get_related_pages(&self, page_id: &str, limit: usize) -> Result<Vec<PageIndexEntry>> {
+        let page_id = page_id.to_string();
+        self.run_blocking(move |store| store.get_related_pages(&page_id, limit))
+            .await
     }
 
-    fn save_index(&self, index: &StoreIndex) -> Result<()> {
-        fs::create_dir_all(&self.base_dir)
-            .with_context(|| format!("create base dir {:?}", self.base_dir))?;
-        let index_path = self.index_path();
-        let bytes = serde_json::to_vec_pretty(index).context("serialize index.json")?;
-        atomic_write(&index_path, &bytes).context("write index.json")?;
-        Ok(())
+    async fn list_recent_updates(&self, limit: usize) -> Result<Vec<PageIndexEntry>> {
+        self.run_blocking(move |store| store.list_recent_updates(limit))
+            .await
     }
 
-    fn index_path(&self) -> PathBuf {
-        self.base_dir.join("index.json")
+    async fn probe_health(&self) -> Result<StoreHealth> {
+        self.run_blocking(|store| store.probe_health()).await
+    }
+
+    async fn validate_consistency(&self) -> Result<ConsistencyReport> {
+        self.run_blocking(|store| store.validate_consistency())
+            .await
+    }
+
+    async fn repair_consistency(&self, report: &ConsistencyReport) -> Result<Vec<String>> {
+        let report = report.clone();
+        self.run_blocking(move |store| store.repair_consistency(&report))
+            .await
+    }
+}
+
+/// `validate_html` 的失败原因，调用方可通过 `downcast_ref::<HtmlError>()` 取出出错位置
+#[derive(Debug, Clone)]
+pub struct HtmlError {
+    pub message: String,
+    /// 出错位置的字节偏移，空文档等无法定位时为空
+    pub offset: Option<usize>,
+}
+
+impl std::fmt::Display for HtmlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for HtmlError {}
+
+/// 带 `expected_updated_at` 的更新发现页面已被其他写入者修改，可通过 `downcast_ref` 取出当前版本
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdateConflict {
+    pub page_id: String,
+    pub expected_updated_at: i64,
+    pub current_updated_at: i64,
+    pub title: String,
+}
+
+impl std::fmt::Display for UpdateConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "page {} was modified by another writer: expected updated_at {}, current updated_at {} (title: {})",
+            self.page_id, self.expected_updated_at, self.current_updated_at, self.title
+        )
     }
 }
 
+impl std::error::Error for UpdateConflict {}
+
+fn html_error(offset: usize, message: String) -> anyhow::Error {
+    HtmlError {
+        message,
+        offset: Some(offset),
+    }
+    .into()
+}
+
 pub fn validate_html(html: &str) -> Result<()> {
     if html.trim().is_empty() {
-        bail!("html is empty or whitespace");
+        return Err(HtmlError {
+            message: "html is empty or whitespace".to_string(),
+            offset: None,
+        }
+        .into());
     }
     if let Some(pos) = html.as_bytes().iter().position(|byte| *byte == 0) {
-        bail!("html contains NUL byte at index {}", pos);
+        return Err(html_error(
+            pos,
+            format!("html contains NUL byte at index {}", pos),
+        ));
     }
 
     let bytes = html.as_bytes();
@@ -668,7 +2685,10 @@ pub fn validate_html(html: &str) -> Result<()> {
                 index = end + 3;
                 continue;
             }
-            bail!("unterminated comment at index {}", index);
+            return Err(html_error(
+                index,
+                format!("unterminated comment at index {}", index),
+            ));
         }
 
         if index + 1 < bytes.len() && bytes[index + 1] == b'!' {
@@ -676,33 +2696,47 @@ pub fn validate_html(html: &str) -> Result<()> {
                 index = end + 1;
                 continue;
             }
-            bail!("unterminated declaration at index {}", index);
+            return Err(html_error(
+                index,
+                format!("unterminated declaration at index {}", index),
+            ));
         }
 
         if index + 1 < bytes.len() && bytes[index + 1] == b'/' {
             let (name, after_name) = parse_tag_name(bytes, index + 2, index)?;
-            let end = find_tag_end(bytes, after_name)
-                .ok_or_else(|| anyhow::anyhow!("unterminated closing tag at index {}", index))?;
+            let end = find_tag_end(bytes, after_name).ok_or_else(|| {
+                html_error(
+                    index,
+                    format!("unterminated closing tag at index {}", index),
+                )
+            })?;
             let name = name.to_ascii_lowercase();
             let Some((open_tag, open_index)) = stack.pop() else {
-                bail!("unexpected closing tag </{}> at index {}", name, index);
+                return Err(html_error(
+                    index,
+                    format!("unexpected closing tag </{}> at index {}", name, index),
+                ));
             };
             if open_tag != name {
-                bail!(
-                    "mismatched closing tag </{}> at index {}, expected </{}> for tag opened at index {}",
-                    name,
+                return Err(html_error(
                     index,
-                    open_tag,
-                    open_index
-                );
+                    format!(
+                        "mismatched closing tag </{}> at index {}, expected </{}> for tag opened at index {}",
+                        name, index, open_tag, open_index
+                    ),
+                ));
             }
             index = end + 1;
             continue;
         }
 
         let (name, after_name) = parse_tag_name(bytes, index + 1, index)?;
-        let end = find_tag_end(bytes, after_name)
-            .ok_or_else(|| anyhow::anyhow!("unterminated opening tag at index {}", index))?;
+        let end = find_tag_end(bytes, after_name).ok_or_else(|| {
+            html_error(
+                index,
+                format!("unterminated opening tag at index {}", index),
+            )
+        })?;
         let is_self_closing = is_self_closing(bytes, index + 1, end);
         let name = name.to_ascii_lowercase();
 
@@ -721,7 +2755,23 @@ pub fn validate_html(html: &str) -> Result<()> {
                 index = close_end;
                 continue;
             }
-            bail!("unterminated <{}> starting at index {}", name, index);
+            return Err(html_error(
+                index,
+                format!("unterminated <{}> starting at index {}", name, index),
+            ));
+        }
+
+        // `<template>` 的内容是惰性片段（常见于 Shadow DOM 组件），只定位匹配的结束标签，不检查内部结构；
+        // `<slot>` 与其他元素一样按普通容器检查
+        if name == "template" && !is_self_closing {
+            let Some(close_end) = find_template_close(bytes, end + 1) else {
+                return Err(html_error(
+                    index,
+                    format!("unterminated <template> starting at index {}", index),
+                ));
+            };
+            index = close_end;
+            continue;
         }
 
         if !is_self_closing && !is_void_element(&name) {
@@ -731,7 +2781,10 @@ pub fn validate_html(html: &str) -> Result<()> {
     }
 
     if let Some((tag, open_index)) = stack.pop() {
-        bail!("unclosed tag <{}> starting at index {}", tag, open_index);
+        return Err(html_error(
+            open_index,
+            format!("unclosed tag <{}> starting at index {}", tag, open_index),
+        ));
     }
 
     Ok(())
@@ -770,7 +2823,7 @@ fn generate_page_uid() -> Result<String> {
     Ok(out)
 }
 
-fn generate_unique_page_uid(index: &StoreIndex) -> Result<String> {
+pub(crate) fn generate_unique_page_uid(index: &StoreIndex) -> Result<String> {
     for _ in 0..8 {
         let uid = generate_page_uid()?;
         if !index.pages.values().any(|entry| entry.page_uid == uid) {
@@ -780,14 +2833,247 @@ fn generate_unique_page_uid(index: &StoreIndex) -> Result<String> {
     bail!("failed to generate unique page uid")
 }
 
-fn now_unix_seconds() -> Result<i64> {
+/// 非文件系统后端写入 meta 时与 `PageStore` 保持一致：uid、created_at、published_at 沿用已有值，updated_at 取当前时间
+pub(crate) fn merge_page_meta(
+    meta: &PageMeta,
+    existing_meta: Option<&PageMeta>,
+    index: &StoreIndex,
+    safe_id: &str,
+) -> Result<PageMeta> {
+    let existing_uid = existing_meta
+        .map(|value| value.page_uid.clone())
+        .filter(|uid| !uid.is_empty());
+    let index_uid = index
+        .pages
+        .get(safe_id)
+        .map(|entry| entry.page_uid.clone())
+        .filter(|uid| !uid.is_empty());
+    let fallback_uid = Some(meta.page_uid.clone()).filter(|uid| !uid.is_empty());
+    let page_uid = match existing_uid.or(index_uid).or(fallback_uid) {
+        Some(uid) => uid,
+        None => generate_unique_page_uid(index)?,
+    };
+    let now_ts = now_unix_seconds()?;
+    let created_at = existing_meta
+        .map(|value| value.created_at)
+        .filter(|value| *value > 0)
+        .or(Some(meta.created_at).filter(|value| *value > 0))
+        .unwrap_or(now_ts);
+
+    let mut meta_to_write = meta.clone();
+    if meta_to_write.seo.title.is_empty() {
+        meta_to_write.seo.title = meta_to_write.seo.seo_title.clone();
+    }
+    meta_to_write.page_uid = page_uid;
+    meta_to_write.created_at = created_at;
+    let previous_updated_at = existing_meta.map_or(0, |value| value.updated_at);
+    meta_to_write.updated_at = next_updated_at(previous_updated_at, now_ts);
+    meta_to_write.published_at = meta
+        .published_at
+        .or_else(|| existing_meta.and_then(|value| value.published_at));
+    Ok(meta_to_write)
+}
+
+/// `{page_id}-{deleted_at}` 已被占用时依次尝试 `-2`、`-3`…
+pub(crate) fn unique_trash_id(
+    safe_id: &str,
+    deleted_at: i64,
+    exists: impl Fn(&str) -> bool,
+) -> String {
+    let base = format!("{}-{}", safe_id, deleted_at);
+    let mut candidate = base.clone();
+    let mut suffix = 2;
+    while exists(&candidate) {
+        candidate = format!("{}-{}", base, suffix);
+        suffix += 1;
+    }
+    candidate
+}
+
+/// 新的删除在前，同一秒内按 `trash_id` 排序
+pub(crate) fn sort_deleted_pages(pages: &mut [DeletedPage]) {
+    pages.sort_by(|left, right| {
+        right
+            .deleted_at
+            .cmp(&left.deleted_at)
+            .then_with(|| left.trash_id.cmp(&right.trash_id))
+    });
+}
+
+/// 新的 updated_at 严格大于旧值，同一秒内的连续写入也能被 `expected_updated_at` 区分
+pub(crate) fn next_updated_at(previous: i64, now_ts: i64) -> i64 {
+    now_ts.max(previous.saturating_add(1))
+}
+
+/// `expected_updated_at` 为空或页面不存在时不校验
+pub(crate) fn check_expected_updated_at(
+    page_id: &str,
+    existing_meta: Option<&PageMeta>,
+    expected_updated_at: Option<i64>,
+) -> Result<()> {
+    let (Some(expected), Some(existing)) = (expected_updated_at, existing_meta) else {
+        return Ok(());
+    };
+    if existing.updated_at == expected {
+        return Ok(());
+    }
+    Err(UpdateConflict {
+        page_id: page_id.to_string(),
+        expected_updated_at: expected,
+        current_updated_at: existing.updated_at,
+        title: existing.seo.title.clone(),
+    }
+    .into())
+}
+
+/// `extra` 键去除首尾空白后不能为空
+pub(crate) fn validate_extra_key(key: &str) -> Result<&str> {
+    let key = key.trim();
+    if key.is_empty() {
+        bail!("extra key is empty");
+    }
+    Ok(key)
+}
+
+pub(crate) fn set_extra_value(meta: &mut PageMeta, key: &str, value: serde_json::Value) {
+    if value.is_null() {
+        meta.extra.remove(key);
+    } else {
+        meta.extra.insert(key.to_string(), value);
+    }
+}
+
+/// `extra.head_html` 写入前的校验：值必须是字符串，片段本身与追加到 `html` 后的页面都要通过校验。
+/// null 原样返回（删除该键）；失败时返回 `HtmlError`，与正文校验失败一样按校验错误处理
+pub(crate) fn check_extra_head_html(
+    value: serde_json::Value,
+    html: &str,
+) -> Result<serde_json::Value> {
+    if value.is_null() {
+        return Ok(value);
+    }
+    let Some(snippet) = value.as_str() else {
+        return Err(HtmlError {
+            message: "invalid head_html: value must be a string".to_string(),
+            offset: None,
+        }
+        .into());
+    };
+    let snippet = sanitize_extra_head_html(snippet)
+        .and_then(|snippet| append_extra_head_html(html, &snippet).map(|_| snippet))
+        .map_err(|err| HtmlError {
+            message: format!("invalid head_html: {err:#}"),
+            offset: None,
+        })?;
+    Ok(snippet.into())
+}
+
+/// 页面目录下记录访客 IP 哈希的子目录
+pub const VISITORS_DIR: &str = "visitors";
+
+/// 数据目录下的回收站，页面 ID 不含 `.`，不会与页面目录重名
+pub const TRASH_DIR: &str = ".trash";
+
+/// 回收站条目中的删除记录，内容为 `DeletedPage`
+pub const DELETED_RECORD_FILE: &str = "deleted.json";
+
+/// 页面目录下保存历史版本的子目录，每个版本一个 `{revision_id}.json`
+pub const REVISIONS_DIR: &str = "revisions";
+
+/// 版本号按最新版本递增，版本号越大越新；全部删除后会从 1 重新分配，因此至少保留一个
+pub(crate) fn check_revision_keep(keep: usize) -> Result<()> {
+    if keep == 0 {
+        bail!("keep must be at least 1");
+    }
+    Ok(())
+}
+
+/// 目录不存在时视为没有版本；忽略非 `{数字}.json` 的文件
+/// 递归统计目录下所有文件的字节数，不跟随符号链接
+fn dir_size(dir: &Path) -> Result<u64> {
+    let mut total = 0;
+    for entry in fs::read_dir(dir).with_context(|| format!("read dir {:?}", dir))? {
+        let entry = entry.context("read dir entry")?;
+        let file_type = entry.file_type().context("read dir entry type")?;
+        if file_type.is_dir() {
+            total += dir_size(&entry.path())?;
+        } else if file_type.is_file() {
+            total += entry.metadata().context("read file metadata")?.len();
+        }
+    }
+    Ok(total)
+}
+
+fn read_revision_ids(revisions_dir: &Path) -> Result<Vec<u64>> {
+    let entries = match fs::read_dir(revisions_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(err).with_context(|| format!("read revisions dir {:?}", revisions_dir));
+        }
+    };
+    let mut ids = Vec::new();
+    for entry in entries {
+        let path = entry.context("read revisions dir entry")?.path();
+        if path.extension().is_some_and(|ext| ext == "json")
+            && let Some(id) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<u64>().ok())
+        {
+            ids.push(id);
+        }
+    }
+    ids.sort_unstable();
+    Ok(ids)
+}
+
+/// 访客去重按 UTC 自然日划分，日期同时作为当天的哈希盐
+pub fn visitor_day() -> String {
+    Utc::now().format("%Y-%m-%d").to_string()
+}
+
+/// SHA-256(日期 + `VISITOR_HASH_SECRET` + IP)，磁盘上不保存原始 IP，跨天无法关联同一访客
+pub fn hash_visitor_ip(client_ip: &str, day: &str) -> String {
+    let secret = std::env::var("VISITOR_HASH_SECRET").unwrap_or_default();
+    let mut hasher = Sha256::new();
+    hasher.update(day.as_bytes());
+    hasher.update(b":");
+    hasher.update(secret.trim().as_bytes());
+    hasher.update(b":");
+    hasher.update(client_ip.trim().as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// 访客哈希当天首次出现时追加到 `{day}.txt` 并返回 true
+fn record_visitor(visitors_dir: &Path, day: &str, visitor_hash: &str) -> Result<bool> {
+    let path = visitors_dir.join(format!("{day}.txt"));
+    let seen = fs::read_to_string(&path)
+        .map(|raw| raw.lines().any(|line| line.trim() == visitor_hash))
+        .unwrap_or(false);
+    if seen {
+        return Ok(false);
+    }
+    fs::create_dir_all(visitors_dir)
+        .with_context(|| format!("create visitors dir {:?}", visitors_dir))?;
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("open visitors file {:?}", path))?;
+    writeln!(file, "{}", visitor_hash)
+        .with_context(|| format!("append visitors file {:?}", path))?;
+    Ok(true)
+}
+
+pub(crate) fn now_unix_seconds() -> Result<i64> {
     let duration = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .context("system time before unix epoch")?;
     Ok(duration.as_secs().min(i64::MAX as u64) as i64)
 }
 
-fn atomic_write(path: &Path, data: &[u8]) -> Result<()> {
+pub(crate) fn atomic_write(path: &Path, data: &[u8]) -> Result<()> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent).with_context(|| format!("create parent dir {:?}", parent))?;
     }
@@ -805,14 +3091,19 @@ fn parse_tag_name(bytes: &[u8], mut index: usize, tag_start: usize) -> Result<(S
     let start = index;
     while index < bytes.len() {
         let byte = bytes[index];
-        if byte.is_ascii_alphanumeric() || byte == b'-' || byte == b':' {
+        // `.`、`_` 与非 ASCII 字节只出现在自定义元素名称中
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b':' | b'.' | b'_') || byte >= 0x80
+        {
             index += 1;
         } else {
             break;
         }
     }
     if start == index {
-        bail!("missing tag name at index {}", tag_start);
+        return Err(html_error(
+            tag_start,
+            format!("missing tag name at index {}", tag_start),
+        ));
     }
     let name = std::str::from_utf8(&bytes[start..index]).context("read tag name")?;
     Ok((name.to_string(), index))
@@ -841,6 +3132,43 @@ fn find_tag_end(bytes: &[u8], mut index: usize) -> Option<usize> {
     None
 }
 
+/// 从 `<template>` 开始标签之后查找与之匹配的 `</template>`，计入嵌套的 `<template>`，
+/// 跳过 `<!-- -->` 注释（注释中的 `</template>` 不算结束）；返回结束标签之后的位置
+fn find_template_close(bytes: &[u8], mut index: usize) -> Option<usize> {
+    let mut depth = 1usize;
+    while let Some(tag_start) = find_bytes(bytes, index, b"<") {
+        if bytes[tag_start..].starts_with(b"<!--") {
+            index = find_bytes(bytes, tag_start + 4, b"-->")? + 3;
+            continue;
+        }
+        let tag_end = find_tag_end(bytes, tag_start + 1)?;
+        let closing = bytes.get(tag_start + 1) == Some(&b'/');
+        let name_start = if closing {
+            tag_start + 2
+        } else {
+            tag_start + 1
+        };
+        let is_template = bytes
+            .get(name_start..name_start + 8)
+            .is_some_and(|name| name.eq_ignore_ascii_case(b"template"))
+            && bytes
+                .get(name_start + 8)
+                .is_some_and(|byte| byte.is_ascii_whitespace() || matches!(byte, b'>' | b'/'));
+        if is_template {
+            if closing {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(tag_end + 1);
+                }
+            } else if !is_self_closing(bytes, tag_start + 1, tag_end) {
+                depth += 1;
+            }
+        }
+        index = tag_end + 1;
+    }
+    None
+}
+
 fn is_self_closing(bytes: &[u8], start: usize, end: usize) -> bool {
     let mut index = end;
     while index > start {
@@ -854,7 +3182,8 @@ fn is_self_closing(bytes: &[u8], start: usize, end: usize) -> bool {
     false
 }
 
-fn is_void_element(name: &str) -> bool {
+/// HTML void 元素（`<br>`、`<img>` 等）没有结束标签，`name` 需为小写
+pub fn is_void_element(name: &str) -> bool {
     matches!(
         name,
         "area"
@@ -874,45 +3203,81 @@ fn is_void_element(name: &str) -> bool {
     )
 }
 
-fn find_bytes(haystack: &[u8], start: usize, needle: &[u8]) -> Option<usize> {
-    if needle.is_empty() {
-        return Some(start.min(haystack.len()));
-    }
-    if start >= haystack.len() || needle.len() > haystack.len() {
-        return None;
-    }
-    let end = haystack.len().saturating_sub(needle.len());
-    for index in start..=end {
-        if &haystack[index..index + needle.len()] == needle {
-            return Some(index);
-        }
-    }
-    None
+/// 自定义元素名称必须包含连字符且以 ASCII 字母开头，SVG / MathML 保留的连字符名称除外；
+/// `validate_html` 按普通容器元素检查其嵌套，名称中的 `.`、`_` 与非 ASCII 字符同样计入标签名
+pub fn is_custom_element(name: &str) -> bool {
+    const RESERVED: &[&str] = &[
+        "annotation-xml",
+        "color-profile",
+        "font-face",
+        "font-face-src",
+        "font-face-uri",
+        "font-face-format",
+        "font-face-name",
+        "missing-glyph",
+    ];
+    name.starts_with(|ch: char| ch.is_ascii_alphabetic())
+        && name.contains('-')
+        && !RESERVED
+            .iter()
+            .any(|reserved| name.eq_ignore_ascii_case(reserved))
 }
 
-fn find_bytes_case_insensitive(haystack: &[u8], start: usize, needle: &[u8]) -> Option<usize> {
-    if needle.is_empty() {
-        return Some(start.min(haystack.len()));
-    }
-    if start >= haystack.len() || needle.len() > haystack.len() {
-        return None;
-    }
-    let needle_lower: Vec<u8> = needle
-        .iter()
-        .map(|byte| byte.to_ascii_lowercase())
-        .collect();
-    let end = haystack.len().saturating_sub(needle_lower.len());
-    for index in start..=end {
-        let mut matched = true;
-        for (offset, expected) in needle_lower.iter().enumerate() {
-            if haystack[index + offset].to_ascii_lowercase() != *expected {
-                matched = false;
-                break;
+/// 字节串查找工具，供存储层与自定义的 HTML 处理代码使用
+pub mod html_util {
+    /// 从 `start` 开始查找 `needle` 第一次出现的位置，匹配可以与之前的匹配重叠；
+    /// `start > haystack.len()` 时返回 `None`，`needle` 为空时返回 `start`，`needle` 更长时返回 `None`
+    pub fn find_bytes(haystack: &[u8], start: usize, needle: &[u8]) -> Option<usize> {
+        if start > haystack.len() {
+            return None;
+        }
+        if needle.is_empty() {
+            return Some(start);
+        }
+        if start >= haystack.len() || needle.len() > haystack.len() {
+            return None;
+        }
+        let end = haystack.len().saturating_sub(needle.len());
+        for index in start..=end {
+            if &haystack[index..index + needle.len()] == needle {
+                return Some(index);
             }
         }
-        if matched {
-            return Some(index);
+        None
+    }
+
+    /// 与 `find_bytes` 相同，但按 ASCII 忽略大小写比较，用于匹配 HTML 标签名
+    pub fn find_bytes_case_insensitive(
+        haystack: &[u8],
+        start: usize,
+        needle: &[u8],
+    ) -> Option<usize> {
+        if start > haystack.len() {
+            return None;
+        }
+        if needle.is_empty() {
+            return Some(start);
+        }
+        if start >= haystack.len() || needle.len() > haystack.len() {
+            return None;
+        }
+        let needle_lower: Vec<u8> = needle
+            .iter()
+            .map(|byte| byte.to_ascii_lowercase())
+            .collect();
+        let end = haystack.len().saturating_sub(needle_lower.len());
+        for index in start..=end {
+            let mut matched = true;
+            for (offset, expected) in needle_lower.iter().enumerate() {
+                if haystack[index + offset].to_ascii_lowercase() != *expected {
+                    matched = false;
+                    break;
+                }
+            }
+            if matched {
+                return Some(index);
+            }
         }
+        None
     }
-    None
 }
@@ -0,0 +1,508 @@
+use std::collections::HashSet;
+
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use dashmap::DashMap;
+use tokio::sync::Mutex;
+
+use crate::store::{
+    DeletedPage, IdempotencyRecords, PageIndexEntry, PageMeta, PageRevision, PageStoreTrait,
+    RevisionInfo, StoreHealth, StoreIndex, check_expected_updated_at, check_extra_head_html,
+    check_revision_keep, generate_unique_page_uid, hash_visitor_ip, merge_page_meta,
+    next_updated_at, normalize_page_tags, now_unix_seconds, page_index_tags, sanitize_page_id,
+    set_extra_value, sort_deleted_pages, to_url_slug, unique_trash_id, validate_extra_key,
+    validate_html, visitor_day,
+};
+use crate::web::EXTRA_HEAD_HTML_KEY;
+
+/// 纯内存存储后端，不访问文件系统，供测试使用（`test-helpers` feature）
+/// 索引由页面数据实时生成，`rebuild_index` 直接返回当前索引
+#[derive(Debug, Default)]
+pub struct MemoryPageStore {
+    pages: DashMap<String, (PageMeta, String)>,
+    markdown: DashMap<String, String>,
+    /// 键为 `(page_id, YYYY-MM-DD)`，值为当天访客的 IP 哈希
+    visitors: DashMap<(String, String), HashSet<String>>,
+    /// 按版本号升序保存的历史版本
+    revisions: DashMap<String, Vec<PageRevision>>,
+    /// 回收站只保留删除记录，键为 `trash_id`
+    trash: DashMap<String, DeletedPage>,
+    /// 写操作串行执行，保证 uid 分配与 meta 合并不会交错
+    write_lock: Mutex<()>,
+    idempotency: Mutex<IdempotencyRecords>,
+}
+
+impl MemoryPageStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn index(&self) -> StoreIndex {
+        let mut index = StoreIndex::default();
+        for entry in self.pages.iter() {
+            let (meta, _) = entry.value();
+            index.set_page_tags(entry.key(), &page_index_tags(meta));
+            index.pages.insert(
+                entry.key().clone(),
+                PageIndexEntry {
+                    page_id: entry.key().clone(),
+                    seo: meta.seo.clone(),
+                    page_uid: meta.page_uid.clone(),
+                    original_id: None,
+                    view_count: meta.view_count,
+                    sort_order: meta.sort_order,
+                    updated_at: meta.updated_at,
+                },
+            );
+        }
+        index
+    }
+
+    fn write_page(
+        &self,
+        page_id: &str,
+        meta: &PageMeta,
+        html: &str,
+        markdown: Option<&str>,
+    ) -> Result<()> {
+        self.write_page_checked(page_id, meta, html, markdown, None)
+    }
+
+    /// 调用方持有 `write_lock`，比较与写入之间不会有其他写入
+    fn write_page_checked(
+        &self,
+        page_id: &str,
+        meta: &PageMeta,
+        html: &str,
+        markdown: Option<&str>,
+        expected_updated_at: Option<i64>,
+    ) -> Result<()> {
+        validate_html(html)?;
+        let safe_id = sanitize_page_id(page_id);
+        let index = self.index();
+        let existing_meta = self.pages.get(&safe_id).map(|entry| entry.0.clone());
+        check_expected_updated_at(page_id, existing_meta.as_ref(), expected_updated_at)?;
+        let meta_to_write = merge_page_meta(meta, existing_meta.as_ref(), &index, &safe_id)?;
+        if let Some(markdown) = markdown {
+            self.markdown.insert(safe_id.clone(), markdown.to_string());
+        }
+        self.pages
+            .insert(safe_id, (meta_to_write, html.to_string()));
+        Ok(())
+    }
+
+    async fn create_page_with_new_uid(
+        &self,
+        meta: &PageMeta,
+        html: &str,
+        markdown: Option<&str>,
+    ) -> Result<PageMeta> {
+        let _guard = self.write_lock.lock().await;
+        let uid = generate_unique_page_uid(&self.index())?;
+        let mut meta_with_uid = meta.clone();
+        if meta_with_uid.seo.title.is_empty() {
+            meta_with_uid.seo.title = meta_with_uid.seo.seo_title.clone();
+        }
+        meta_with_uid.seo.seo_title = to_url_slug(&meta_with_uid.seo.seo_title);
+        meta_with_uid.page_uid = uid.clone();
+        self.write_page(&uid, &meta_with_uid, html, markdown)?;
+        self.get_page_meta(&uid).await
+    }
+
+    fn touch_page(&self, page_id: &str) -> Result<()> {
+        let now_ts = now_unix_seconds()?;
+        let Some(mut entry) = self.pages.get_mut(page_id) else {
+            bail!("page not found: {}", page_id);
+        };
+        entry.0.updated_at = next_updated_at(entry.0.updated_at, now_ts);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PageStoreTrait for MemoryPageStore {
+    async fn create_page_auto_uid(&self, meta: &PageMeta, html: &str) -> Result<PageMeta> {
+        self.create_page_with_new_uid(meta, html, None).await
+    }
+
+    async fn create_page_auto_uid_with_markdown(
+        &self,
+        meta: &PageMeta,
+        html: &str,
+        markdown: Option<&str>,
+    ) -> Result<PageMeta> {
+        self.create_page_with_new_uid(meta, html, markdown).await
+    }
+
+    async fn resolve_page_id_by_uid(&self, page_uid: &str) -> Result<Option<String>> {
+        if self.pages.contains_key(page_uid) {
+            return Ok(Some(page_uid.to_string()));
+        }
+        Ok(self
+            .pages
+            .iter()
+            .find(|entry| entry.value().0.page_uid == page_uid)
+            .map(|entry| entry.key().clone()))
+    }
+
+    async fn create_page(&self, page_id: &str, meta: &PageMeta, html: &str) -> Result<()> {
+        let _guard = self.write_lock.lock().await;
+        if self.pages.contains_key(&sanitize_page_id(page_id)) {
+            bail!("page already exists: {}", page_id);
+        }
+        self.write_page(page_id, meta, html, None)
+    }
+
+    async fn save_page(&self, page_id: &str, meta: &PageMeta, html: &str) -> Result<()> {
+        let _guard = self.write_lock.lock().await;
+        self.write_page(page_id, meta, html, None)
+    }
+
+    async fn save_page_with_markdown(
+        &self,
+        page_id: &str,
+        meta: &PageMeta,
+        html: &str,
+        markdown: Option<&str>,
+    ) -> Result<()> {
+        let _guard = self.write_lock.lock().await;
+        self.write_page(page_id, meta, html, markdown)
+    }
+
+    async fn update_page(&self, page_id: &str, meta: &PageMeta, html: &str) -> Result<()> {
+        self.update_page_with_markdown(page_id, meta, html, None)
+            .await
+    }
+
+    async fn update_page_with_markdown(
+        &self,
+        page_id: &str,
+        meta: &PageMeta,
+        html: &str,
+        markdown: Option<&str>,
+    ) -> Result<()> {
+        let _guard = self.write_lock.lock().await;
+        if !self.pages.contains_key(&sanitize_page_id(page_id)) {
+            bail!("page not found: {}", page_id);
+        }
+        let mut meta_to_update = meta.clone();
+        if meta_to_update.seo.title.is_empty() {
+            meta_to_update.seo.title = meta_to_update.seo.seo_title.clone();
+        }
+        meta_to_update.seo.seo_title = to_url_slug(&meta_to_update.seo.seo_title);
+        self.write_page(page_id, &meta_to_update, html, markdown)
+    }
+
+    async fn update_page_if_unmodified(
+        &self,
+        page_id: &str,
+        meta: &PageMeta,
+        html: &str,
+        markdown: Option<&str>,
+        expected_updated_at: i64,
+    ) -> Result<()> {
+        let _guard = self.write_lock.lock().await;
+        if !self.pages.contains_key(&sanitize_page_id(page_id)) {
+            bail!("page not found: {}", page_id);
+        }
+        let mut meta_to_update = meta.clone();
+        if meta_to_update.seo.title.is_empty() {
+            meta_to_update.seo.title = meta_to_update.seo.seo_title.clone();
+        }
+        meta_to_update.seo.seo_title = to_url_slug(&meta_to_update.seo.seo_title);
+        let expected = Some(expected_updated_at);
+        self.write_page_checked(page_id, &meta_to_update, html, markdown, expected)
+    }
+
+    async fn load_page(&self, page_id: &str) -> Result<(PageMeta, String)> {
+        match self.pages.get(&sanitize_page_id(page_id)) {
+            Some(entry) => Ok(entry.value().clone()),
+            None => bail!("page not found: {}", page_id),
+        }
+    }
+
+    async fn load_page_markdown(&self, page_id: &str) -> Result<Option<String>> {
+        Ok(self
+            .markdown
+            .get(&sanitize_page_id(page_id))
+            .map(|entry| entry.value().clone()))
+    }
+
+    async fn get_page_meta(&self, page_id: &str) -> Result<PageMeta> {
+        let (meta, _) = self.load_page(page_id).await?;
+        Ok(meta)
+    }
+
+    async fn get_page_html(&self, page_id: &str) -> Result<String> {
+        let (_, html) = self.load_page(page_id).await?;
+        Ok(html)
+    }
+
+    async fn update_page_meta(&self, page_id: &str, meta: &PageMeta) -> Result<()> {
+        let _guard = self.write_lock.lock().await;
+        let safe_id = sanitize_page_id(page_id);
+        let Some((existing_meta, html)) = self.pages.get(&safe_id).map(|entry| entry.clone())
+        else {
+            bail!("page not found: {}", page_id);
+        };
+        let meta_to_write = merge_page_meta(meta, Some(&existing_meta), &self.index(), &safe_id)?;
+        self.pages.insert(safe_id, (meta_to_write, html));
+        Ok(())
+    }
+
+    async fn update_page_html(
+        &self,
+        page_id: &str,
+        html: &str,
+        bump_updated_at: bool,
+    ) -> Result<()> {
+        validate_html(html)?;
+        let _guard = self.write_lock.lock().await;
+        let safe_id = sanitize_page_id(page_id);
+        match self.pages.get_mut(&safe_id) {
+            Some(mut entry) => entry.1 = html.to_string(),
+            None => bail!("page not found: {}", page_id),
+        }
+        if bump_updated_at {
+            self.touch_page(&safe_id)?;
+        }
+        Ok(())
+    }
+
+    async fn update_page_markdown(&self, page_id: &str, markdown: &str) -> Result<()> {
+        let _guard = self.write_lock.lock().await;
+        let safe_id = sanitize_page_id(page_id);
+        if !self.pages.contains_key(&safe_id) {
+            bail!("page not found: {}", page_id);
+        }
+        self.markdown.insert(safe_id.clone(), markdown.to_string());
+        self.touch_page(&safe_id)
+    }
+
+    async fn increment_view_count(
+        &self,
+        page_id: &str,
+        client_ip: Option<&str>,
+    ) -> Result<PageMeta> {
+        let safe_id = sanitize_page_id(page_id);
+        let Some(mut entry) = self.pages.get_mut(&safe_id) else {
+            bail!("page not found: {}", page_id);
+        };
+        if let Some(client_ip) = client_ip {
+            let day = visitor_day();
+            let visitor_hash = hash_visitor_ip(client_ip, &day);
+            if !self
+                .visitors
+                .entry((safe_id, day))
+                .or_default()
+                .insert(visitor_hash)
+            {
+                return Ok(entry.0.clone());
+            }
+            entry.0.unique_view_count = entry.0.unique_view_count.saturating_add(1);
+        }
+        entry.0.view_count = entry.0.view_count.saturating_add(1);
+        Ok(entry.0.clone())
+    }
+
+    async fn set_page_extra(
+        &self,
+        page_id: &str,
+        key: &str,
+        value: serde_json::Value,
+    ) -> Result<()> {
+        let key = validate_extra_key(key)?;
+        let _guard = self.write_lock.lock().await;
+        let Some(mut entry) = self.pages.get_mut(&sanitize_page_id(page_id)) else {
+            bail!("page not found: {}", page_id);
+        };
+        let head_html = key == EXTRA_HEAD_HTML_KEY;
+        let value = if head_html {
+            check_extra_head_html(value, &entry.1)?
+        } else {
+            value
+        };
+        set_extra_value(&mut entry.0, key, value);
+        if head_html {
+            entry.0.updated_at = next_updated_at(entry.0.updated_at, now_unix_seconds()?);
+        }
+        Ok(())
+    }
+
+    async fn set_page_tags(&self, page_id: &str, tags: &[String]) -> Result<PageMeta> {
+        let tags = normalize_page_tags(tags)?;
+        let _guard = self.write_lock.lock().await;
+        let Some(mut entry) = self.pages.get_mut(&sanitize_page_id(page_id)) else {
+            bail!("page not found: {}", page_id);
+        };
+        entry.0.tags = Some(tags).filter(|tags| !tags.is_empty());
+        Ok(entry.0.clone())
+    }
+
+    async fn set_page_sort_order(
+        &self,
+        page_id: &str,
+        sort_order: Option<i32>,
+    ) -> Result<PageMeta> {
+        let _guard = self.write_lock.lock().await;
+        let Some(mut entry) = self.pages.get_mut(&sanitize_page_id(page_id)) else {
+            bail!("page not found: {}", page_id);
+        };
+        entry.0.sort_order = sort_order;
+        Ok(entry.0.clone())
+    }
+
+    async fn mark_page_published(&self, page_id: &str) -> Result<PageMeta> {
+        let _guard = self.write_lock.lock().await;
+        let Some(mut entry) = self.pages.get_mut(&sanitize_page_id(page_id)) else {
+            bail!("page not found: {}", page_id);
+        };
+        if entry.0.published_at.is_none() {
+            entry.0.published_at = Some(now_unix_seconds()?);
+        }
+        Ok(entry.0.clone())
+    }
+
+    async fn find_idempotency_key(&self, key: &str, since: i64) -> Result<Option<String>> {
+        Ok(self.idempotency.lock().await.find(key, since))
+    }
+
+    async fn record_idempotency_key(&self, key: &str, page_uid: &str, since: i64) -> Result<()> {
+        self.idempotency.lock().await.insert(key, page_uid, since)
+    }
+
+    async fn probe_health(&self) -> Result<StoreHealth> {
+        Ok(StoreHealth {
+            backend: "memory".to_string(),
+            index_ok: true,
+            readable: Some(true),
+            writable: Some(true),
+            ..Default::default()
+        })
+    }
+
+    async fn delete_page(&self, page_id: &str) -> Result<()> {
+        let _guard = self.write_lock.lock().await;
+        let safe_id = sanitize_page_id(page_id);
+        let Some((_, (meta, _))) = self.pages.remove(&safe_id) else {
+            bail!("page not found: {}", page_id);
+        };
+        let deleted_at = now_unix_seconds()?;
+        let trash_id = unique_trash_id(&safe_id, deleted_at, |id| self.trash.contains_key(id));
+        let page = DeletedPage::new(trash_id.clone(), &safe_id, Some(&meta), deleted_at);
+        self.trash.insert(trash_id, page);
+        self.markdown.remove(&safe_id);
+        self.revisions.remove(&safe_id);
+        self.visitors
+            .retain(|(visitor_page_id, _), _| *visitor_page_id != safe_id);
+        Ok(())
+    }
+
+    async fn list_deleted_pages(&self) -> Result<Vec<DeletedPage>> {
+        let mut pages: Vec<DeletedPage> = self
+            .trash
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect();
+        sort_deleted_pages(&mut pages);
+        Ok(pages)
+    }
+
+    async fn purge_deleted_page(&self, trash_id: &str) -> Result<DeletedPage> {
+        let _guard = self.write_lock.lock().await;
+        match self.trash.remove(trash_id) {
+            Some((_, page)) => Ok(page),
+            None => bail!("trash entry not found: {}", trash_id),
+        }
+    }
+
+    async fn page_exists(&self, page_id: &str) -> Result<bool> {
+        Ok(self.pages.contains_key(&sanitize_page_id(page_id)))
+    }
+
+    async fn list_pages(&self) -> Result<Vec<String>> {
+        Ok(self.index().pages.into_keys().collect())
+    }
+
+    async fn list_page_entries(&self) -> Result<Vec<PageIndexEntry>> {
+        Ok(self.index().pages.into_values().collect())
+    }
+
+    async fn list_pages_by_tag(&self, tag: &str) -> Result<Vec<PageIndexEntry>> {
+        Ok(self.index().entries_by_tag(tag))
+    }
+
+    async fn list_tags(&self) -> Result<Vec<(String, usize)>> {
+        Ok(self.index().tag_counts())
+    }
+
+    async fn rebuild_index(&self) -> Result<StoreIndex> {
+        Ok(self.index())
+    }
+
+    async fn create_revision(&self, page_id: &str) -> Result<RevisionInfo> {
+        let _guard = self.write_lock.lock().await;
+        let safe_id = sanitize_page_id(page_id);
+        let Some(html) = self.pages.get(&safe_id).map(|entry| entry.1.clone()) else {
+            bail!("page not found: {}", page_id);
+        };
+        let markdown = self
+            .markdown
+            .get(&safe_id)
+            .map(|entry| entry.value().clone());
+        let created_at = now_unix_seconds()?;
+        let mut revisions = self.revisions.entry(safe_id).or_default();
+        let revision = PageRevision {
+            revision_id: revisions.last().map_or(1, |last| last.revision_id + 1),
+            created_at,
+            html,
+            markdown,
+        };
+        let info = RevisionInfo::from(&revision);
+        revisions.push(revision);
+        Ok(info)
+    }
+
+    async fn list_revisions(&self, page_id: &str) -> Result<Vec<RevisionInfo>> {
+        let safe_id = sanitize_page_id(page_id);
+        if !self.pages.contains_key(&safe_id) {
+            bail!("page not found: {}", page_id);
+        }
+        Ok(self
+            .revisions
+            .get(&safe_id)
+            .map(|revisions| revisions.iter().map(RevisionInfo::from).collect())
+            .unwrap_or_default())
+    }
+
+    async fn load_revision(&self, page_id: &str, revision_id: u64) -> Result<PageRevision> {
+        let revision = self
+            .revisions
+            .get(&sanitize_page_id(page_id))
+            .and_then(|revisions| {
+                revisions
+                    .iter()
+                    .find(|revision| revision.revision_id == revision_id)
+                    .cloned()
+            });
+        match revision {
+            Some(revision) => Ok(revision),
+            None => bail!("revision not found: {}", revision_id),
+        }
+    }
+
+    async fn prune_revisions(&self, page_id: &str, keep: usize) -> Result<usize> {
+        check_revision_keep(keep)?;
+        let _guard = self.write_lock.lock().await;
+        let safe_id = sanitize_page_id(page_id);
+        if !self.pages.contains_key(&safe_id) {
+            bail!("page not found: {}", page_id);
+        }
+        let Some(mut revisions) = self.revisions.get_mut(&safe_id) else {
+            return Ok(0);
+        };
+        let excess = revisions.len().saturating_sub(keep);
+        revisions.drain(..excess);
+        Ok(excess)
+    }
+}
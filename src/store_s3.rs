@@ -0,0 +1,985 @@
+use std::collections::HashSet;
+
+use anyhow::{Context, Result, anyhow, bail};
+use async_trait::async_trait;
+use aws_sdk_s3::{Client, config::Region, error::DisplayErrorContext, primitives::ByteStream};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::store::{
+    DELETED_RECORD_FILE, DeletedPage, IdempotencyRecords, PageIndexEntry, PageMeta, PageRevision,
+    PageStoreTrait, REVISIONS_DIR, RevisionInfo, StoreHealth, StoreIndex, VISITORS_DIR,
+    check_expected_updated_at, check_extra_head_html, check_revision_keep,
+    generate_unique_page_uid, hash_visitor_ip, merge_page_meta, next_updated_at,
+    normalize_page_tags, now_unix_seconds, page_index_tags, sanitize_page_id, set_extra_value,
+    sort_deleted_pages, to_url_slug, unique_trash_id, validate_extra_key, validate_html,
+    visitor_day,
+};
+use crate::web::EXTRA_HEAD_HTML_KEY;
+
+const INDEX_KEY: &str = "index.json";
+const IDEMPOTENCY_KEY: &str = "idempotency.json";
+const PAGES_PREFIX: &str = "pages/";
+const TRASH_PREFIX: &str = "trash/";
+
+/// CopyObject 的 `x-amz-copy-source` 需要 URL 编码，`/` 作为路径分隔符保留
+const COPY_SOURCE_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~')
+    .remove(b'/');
+
+/// S3 兼容对象存储后端，对象布局与文件系统一致：
+/// - `index.json`：全局索引
+/// - `idempotency.json`：`push_page` 幂等键记录
+/// - `pages/{page_id}/meta.json`、`pages/{page_id}/index.html`、`pages/{page_id}/content.md`
+/// - `pages/{page_id}/revisions/{revision_id}.json`：历史版本
+/// - `trash/{trash_id}/`：删除的页面对象与 `deleted.json`
+pub struct S3PageStore {
+    client: Client,
+    bucket: String,
+    /// index.json 的读改写需要串行，避免并发写入互相覆盖
+    index_lock: Mutex<()>,
+}
+
+impl S3PageStore {
+    pub fn new(client: Client, bucket: impl Into<String>) -> Self {
+        Self {
+            client,
+            bucket: bucket.into(),
+            index_lock: Mutex::new(()),
+        }
+    }
+
+    /// 从环境变量初始化：
+    /// - `S3_BUCKET`：必填
+    /// - `S3_REGION`：默认 `us-east-1`
+    /// - `AWS_ACCESS_KEY_ID` / `AWS_SECRET_ACCESS_KEY`：由 AWS 默认凭证链读取
+    /// - `S3_ENDPOINT`：可选，MinIO / R2 等兼容服务的地址，设置后使用 path-style 访问
+    pub async fn from_env() -> Result<Self> {
+        let bucket = std::env::var("S3_BUCKET")
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        if bucket.is_empty() {
+            bail!("S3_BUCKET is not set");
+        }
+        let region = std::env::var("S3_REGION")
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| "us-east-1".to_string());
+        let endpoint = std::env::var("S3_ENDPOINT")
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty());
+
+        let mut loader =
+            aws_config::defaults(aws_config::BehaviorVersion::latest()).region(Region::new(region));
+        if let Some(endpoint) = &endpoint {
+            loader = loader.endpoint_url(endpoint);
+        }
+        let shared_config = loader.load().await;
+        let config = aws_sdk_s3::config::Builder::from(&shared_config)
+            .force_path_style(endpoint.is_some())
+            .build();
+        Ok(Self::new(Client::from_conf(config), bucket))
+    }
+
+    pub fn bucket(&self) -> &str {
+        &self.bucket
+    }
+
+    async fn write_page(
+        &self,
+        page_id: &str,
+        meta: &PageMeta,
+        html: &str,
+        markdown: Option<&str>,
+    ) -> Result<()> {
+        self.write_page_checked(page_id, meta, html, markdown, None)
+            .await
+    }
+
+    /// 在索引锁内读取现有 meta 后校验 `expected_updated_at`
+    async fn write_page_checked(
+        &self,
+        page_id: &str,
+        meta: &PageMeta,
+        html: &str,
+        markdown: Option<&str>,
+        expected_updated_at: Option<i64>,
+    ) -> Result<()> {
+        validate_html(html).context("validate html")?;
+        let safe_id = sanitize_page_id(page_id);
+        let _guard = self.index_lock.lock().await;
+        let mut index = self.load_index().await?;
+        let existing_meta = self.read_meta(&safe_id).await?;
+        check_expected_updated_at(page_id, existing_meta.as_ref(), expected_updated_at)?;
+        let meta_to_write = merge_page_meta(meta, existing_meta.as_ref(), &index, &safe_id)?;
+
+        self.put_object(
+            &page_key(&safe_id, "meta.json"),
+            serde_json::to_vec_pretty(&meta_to_write).context("serialize meta.json")?,
+            "application/json",
+        )
+        .await?;
+        if let Some(markdown) = markdown {
+            self.put_object(
+                &page_key(&safe_id, "content.md"),
+                markdown.as_bytes().to_vec(),
+                "text/markdown; charset=utf-8",
+            )
+            .await?;
+        }
+        self.put_object(
+            &page_key(&safe_id, "index.html"),
+            html.as_bytes().to_vec(),
+            "text/html; charset=utf-8",
+        )
+        .await?;
+
+        upsert_index_entry(&mut index, &safe_id, page_id, &meta_to_write);
+        self.save_index(&index).await
+    }
+
+    async fn create_page_with_new_uid(
+        &self,
+        meta: &PageMeta,
+        html: &str,
+        markdown: Option<&str>,
+    ) -> Result<PageMeta> {
+        let index = self.load_index().await?;
+        let uid = generate_unique_page_uid(&index)?;
+        let mut meta_with_uid = meta.clone();
+        if meta_with_uid.seo.title.is_empty() {
+            meta_with_uid.seo.title = meta_with_uid.seo.seo_title.clone();
+        }
+        meta_with_uid.seo.seo_title = to_url_slug(&meta_with_uid.seo.seo_title);
+        meta_with_uid.page_uid = uid.clone();
+        if self.page_exists(&uid).await? {
+            bail!("page already exists: {}", uid);
+        }
+        self.write_page(&uid, &meta_with_uid, html, markdown)
+            .await?;
+        self.get_page_meta(&uid).await
+    }
+
+    async fn read_meta(&self, safe_id: &str) -> Result<Option<PageMeta>> {
+        let key = page_key(safe_id, "meta.json");
+        match self.get_object_string(&key).await? {
+            Some(raw) => {
+                let meta = serde_json::from_str(&raw)
+                    .with_context(|| format!("parse meta.json {}", key))?;
+                Ok(Some(meta))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn write_meta(&self, safe_id: &str, meta: &PageMeta) -> Result<()> {
+        self.put_object(
+            &page_key(safe_id, "meta.json"),
+            serde_json::to_vec_pretty(meta).context("serialize meta.json")?,
+            "application/json",
+        )
+        .await
+    }
+
+    async fn load_index(&self) -> Result<StoreIndex> {
+        match self.get_object_string(INDEX_KEY).await? {
+            Some(raw) => match serde_json::from_str::<StoreIndex>(&raw) {
+                Ok(index) => Ok(index),
+                Err(_) => self.build_and_save_index().await,
+            },
+            None => self.build_and_save_index().await,
+        }
+    }
+
+    async fn save_index(&self, index: &StoreIndex) -> Result<()> {
+        let bytes = serde_json::to_vec_pretty(index).context("serialize index.json")?;
+        self.put_object(INDEX_KEY, bytes, "application/json").await
+    }
+
+    /// 对象不存在时视为没有记录
+    async fn read_idempotency_records(&self) -> Result<IdempotencyRecords> {
+        match self.get_object_string(IDEMPOTENCY_KEY).await? {
+            Some(raw) => serde_json::from_str(&raw).context("parse idempotency.json"),
+            None => Ok(IdempotencyRecords::default()),
+        }
+    }
+
+    async fn build_and_save_index(&self) -> Result<StoreIndex> {
+        let mut index = StoreIndex::default();
+        for page_id in self.list_page_ids().await? {
+            let meta = match self.read_meta(&page_id).await {
+                Ok(Some(meta)) => meta,
+                Ok(None) | Err(_) => continue,
+            };
+            index.set_page_tags(&page_id, &page_index_tags(&meta));
+            index.pages.insert(
+                page_id.clone(),
+                PageIndexEntry {
+                    page_id,
+                    seo: meta.seo,
+                    page_uid: meta.page_uid,
+                    original_id: None,
+                    view_count: meta.view_count,
+                    sort_order: meta.sort_order,
+                    updated_at: meta.updated_at,
+                },
+            );
+        }
+        self.save_index(&index).await?;
+        Ok(index)
+    }
+
+    async fn list_page_ids(&self) -> Result<Vec<String>> {
+        let mut page_ids = Vec::new();
+        let mut continuation_token: Option<String> = None;
+        loop {
+            let output = self
+                .client
+                .list_objects_v2()
+                .bucket(&self.bucket)
+                .prefix(PAGES_PREFIX)
+                .delimiter("/")
+                .set_continuation_token(continuation_token.take())
+                .send()
+                .await
+                .map_err(|err| anyhow!("list s3 pages: {}", DisplayErrorContext(&err)))?;
+            for prefix in output.common_prefixes() {
+                let page_id = prefix
+                    .prefix()
+                    .and_then(|value| value.strip_prefix(PAGES_PREFIX))
+                    .map(|value| value.trim_end_matches('/'))
+                    .filter(|value| !value.is_empty());
+                if let Some(page_id) = page_id {
+                    page_ids.push(page_id.to_string());
+                }
+            }
+            match output.next_continuation_token() {
+                Some(token) if output.is_truncated() == Some(true) => {
+                    continuation_token = Some(token.to_string());
+                }
+                _ => break,
+            }
+        }
+        Ok(page_ids)
+    }
+
+    async fn list_object_keys(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut keys = Vec::new();
+        let mut continuation_token: Option<String> = None;
+        loop {
+            let output = self
+                .client
+                .list_objects_v2()
+                .bucket(&self.bucket)
+                .prefix(prefix)
+                .set_continuation_token(continuation_token.take())
+                .send()
+                .await
+                .map_err(|err| {
+                    anyhow!("list s3 objects {}: {}", prefix, DisplayErrorContext(&err))
+                })?;
+            keys.extend(
+                output
+                    .contents()
+                    .iter()
+                    .filter_map(|object| object.key().map(str::to_string)),
+            );
+            match output.next_continuation_token() {
+                Some(token) if output.is_truncated() == Some(true) => {
+                    continuation_token = Some(token.to_string());
+                }
+                _ => break,
+            }
+        }
+        Ok(keys)
+    }
+
+    async fn get_object_string(&self, key: &str) -> Result<Option<String>> {
+        let output = match self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+        {
+            Ok(output) => output,
+            Err(err) => {
+                if err
+                    .as_service_error()
+                    .is_some_and(|service_err| service_err.is_no_such_key())
+                {
+                    return Ok(None);
+                }
+                return Err(anyhow!(
+                    "get s3 object {}: {}",
+                    key,
+                    DisplayErrorContext(&err)
+                ));
+            }
+        };
+        let bytes = output
+            .body
+            .collect()
+            .await
+            .with_context(|| format!("read s3 object {}", key))?
+            .into_bytes();
+        let text = String::from_utf8(bytes.to_vec())
+            .with_context(|| format!("decode s3 object {} as utf-8", key))?;
+        Ok(Some(text))
+    }
+
+    async fn object_exists(&self, key: &str) -> Result<bool> {
+        match self
+            .client
+            .head_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(err) => {
+                if err
+                    .as_service_error()
+                    .is_some_and(|service_err| service_err.is_not_found())
+                {
+                    return Ok(false);
+                }
+                Err(anyhow!(
+                    "head s3 object {}: {}",
+                    key,
+                    DisplayErrorContext(&err)
+                ))
+            }
+        }
+    }
+
+    async fn put_object(&self, key: &str, body: Vec<u8>, content_type: &str) -> Result<()> {
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .content_type(content_type)
+            .body(ByteStream::from(body))
+            .send()
+            .await
+            .map_err(|err| anyhow!("put s3 object {}: {}", key, DisplayErrorContext(&err)))?;
+        Ok(())
+    }
+
+    /// 服务端复制（CopyObject），不经过本进程下载；Content-Type 等元数据随对象一起复制
+    async fn copy_object(&self, source_key: &str, target_key: &str) -> Result<()> {
+        let copy_source = format!(
+            "{}/{}",
+            self.bucket,
+            utf8_percent_encode(source_key, COPY_SOURCE_ENCODE_SET)
+        );
+        self.client
+            .copy_object()
+            .bucket(&self.bucket)
+            .key(target_key)
+            .copy_source(copy_source)
+            .send()
+            .await
+            .map_err(|err| {
+                anyhow!(
+                    "copy s3 object {} to {}: {}",
+                    source_key,
+                    target_key,
+                    DisplayErrorContext(&err)
+                )
+            })?;
+        Ok(())
+    }
+
+    /// 按版本号升序返回，忽略非 `{数字}.json` 的对象
+    async fn revision_ids(&self, safe_id: &str) -> Result<Vec<u64>> {
+        let prefix = page_key(safe_id, &format!("{}/", REVISIONS_DIR));
+        let mut ids: Vec<u64> = self
+            .list_object_keys(&prefix)
+            .await?
+            .iter()
+            .filter_map(|key| {
+                key.strip_prefix(&prefix)?
+                    .strip_suffix(".json")?
+                    .parse()
+                    .ok()
+            })
+            .collect();
+        ids.sort_unstable();
+        Ok(ids)
+    }
+
+    async fn delete_object(&self, key: &str) -> Result<()> {
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|err| anyhow!("delete s3 object {}: {}", key, DisplayErrorContext(&err)))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PageStoreTrait for S3PageStore {
+    async fn create_page_auto_uid(&self, meta: &PageMeta, html: &str) -> Result<PageMeta> {
+        self.create_page_with_new_uid(meta, html, None).await
+    }
+
+    async fn create_page_auto_uid_with_markdown(
+        &self,
+        meta: &PageMeta,
+        html: &str,
+        markdown: Option<&str>,
+    ) -> Result<PageMeta> {
+        self.create_page_with_new_uid(meta, html, markdown).await
+    }
+
+    async fn resolve_page_id_by_uid(&self, page_uid: &str) -> Result<Option<String>> {
+        let index = self.load_index().await?;
+        if index.pages.contains_key(page_uid) {
+            return Ok(Some(page_uid.to_string()));
+        }
+        Ok(index
+            .pages
+            .iter()
+            .find(|(_, entry)| entry.page_uid == page_uid)
+            .map(|(page_id, _)| page_id.clone()))
+    }
+
+    async fn create_page(&self, page_id: &str, meta: &PageMeta, html: &str) -> Result<()> {
+        if self.page_exists(page_id).await? {
+            bail!("page already exists: {}", page_id);
+        }
+        self.write_page(page_id, meta, html, None).await
+    }
+
+    async fn save_page(&self, page_id: &str, meta: &PageMeta, html: &str) -> Result<()> {
+        self.write_page(page_id, meta, html, None).await
+    }
+
+    async fn save_page_with_markdown(
+        &self,
+        page_id: &str,
+        meta: &PageMeta,
+        html: &str,
+        markdown: Option<&str>,
+    ) -> Result<()> {
+        self.write_page(page_id, meta, html, markdown).await
+    }
+
+    async fn load_page(&self, page_id: &str) -> Result<(PageMeta, String)> {
+        let safe_id = sanitize_page_id(page_id);
+        let Some(meta) = self.read_meta(&safe_id).await? else {
+            bail!("page not found: {}", page_id);
+        };
+        let html_key = page_key(&safe_id, "index.html");
+        let Some(html) = self.get_object_string(&html_key).await? else {
+            bail!("read index.html {} failed: object not found", html_key);
+        };
+        Ok((meta, html))
+    }
+
+    async fn load_page_markdown(&self, page_id: &str) -> Result<Option<String>> {
+        let safe_id = sanitize_page_id(page_id);
+        self.get_object_string(&page_key(&safe_id, "content.md"))
+            .await
+    }
+
+    async fn get_page_meta(&self, page_id: &str) -> Result<PageMeta> {
+        let safe_id = sanitize_page_id(page_id);
+        match self.read_meta(&safe_id).await? {
+            Some(meta) => Ok(meta),
+            None => bail!("page not found: {}", page_id),
+        }
+    }
+
+    async fn get_page_html(&self, page_id: &str) -> Result<String> {
+        let (_, html) = self.load_page(page_id).await?;
+        Ok(html)
+    }
+
+    async fn update_page(&self, page_id: &str, meta: &PageMeta, html: &str) -> Result<()> {
+        self.update_page_with_markdown(page_id, meta, html, None)
+            .await
+    }
+
+    async fn update_page_with_markdown(
+        &self,
+        page_id: &str,
+        meta: &PageMeta,
+        html: &str,
+        markdown: Option<&str>,
+    ) -> Result<()> {
+        if !self.page_exists(page_id).await? {
+            bail!("page not found: {}", page_id);
+        }
+        let mut meta_to_update = meta.clone();
+        if meta_to_update.seo.title.is_empty() {
+            meta_to_update.seo.title = meta_to_update.seo.seo_title.clone();
+        }
+        meta_to_update.seo.seo_title = to_url_slug(&meta_to_update.seo.seo_title);
+        self.write_page(page_id, &meta_to_update, html, markdown)
+            .await
+    }
+
+    async fn update_page_if_unmodified(
+        &self,
+        page_id: &str,
+        meta: &PageMeta,
+        html: &str,
+        markdown: Option<&str>,
+        expected_updated_at: i64,
+    ) -> Result<()> {
+        if !self.page_exists(page_id).await? {
+            bail!("page not found: {}", page_id);
+        }
+        let mut meta_to_update = meta.clone();
+        if meta_to_update.seo.title.is_empty() {
+            meta_to_update.seo.title = meta_to_update.seo.seo_title.clone();
+        }
+        meta_to_update.seo.seo_title = to_url_slug(&meta_to_update.seo.seo_title);
+        let expected = Some(expected_updated_at);
+        self.write_page_checked(page_id, &meta_to_update, html, markdown, expected)
+            .await
+    }
+
+    async fn update_page_meta(&self, page_id: &str, meta: &PageMeta) -> Result<()> {
+        if !self.page_exists(page_id).await? {
+            bail!("page not found: {}", page_id);
+        }
+        let safe_id = sanitize_page_id(page_id);
+        let _guard = self.index_lock.lock().await;
+        let mut index = self.load_index().await?;
+        let existing_meta = self.read_meta(&safe_id).await?;
+        let meta_to_write = merge_page_meta(meta, existing_meta.as_ref(), &index, &safe_id)?;
+        self.write_meta(&safe_id, &meta_to_write).await?;
+        upsert_index_entry(&mut index, &safe_id, page_id, &meta_to_write);
+        self.save_index(&index).await
+    }
+
+    async fn update_page_html(
+        &self,
+        page_id: &str,
+        html: &str,
+        bump_updated_at: bool,
+    ) -> Result<()> {
+        if bump_updated_at {
+            let (meta, _) = self.load_page(page_id).await?;
+            return self.write_page(page_id, &meta, html, None).await;
+        }
+        // meta.json 与索引都不变，只替换 index.html
+        validate_html(html).context("validate html")?;
+        if !self.page_exists(page_id).await? {
+            bail!("page not found: {}", page_id);
+        }
+        let safe_id = sanitize_page_id(page_id);
+        let _guard = self.index_lock.lock().await;
+        self.put_object(
+            &page_key(&safe_id, "index.html"),
+            html.as_bytes().to_vec(),
+            "text/html; charset=utf-8",
+        )
+        .await
+    }
+
+    async fn update_page_markdown(&self, page_id: &str, markdown: &str) -> Result<()> {
+        if !self.page_exists(page_id).await? {
+            bail!("page not found: {}", page_id);
+        }
+        let safe_id = sanitize_page_id(page_id);
+        self.put_object(
+            &page_key(&safe_id, "content.md"),
+            markdown.as_bytes().to_vec(),
+            "text/markdown; charset=utf-8",
+        )
+        .await?;
+        let meta = self.get_page_meta(page_id).await?;
+        self.update_page_meta(page_id, &meta).await
+    }
+
+    async fn increment_view_count(
+        &self,
+        page_id: &str,
+        client_ip: Option<&str>,
+    ) -> Result<PageMeta> {
+        let safe_id = sanitize_page_id(page_id);
+        let _guard = self.index_lock.lock().await;
+        let Some(mut meta) = self.read_meta(&safe_id).await? else {
+            bail!("page not found: {}", page_id);
+        };
+        if let Some(client_ip) = client_ip {
+            let day = visitor_day();
+            let visitor_hash = hash_visitor_ip(client_ip, &day);
+            let key = page_key(&safe_id, &format!("{}/{}.txt", VISITORS_DIR, day));
+            let mut visitors = self.get_object_string(&key).await?.unwrap_or_default();
+            if visitors.lines().any(|line| line.trim() == visitor_hash) {
+                return Ok(meta);
+            }
+            visitors.push_str(&visitor_hash);
+            visitors.push('\n');
+            self.put_object(&key, visitors.into_bytes(), "text/plain; charset=utf-8")
+                .await?;
+            meta.unique_view_count = meta.unique_view_count.saturating_add(1);
+        }
+        meta.view_count = meta.view_count.saturating_add(1);
+        self.write_meta(&safe_id, &meta).await?;
+        let mut index = self.load_index().await?;
+        if let Some(entry) = index.pages.get_mut(&safe_id) {
+            entry.view_count = meta.view_count;
+            self.save_index(&index).await?;
+        }
+        Ok(meta)
+    }
+
+    async fn set_page_extra(
+        &self,
+        page_id: &str,
+        key: &str,
+        value: serde_json::Value,
+    ) -> Result<()> {
+        let key = validate_extra_key(key)?;
+        let safe_id = sanitize_page_id(page_id);
+        let _guard = self.index_lock.lock().await;
+        let Some(mut meta) = self.read_meta(&safe_id).await? else {
+            bail!("page not found: {}", page_id);
+        };
+        let head_html = key == EXTRA_HEAD_HTML_KEY;
+        let value = if head_html {
+            check_extra_head_html(value, &self.get_page_html(&safe_id).await?)?
+        } else {
+            value
+        };
+        set_extra_value(&mut meta, key, value);
+        if head_html {
+            meta.updated_at = next_updated_at(meta.updated_at, now_unix_seconds()?);
+        }
+        self.write_meta(&safe_id, &meta).await?;
+        if head_html {
+            let mut index = self.load_index().await?;
+            if let Some(entry) = index.pages.get_mut(&safe_id) {
+                entry.updated_at = meta.updated_at;
+                self.save_index(&index).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn set_page_tags(&self, page_id: &str, tags: &[String]) -> Result<PageMeta> {
+        let tags = normalize_page_tags(tags)?;
+        let safe_id = sanitize_page_id(page_id);
+        let _guard = self.index_lock.lock().await;
+        let Some(mut meta) = self.read_meta(&safe_id).await? else {
+            bail!("page not found: {}", page_id);
+        };
+        meta.tags = Some(tags).filter(|tags| !tags.is_empty());
+        self.write_meta(&safe_id, &meta).await?;
+        let mut index = self.load_index().await?;
+        index.set_page_tags(&safe_id, &page_index_tags(&meta));
+        self.save_index(&index).await?;
+        Ok(meta)
+    }
+
+    async fn set_page_sort_order(
+        &self,
+        page_id: &str,
+        sort_order: Option<i32>,
+    ) -> Result<PageMeta> {
+        let safe_id = sanitize_page_id(page_id);
+        let _guard = self.index_lock.lock().await;
+        let Some(mut meta) = self.read_meta(&safe_id).await? else {
+            bail!("page not found: {}", page_id);
+        };
+        meta.sort_order = sort_order;
+        self.write_meta(&safe_id, &meta).await?;
+        let mut index = self.load_index().await?;
+        if let Some(entry) = index.pages.get_mut(&safe_id) {
+            entry.sort_order = sort_order;
+            self.save_index(&index).await?;
+        }
+        Ok(meta)
+    }
+
+    async fn mark_page_published(&self, page_id: &str) -> Result<PageMeta> {
+        let safe_id = sanitize_page_id(page_id);
+        let _guard = self.index_lock.lock().await;
+        let Some(mut meta) = self.read_meta(&safe_id).await? else {
+            bail!("page not found: {}", page_id);
+        };
+        if meta.published_at.is_none() {
+            meta.published_at = Some(now_unix_seconds()?);
+            self.write_meta(&safe_id, &meta).await?;
+        }
+        Ok(meta)
+    }
+
+    async fn find_idempotency_key(&self, key: &str, since: i64) -> Result<Option<String>> {
+        Ok(self.read_idempotency_records().await?.find(key, since))
+    }
+
+    async fn record_idempotency_key(&self, key: &str, page_uid: &str, since: i64) -> Result<()> {
+        let _guard = self.index_lock.lock().await;
+        let mut records = self.read_idempotency_records().await?;
+        records.insert(key, page_uid, since)?;
+        let bytes = serde_json::to_vec_pretty(&records).context("serialize idempotency.json")?;
+        self.put_object(IDEMPOTENCY_KEY, bytes, "application/json")
+            .await
+    }
+
+    /// 只读取 index.json，不向桶中写入探测对象
+    async fn probe_health(&self) -> Result<StoreHealth> {
+        let mut health = StoreHealth {
+            backend: "s3".to_string(),
+            data_dir: Some(format!("s3://{}", self.bucket)),
+            ..Default::default()
+        };
+        match self.get_object_string(INDEX_KEY).await {
+            Ok(Some(raw)) => match serde_json::from_str::<StoreIndex>(&raw) {
+                Ok(_) => health.index_ok = true,
+                Err(err) => health
+                    .errors
+                    .push(format!("parse index.json failed: {}", err)),
+            },
+            Ok(None) => health.index_ok = true,
+            Err(err) => health
+                .errors
+                .push(format!("read index.json failed: {:#}", err)),
+        }
+        Ok(health)
+    }
+
+    async fn delete_page(&self, page_id: &str) -> Result<()> {
+        if !self.page_exists(page_id).await? {
+            bail!("page not found: {}", page_id);
+        }
+        let safe_id = sanitize_page_id(page_id);
+        let existing_meta = self.read_meta(&safe_id).await?;
+        let deleted_at = now_unix_seconds()?;
+        let trash_ids: HashSet<String> = self
+            .list_deleted_pages()
+            .await?
+            .into_iter()
+            .map(|page| page.trash_id)
+            .collect();
+        let trash_id = unique_trash_id(&safe_id, deleted_at, |id| trash_ids.contains(id));
+
+        // 先在服务端复制到回收站并写入删除记录，再删除原对象；中断时页面仍可访问。
+        // 复制不持有 index_lock，只有删除原对象与更新索引需要串行
+        let prefix = page_key(&safe_id, "");
+        let keys = self.list_object_keys(&prefix).await?;
+        for key in &keys {
+            self.copy_object(key, &trash_key(&trash_id, &key[prefix.len()..]))
+                .await?;
+        }
+        let page = DeletedPage::new(trash_id, &safe_id, existing_meta.as_ref(), deleted_at);
+        self.put_object(
+            &trash_key(&page.trash_id, DELETED_RECORD_FILE),
+            serde_json::to_vec_pretty(&page).context("serialize deleted.json")?,
+            "application/json",
+        )
+        .await?;
+
+        let _guard = self.index_lock.lock().await;
+        // 复制期间页面已被并发删除时撤回本次回收站条目
+        if !self.object_exists(&page_key(&safe_id, "meta.json")).await? {
+            for key in self
+                .list_object_keys(&trash_key(&page.trash_id, ""))
+                .await?
+            {
+                self.delete_object(&key).await?;
+            }
+            bail!("page not found: {}", page_id);
+        }
+        // 重新列出对象，复制之后写入的新对象（如历史版本）一并删除，不留下孤儿对象
+        for key in self.list_object_keys(&prefix).await? {
+            self.delete_object(&key).await?;
+        }
+        let mut index = self.load_index().await?;
+        index.pages.remove(&safe_id);
+        index.remove_page_tags(&safe_id);
+        self.save_index(&index).await
+    }
+
+    async fn list_deleted_pages(&self) -> Result<Vec<DeletedPage>> {
+        let suffix = format!("/{}", DELETED_RECORD_FILE);
+        let mut pages = Vec::new();
+        for key in self.list_object_keys(TRASH_PREFIX).await? {
+            if !key.ends_with(&suffix) {
+                continue;
+            }
+            let Some(raw) = self.get_object_string(&key).await? else {
+                continue;
+            };
+            match serde_json::from_str::<DeletedPage>(&raw) {
+                Ok(page) => pages.push(page),
+                Err(err) => warn!("parse trash record {} failed: {err}", key),
+            }
+        }
+        sort_deleted_pages(&mut pages);
+        Ok(pages)
+    }
+
+    async fn purge_deleted_page(&self, trash_id: &str) -> Result<DeletedPage> {
+        if trash_id.is_empty() || sanitize_page_id(trash_id) != trash_id {
+            bail!("trash entry not found: {}", trash_id);
+        }
+        let _guard = self.index_lock.lock().await;
+        let record_key = trash_key(trash_id, DELETED_RECORD_FILE);
+        let Some(raw) = self.get_object_string(&record_key).await? else {
+            bail!("trash entry not found: {}", trash_id);
+        };
+        let page: DeletedPage = serde_json::from_str(&raw).context("parse deleted.json")?;
+        for key in self.list_object_keys(&trash_key(trash_id, "")).await? {
+            self.delete_object(&key).await?;
+        }
+        Ok(page)
+    }
+
+    async fn page_exists(&self, page_id: &str) -> Result<bool> {
+        let safe_id = sanitize_page_id(page_id);
+        let index = self.load_index().await?;
+        if index.pages.contains_key(&safe_id) {
+            return Ok(true);
+        }
+        self.object_exists(&page_key(&safe_id, "meta.json")).await
+    }
+
+    async fn list_pages(&self) -> Result<Vec<String>> {
+        let index = self.load_index().await?;
+        Ok(index.pages.into_keys().collect())
+    }
+
+    async fn list_page_entries(&self) -> Result<Vec<PageIndexEntry>> {
+        let index = self.load_index().await?;
+        Ok(index.pages.into_values().collect())
+    }
+
+    async fn list_pages_by_tag(&self, tag: &str) -> Result<Vec<PageIndexEntry>> {
+        let index = self.load_index().await?;
+        Ok(index.entries_by_tag(tag))
+    }
+
+    async fn list_tags(&self) -> Result<Vec<(String, usize)>> {
+        let index = self.load_index().await?;
+        Ok(index.tag_counts())
+    }
+
+    async fn rebuild_index(&self) -> Result<StoreIndex> {
+        let _guard = self.index_lock.lock().await;
+        self.build_and_save_index().await
+    }
+
+    async fn create_revision(&self, page_id: &str) -> Result<RevisionInfo> {
+        let safe_id = sanitize_page_id(page_id);
+        // 与页面写入共用锁，保证快照完整且版本号不会重复分配
+        let _guard = self.index_lock.lock().await;
+        let html_key = page_key(&safe_id, "index.html");
+        if self.read_meta(&safe_id).await?.is_none() {
+            bail!("page not found: {}", page_id);
+        }
+        let Some(html) = self.get_object_string(&html_key).await? else {
+            bail!("read index.html {} failed: object not found", html_key);
+        };
+        let markdown = self
+            .get_object_string(&page_key(&safe_id, "content.md"))
+            .await?;
+        let revision_id = self
+            .revision_ids(&safe_id)
+            .await?
+            .last()
+            .map_or(1, |id| id + 1);
+        let revision = PageRevision {
+            revision_id,
+            created_at: now_unix_seconds()?,
+            html,
+            markdown,
+        };
+        self.put_object(
+            &revision_key(&safe_id, revision_id),
+            serde_json::to_vec(&revision).context("serialize revision")?,
+            "application/json",
+        )
+        .await?;
+        Ok(RevisionInfo::from(&revision))
+    }
+
+    async fn list_revisions(&self, page_id: &str) -> Result<Vec<RevisionInfo>> {
+        let safe_id = sanitize_page_id(page_id);
+        if self.read_meta(&safe_id).await?.is_none() {
+            bail!("page not found: {}", page_id);
+        }
+        let mut revisions = Vec::new();
+        for revision_id in self.revision_ids(&safe_id).await? {
+            let revision = self.load_revision(page_id, revision_id).await?;
+            revisions.push(RevisionInfo::from(&revision));
+        }
+        Ok(revisions)
+    }
+
+    async fn load_revision(&self, page_id: &str, revision_id: u64) -> Result<PageRevision> {
+        let key = revision_key(&sanitize_page_id(page_id), revision_id);
+        let Some(raw) = self.get_object_string(&key).await? else {
+            bail!("revision not found: {}", revision_id);
+        };
+        serde_json::from_str(&raw).with_context(|| format!("parse revision {}", key))
+    }
+
+    async fn prune_revisions(&self, page_id: &str, keep: usize) -> Result<usize> {
+        check_revision_keep(keep)?;
+        let safe_id = sanitize_page_id(page_id);
+        let _guard = self.index_lock.lock().await;
+        if self.read_meta(&safe_id).await?.is_none() {
+            bail!("page not found: {}", page_id);
+        }
+        let revision_ids = self.revision_ids(&safe_id).await?;
+        let excess = revision_ids.len().saturating_sub(keep);
+        for revision_id in &revision_ids[..excess] {
+            self.delete_object(&revision_key(&safe_id, *revision_id))
+                .await?;
+        }
+        Ok(excess)
+    }
+}
+
+fn page_key(page_id: &str, file_name: &str) -> String {
+    format!("{}{}/{}", PAGES_PREFIX, page_id, file_name)
+}
+
+fn trash_key(trash_id: &str, file_name: &str) -> String {
+    format!("{}{}/{}", TRASH_PREFIX, trash_id, file_name)
+}
+
+fn revision_key(page_id: &str, revision_id: u64) -> String {
+    page_key(page_id, &format!("{}/{}.json", REVISIONS_DIR, revision_id))
+}
+
+fn upsert_index_entry(index: &mut StoreIndex, safe_id: &str, page_id: &str, meta: &PageMeta) {
+    let original_id = index
+        .pages
+        .get(safe_id)
+        .and_then(|entry| entry.original_id.clone())
+        .or_else(|| (safe_id != page_id).then(|| page_id.to_string()));
+    index.set_page_tags(safe_id, &page_index_tags(meta));
+    index.pages.insert(
+        safe_id.to_string(),
+        PageIndexEntry {
+            page_id: safe_id.to_string(),
+            seo: meta.seo.clone(),
+            page_uid: meta.page_uid.clone(),
+            original_id,
+            view_count: meta.view_count,
+            sort_order: meta.sort_order,
+            updated_at: meta.updated_at,
+        },
+    );
+}
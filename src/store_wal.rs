@@ -0,0 +1,141 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::store::{PageMeta, atomic_write, now_unix_seconds};
+
+/// 文件系统存储的预写日志 `data/wal.json`
+/// 写操作执行前先记录完整的目标状态，执行成功后追加 `Commit` 并压缩，
+/// 因此日志中残留的条目都是中途中断、需要重放的操作
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WalLog {
+    #[serde(default)]
+    pub entries: Vec<WalEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalEntry {
+    pub seq: u64,
+    pub timestamp: i64,
+    pub op: WalOp,
+}
+
+/// 日志记录的是写入后的最终内容，重放是幂等的
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum WalOp {
+    CreatePage {
+        page_id: String,
+        meta: Box<PageMeta>,
+        html: String,
+        markdown: Option<String>,
+    },
+    UpdatePage {
+        page_id: String,
+        meta: Box<PageMeta>,
+        html: Option<String>,
+        markdown: Option<String>,
+    },
+    DeletePage {
+        page_id: String,
+    },
+    Commit {
+        seq: u64,
+    },
+}
+
+impl WalOp {
+    pub fn page_id(&self) -> Option<&str> {
+        match self {
+            WalOp::CreatePage { page_id, .. }
+            | WalOp::UpdatePage { page_id, .. }
+            | WalOp::DeletePage { page_id } => Some(page_id),
+            WalOp::Commit { .. } => None,
+        }
+    }
+}
+
+/// 同一进程内的写操作串行执行，避免多个线程交错读写 wal.json
+static WAL_LOCK: Mutex<()> = Mutex::new(());
+
+pub(crate) fn lock_wal() -> MutexGuard<'static, ()> {
+    WAL_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl WalLog {
+    /// 文件不存在时返回空日志
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(raw) => serde_json::from_str(&raw).context("parse wal.json"),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err).with_context(|| format!("read wal.json {:?}", path)),
+        }
+    }
+
+    /// 日志为空时直接删除文件
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if self.entries.is_empty() {
+            return match fs::remove_file(path) {
+                Ok(()) => Ok(()),
+                Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+                Err(err) => Err(err).with_context(|| format!("remove wal.json {:?}", path)),
+            };
+        }
+        let bytes = serde_json::to_vec_pretty(self).context("serialize wal.json")?;
+        atomic_write(path, &bytes).context("write wal.json")
+    }
+
+    pub fn append(&mut self, op: WalOp) -> Result<u64> {
+        let seq = self
+            .entries
+            .iter()
+            .map(|entry| entry.seq)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        self.entries.push(WalEntry {
+            seq,
+            timestamp: now_unix_seconds()?,
+            op,
+        });
+        Ok(seq)
+    }
+
+    /// 尚未提交的操作，按序号排列
+    pub fn pending(&self) -> Vec<&WalEntry> {
+        let committed = self.committed_seqs();
+        let mut pending: Vec<&WalEntry> = self
+            .entries
+            .iter()
+            .filter(|entry| !matches!(entry.op, WalOp::Commit { .. }))
+            .filter(|entry| !committed.contains(&entry.seq))
+            .collect();
+        pending.sort_by_key(|entry| entry.seq);
+        pending
+    }
+
+    /// 移除已提交的操作及其 `Commit` 记录
+    pub fn compact(&mut self) {
+        let committed = self.committed_seqs();
+        self.entries.retain(|entry| {
+            !matches!(entry.op, WalOp::Commit { .. }) && !committed.contains(&entry.seq)
+        });
+    }
+
+    fn committed_seqs(&self) -> BTreeSet<u64> {
+        self.entries
+            .iter()
+            .filter_map(|entry| match entry.op {
+                WalOp::Commit { seq } => Some(seq),
+                _ => None,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,154 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+/// 博文风格指南：`public/prompt/blog-styles/{name}.xml`
+pub const BLOG_STYLES_DIR: &str = "public/prompt/blog-styles";
+
+/// HTML 样板：`public/prompt/html-styles/{name}/`，包含 `template.xml`，可选 `style.css` 与 `example.html`
+pub const HTML_STYLES_DIR: &str = "public/prompt/html-styles";
+
+/// 样板目录未提供 `style.css` / `example.html` 时使用的站点文件
+const DEFAULT_EXAMPLE_CSS: &str = "front/example.css";
+const DEFAULT_EXAMPLE_HTML: &str = "front/index.html";
+
+/// 可用风格的名称与描述，描述取自文件开头的 `<!-- ... -->` 注释
+#[derive(Debug, Clone)]
+pub struct StyleInfo {
+    pub name: String,
+    pub description: Option<String>,
+    path: PathBuf,
+}
+
+/// 请求的风格不在可用列表中
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownStyle {
+    pub name: String,
+    pub available: Vec<String>,
+}
+
+impl std::fmt::Display for UnknownStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.available.is_empty() {
+            write!(f, "unknown style: {}, no styles available", self.name)
+        } else {
+            let available = self.available.join(", ");
+            write!(f, "unknown style: {}, available: {}", self.name, available)
+        }
+    }
+}
+
+impl std::error::Error for UnknownStyle {}
+
+/// 按名称排序列出 `blog-styles/` 下的 `.xml` 文件
+pub fn list_blog_styles() -> Result<Vec<StyleInfo>> {
+    let mut styles = Vec::new();
+    for (name, path) in read_style_entries(Path::new(BLOG_STYLES_DIR))? {
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "xml") {
+            let name = name.trim_end_matches(".xml").to_string();
+            styles.push(style_info(name, path)?);
+        }
+    }
+    Ok(styles)
+}
+
+/// 按名称排序列出 `html-styles/` 下含 `template.xml` 的子目录
+pub fn list_html_styles() -> Result<Vec<StyleInfo>> {
+    let mut styles = Vec::new();
+    for (name, path) in read_style_entries(Path::new(HTML_STYLES_DIR))? {
+        let template = path.join("template.xml");
+        if template.is_file() {
+            styles.push(style_info(name, template)?);
+        }
+    }
+    Ok(styles)
+}
+
+/// 返回去掉描述注释后的风格指南，名称不区分大小写
+pub fn load_blog_style(name: &str) -> Result<String> {
+    let style = find_style(list_blog_styles()?, name)?;
+    read_style_body(&style.path)
+}
+
+/// 返回填充了 `{{EXAMPLE_CSS}}` 与 `{{EXAMPLE_HTML}}` 的 HTML 样板，名称不区分大小写
+pub fn load_html_style(name: &str) -> Result<String> {
+    let style = find_style(list_html_styles()?, name)?;
+    let template = read_style_body(&style.path)?;
+    let style_dir = style.path.parent().unwrap_or(Path::new(HTML_STYLES_DIR));
+    let example_css = read_style_asset(style_dir, "style.css", DEFAULT_EXAMPLE_CSS)?;
+    let example_html = read_style_asset(style_dir, "example.html", DEFAULT_EXAMPLE_HTML)?;
+    Ok(template
+        .replace("{{EXAMPLE_CSS}}", &example_css)
+        .replace("{{EXAMPLE_HTML}}", &example_html))
+}
+
+/// 目录不存在时视为没有可用风格
+fn read_style_entries(dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut entries = Vec::new();
+    for entry in std::fs::read_dir(dir).with_context(|| format!("read style dir {:?}", dir))? {
+        let entry = entry.context("read style dir entry")?;
+        entries.push((
+            entry.file_name().to_string_lossy().to_string(),
+            entry.path(),
+        ));
+    }
+    entries.sort();
+    Ok(entries)
+}
+
+fn style_info(name: String, path: PathBuf) -> Result<StyleInfo> {
+    let raw = std::fs::read_to_string(&path).with_context(|| format!("read style {:?}", path))?;
+    Ok(StyleInfo {
+        name,
+        description: split_header_comment(&raw).0,
+        path,
+    })
+}
+
+/// 只在列出的风格中查找，名称不会被拼进路径
+fn find_style(styles: Vec<StyleInfo>, name: &str) -> Result<StyleInfo> {
+    let name = name.trim();
+    let available: Vec<String> = styles.iter().map(|style| style.name.clone()).collect();
+    styles
+        .into_iter()
+        .find(|style| style.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| {
+            UnknownStyle {
+                name: name.to_string(),
+                available,
+            }
+            .into()
+        })
+}
+
+fn read_style_body(path: &Path) -> Result<String> {
+    let raw = std::fs::read_to_string(path).with_context(|| format!("read style {:?}", path))?;
+    Ok(split_header_comment(&raw).1.to_string())
+}
+
+fn read_style_asset(style_dir: &Path, file_name: &str, fallback: &str) -> Result<String> {
+    let path = style_dir.join(file_name);
+    let path = if path.is_file() {
+        path
+    } else {
+        PathBuf::from(fallback)
+    };
+    std::fs::read_to_string(&path).with_context(|| format!("read style asset {:?}", path))
+}
+
+/// 拆出文件开头的 `<!-- 描述 -->`，返回 (描述, 其余内容)
+fn split_header_comment(raw: &str) -> (Option<String>, &str) {
+    let trimmed = raw.trim_start();
+    let Some(rest) = trimmed.strip_prefix("<!--") else {
+        return (None, raw);
+    };
+    let Some((comment, body)) = rest.split_once("-->") else {
+        return (None, raw);
+    };
+    let description = comment.split_whitespace().collect::<Vec<_>>().join(" ");
+    let description = Some(description).filter(|value| !value.is_empty());
+    (description, body.trim_start_matches(['\r', '\n']))
+}
@@ -0,0 +1,282 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::SystemTime;
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{
+    config::{McpScope, generate_mcp_token},
+    server::admin::constant_time_eq,
+    store::{atomic_write, now_unix_seconds},
+};
+
+/// 数据目录下保存 MCP token 的文件名
+pub const TOKENS_FILE_NAME: &str = "tokens.json";
+
+/// `last_used_at` 的落盘间隔（秒），内存中的时间每次请求都更新，文件最多每分钟写一次
+const LAST_USED_PERSIST_SECS: i64 = 60;
+
+const TOKEN_NAME_MAX_LEN: usize = 64;
+
+/// `data/tokens.json` 的内容
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TokenFile {
+    #[serde(default)]
+    pub tokens: Vec<StoredToken>,
+}
+
+/// 一个具名的 MCP token，通过 `Authorization: Bearer` 访问 `/mcp`（`MCP_LEGACY_PATH=1` 时也可用
+/// `/{token}/mcp`），read-write token 另有 `/{token}/admin`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredToken {
+    pub name: String,
+    pub token: String,
+    pub scope: McpScope,
+    pub created_at: i64,
+    #[serde(default)]
+    pub last_used_at: Option<i64>,
+}
+
+impl StoredToken {
+    /// 列表展示用，只保留前 4 个字符
+    pub fn masked(&self) -> String {
+        let prefix: String = self.token.chars().take(4).collect();
+        format!("{prefix}…")
+    }
+}
+
+/// MCP token 列表，文件在服务运行期间被 `token add` / `token revoke` 修改后，
+/// 下一次校验时按修改时间重新读取，新增与吊销无需重启即可生效
+#[derive(Debug)]
+pub struct TokenStore {
+    path: PathBuf,
+    state: Mutex<TokenState>,
+}
+
+#[derive(Debug, Default)]
+struct TokenState {
+    file: TokenFile,
+    modified: Option<SystemTime>,
+    /// 上次写入文件时各 token 的 `last_used_at`，用于节流
+    persisted_last_used: Vec<(String, i64)>,
+}
+
+impl TokenStore {
+    /// 读取 `{data_dir}/tokens.json`，文件不存在时为空列表，第一次写入时创建
+    pub fn open(data_dir: impl AsRef<Path>) -> Result<Self> {
+        let store = Self {
+            path: data_dir.as_ref().join(TOKENS_FILE_NAME),
+            state: Mutex::new(TokenState::default()),
+        };
+        store.refresh(&mut store.lock())?;
+        Ok(store)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 首次启动（文件尚未创建）时为 `false`
+    pub fn file_exists(&self) -> bool {
+        self.path.is_file()
+    }
+
+    pub fn list(&self) -> Result<Vec<StoredToken>> {
+        let mut state = self.lock();
+        self.refresh(&mut state)?;
+        Ok(state.file.tokens.clone())
+    }
+
+    /// 生成新的随机 token；名称已存在时返回错误
+    pub fn add(&self, name: &str, scope: McpScope) -> Result<StoredToken> {
+        self.insert(name, generate_mcp_token(), scope)
+    }
+
+    /// 以指定的 token 值新增，用于导入 `MCP_TOKEN` / `MCP_TOKENS`；名称或 token 已存在时返回错误
+    pub fn insert(&self, name: &str, token: String, scope: McpScope) -> Result<StoredToken> {
+        validate_token_name(name)?;
+        let token = token.trim().to_string();
+        if token.is_empty() || token.contains('/') {
+            bail!("token must be non-empty and must not contain '/'");
+        }
+        let mut state = self.lock();
+        self.refresh(&mut state)?;
+        if state
+            .file
+            .tokens
+            .iter()
+            .any(|existing| existing.name == name)
+        {
+            bail!("token {name:?} already exists");
+        }
+        if state
+            .file
+            .tokens
+            .iter()
+            .any(|existing| existing.token == token)
+        {
+            bail!("the same token value is already stored under another name");
+        }
+        let stored = StoredToken {
+            name: name.to_string(),
+            token,
+            scope,
+            created_at: now_unix_seconds()?,
+            last_used_at: None,
+        };
+        state.file.tokens.push(stored.clone());
+        self.save(&mut state)?;
+        Ok(stored)
+    }
+
+    /// 删除指定名称的 token，返回是否存在；吊销后该 token 的请求返回 404
+    pub fn revoke(&self, name: &str) -> Result<bool> {
+        let mut state = self.lock();
+        self.refresh(&mut state)?;
+        let before = state.file.tokens.len();
+        state.file.tokens.retain(|token| token.name != name);
+        if state.file.tokens.len() == before {
+            return Ok(false);
+        }
+        self.save(&mut state)?;
+        Ok(true)
+    }
+
+    /// 按常量时间比对 token，匹配时更新 `last_used_at` 并返回名称与权限范围；
+    /// 文件读取或写入失败时打印警告，沿用内存中的列表
+    pub fn authenticate(&self, token: &str) -> Option<(String, McpScope)> {
+        let mut state = self.lock();
+        if let Err(err) = self.refresh(&mut state) {
+            warn!(
+                "reload {} failed, using cached tokens: {err:#}",
+                self.path.display()
+            );
+        }
+        let now = now_unix_seconds().unwrap_or_default();
+        let stored = state
+            .file
+            .tokens
+            .iter_mut()
+            .find(|stored| constant_time_eq(stored.token.as_bytes(), token.as_bytes()))?;
+        stored.last_used_at = Some(now);
+        let matched = (stored.name.clone(), stored.scope);
+        let persisted = state
+            .persisted_last_used
+            .iter()
+            .find(|(name, _)| *name == matched.0)
+            .map(|(_, last_used)| *last_used);
+        if persisted.is_none_or(|last_used| now - last_used >= LAST_USED_PERSIST_SECS)
+            && let Err(err) = self.save(&mut state)
+        {
+            warn!("save {} failed: {err:#}", self.path.display());
+        }
+        Some(matched)
+    }
+
+    /// 把路径中与已存 token 相同的段替换为 `***`，用于日志脱敏，不更新 `last_used_at`
+    pub fn redact_path(&self, path: &str) -> String {
+        let mut state = self.lock();
+        // 读取失败时沿用内存中的列表，警告由 `authenticate` 打印
+        let _ = self.refresh(&mut state);
+        path.split('/')
+            .map(|segment| {
+                let matched = !segment.is_empty()
+                    && state.file.tokens.iter().any(|stored| {
+                        constant_time_eq(stored.token.as_bytes(), segment.as_bytes())
+                    });
+                if matched { "***" } else { segment }
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// 把尚未落盘的 `last_used_at` 写入文件，服务退出前调用；有写入时返回 `true`
+    pub fn flush(&self) -> Result<bool> {
+        let mut state = self.lock();
+        if last_used_pairs(&state.file) == state.persisted_last_used {
+            return Ok(false);
+        }
+        // 退出前文件可能已被 `token revoke` 修改，先合并再写入，避免恢复已吊销的 token
+        self.refresh(&mut state)?;
+        self.save(&mut state)?;
+        Ok(true)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, TokenState> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// 文件修改时间变化时重新读取；内存中较新的 `last_used_at` 保留
+    fn refresh(&self, state: &mut TokenState) -> Result<()> {
+        let modified = match fs::metadata(&self.path) {
+            Ok(metadata) => metadata.modified().ok(),
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                state.file = TokenFile::default();
+                state.modified = None;
+                return Ok(());
+            }
+            Err(err) => {
+                return Err(err).with_context(|| format!("stat {}", self.path.display()));
+            }
+        };
+        if modified.is_some() && modified == state.modified {
+            return Ok(());
+        }
+        let raw = fs::read_to_string(&self.path)
+            .with_context(|| format!("read {}", self.path.display()))?;
+        let mut file: TokenFile =
+            serde_json::from_str(&raw).with_context(|| format!("parse {}", self.path.display()))?;
+        state.persisted_last_used = last_used_pairs(&file);
+        for token in &mut file.tokens {
+            let cached = state
+                .file
+                .tokens
+                .iter()
+                .find(|cached| cached.name == token.name && cached.token == token.token)
+                .and_then(|cached| cached.last_used_at);
+            token.last_used_at = token.last_used_at.max(cached);
+        }
+        state.file = file;
+        state.modified = modified;
+        Ok(())
+    }
+
+    fn save(&self, state: &mut TokenState) -> Result<()> {
+        let data = serde_json::to_vec_pretty(&state.file).context("serialize tokens")?;
+        atomic_write(&self.path, &data)?;
+        state.modified = fs::metadata(&self.path)
+            .ok()
+            .and_then(|metadata| metadata.modified().ok());
+        state.persisted_last_used = last_used_pairs(&state.file);
+        Ok(())
+    }
+}
+
+fn last_used_pairs(file: &TokenFile) -> Vec<(String, i64)> {
+    file.tokens
+        .iter()
+        .filter_map(|token| {
+            token
+                .last_used_at
+                .map(|last_used| (token.name.clone(), last_used))
+        })
+        .collect()
+}
+
+/// 名称只允许字母、数字与 `-` `_` `.`，便于在命令行中引用
+pub fn validate_token_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.len() > TOKEN_NAME_MAX_LEN {
+        bail!("token name must be 1-{TOKEN_NAME_MAX_LEN} characters");
+    }
+    if !name
+        .bytes()
+        .all(|byte| byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.'))
+    {
+        bail!("token name may only contain letters, digits, '-', '_' and '.'");
+    }
+    Ok(())
+}
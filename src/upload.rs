@@ -0,0 +1,179 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use mime_guess::MimeGuess;
+
+use crate::{
+    config::resolve_upload_max_bytes_from_env,
+    server::assets::sanitize_public_path,
+    store::{atomic_write, sanitize_page_id},
+};
+
+/// 上传文件保存在 `public/` 下，由 `/public/{*path}` 直接提供访问
+pub const UPLOADS_DIR: &str = "uploads";
+
+/// 允许上传的扩展名；SVG 可内嵌脚本且与站点同源，不在列表中
+const ALLOWED_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "webp", "avif", "ico", "pdf", "mp3", "mp4", "webm",
+];
+
+/// 解码 base64 内容，兼容 `data:image/png;base64,` 前缀与换行
+pub fn decode_base64_content(content: &str) -> Result<Vec<u8>> {
+    let content = match content.trim().strip_prefix("data:") {
+        Some(data_url) => match data_url.split_once(";base64,") {
+            Some((_, payload)) => payload,
+            None => bail!("invalid base64: data url must use ;base64,"),
+        },
+        None => content.trim(),
+    };
+    let compact: String = content.split_whitespace().collect();
+    STANDARD
+        .decode(compact.as_bytes())
+        .map_err(|err| anyhow::anyhow!("invalid base64: {}", err))
+}
+
+/// 文件名只允许字母数字与 `-` `_` `.`，扩展名需在白名单中且与 `mime_type` 一致
+pub fn validate_upload_filename(filename: &str, mime_type: &str) -> Result<String> {
+    let filename = filename.trim();
+    let valid_chars = filename
+        .chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.'));
+    if filename.is_empty() || filename.starts_with('.') || !valid_chars {
+        bail!(
+            "invalid filename {:?}: use letters, digits, '-', '_' and '.'",
+            filename
+        );
+    }
+    let Some((_, extension)) = filename.rsplit_once('.') else {
+        bail!("filename {:?} has no extension", filename);
+    };
+    let extension = extension.to_ascii_lowercase();
+    if !ALLOWED_EXTENSIONS.contains(&extension.as_str()) {
+        bail!(
+            "file type .{} is not allowed (allowed: {})",
+            extension,
+            ALLOWED_EXTENSIONS.join(", ")
+        );
+    }
+    let mime_type = mime_type.trim().to_ascii_lowercase();
+    if !MimeGuess::from_ext(&extension)
+        .iter()
+        .any(|mime| mime.essence_str() == mime_type)
+    {
+        bail!("mime_type {} does not match .{}", mime_type, extension);
+    }
+    Ok(filename.to_string())
+}
+
+/// 校验大小后写入 `public/uploads/[{page_uid}/]{filename}`，同名文件直接覆盖
+/// 返回站点内的访问路径，如 `/public/uploads/{page_uid}/cover.png`
+pub fn save_upload(page_uid: Option<&str>, filename: &str, data: &[u8]) -> Result<String> {
+    let max_bytes = resolve_upload_max_bytes_from_env();
+    if data.is_empty() {
+        bail!("asset is empty");
+    }
+    if data.len() > max_bytes {
+        bail!(
+            "asset is {} bytes, exceeds the {} byte limit",
+            data.len(),
+            max_bytes
+        );
+    }
+    let relative = uploads_relative_dir(page_uid).join(filename);
+    let full_path = Path::new("public").join(&relative);
+    atomic_write(&full_path, data).with_context(|| format!("write asset {:?}", full_path))?;
+    Ok(public_asset_path(&relative))
+}
+
+/// `list_uploads` 返回的单个资源
+#[derive(Debug, Clone)]
+pub struct UploadedAsset {
+    pub name: String,
+    /// 页面资源的归属页面，全局资源为空
+    pub page_uid: Option<String>,
+    pub size: u64,
+    pub mime_type: String,
+    /// 站点内的访问路径
+    pub path: String,
+}
+
+/// 列出页面资源目录（`page_uid` 为空时为全局目录）下的文件，不递归子目录，按文件名排序
+/// 目录不存在时返回空列表
+pub fn list_uploads(page_uid: Option<&str>) -> Result<Vec<UploadedAsset>> {
+    let relative_dir = uploads_relative_dir(page_uid);
+    let dir = Path::new("public").join(&relative_dir);
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err).with_context(|| format!("read asset dir {:?}", dir)),
+    };
+    let mut assets = Vec::new();
+    for entry in entries {
+        let entry = entry.with_context(|| format!("read asset dir {:?}", dir))?;
+        let metadata = entry
+            .metadata()
+            .with_context(|| format!("read asset metadata {:?}", entry.path()))?;
+        // 全局目录下的子目录是各页面的资源目录
+        if !metadata.is_file() {
+            continue;
+        }
+        let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        // 跳过 atomic_write 中断后残留的临时文件
+        if name.starts_with('.') || name.ends_with(".tmp") {
+            continue;
+        }
+        let relative = relative_dir.join(&name);
+        assets.push(UploadedAsset {
+            mime_type: MimeGuess::from_path(&relative)
+                .first_or_octet_stream()
+                .essence_str()
+                .to_string(),
+            path: public_asset_path(&relative),
+            name,
+            page_uid: page_uid.map(str::to_string),
+            size: metadata.len(),
+        });
+    }
+    assets.sort_by(|left, right| left.name.cmp(&right.name));
+    Ok(assets)
+}
+
+/// 资源名必须是单个普通路径段：先按 `sanitize_public_path` 的规则拒绝 `..` 等路径段，
+/// 再拒绝 `/`、`\`、控制字符与以 `.` 开头的名称
+pub fn validate_asset_name(name: &str) -> Result<String> {
+    let invalid = || anyhow::anyhow!("invalid asset name {:?}", name);
+    sanitize_public_path(name).ok_or_else(invalid)?;
+    if name.starts_with('.') || name.contains(['/', '\\']) || name.chars().any(|ch| ch.is_control())
+    {
+        return Err(invalid());
+    }
+    Ok(name.to_string())
+}
+
+/// 删除一个资源并返回其站点内路径，文件不存在时报错
+pub fn delete_upload(page_uid: Option<&str>, name: &str) -> Result<String> {
+    let name = validate_asset_name(name)?;
+    let relative = uploads_relative_dir(page_uid).join(&name);
+    let full_path = Path::new("public").join(&relative);
+    if !full_path.is_file() {
+        bail!("asset not found: {}", name);
+    }
+    std::fs::remove_file(&full_path).with_context(|| format!("remove asset {:?}", full_path))?;
+    Ok(public_asset_path(&relative))
+}
+
+/// `uploads/` 或 `uploads/{page_uid}/`，相对于 `public/`
+fn uploads_relative_dir(page_uid: Option<&str>) -> PathBuf {
+    let mut relative = PathBuf::from(UPLOADS_DIR);
+    if let Some(page_uid) = page_uid {
+        relative.push(sanitize_page_id(page_uid));
+    }
+    relative
+}
+
+fn public_asset_path(relative: &Path) -> String {
+    format!("/public/{}", relative.to_string_lossy().replace('\\', "/"))
+}
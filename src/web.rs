@@ -1,211 +1,2153 @@
-use crate::store::{PageMeta, PageStore};
-use anyhow::{bail, Context, Result};
-use pulldown_cmark::{Options, Parser, html};
+use crate::config::{
+    CommentsConfig, CommentsProvider, resolve_comments_config_from_env,
+    resolve_pwa_colors_from_env, resolve_share_links_enabled_from_env, resolve_site_title_from_env,
+};
+use crate::store::{
+    PageIndexEntry, PageMeta, PageStoreTrait, SeoMeta, estimate_reading_time,
+    html_util::find_bytes_case_insensitive as find_bytes_ci, html_visible_text, is_cjk_char,
+    is_void_element, most_popular_entries, normalize_tag, recent_update_entries,
+    site_keyword_counts, validate_html,
+};
+use anyhow::{Context, Result, bail};
 use chrono::{TimeZone, Utc};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, percent_decode_str, utf8_percent_encode};
+use pulldown_cmark::{Options, Parser, html};
+use regex::Regex;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::SystemTime;
+use tracing::warn;
+
+/// RFC 3986 unreserved 字符（`A-Za-z0-9-._~`）以外的字节全部编码
+const PATH_SEGMENT_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// 编码单个路径段，标题中的 `/`、`+`、`?` 与中文、emoji 都会被转义
+pub fn url_encode_path_segment(s: &str) -> String {
+    utf8_percent_encode(s, PATH_SEGMENT_ENCODE_SET).to_string()
+}
+
+pub fn build_page_url(page_id: &str, seo_title: &str) -> String {
+    if seo_title.is_empty() {
+        format!("/pages/{}", page_id)
+    } else {
+        format!("/pages/{}+{}", url_encode_path_segment(seo_title), page_id)
+    }
+}
+
+/// 先解码再取最后一个 `+` 之后的部分，编码前后的地址都能解析
+pub fn parse_page_id_from_slug(slug: &str) -> Option<String> {
+    let slug = percent_decode_str(slug).decode_utf8_lossy();
+    let mut parts = slug.rsplitn(2, '+');
+    let page_id = parts.next()?;
+    if page_id.is_empty() {
+        return None;
+    }
+    Some(page_id.to_string())
+}
+
+/// 页面地址无法还原为站内页面的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PageUrlError {
+    /// 主机与 `SITE_URL` 不一致
+    ForeignHost(String),
+    /// 不是 `/pages/{slug}` 形式的地址
+    NotPageUrl,
+}
+
+impl std::fmt::Display for PageUrlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PageUrlError::ForeignHost(host) => {
+                write!(f, "foreign url: host {host} does not match SITE_URL")
+            }
+            PageUrlError::NotPageUrl => write!(f, "not a page url, expected /pages/{{slug}}"),
+        }
+    }
+}
+
+impl std::error::Error for PageUrlError {}
+
+/// 地址中的主机部分：`https://example.com/blog` → `example.com`
+pub fn url_host(url: &str) -> Option<&str> {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    rest.split('/').next().filter(|host| !host.is_empty())
+}
+
+/// 把页面地址还原为 slug，支持绝对地址、`//host/...` 与相对路径
+/// 忽略查询串、锚点与末尾的 `/`，slug 会做百分号解码；`site_url` 为空时不校验主机
+pub fn page_slug_from_url(url: &str, site_url: &str) -> Result<String, PageUrlError> {
+    let url = url.trim().split(['?', '#']).next().unwrap_or_default();
+    let authority = url.strip_prefix("//").or_else(|| {
+        url.split_once("://")
+            .filter(|(scheme, _)| !scheme.contains('/'))
+            .map(|(_, rest)| rest)
+    });
+    let path = match authority {
+        Some(rest) => {
+            let (host, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+            if let Some(site_host) = url_host(site_url)
+                && !host.eq_ignore_ascii_case(site_host)
+            {
+                return Err(PageUrlError::ForeignHost(host.to_string()));
+            }
+            path
+        }
+        None => url,
+    };
+    // SITE_URL 带子路径（如 `https://example.com/blog`）时先去掉该前缀
+    let site_path = site_url
+        .split_once("://")
+        .and_then(|(_, rest)| rest.find('/').map(|index| &rest[index..]))
+        .unwrap_or_default()
+        .trim_end_matches('/');
+    let path = path
+        .strip_prefix(site_path)
+        .filter(|rest| !site_path.is_empty() && (rest.is_empty() || rest.starts_with('/')))
+        .unwrap_or(path);
+    let slug = path
+        .trim_start_matches('/')
+        .strip_prefix("pages/")
+        .and_then(|rest| rest.split('/').next())
+        .map(|slug| slug.strip_suffix(".md").unwrap_or(slug))
+        .filter(|slug| !slug.is_empty())
+        .ok_or(PageUrlError::NotPageUrl)?;
+    percent_decode_str(slug)
+        .decode_utf8()
+        .map(|slug| slug.into_owned())
+        .map_err(|_| PageUrlError::NotPageUrl)
+}
+
+/// `PageMeta::extra` 中页面发布状态的键
+pub const EXTRA_STATUS_KEY: &str = "status";
+
+/// `set_page_visibility` 取消发布时写入的状态值
+pub const STATUS_UNPUBLISHED: &str = "unpublished";
+
+/// 页面是否可公开访问：`extra.status` 为 draft / unpublished 的页面不对外列出
+pub fn is_page_public(meta: &PageMeta) -> bool {
+    !matches!(
+        meta.extra
+            .get(EXTRA_STATUS_KEY)
+            .and_then(|value| value.as_str()),
+        Some("draft" | STATUS_UNPUBLISHED)
+    )
+}
+
+/// 已取消发布的页面：与草稿不同，直链也不再可访问，页面路由返回 404
+pub fn is_page_unpublished(meta: &PageMeta) -> bool {
+    matches!(
+        meta.extra
+            .get(EXTRA_STATUS_KEY)
+            .and_then(|value| value.as_str()),
+        Some(STATUS_UNPUBLISHED)
+    )
+}
+
+/// `PageMeta::extra` 中标记页面禁止搜索引擎收录的键，值为 `true` 时生效
+pub const EXTRA_NOINDEX_KEY: &str = "noindex";
+
+/// 禁止搜索引擎收录与跟踪链接的 robots meta
+pub const ROBOTS_NOINDEX_META: &str = "<meta name=\"robots\" content=\"noindex,nofollow\">";
+
+/// 页面是否允许搜索引擎收录：未公开或 `extra.noindex=true` 的页面不进入 sitemap
+pub fn is_page_indexable(meta: &PageMeta) -> bool {
+    let noindex = meta
+        .extra
+        .get(EXTRA_NOINDEX_KEY)
+        .and_then(|value| value.as_bool())
+        .unwrap_or(false);
+    is_page_public(meta) && !noindex
+}
+
+/// 设置或清除页面的 `extra.noindex` 标记，`false` 时直接移除该键
+pub fn set_page_noindex(meta: &mut PageMeta, noindex: bool) {
+    if noindex {
+        meta.extra
+            .insert(EXTRA_NOINDEX_KEY.to_string(), true.into());
+    } else {
+        meta.extra.remove(EXTRA_NOINDEX_KEY);
+    }
+}
+
+/// 在 `</head>` 之前插入 robots noindex meta，页面没有 `<head>` 时补一个
+pub fn inject_robots_noindex(html: &str) -> String {
+    if let Some(close_start) = find_bytes_ci(html.as_bytes(), 0, b"</head") {
+        let mut out = String::with_capacity(html.len() + ROBOTS_NOINDEX_META.len());
+        out.push_str(&html[..close_start]);
+        out.push_str(ROBOTS_NOINDEX_META);
+        out.push_str(&html[close_start..]);
+        return out;
+    }
+    format!("<head>{}</head>{}", ROBOTS_NOINDEX_META, html)
+}
+
+pub async fn render_index_html(store: &dyn PageStoreTrait) -> Result<String> {
+    let entries = store
+        .list_page_entries()
+        .await
+        .context("list page entries")?;
+    let pages = load_public_pages(store, entries).await?;
+    render_listing_html(pages, None)
+}
+
+/// 标签页 `/tags/{tag}`，复用首页模板；标签下没有公开页面时返回 `None`
+pub async fn render_tag_html(store: &dyn PageStoreTrait, tag: &str) -> Result<Option<String>> {
+    let entries = store
+        .list_pages_by_tag(tag)
+        .await
+        .with_context(|| format!("list pages by tag {}", tag))?;
+    let pages = load_public_pages(store, entries).await?;
+    if pages.is_empty() {
+        return Ok(None);
+    }
+    render_listing_html(pages, Some(tag)).map(Some)
+}
+
+/// 过滤掉未公开的页面，保持原有顺序
+pub async fn load_public_pages(
+    store: &dyn PageStoreTrait,
+    entries: Vec<PageIndexEntry>,
+) -> Result<Vec<(PageIndexEntry, PageMeta)>> {
+    let mut pages = Vec::new();
+    for entry in entries {
+        let meta = store
+            .get_page_meta(&entry.page_id)
+            .await
+            .with_context(|| format!("load page meta {}", entry.page_id))?;
+        if is_page_public(&meta) {
+            pages.push((entry, meta));
+        }
+    }
+    Ok(pages)
+}
+
+/// 首页订阅 `/events`，页面增删改后重新拉取首页并替换卡片列表；连续事件合并为一次刷新
+const LIVE_UPDATES_SCRIPT: &str = r#"<script>
+      if ("EventSource" in window) {
+        let refreshTimer = null;
+        const refreshCards = async () => {
+          try {
+            const response = await fetch(window.location.href, { headers: { Accept: "text/html" } });
+            if (!response.ok) {
+              return;
+            }
+            const doc = new DOMParser().parseFromString(await response.text(), "text/html");
+            const next = doc.querySelector(".card-list");
+            const current = document.querySelector(".card-list");
+            if (next && current) {
+              current.innerHTML = next.innerHTML;
+              document.dispatchEvent(new Event("cardlist:updated"));
+            }
+          } catch (_) {}
+        };
+        new EventSource("/events").onmessage = () => {
+          clearTimeout(refreshTimer);
+          refreshTimer = setTimeout(refreshCards, 500);
+        };
+      }
+    </script>"#;
+
+/// build.rs 生成的指纹清单：`front/` 下 CSS / JS 的原文件名 → 带内容哈希的文件名
+const ASSET_MANIFEST_PATH: &str = "front/manifest.json";
+/// 模板中引用 `front/` 资源的占位符，如 `{{asset:example.css}}`
+const ASSET_PLACEHOLDER_PREFIX: &str = "{{asset:";
+
+/// 参与指纹的 `front/` 资源：CSS 与 JS，`sw.js` 的地址必须固定，不参与；与 build.rs 保持一致
+pub fn is_fingerprinted_asset_name(name: &str) -> bool {
+    name != "sw.js" && (name.ends_with(".css") || name.ends_with(".js"))
+}
+
+/// 读取 `front/manifest.json`，文件不存在（未经过 build.rs）或无法解析时返回空表
+pub fn load_asset_manifest() -> BTreeMap<String, String> {
+    let Ok(raw) = std::fs::read_to_string(ASSET_MANIFEST_PATH) else {
+        return BTreeMap::new();
+    };
+    serde_json::from_str(&raw).unwrap_or_else(|err| {
+        warn!("parse {ASSET_MANIFEST_PATH} failed: {err}");
+        BTreeMap::new()
+    })
+}
+
+/// 模板中引用 `public/` 文件的占位符，如 `{{public:light.png}}`
+const PUBLIC_PLACEHOLDER_PREFIX: &str = "{{public:";
+/// 运行时指纹保留的 SHA-256 十六进制位数，与 build.rs 的 `FINGERPRINT_LEN` 一致
+const FILE_FINGERPRINT_LEN: usize = 8;
+
+/// 文件指纹缓存项：(修改时间, 文件大小, 指纹)
+type FileFingerprint = (SystemTime, u64, String);
+
+/// 运行时计算的文件指纹：路径 → 缓存项，修改时间与文件大小任一变化时重新计算
+static FILE_FINGERPRINTS: LazyLock<Mutex<HashMap<PathBuf, FileFingerprint>>> =
+    LazyLock::new(Default::default);
+
+/// `front/` 资源的访问地址：清单中有该文件时为 `/public/assets/example.abc123de.css`，
+/// 否则退回 `/public/assets/example.css?v=…`（按 `front/` 原文件的内容计算），
+/// 两者都由 `public_asset_handler` 映射到原文件
+pub fn front_asset_url(manifest: &BTreeMap<String, String>, name: &str) -> String {
+    if let Some(file_name) = manifest.get(name) {
+        return format!("/public/assets/{}", file_name);
+    }
+    versioned_url(
+        &format!("/public/assets/{}", name),
+        &Path::new("front").join(name),
+    )
+}
+
+/// `public/` 下文件的访问地址，带内容指纹查询参数，如 `/public/example.css?v=abc123de`；
+/// 文件不存在或路径不合法时返回不带参数的地址。指纹按修改时间缓存，替换文件后无需重启
+pub fn asset_url(name: &str) -> String {
+    let name = name.trim_start_matches('/');
+    let url = format!("/public/{}", name);
+    let is_relative = Path::new(name)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    if !is_relative {
+        return url;
+    }
+    versioned_url(&url, &Path::new("public").join(name))
+}
+
+fn versioned_url(url: &str, path: &Path) -> String {
+    match file_fingerprint(path) {
+        Some(fingerprint) => format!("{}?v={}", url, fingerprint),
+        None => url.to_string(),
+    }
+}
+
+/// 文件内容 SHA-256 的前 8 位十六进制，文件不存在或无法读取时返回 `None`；
+/// `public_asset_handler` 用它判断请求中的 `?v=` 是否仍对应当前内容
+pub fn file_fingerprint(path: &Path) -> Option<String> {
+    let metadata = std::fs::metadata(path)
+        .ok()
+        .filter(|metadata| metadata.is_file())?;
+    let modified = metadata.modified().ok()?;
+    let key = (modified, metadata.len());
+    {
+        let cache = FILE_FINGERPRINTS
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        if let Some((cached_modified, cached_len, fingerprint)) = cache.get(path)
+            && (*cached_modified, *cached_len) == key
+        {
+            return Some(fingerprint.clone());
+        }
+    }
+    // 读文件与计算哈希不持锁，并发请求最多重复计算一次
+    let content = std::fs::read(path).ok()?;
+    let digest = format!("{:x}", Sha256::digest(&content));
+    let fingerprint = digest[..FILE_FINGERPRINT_LEN].to_string();
+    FILE_FINGERPRINTS
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .insert(path.to_path_buf(), (key.0, key.1, fingerprint.clone()));
+    Some(fingerprint)
+}
+
+/// 把模板中的 `{{asset:NAME}}`（`front/` 的 CSS / JS）与 `{{public:NAME}}`（`public/` 下的文件）
+/// 替换为带指纹的地址，模板未引用资源时原样返回
+fn replace_asset_placeholders(template: String) -> String {
+    let template = if template.contains(ASSET_PLACEHOLDER_PREFIX) {
+        let manifest = load_asset_manifest();
+        replace_prefixed_placeholders(template, ASSET_PLACEHOLDER_PREFIX, |name| {
+            front_asset_url(&manifest, name)
+        })
+    } else {
+        template
+    };
+    replace_prefixed_placeholders(template, PUBLIC_PLACEHOLDER_PREFIX, asset_url)
+}
+
+fn replace_prefixed_placeholders(
+    template: String,
+    prefix: &str,
+    url: impl Fn(&str) -> String,
+) -> String {
+    if !template.contains(prefix) {
+        return template;
+    }
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template.as_str();
+    while let Some(start) = rest.find(prefix) {
+        let after = &rest[start + prefix.len()..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        out.push_str(&escape_html_attr(&url(after[..end].trim())));
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 首页与标签页共用的列表渲染，标签页不展示热门区块，副标题替换为当前标签
+fn render_listing_html(
+    mut pages: Vec<(PageIndexEntry, PageMeta)>,
+    tag: Option<&str>,
+) -> Result<String> {
+    let header_html =
+        std::fs::read_to_string("front/header.html").context("read front/header.html template")?;
+    let header_html = replace_asset_placeholders(header_html);
+    let template =
+        std::fs::read_to_string("front/index.html").context("read front/index.html template")?;
+    let template = replace_asset_placeholders(template);
+    let (popular_html, recent_html) = match tag {
+        Some(_) => (String::new(), String::new()),
+        None => (
+            render_popular_pages_html(&pages),
+            render_recent_updates_html(&pages),
+        ),
+    };
+    let keyword_cloud_html = match tag {
+        Some(_) => String::new(),
+        None => render_keyword_cloud_html(&pages),
+    };
+    // 设置了 `sort_order` 的页面按升序排在前面，其余页面按更新时间倒序
+    let manual_order = |meta: &PageMeta| (meta.sort_order.is_none(), meta.sort_order);
+    pages.sort_by(|(left_entry, left_meta), (right_entry, right_meta)| {
+        manual_order(left_meta)
+            .cmp(&manual_order(right_meta))
+            .then_with(|| right_meta.updated_at.cmp(&left_meta.updated_at))
+            .then_with(|| right_meta.created_at.cmp(&left_meta.created_at))
+            .then_with(|| right_entry.page_id.cmp(&left_entry.page_id))
+    });
+    let mut rows = String::new();
+    for (entry, meta) in pages {
+        let display_title = if entry.seo.title.is_empty() {
+            &entry.seo.seo_title
+        } else {
+            &entry.seo.title
+        };
+        let title = escape_html(display_title);
+        let description = escape_html(&entry.seo.description);
+        let data_title = escape_html_attr(display_title);
+        let data_description = escape_html_attr(&entry.seo.description);
+        let keywords = entry
+            .seo
+            .keywords
+            .as_ref()
+            .map(|items| items.join(", "))
+            .filter(|value| !value.trim().is_empty())
+            .map(|value| escape_html(&value))
+            .unwrap_or_else(|| "无".to_string());
+        let data_keywords = entry
+            .seo
+            .keywords
+            .as_ref()
+            .map(|items| items.join(", "))
+            .filter(|value| !value.trim().is_empty())
+            .map(|value| escape_html_attr(&value))
+            .unwrap_or_else(|| "无".to_string());
+        let page_id_attr = escape_html_attr(&entry.page_id);
+        let url = build_page_url(&entry.page_id, &entry.seo.seo_title);
+        let url_attr = escape_html_attr(&url);
+        let published_at = escape_html(&format_display_timestamp(page_published_at(&meta)));
+        let updated_at = escape_html(&format_display_timestamp(meta.updated_at));
+        let tags = render_tag_links_html(&meta);
+        let view_count = format_number(entry.view_count);
+        rows.push_str(&format!(
+            "<article class=\"card\" data-page-id=\"{page_id_attr}\" data-title=\"{data_title}\" data-description=\"{data_description}\" data-keywords=\"{data_keywords}\"><div class=\"card-header\"><h2><a href=\"{url_attr}\">{title}</a></h2><span class=\"published-at\">发布：{published_at}</span><span class=\"updated-at\">更新：{updated_at}</span><span class=\"view-count\">{view_count} 次浏览</span></div><p class=\"description\">{description}</p><div class=\"keywords\"><span>关键词：</span><span class=\"keyword-value\">{keywords}</span></div>{tags}<div class=\"actions\"><a class=\"read-more\" href=\"{url_attr}\">阅读页面</a></div></article>",
+        ));
+    }
+
+    if rows.is_empty() {
+        rows.push_str(
+            "<div class=\"empty\">暂无页面内容，请先通过 MCP 接口发布页面。</div>",
+        );
+    }
+
+    let beian_number = std::env::var("BEIAN_NUMBER")
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    let beian_html = if beian_number.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "<footer class=\"beian\">{}</footer>",
+            escape_html(&beian_number)
+        )
+    };
+
+    let site_subtitle = std::env::var("SITE_SUBTITLE")
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    let site_subtitle = if let Some(tag) = tag {
+        format!("标签：{}", escape_html(tag))
+    } else if site_subtitle.is_empty() {
+        "AI 原生博客 · 最新页面列表".to_string()
+    } else {
+        site_subtitle
+    };
+
+    // 旧版自定义模板可能没有热门与最近更新区块，缺少占位符时直接跳过
+    let template = if template.contains("{{popular_pages}}") {
+        replace_template(&template, &[("popular_pages", &popular_html)])?
+    } else {
+        template
+    };
+    let template = if template.contains("{{recent_updates}}") {
+        replace_template(&template, &[("recent_updates", &recent_html)])?
+    } else {
+        template
+    };
+    let template = if template.contains("{{keyword_cloud}}") {
+        replace_template(&template, &[("keyword_cloud", &keyword_cloud_html)])?
+    } else {
+        template
+    };
+    let live_updates_html = match tag {
+        Some(_) => "",
+        None => LIVE_UPDATES_SCRIPT,
+    };
+    let template = if template.contains("{{live_updates}}") {
+        replace_template(&template, &[("live_updates", live_updates_html)])?
+    } else {
+        template
+    };
+    let rendered = replace_template(
+        &template,
+        &[
+            ("site_header", &header_html),
+            ("page_list", &rows),
+            ("site_title", "SolinBlog"),
+            ("site_subtitle", &site_subtitle),
+            ("beian_number", &beian_html),
+            ("pwa_head", &pwa_head_tags()),
+        ],
+    )?;
+
+    Ok(rendered)
+}
+
+/// `/tags/{tag}` 路径段的编码集，保留命名空间分隔符 `:`
+const TAG_PATH_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~')
+    .remove(b':');
+
+pub fn build_tag_url(tag: &str) -> String {
+    format!(
+        "/tags/{}",
+        utf8_percent_encode(&normalize_tag(tag), TAG_PATH_ENCODE_SET)
+    )
+}
+
+/// 卡片中的标签链接，只展示显式设置的 `tags`
+fn render_tag_links_html(meta: &PageMeta) -> String {
+    let mut links = String::new();
+    for tag in meta.tags.iter().flatten() {
+        let tag = normalize_tag(tag);
+        if tag.is_empty() {
+            continue;
+        }
+        links.push_str(&format!(
+            "<a class=\"tag\" href=\"{}\">{}</a>",
+            escape_html_attr(&build_tag_url(&tag)),
+            escape_html(&tag)
+        ));
+    }
+    if links.is_empty() {
+        return String::new();
+    }
+    format!("<div class=\"tags\"><span>标签：</span>{}</div>", links)
+}
+
+/// 首页侧栏展示的热门页面数量
+const INDEX_POPULAR_PAGES_LIMIT: usize = 5;
+
+/// 首页侧栏展示的最近更新页面数量
+const INDEX_RECENT_UPDATES_LIMIT: usize = 5;
+
+/// 首页关键词云最多展示的关键词数量
+const INDEX_KEYWORD_CLOUD_LIMIT: usize = 50;
+
+/// 关键词云字号范围（em），按使用页面数线性插值
+const KEYWORD_CLOUD_MIN_EM: f64 = 0.85;
+const KEYWORD_CLOUD_MAX_EM: f64 = 1.8;
+
+/// 首页关键词云，只统计公开页面，关键词取自索引条目，不读取正文
+/// 按使用页面数降序取前 `INDEX_KEYWORD_CLOUD_LIMIT` 个，点击后跳转 `/?keyword=` 由前端过滤列表
+fn render_keyword_cloud_html(pages: &[(PageIndexEntry, PageMeta)]) -> String {
+    let entries: Vec<PageIndexEntry> = pages.iter().map(|(entry, _)| entry.clone()).collect();
+    let mut counts: Vec<(String, usize)> = site_keyword_counts(&entries).into_iter().collect();
+    if counts.is_empty() {
+        return String::new();
+    }
+    counts.sort_by(|left, right| right.1.cmp(&left.1).then_with(|| left.0.cmp(&right.0)));
+    counts.truncate(INDEX_KEYWORD_CLOUD_LIMIT);
+    let max_count = counts.first().map(|(_, count)| *count).unwrap_or(1);
+    let min_count = counts.last().map(|(_, count)| *count).unwrap_or(1);
+    let mut items = String::new();
+    for (keyword, count) in counts {
+        let ratio = if max_count > min_count {
+            (count - min_count) as f64 / (max_count - min_count) as f64
+        } else {
+            0.0
+        };
+        let font_size =
+            KEYWORD_CLOUD_MIN_EM + (KEYWORD_CLOUD_MAX_EM - KEYWORD_CLOUD_MIN_EM) * ratio;
+        let url = format!("/?keyword={}", url_encode_path_segment(&keyword));
+        items.push_str(&format!(
+            "<a class=\"keyword-tag\" href=\"{}\" data-keyword=\"{}\" style=\"font-size: {:.2}em\" title=\"{} 篇页面\">{}</a>",
+            escape_html_attr(&url),
+            escape_html_attr(&keyword),
+            font_size,
+            count,
+            escape_html(&keyword)
+        ));
+    }
+    format!(
+        "<section class=\"keyword-cloud\" aria-label=\"关键词云\"><h2>关键词</h2><div class=\"keyword-cloud-items\">{}</div></section>",
+        items
+    )
+}
+
+/// 首页热门区块，只统计公开页面，没有浏览记录时返回空字符串
+fn render_popular_pages_html(pages: &[(PageIndexEntry, PageMeta)]) -> String {
+    let entries = pages
+        .iter()
+        .filter(|(_, meta)| meta.view_count > 0)
+        .map(|(entry, meta)| PageIndexEntry {
+            view_count: meta.view_count,
+            ..entry.clone()
+        })
+        .collect();
+    let entries = most_popular_entries(entries, INDEX_POPULAR_PAGES_LIMIT);
+    if entries.is_empty() {
+        return String::new();
+    }
+    let mut items = String::new();
+    for entry in entries {
+        let display_title = if entry.seo.title.is_empty() {
+            &entry.seo.seo_title
+        } else {
+            &entry.seo.title
+        };
+        let url = build_page_url(&entry.page_id, &entry.seo.seo_title);
+        items.push_str(&format!(
+            "<li><a href=\"{}\">{}</a><span class=\"view-count\">{} 次浏览</span></li>",
+            escape_html_attr(&url),
+            escape_html(display_title),
+            format_number(entry.view_count)
+        ));
+    }
+    format!(
+        "<aside class=\"popular\" aria-label=\"热门页面\"><h2>热门页面</h2><ol>{}</ol></aside>",
+        items
+    )
+}
+
+/// 首页最近更新区块，只列出公开页面，更新时间取自已读取的 meta
+fn render_recent_updates_html(pages: &[(PageIndexEntry, PageMeta)]) -> String {
+    let entries = pages
+        .iter()
+        .map(|(entry, meta)| PageIndexEntry {
+            updated_at: meta.updated_at,
+            ..entry.clone()
+        })
+        .collect();
+    let entries = recent_update_entries(entries, INDEX_RECENT_UPDATES_LIMIT);
+    if entries.is_empty() {
+        return String::new();
+    }
+    let mut items = String::new();
+    for entry in entries {
+        let display_title = if entry.seo.title.is_empty() {
+            &entry.seo.seo_title
+        } else {
+            &entry.seo.title
+        };
+        let url = build_page_url(&entry.page_id, &entry.seo.seo_title);
+        items.push_str(&format!(
+            "<li><a href=\"{}\">{}</a><span class=\"updated-at\">{}</span></li>",
+            escape_html_attr(&url),
+            escape_html(display_title),
+            escape_html(&format_display_timestamp(entry.updated_at))
+        ));
+    }
+    format!(
+        "<aside class=\"recent-updates\" aria-label=\"最近更新\"><h2>最近更新</h2><ol>{}</ol></aside>",
+        items
+    )
+}
+
+pub fn render_404_html() -> Result<String> {
+    let header_html = std::fs::read_to_string("front/header.html")
+        .context("read front/header.html template")?;
+    let header_html = replace_asset_placeholders(header_html);
+    let template = std::fs::read_to_string("front/404.html")
+        .context("read front/404.html template")?;
+    let template = replace_asset_placeholders(template);
+    let rendered = replace_template(&template, &[("site_header", &header_html)])?;
+    Ok(rendered)
+}
+
+pub(crate) fn replace_template(template: &str, values: &[(&str, &str)]) -> Result<String> {
+    let mut out = template.to_string();
+    for (key, value) in values {
+        let placeholder = format!("{{{{{}}}}}", key);
+        if !out.contains(&placeholder) {
+            bail!("template missing placeholder {placeholder}");
+        }
+        out = out.replace(&placeholder, value);
+    }
+    Ok(out)
+}
+
+pub fn render_page_html(meta: &PageMeta, html: &str) -> String {
+    let title = if meta.seo.title.is_empty() {
+        &meta.seo.seo_title
+    } else {
+        &meta.seo.title
+    };
+    let mut rendered = inject_seo_meta(html, title, &meta.seo);
+    if let Some(close_start) = find_bytes_ci(rendered.as_bytes(), 0, b"</head") {
+        let view_count_meta = format!("<meta name=\"view-count\" content=\"{}\">", meta.view_count);
+        rendered.insert_str(close_start, &view_count_meta);
+    }
+    if !is_page_indexable(meta) {
+        rendered = inject_robots_noindex(&rendered);
+    }
+    let Some(snippet) = extra_head_html(meta) else {
+        return rendered;
+    };
+    match sanitize_extra_head_html(snippet)
+        .and_then(|snippet| append_extra_head_html(&rendered, &snippet))
+    {
+        Ok(out) => out,
+        Err(err) => {
+            warn!("skip extra.head_html for page {}: {:#}", meta.page_uid, err);
+            rendered
+        }
+    }
+}
+
+/// `PageMeta::extra` 中存放页面自定义 head 片段的键
+pub const EXTRA_HEAD_HTML_KEY: &str = "head_html";
+
+pub fn extra_head_html(meta: &PageMeta) -> Option<&str> {
+    meta.extra
+        .get(EXTRA_HEAD_HTML_KEY)
+        .and_then(|value| value.as_str())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+/// 校验页面自定义 head 片段
+/// - 片段本身必须通过 `validate_html`
+/// - 默认禁止 `<script>`，设置 `ALLOW_EXTRA_HEAD_SCRIPTS=1` 后放行
+pub fn sanitize_extra_head_html(snippet: &str) -> Result<String> {
+    let trimmed = snippet.trim();
+    if trimmed.is_empty() {
+        bail!("extra.head_html is empty");
+    }
+    let allow_scripts = std::env::var("ALLOW_EXTRA_HEAD_SCRIPTS")
+        .map(|value| value.trim() == "1")
+        .unwrap_or(false);
+    if !allow_scripts && find_bytes_ci(trimmed.as_bytes(), 0, b"<script").is_some() {
+        bail!(
+            "extra.head_html must not contain <script> (set ALLOW_EXTRA_HEAD_SCRIPTS=1 to allow)"
+        );
+    }
+    validate_html(trimmed).context("extra.head_html is not valid html")?;
+    Ok(trimmed.to_string())
+}
+
+/// 将自定义片段追加到 `</head>` 之前，并对拼接后的完整页面再做一次校验
+pub fn append_extra_head_html(html: &str, snippet: &str) -> Result<String> {
+    let Some(close_start) = find_bytes_ci(html.as_bytes(), 0, b"</head") else {
+        bail!("page html has no </head> to append extra.head_html");
+    };
+    let mut out = String::with_capacity(html.len() + snippet.len());
+    out.push_str(&html[..close_start]);
+    out.push_str(snippet);
+    out.push_str(&html[close_start..]);
+    validate_html(&out).context("page html with extra.head_html is not valid")?;
+    Ok(out)
+}
+
+/// `PageMeta::extra` 中页面分类的键，存在时面包屑为 首页 → 分类 → 标题
+pub const EXTRA_CATEGORY_KEY: &str = "category";
+
+/// 面包屑中的一级，`url` 为空表示不可点击（分类、当前页面）
+#[derive(Debug, Clone)]
+pub struct Breadcrumb {
+    pub name: String,
+    pub url: Option<String>,
+}
+
+pub fn build_breadcrumbs(meta: &PageMeta, base_url: &str) -> Vec<Breadcrumb> {
+    let base = normalize_base_url(base_url);
+    let mut crumbs = vec![Breadcrumb {
+        name: "首页".to_string(),
+        url: Some(format!("{}/", base)),
+    }];
+    if let Some(category) = meta
+        .extra
+        .get(EXTRA_CATEGORY_KEY)
+        .and_then(|value| value.as_str())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        crumbs.push(Breadcrumb {
+            name: category.to_string(),
+            url: None,
+        });
+    }
+    let title = if meta.seo.title.is_empty() {
+        &meta.seo.seo_title
+    } else {
+        &meta.seo.title
+    };
+    crumbs.push(Breadcrumb {
+        name: title.clone(),
+        url: Some(format!(
+            "{}{}",
+            base,
+            build_page_url(&meta.page_uid, &meta.seo.seo_title)
+        )),
+    });
+    crumbs
+}
+
+/// 使用 `front/breadcrumb.html` 片段渲染可见面包屑，主题可自行修改该片段的结构与样式
+pub fn render_breadcrumb_html(crumbs: &[Breadcrumb]) -> Result<String> {
+    let template = std::fs::read_to_string("front/breadcrumb.html")
+        .context("read front/breadcrumb.html template")?;
+    let mut items = String::new();
+    for (position, crumb) in crumbs.iter().enumerate() {
+        let name = escape_html(&crumb.name);
+        if position + 1 == crumbs.len() {
+            items.push_str(&format!("<li aria-current=\"page\">{}</li>", name));
+        } else if let Some(url) = &crumb.url {
+            items.push_str(&format!(
+                "<li><a href=\"{}\">{}</a></li>",
+                escape_html_attr(url),
+                name
+            ));
+        } else {
+            items.push_str(&format!("<li>{}</li>", name));
+        }
+    }
+    replace_template(&template, &[("breadcrumb_items", &items)])
+}
+
+/// schema.org `BreadcrumbList`，`<` `>` `&` 转义为 `\uXXXX`，标题中的 `</script>` 无法提前闭合标签
+pub fn breadcrumb_json_ld(crumbs: &[Breadcrumb]) -> String {
+    let items: Vec<serde_json::Value> = crumbs
+        .iter()
+        .enumerate()
+        .map(|(position, crumb)| {
+            let mut item = serde_json::json!({
+                "@type": "ListItem",
+                "position": position + 1,
+                "name": crumb.name,
+            });
+            if let Some(url) = &crumb.url {
+                item["item"] = url.clone().into();
+            }
+            item
+        })
+        .collect();
+    json_ld_script(&serde_json::json!({
+        "@context": "https://schema.org",
+        "@type": "BreadcrumbList",
+        "itemListElement": items,
+    }))
+}
+
+/// schema.org `Article`：`datePublished` 取 `page_published_at`，`dateModified` 取 `updated_at`
+pub fn article_json_ld(meta: &PageMeta, page_url: &str) -> String {
+    let mut article = serde_json::json!({
+        "@context": "https://schema.org",
+        "@type": "Article",
+        "headline": meta.seo.seo_title,
+        "datePublished": format_unix_timestamp(page_published_at(meta)),
+        "dateModified": format_unix_timestamp(meta.updated_at),
+    });
+    if !meta.seo.description.is_empty() {
+        article["description"] = meta.seo.description.clone().into();
+    }
+    if !page_url.is_empty() {
+        article["url"] = page_url.into();
+    }
+    json_ld_script(&article)
+}
+
+/// 页面的发布日期：首次发布时间，早于该字段的旧页面回退为 `created_at`
+pub fn page_published_at(meta: &PageMeta) -> i64 {
+    meta.published_at.unwrap_or(meta.created_at)
+}
+
+/// `<` `>` `&` 转义为 `\uXXXX` 后包进 `<script type="application/ld+json">`
+fn json_ld_script(value: &serde_json::Value) -> String {
+    let json = value
+        .to_string()
+        .replace('<', "\\u003c")
+        .replace('>', "\\u003e")
+        .replace('&', "\\u0026");
+    format!("<script type=\"application/ld+json\">{}</script>", json)
+}
+
+/// 两级 `BreadcrumbList`：站点首页 → 当前页面
+pub fn build_breadcrumb_json_ld(
+    site_name: &str,
+    site_url: &str,
+    page_title: &str,
+    page_url: &str,
+) -> String {
+    breadcrumb_json_ld(&[
+        Breadcrumb {
+            name: site_name.to_string(),
+            url: Some(site_url.to_string()),
+        },
+        Breadcrumb {
+            name: page_title.to_string(),
+            url: Some(page_url.to_string()),
+        },
+    ])
+}
+
+/// 分享链接查询参数的编码集，与 `encodeURIComponent` 一致保留 `-_.!~*'()`
+const SHARE_QUERY_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'!')
+    .remove(b'~')
+    .remove(b'*')
+    .remove(b'\'')
+    .remove(b'(')
+    .remove(b')');
+
+/// 使用 `front/share.html` 片段渲染分享栏
+/// - `{{page_url}}` / `{{page_title}}`：属性转义后的原值
+/// - `{{page_url_encoded}}` / `{{page_title_encoded}}`：百分号编码，用于拼接分享链接
+pub fn render_share_html(page_url: &str, page_title: &str) -> Result<String> {
+    let template =
+        std::fs::read_to_string("front/share.html").context("read front/share.html template")?;
+    let url_encoded = utf8_percent_encode(page_url, SHARE_QUERY_ENCODE_SET).to_string();
+    let title_encoded = utf8_percent_encode(page_title, SHARE_QUERY_ENCODE_SET).to_string();
+    replace_template(
+        &template,
+        &[
+            ("page_url_encoded", &url_encoded),
+            ("page_title_encoded", &title_encoded),
+            ("page_url", &escape_html_attr(page_url)),
+            ("page_title", &escape_html_attr(page_title)),
+        ],
+    )
+}
+
+/// 正文末尾展示的相关页面数量
+pub const RELATED_PAGES_LIMIT: usize = 5;
+
+/// 正文末尾的相关页面，只保留公开页面；查询失败时记录日志并返回空列表
+pub async fn load_related_pages(store: &dyn PageStoreTrait, page_id: &str) -> Vec<PageIndexEntry> {
+    // 多取一倍候选，过滤掉未公开页面后尽量凑满
+    let entries = match store
+        .get_related_pages(page_id, RELATED_PAGES_LIMIT * 2)
+        .await
+    {
+        Ok(entries) => entries,
+        Err(err) => {
+            warn!("load related pages for {} failed: {:#}", page_id, err);
+            return Vec::new();
+        }
+    };
+    match load_public_pages(store, entries).await {
+        Ok(pages) => pages
+            .into_iter()
+            .map(|(entry, _)| entry)
+            .take(RELATED_PAGES_LIMIT)
+            .collect(),
+        Err(err) => {
+            warn!("load related pages for {} failed: {:#}", page_id, err);
+            Vec::new()
+        }
+    }
+}
+
+/// 使用 `front/related.html` 片段渲染相关页面区块，没有相关页面时返回空字符串
+pub fn render_related_pages_html(entries: &[PageIndexEntry]) -> Result<String> {
+    if entries.is_empty() {
+        return Ok(String::new());
+    }
+    let template = std::fs::read_to_string("front/related.html")
+        .context("read front/related.html template")?;
+    let mut items = String::new();
+    for entry in entries {
+        let display_title = if entry.seo.title.is_empty() {
+            &entry.seo.seo_title
+        } else {
+            &entry.seo.title
+        };
+        let url = build_page_url(&entry.page_id, &entry.seo.seo_title);
+        items.push_str(&format!(
+            "<li><a href=\"{}\">{}</a></li>",
+            escape_html_attr(&url),
+            escape_html(display_title)
+        ));
+    }
+    replace_template(&template, &[("related_items", &items)])
+}
+
+/// `PageMeta::extra` 中单页评论开关的键，值为 `false` 时不嵌入评论
+pub const EXTRA_COMMENTS_KEY: &str = "comments";
+
+pub fn page_comments_enabled(meta: &PageMeta) -> bool {
+    meta.extra
+        .get(EXTRA_COMMENTS_KEY)
+        .and_then(|value| value.as_bool())
+        .unwrap_or(true)
+}
+
+/// 评论服务的嵌入片段，所有配置值都经过属性转义
+pub fn comments_embed_html(config: &CommentsConfig) -> String {
+    let embed = match config.provider {
+        CommentsProvider::Giscus => format!(
+            "<div class=\"giscus\"></div><script src=\"https://giscus.app/client.js\" data-repo=\"{}\" data-repo-id=\"{}\" data-category=\"{}\" data-category-id=\"{}\" data-mapping=\"{}\" data-strict=\"0\" data-reactions-enabled=\"1\" data-emit-metadata=\"0\" data-input-position=\"bottom\" data-theme=\"{}\" data-lang=\"{}\" crossorigin=\"anonymous\" async></script>",
+            escape_html_attr(&config.repo),
+            escape_html_attr(&config.repo_id),
+            escape_html_attr(&config.category),
+            escape_html_attr(&config.category_id),
+            escape_html_attr(&config.mapping),
+            escape_html_attr(&config.theme),
+            escape_html_attr(&config.lang),
+        ),
+        CommentsProvider::Utterances => format!(
+            "<script src=\"https://utteranc.es/client.js\" repo=\"{}\" issue-term=\"{}\" theme=\"{}\" crossorigin=\"anonymous\" async></script>",
+            escape_html_attr(&config.repo),
+            escape_html_attr(&config.mapping),
+            escape_html_attr(&config.theme),
+        ),
+    };
+    format!("<section class=\"solin-comments\">{}</section>", embed)
+}
+
+/// 千位分隔的整数，如 `1234567` → `1,234,567`
+pub fn format_number(n: u64) -> String {
+    let digits = n.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(digit);
+    }
+    out
+}
+
+/// 页面顶部的阅读计数，紧跟在面包屑之后，样式内联以免依赖页面自身的 CSS
+fn render_view_count_html(view_count: u64) -> String {
+    format!(
+        "<p class=\"solin-view-count\" style=\"max-width: 960px; margin: 0 auto 12px; padding: 0 16px; font-size: 13px; color: rgba(38, 38, 36, 0.68);\">📖 {} 次阅读</p>",
+        format_number(view_count)
+    )
+}
+
+/// 页面路由输出：在 SEO 注入结果上追加面包屑与阅读计数（`<body>` 开头）、面包屑与文章 JSON-LD（`</head>` 前），
+/// 以及分享栏与公开页面的评论区（`</body>` 前）
+pub fn render_served_page(
+    meta: &PageMeta,
+    html: &str,
+    base_url: &str,
+    related: &[PageIndexEntry],
+) -> String {
+    let rendered = render_page_html(meta, html);
+    let crumbs = build_breadcrumbs(meta, base_url);
+    let home_url = crumbs
+        .first()
+        .and_then(|home| home.url.as_deref())
+        .unwrap_or_default();
+    let page = crumbs.last();
+    let page_url = page
+        .and_then(|page| page.url.as_deref())
+        .unwrap_or_default();
+    let page_title = page.map(|page| page.name.as_str()).unwrap_or_default();
+    // 结构化数据只含 站点名（`SITE_TITLE`）→ 标题 两级，分类没有地址，只出现在可见面包屑中
+    let breadcrumb_ld = build_breadcrumb_json_ld(
+        &resolve_site_title_from_env(),
+        home_url,
+        page_title,
+        page_url,
+    );
+    let mut out = match find_bytes_ci(rendered.as_bytes(), 0, b"</head") {
+        Some(close_start) => format!(
+            "{}{}{}{}",
+            &rendered[..close_start],
+            breadcrumb_ld,
+            article_json_ld(meta, page_url),
+            &rendered[close_start..]
+        ),
+        None => rendered,
+    };
+    let mut header = match render_breadcrumb_html(&crumbs) {
+        Ok(nav) => nav,
+        Err(err) => {
+            warn!("skip breadcrumb for page {}: {:#}", meta.page_uid, err);
+            String::new()
+        }
+    };
+    header.push_str(&render_view_count_html(meta.view_count));
+    let body_start = find_bytes_ci(out.as_bytes(), 0, b"<body")
+        .and_then(|pos| find_tag_end(out.as_bytes(), pos + 5));
+    if let Some(end) = body_start {
+        out.insert_str(end + 1, &header);
+    }
+    let mut tail = String::new();
+    match render_related_pages_html(related) {
+        Ok(section) => tail.push_str(&section),
+        Err(err) => warn!("skip related pages for page {}: {:#}", meta.page_uid, err),
+    }
+    // 面包屑最后一项即当前页面的标题与规范 URL
+    if resolve_share_links_enabled_from_env()
+        && let Some(page) = crumbs.last()
+    {
+        match render_share_html(page.url.as_deref().unwrap_or_default(), &page.name) {
+            Ok(share) => tail.push_str(&share),
+            Err(err) => warn!("skip share links for page {}: {:#}", meta.page_uid, err),
+        }
+    }
+    if is_page_public(meta)
+        && page_comments_enabled(meta)
+        && let Some(config) = resolve_comments_config_from_env()
+    {
+        tail.push_str(&comments_embed_html(&config));
+    }
+    match find_bytes_ci(out.as_bytes(), 0, b"</body") {
+        Some(close_start) => out.insert_str(close_start, &tail),
+        None => out.push_str(&tail),
+    }
+    out
+}
+
+/// 追加片段的插入位置
+#[derive(Debug, Clone, Copy)]
+pub enum SectionPosition<'a> {
+    /// `</body>` 之前，缺少 `</body>` 时追加到末尾
+    BeforeEndOfBody,
+    /// 指定 id 元素的闭合标签之后
+    AfterElement(&'a str),
+}
+
+/// 将片段拼接进页面 HTML，不做校验，调用方需对结果执行 `validate_html`
+pub fn insert_html_section(
+    html: &str,
+    fragment: &str,
+    position: SectionPosition<'_>,
+) -> Result<String> {
+    let insert_at = match position {
+        SectionPosition::BeforeEndOfBody => {
+            find_bytes_ci(html.as_bytes(), 0, b"</body").unwrap_or(html.len())
+        }
+        SectionPosition::AfterElement(id) => find_element_end_by_id(html, id)
+            .ok_or_else(|| anyhow::anyhow!("element #{} not found", id))?,
+    };
+    let mut out = String::with_capacity(html.len() + fragment.len());
+    out.push_str(&html[..insert_at]);
+    out.push_str(fragment);
+    out.push_str(&html[insert_at..]);
+    Ok(out)
+}
+
+/// 返回 id 匹配的元素闭合标签之后的位置；空元素与自闭合标签返回起始标签之后
+fn find_element_end_by_id(html: &str, id: &str) -> Option<usize> {
+    let bytes = html.as_bytes();
+    let mut index = 0usize;
+    let (name, open_end) = loop {
+        let start = index + html[index..].find('<')?;
+        let tag_end = find_tag_end(bytes, start + 1)?;
+        index = tag_end + 1;
+        if matches!(bytes.get(start + 1), Some(b'/' | b'!')) {
+            continue;
+        }
+        let Some((name, _)) = parse_tag_name_ci(bytes, start + 1) else {
+            continue;
+        };
+        if find_attr_value(&html[start..=tag_end], "id").as_deref() == Some(id) {
+            break (name.to_ascii_lowercase(), tag_end);
+        }
+    };
+    if bytes[open_end - 1] == b'/' || is_void_element(&name) {
+        return Some(open_end + 1);
+    }
+    // 只统计同名标签的嵌套深度，存储的 HTML 已通过 validate_html，标签必然配对
+    let mut depth = 1usize;
+    while let Some(found) = html[index..].find('<') {
+        let start = index + found;
+        let tag_end = find_tag_end(bytes, start + 1)?;
+        index = tag_end + 1;
+        let Some((tag_name, _)) = parse_tag_name_ci(bytes, start + 1) else {
+            continue;
+        };
+        if !tag_name.eq_ignore_ascii_case(&name) {
+            continue;
+        }
+        if bytes[start + 1] == b'/' {
+            depth -= 1;
+            if depth == 0 {
+                return Some(index);
+            }
+        } else if bytes[tag_end - 1] != b'/' {
+            depth += 1;
+        }
+    }
+    None
+}
+
+pub fn markdown_to_html(markdown: &str) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_FOOTNOTES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+
+    let parser = Parser::new_ext(markdown, options);
+    let mut output = String::new();
+    html::push_html(&mut output, parser);
+    output
+}
+
+pub fn render_markdown_page(markdown: &str) -> Result<String> {
+    let markdown_html = markdown_to_html(markdown);
+    let header_html = std::fs::read_to_string("front/header.html")
+        .context("read front/header.html template")?;
+    let header_html = replace_asset_placeholders(header_html);
+    // 指纹在渲染时写入页面 HTML，`public/` 文件更新后可用 `rerender_markdown_pages` 刷新
+    let template = std::fs::read_to_string("front/markdown.html")
+        .context("read front/markdown.html template")?;
+    let template = replace_asset_placeholders(template);
+    let rendered = replace_template(
+        &template,
+        &[("site_header", &header_html), ("markdown_html", &markdown_html)],
+    )?;
+    Ok(rendered)
+}
+
+/// `rerender_markdown_page` 的处理结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RerenderOutcome {
+    /// 重新渲染的 HTML 与已保存的不同，已写入
+    Updated,
+    /// 重新渲染的 HTML 与已保存的相同，未写入
+    Unchanged,
+    /// 页面没有 Markdown 源文件
+    NoMarkdown,
+}
+
+/// 用当前的 `front/markdown.html` 与 `front/header.html` 重新渲染已保存的 Markdown 源文件；
+/// 只替换 HTML，`created_at`、`page_uid` 与 `updated_at` 均保持不变，渲染或校验失败时不写入
+pub async fn rerender_markdown_page(
+    store: &dyn PageStoreTrait,
+    page_id: &str,
+) -> Result<RerenderOutcome> {
+    let Some(markdown) = store.load_page_markdown(page_id).await? else {
+        return Ok(RerenderOutcome::NoMarkdown);
+    };
+    let rendered = render_markdown_page(&markdown)?;
+    validate_html(&rendered).context("validate rendered html")?;
+    if store.get_page_html(page_id).await? == rendered {
+        return Ok(RerenderOutcome::Unchanged);
+    }
+    store.update_page_html(page_id, &rendered, false).await?;
+    Ok(RerenderOutcome::Updated)
+}
+
+/// 将 HTML 粗略转换为 Markdown，用于导出没有 Markdown 源文件的页面
+/// - 仅转换 `<body>` 内容（无 body 时转换全文）
+/// - 跳过 script/style/head 等不可见内容
+/// - 支持标题、段落、列表、链接、图片、强调、代码块、引用、分割线
+pub fn html_to_markdown(html: &str) -> String {
+    let bytes = html.as_bytes();
+    let (start, end) = find_body_range(bytes).unwrap_or((0, bytes.len()));
+    let mut out = String::new();
+    let mut index = start;
+    let mut text_from = start;
+    let mut skip_until: Option<String> = None;
+    let mut lists: Vec<(bool, usize)> = Vec::new();
+    let mut link_hrefs: Vec<Option<String>> = Vec::new();
+    let mut in_pre = false;
+
+    while index < end {
+        if bytes[index] != b'<' {
+            index += 1;
+            continue;
+        }
+
+        if index + 3 < end
+            && bytes[index + 1] == b'!'
+            && bytes[index + 2] == b'-'
+            && bytes[index + 3] == b'-'
+        {
+            if skip_until.is_none() {
+                push_markdown_text(&mut out, &html[text_from..index], in_pre);
+            }
+            index = find_bytes_ci(bytes, index + 4, b"-->")
+                .map(|value| value + 3)
+                .unwrap_or(end)
+                .min(end);
+            text_from = index;
+            continue;
+        }
+
+        let closing = index + 1 < end && bytes[index + 1] == b'/';
+        let Some((name, after_name)) = parse_tag_name_ci(bytes, index + 1) else {
+            if index + 1 < end && bytes[index + 1] == b'!' {
+                if skip_until.is_none() {
+                    push_markdown_text(&mut out, &html[text_from..index], in_pre);
+                }
+                index = find_tag_end(bytes, index + 2)
+                    .map(|value| value + 1)
+                    .unwrap_or(end)
+                    .min(end);
+                text_from = index;
+                continue;
+            }
+            index += 1;
+            continue;
+        };
+        let Some(tag_end) = find_tag_end(bytes, after_name) else {
+            break;
+        };
+        let tag_end = tag_end.min(end - 1);
+        let name = name.to_ascii_lowercase();
+        let tag_html = &html[index..=tag_end];
+
+        if let Some(skip_name) = &skip_until {
+            if closing && *skip_name == name {
+                skip_until = None;
+            }
+            index = tag_end + 1;
+            text_from = index;
+            continue;
+        }
+
+        push_markdown_text(&mut out, &html[text_from..index], in_pre);
+        index = tag_end + 1;
+        text_from = index;
+
+        match (name.as_str(), closing) {
+            ("script" | "style" | "head" | "title" | "noscript" | "template", false)
+                if !tag_html.trim_end_matches('>').trim_end().ends_with('/') =>
+            {
+                skip_until = Some(name);
+            }
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", false) => {
+                ensure_blank_line(&mut out);
+                let level = name[1..].parse::<usize>().unwrap_or(1);
+                out.push_str(&"#".repeat(level));
+                out.push(' ');
+            }
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", true) => ensure_blank_line(&mut out),
+            (
+                "p" | "div" | "section" | "article" | "header" | "footer" | "main" | "nav"
+                | "aside" | "figure" | "table" | "tr",
+                _,
+            ) => ensure_blank_line(&mut out),
+            ("br", _) => out.push_str("  \n"),
+            ("hr", false) => {
+                ensure_blank_line(&mut out);
+                out.push_str("---");
+                ensure_blank_line(&mut out);
+            }
+            ("strong" | "b", _) => out.push_str("**"),
+            ("em" | "i", _) => out.push('*'),
+            ("del" | "s", _) => out.push_str("~~"),
+            ("code", _) if !in_pre => out.push('`'),
+            ("pre", false) => {
+                ensure_blank_line(&mut out);
+                out.push_str("```\n");
+                in_pre = true;
+            }
+            ("pre", true) => {
+                if !out.ends_with('\n') {
+                    out.push('\n');
+                }
+                out.push_str("```");
+                ensure_blank_line(&mut out);
+                in_pre = false;
+            }
+            ("blockquote", false) => {
+                ensure_blank_line(&mut out);
+                out.push_str("> ");
+            }
+            ("blockquote", true) => ensure_blank_line(&mut out),
+            ("ul" | "ol", false) => {
+                if lists.is_empty() {
+                    ensure_blank_line(&mut out);
+                }
+                lists.push((name == "ol", 0));
+            }
+            ("ul" | "ol", true) => {
+                lists.pop();
+                if lists.is_empty() {
+                    ensure_blank_line(&mut out);
+                }
+            }
+            ("li", false) => {
+                let depth = lists.len().max(1);
+                if !out.is_empty() && !out.ends_with('\n') {
+                    out.push('\n');
+                }
+                out.push_str(&"  ".repeat(depth - 1));
+                match lists.last_mut() {
+                    Some((true, counter)) => {
+                        *counter += 1;
+                        out.push_str(&format!("{}. ", counter));
+                    }
+                    _ => out.push_str("- "),
+                }
+            }
+            ("td" | "th", false) if !out.is_empty() && !out.ends_with(char::is_whitespace) => {
+                out.push(' ');
+            }
+            ("a", false) => {
+                let href = find_attr_value(tag_html, "href").filter(|value| !value.is_empty());
+                if href.is_some() {
+                    out.push('[');
+                }
+                link_hrefs.push(href);
+            }
+            ("a", true) => {
+                if let Some(Some(href)) = link_hrefs.pop() {
+                    out.push_str(&format!("]({})", href));
+                }
+            }
+            ("img", false) => {
+                if let Some(src) = find_attr_value(tag_html, "src") {
+                    let alt = find_attr_value(tag_html, "alt").unwrap_or_default();
+                    out.push_str(&format!("![{}]({})", alt, src));
+                }
+            }
+            _ => {}
+        }
+    }
+    if skip_until.is_none() && text_from < end {
+        push_markdown_text(&mut out, &html[text_from..end], in_pre);
+    }
+
+    let mut normalized = String::with_capacity(out.len());
+    let mut newline_run = 0usize;
+    for line in out.split('\n') {
+        if line.trim().is_empty() {
+            newline_run += 1;
+            if newline_run > 1 {
+                continue;
+            }
+            normalized.push('\n');
+            continue;
+        }
+        newline_run = 0;
+        normalized.push_str(line.trim_end_matches(' ').trim_end_matches('\t'));
+        if line.ends_with("  ") {
+            normalized.push_str("  ");
+        }
+        normalized.push('\n');
+    }
+    let trimmed = normalized.trim();
+    if trimmed.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n", trimmed)
+    }
+}
+
+fn find_body_range(bytes: &[u8]) -> Option<(usize, usize)> {
+    let body_start = find_bytes_ci(bytes, 0, b"<body")?;
+    let content_start = find_tag_end(bytes, body_start + 5)? + 1;
+    let close_start = find_bytes_ci(bytes, content_start, b"</body").unwrap_or(bytes.len());
+    Some((content_start, close_start))
+}
+
+fn ensure_blank_line(out: &mut String) {
+    let trimmed_len = out.trim_end_matches([' ', '\t']).len();
+    out.truncate(trimmed_len);
+    if out.is_empty() || out.ends_with("\n\n") {
+        return;
+    }
+    if out.ends_with('\n') {
+        out.push('\n');
+    } else {
+        out.push_str("\n\n");
+    }
+}
+
+fn push_markdown_text(out: &mut String, raw: &str, in_pre: bool) {
+    if raw.is_empty() {
+        return;
+    }
+    let decoded = decode_html_entities(raw);
+    if in_pre {
+        out.push_str(&decoded);
+        return;
+    }
+    let mut last_space = out.is_empty() || out.ends_with(char::is_whitespace);
+    for ch in decoded.chars() {
+        if ch.is_whitespace() {
+            if !last_space {
+                out.push(' ');
+                last_space = true;
+            }
+        } else {
+            out.push(ch);
+            last_space = false;
+        }
+    }
+}
+
+fn decode_html_entities(input: &str) -> String {
+    if !input.contains('&') {
+        return input.to_string();
+    }
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(pos) = rest.find('&') {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+        let Some(semi) = after.find(';').filter(|value| *value <= 10) else {
+            out.push('&');
+            rest = after;
+            continue;
+        };
+        let entity = &after[..semi];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" | "#39" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => {
+                if let Some(hex) = entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                {
+                    u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+                } else if let Some(dec) = entity.strip_prefix('#') {
+                    dec.parse::<u32>().ok().and_then(char::from_u32)
+                } else {
+                    None
+                }
+            }
+        };
+        match decoded {
+            Some(ch) => {
+                out.push(ch);
+                rest = &after[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn find_attr_value(tag_html: &str, name: &str) -> Option<String> {
+    let bytes = tag_html.as_bytes();
+    let lower = tag_html.to_ascii_lowercase();
+    let lower_bytes = lower.as_bytes();
+    let name_lower = name.to_ascii_lowercase();
+    let mut search_from = 0usize;
+    while let Some(found) = lower[search_from..].find(&name_lower) {
+        let pos = search_from + found;
+        search_from = pos + name_lower.len();
+        let boundary_before =
+            pos > 0 && (lower_bytes[pos - 1].is_ascii_whitespace() || lower_bytes[pos - 1] == b'/');
+        if !boundary_before {
+            continue;
+        }
+        let mut cursor = pos + name_lower.len();
+        while cursor < bytes.len() && bytes[cursor].is_ascii_whitespace() {
+            cursor += 1;
+        }
+        if cursor >= bytes.len() || bytes[cursor] != b'=' {
+            continue;
+        }
+        cursor += 1;
+        while cursor < bytes.len() && bytes[cursor].is_ascii_whitespace() {
+            cursor += 1;
+        }
+        if cursor >= bytes.len() {
+            return None;
+        }
+        let value = match bytes[cursor] {
+            quote @ (b'"' | b'\'') => {
+                let value_start = cursor + 1;
+                let value_end = tag_html[value_start..]
+                    .find(quote as char)
+                    .map(|value| value_start + value)?;
+                &tag_html[value_start..value_end]
+            }
+            _ => {
+                let value_end = tag_html[cursor..]
+                    .find(|ch: char| ch.is_whitespace() || ch == '>')
+                    .map(|value| cursor + value)
+                    .unwrap_or(tag_html.len());
+                tag_html[cursor..value_end].trim_end_matches('/')
+            }
+        };
+        return Some(decode_html_entities(value.trim()));
+    }
+    None
+}
+
+/// 推送前的安全提示：脚本、内嵌框架、内联事件与 `javascript:` 链接，只提示不拦截
+pub fn scan_html_security(html: &str) -> Vec<String> {
+    let event_attr = Regex::new(r#"(?i)[\s/"']on[a-z]+\s*="#).expect("event attr regex");
+    let bytes = html.as_bytes();
+    let mut warnings = Vec::new();
+    let mut index = 0usize;
+    while let Some(found) = html[index..].find('<') {
+        let start = index + found;
+        let Some(tag_end) = find_tag_end(bytes, start + 1) else {
+            break;
+        };
+        index = tag_end + 1;
+        if matches!(bytes.get(start + 1), Some(b'/' | b'!')) {
+            continue;
+        }
+        let Some((name, _)) = parse_tag_name_ci(bytes, start + 1) else {
+            continue;
+        };
+        let name = name.to_ascii_lowercase();
+        let tag_html = &html[start..=tag_end];
+        if matches!(name.as_str(), "script" | "iframe" | "object" | "embed") {
+            warnings.push(format!("<{}> at index {}", name, start));
+        }
+        if let Some(found) = event_attr.find(tag_html) {
+            let attr = found
+                .as_str()
+                .trim_matches(|ch: char| !ch.is_ascii_alphabetic());
+            warnings.push(format!(
+                "inline event handler {} on <{}> at index {}",
+                attr, name, start
+            ));
+        }
+        for attr in ["href", "src", "action"] {
+            let is_javascript = find_attr_value(tag_html, attr)
+                .is_some_and(|value| value.to_ascii_lowercase().starts_with("javascript:"));
+            if is_javascript {
+                warnings.push(format!(
+                    "javascript: {} on <{}> at index {}",
+                    attr, name, start
+                ));
+            }
+        }
+    }
+    warnings
+}
+
+/// 读取 HTML 自带的 `<title>` 与 description / keywords meta，缺失的字段为空
+pub fn extract_seo_meta(html: &str) -> SeoMeta {
+    let bytes = html.as_bytes();
+    let mut seo = SeoMeta {
+        title: String::new(),
+        seo_title: String::new(),
+        description: String::new(),
+        keywords: None,
+        extra: Default::default(),
+        structured_data: None,
+    };
+    if let Some(open) = find_bytes_ci(bytes, 0, b"<title")
+        && let Some(open_end) = find_tag_end(bytes, open + 6)
+        && let Some(close) = find_bytes_ci(bytes, open_end + 1, b"</title")
+    {
+        seo.title = decode_html_entities(html[open_end + 1..close].trim());
+        seo.seo_title = seo.title.clone();
+    }
+    let mut index = 0usize;
+    while let Some(start) = find_bytes_ci(bytes, index, b"<meta") {
+        let Some(tag_end) = find_tag_end(bytes, start + 5) else {
+            break;
+        };
+        index = tag_end + 1;
+        let tag_html = &html[start..=tag_end];
+        let content = find_attr_value(tag_html, "content").unwrap_or_default();
+        if is_meta_named(tag_html, "description") {
+            seo.description = content;
+        } else if is_meta_named(tag_html, "keywords") {
+            let keywords: Vec<String> = content
+                .split([',', '，'])
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+                .collect();
+            seo.keywords = Some(keywords).filter(|keywords| !keywords.is_empty());
+        }
+    }
+    seo
+}
+
+/// 搜索结果展示的常见截断长度（按字符计）
+const SEO_TITLE_MAX_CHARS: usize = 60;
+const SEO_DESCRIPTION_MIN_CHARS: usize = 50;
+const SEO_DESCRIPTION_MAX_CHARS: usize = 160;
+
+/// SEO 质量提示：标题与描述缺失或长度不合适
+pub fn validate_seo_meta(seo: &SeoMeta) -> Vec<String> {
+    seo_meta_issues(seo)
+        .into_iter()
+        .map(|(_, message)| message)
+        .collect()
+}
+
+/// `suggest_description` 优先在该字数之后的句末截断
+const SUGGESTED_DESCRIPTION_MIN_CHARS: usize = 150;
+
+/// 标题中的词按正文出现次数的该倍数计分
+const TITLE_TERM_WEIGHT: usize = 3;
+
+/// 已被其它页面使用的关键词按该倍数加权，优先沿用全站现有用词
+const SITE_KEYWORD_WEIGHT: usize = 2;
+
+/// 不在全站关键词中的候选词至少需要的加权出现次数
+const MIN_TERM_HITS: usize = 2;
+
+/// 不作为候选关键词的常见英文虚词
+const ENGLISH_STOP_WORDS: &[&str] = &[
+    "about", "after", "also", "and", "are", "but", "can", "for", "from", "has", "have", "how",
+    "into", "its", "not", "one", "our", "that", "the", "their", "then", "there", "these", "this",
+    "use", "was", "were", "what", "when", "which", "will", "with", "you", "your",
+];
+
+/// 含这些字的中文二元组不作为候选关键词
+const CJK_STOP_CHARS: &str = "的了是在和与及或也就都而这那你我他她它们个有为一不以中上到对";
+
+/// `suggest_keywords` 的单个候选
+#[derive(Debug, Clone)]
+pub struct KeywordSuggestion {
+    pub keyword: String,
+    /// 加权出现次数，标题按 `TITLE_TERM_WEIGHT` 计，全站关键词再乘 `SITE_KEYWORD_WEIGHT`
+    pub score: usize,
+    /// 已使用该关键词的页面数，0 表示全站尚未使用
+    pub site_pages: usize,
+}
+
+/// 按词频从草稿中提取候选关键词：英文按单词、中文按相邻二字计数，
+/// 全站已有关键词按子串计数并加权；返回按得分降序的前 `limit` 个
+pub fn suggest_keywords(
+    title: &str,
+    text: &str,
+    site_keywords: &BTreeMap<String, usize>,
+    limit: usize,
+) -> Vec<KeywordSuggestion> {
+    let title = title.to_lowercase();
+    let text = text.to_lowercase();
+    let mut hits: HashMap<String, usize> = HashMap::new();
+    for term in candidate_terms(&title) {
+        *hits.entry(term).or_insert(0) += TITLE_TERM_WEIGHT;
+    }
+    for term in candidate_terms(&text) {
+        *hits.entry(term).or_insert(0) += 1;
+    }
+    for keyword in site_keywords.keys() {
+        let count = title.matches(keyword.as_str()).count() * TITLE_TERM_WEIGHT
+            + text.matches(keyword.as_str()).count();
+        if count > 0 {
+            hits.insert(keyword.clone(), count);
+        }
+    }
 
-pub fn build_page_url(page_id: &str, seo_title: &str) -> String {
-    if seo_title.is_empty() {
-        format!("/pages/{}", page_id)
-    } else {
-        format!("/pages/{}+{}", seo_title, page_id)
+    let mut ranked: Vec<KeywordSuggestion> = hits
+        .into_iter()
+        .filter_map(|(keyword, count)| {
+            let site_pages = site_keywords.get(&keyword).copied().unwrap_or(0);
+            if site_pages == 0 && count < MIN_TERM_HITS {
+                return None;
+            }
+            let weight = if site_pages > 0 {
+                SITE_KEYWORD_WEIGHT
+            } else {
+                1
+            };
+            Some(KeywordSuggestion {
+                keyword,
+                score: count * weight,
+                site_pages,
+            })
+        })
+        .collect();
+    ranked.sort_by(|left, right| {
+        right
+            .score
+            .cmp(&left.score)
+            .then_with(|| left.keyword.cmp(&right.keyword))
+    });
+
+    // 已被选中的全站关键词包含的片段（如“异步编程”中的“编程”）不再单独列出
+    let mut selected: Vec<KeywordSuggestion> = Vec::new();
+    for suggestion in ranked {
+        if selected.len() >= limit {
+            break;
+        }
+        let covered = selected.iter().any(|chosen| {
+            chosen.site_pages > 0 && chosen.keyword.contains(suggestion.keyword.as_str())
+        });
+        if !covered {
+            selected.push(suggestion);
+        }
     }
+    selected
 }
 
-pub fn parse_page_id_from_slug(slug: &str) -> Option<String> {
-    let mut parts = slug.rsplitn(2, '+');
-    let page_id = parts.next()?;
-    if page_id.is_empty() {
-        return None;
+/// 拆出候选词：长度至少 2 的非纯数字英文单词，以及不含虚字的中文二元组
+fn candidate_terms(text: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    let mut word = String::new();
+    let mut previous_cjk: Option<char> = None;
+    for ch in text.chars().chain(std::iter::once(' ')) {
+        if is_cjk_char(ch) {
+            if let Some(previous) = previous_cjk
+                && !CJK_STOP_CHARS.contains(previous)
+                && !CJK_STOP_CHARS.contains(ch)
+            {
+                terms.push(format!("{}{}", previous, ch));
+            }
+            previous_cjk = Some(ch);
+        } else {
+            previous_cjk = None;
+        }
+        if ch.is_alphanumeric() && !is_cjk_char(ch) {
+            word.push(ch);
+            continue;
+        }
+        let is_term = word.chars().count() >= 2
+            && !word.chars().all(|letter| letter.is_ascii_digit())
+            && !ENGLISH_STOP_WORDS.contains(&word.as_str());
+        if is_term {
+            terms.push(std::mem::take(&mut word));
+        } else {
+            word.clear();
+        }
     }
-    Some(page_id.to_string())
+    terms
 }
 
-pub fn render_index_html(store: &PageStore) -> Result<String> {
-    let header_html = std::fs::read_to_string("front/header.html")
-        .context("read front/header.html template")?;
-    let template = std::fs::read_to_string("front/index.html")
-        .context("read front/index.html template")?;
-    let entries = store.list_page_entries().context("list page entries")?;
-    let mut pages = Vec::new();
-    for entry in entries {
-        let meta = store
-            .get_page_meta(&entry.page_id)
-            .with_context(|| format!("load page meta {}", entry.page_id))?;
-        pages.push((entry, meta));
+/// 由正文生成描述候选：合并空白后不超过 160 字原样返回，
+/// 否则优先在 150–160 字之间的句末截断，其次在空格处截断并补省略号
+pub fn suggest_description(text: &str) -> String {
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let chars: Vec<char> = collapsed.chars().collect();
+    if chars.len() <= SEO_DESCRIPTION_MAX_CHARS {
+        return collapsed;
     }
-    pages.sort_by(|(left_entry, left_meta), (right_entry, right_meta)| {
-        right_meta
-            .updated_at
-            .cmp(&left_meta.updated_at)
-            .then_with(|| right_meta.created_at.cmp(&left_meta.created_at))
-            .then_with(|| right_entry.page_id.cmp(&left_entry.page_id))
-    });
-    let mut rows = String::new();
-    for (entry, meta) in pages {
-        let display_title = if entry.seo.title.is_empty() {
-            &entry.seo.seo_title
-        } else {
-            &entry.seo.title
-        };
-        let title = escape_html(display_title);
-        let description = escape_html(&entry.seo.description);
-        let data_title = escape_html_attr(display_title);
-        let data_description = escape_html_attr(&entry.seo.description);
-        let keywords = entry
-            .seo
-            .keywords
-            .as_ref()
-            .map(|items| items.join(", "))
-            .filter(|value| !value.trim().is_empty())
-            .map(|value| escape_html(&value))
-            .unwrap_or_else(|| "无".to_string());
-        let data_keywords = entry
-            .seo
-            .keywords
-            .as_ref()
-            .map(|items| items.join(", "))
-            .filter(|value| !value.trim().is_empty())
-            .map(|value| escape_html_attr(&value))
-            .unwrap_or_else(|| "无".to_string());
-        let page_id_attr = escape_html_attr(&entry.page_id);
-        let url = build_page_url(&entry.page_id, &entry.seo.seo_title);
-        let url_attr = escape_html_attr(&url);
-        let updated_at = escape_html(&format_display_timestamp(meta.updated_at));
-        rows.push_str(&format!(
-            "<article class=\"card\" data-page-id=\"{page_id_attr}\" data-title=\"{data_title}\" data-description=\"{data_description}\" data-keywords=\"{data_keywords}\"><div class=\"card-header\"><h2><a href=\"{url_attr}\">{title}</a></h2><span class=\"updated-at\">更新：{updated_at}</span></div><p class=\"description\">{description}</p><div class=\"keywords\"><span>关键词：</span><span class=\"keyword-value\">{keywords}</span></div><div class=\"actions\"><a class=\"read-more\" href=\"{url_attr}\">阅读页面</a></div></article>",
-        ));
+    let sentence_end = (SUGGESTED_DESCRIPTION_MIN_CHARS..=SEO_DESCRIPTION_MAX_CHARS)
+        .rev()
+        .find(|&end| matches!(chars[end - 1], '。' | '！' | '？' | '.' | '!' | '?'));
+    if let Some(end) = sentence_end {
+        return chars[..end].iter().collect();
     }
+    // 留一个字符给省略号
+    let limit = SEO_DESCRIPTION_MAX_CHARS - 1;
+    let end = (SUGGESTED_DESCRIPTION_MIN_CHARS..limit)
+        .rev()
+        .find(|&end| chars[end] == ' ')
+        .unwrap_or(limit);
+    let mut description: String = chars[..end].iter().collect();
+    description.push('…');
+    description
+}
 
-    if rows.is_empty() {
-        rows.push_str(
-            "<div class=\"empty\">暂无页面内容，请先通过 MCP 接口发布页面。</div>",
-        );
+/// 草稿的可见文本，完整 HTML 文档只取 `<body>` 部分
+pub fn draft_visible_text(html: &str) -> String {
+    match find_body_range(html.as_bytes()) {
+        Some((start, end)) => html_visible_text(&html[start..end]),
+        None => html_visible_text(html),
     }
+}
 
-    let beian_number = std::env::var("BEIAN_NUMBER")
-        .unwrap_or_default()
-        .trim()
-        .to_string();
-    let beian_html = if beian_number.is_empty() {
-        String::new()
-    } else {
-        format!(
-            "<footer class=\"beian\">{}</footer>",
-            escape_html(&beian_number)
-        )
-    };
-
-    let site_subtitle = std::env::var("SITE_SUBTITLE")
-        .unwrap_or_default()
-        .trim()
-        .to_string();
-    let site_subtitle = if site_subtitle.is_empty() {
-        "AI 原生博客 · 最新页面列表".to_string()
-    } else {
-        site_subtitle
-    };
-
-    let rendered = replace_template(
-        &template,
-        &[
-            ("site_header", &header_html),
-            ("page_list", &rows),
-            ("site_title", "SolinBlog"),
-            ("site_subtitle", &site_subtitle),
-            ("beian_number", &beian_html),
-        ],
-    )?;
-
-    Ok(rendered)
+/// `check_page_seo` 的问题级别，得分按级别扣减
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeoSeverity {
+    Error,
+    Warning,
+    Info,
 }
 
-pub fn render_404_html() -> Result<String> {
-    let header_html = std::fs::read_to_string("front/header.html")
-        .context("read front/header.html template")?;
-    let template = std::fs::read_to_string("front/404.html")
-        .context("read front/404.html template")?;
-    let rendered = replace_template(&template, &[("site_header", &header_html)])?;
-    Ok(rendered)
+impl SeoSeverity {
+    fn penalty(self) -> u8 {
+        match self {
+            Self::Error => 20,
+            Self::Warning => 10,
+            Self::Info => 3,
+        }
+    }
 }
 
-fn replace_template(template: &str, values: &[(&str, &str)]) -> Result<String> {
-    let mut out = template.to_string();
-    for (key, value) in values {
-        let placeholder = format!("{{{{{}}}}}", key);
-        if !out.contains(&placeholder) {
-            bail!("template missing placeholder {placeholder}");
+/// 可见文本少于该字数时提示内容过短
+const SEO_MIN_WORD_COUNT: u64 = 300;
+
+/// 页面 SEO 自查：标题、描述与关键词取自 `meta.seo`，其余检查基于存储的 HTML
+/// 返回 0–100 的得分（满分按问题级别扣减）与问题列表
+pub fn check_page_seo(meta: &PageMeta, html: &str) -> (u8, Vec<(SeoSeverity, String)>) {
+    let mut issues = seo_meta_issues(&meta.seo);
+    let has_keywords = meta
+        .seo
+        .keywords
+        .iter()
+        .flatten()
+        .any(|keyword| !keyword.trim().is_empty());
+    if !has_keywords {
+        issues.push((SeoSeverity::Warning, "keywords are empty".to_string()));
+    }
+    if !head_has_link_rel(html, "canonical") {
+        issues.push((
+            SeoSeverity::Warning,
+            "canonical url is not set (<link rel=\"canonical\">)".to_string(),
+        ));
+    }
+
+    let bytes = html.as_bytes();
+    let (mut h1_count, mut images_without_alt, mut has_og_image) = (0usize, 0usize, false);
+    let mut index = 0usize;
+    while let Some(found) = html[index..].find('<') {
+        let start = index + found;
+        let Some(tag_end) = find_tag_end(bytes, start + 1) else {
+            break;
+        };
+        index = tag_end + 1;
+        let Some((name, _)) = parse_tag_name_ci(bytes, start + 1) else {
+            continue;
+        };
+        let tag_html = &html[start..=tag_end];
+        match name.to_ascii_lowercase().as_str() {
+            "h1" => h1_count += 1,
+            "img" if find_attr_value(tag_html, "alt").is_none() => images_without_alt += 1,
+            "meta" if find_attr_value(tag_html, "property").as_deref() == Some("og:image") => {
+                has_og_image = true;
+            }
+            _ => {}
         }
-        out = out.replace(&placeholder, value);
     }
-    Ok(out)
+    if !has_og_image {
+        issues.push((
+            SeoSeverity::Info,
+            "og:image is not set, shared links will have no preview image".to_string(),
+        ));
+    }
+    let (word_count, _) = estimate_reading_time(&html_visible_text(html));
+    if word_count < SEO_MIN_WORD_COUNT {
+        issues.push((
+            SeoSeverity::Warning,
+            format!(
+                "page has {} words, at least {} is recommended",
+                word_count, SEO_MIN_WORD_COUNT
+            ),
+        ));
+    }
+    match h1_count {
+        0 => issues.push((SeoSeverity::Warning, "page has no <h1> heading".to_string())),
+        1 => {}
+        count => issues.push((
+            SeoSeverity::Warning,
+            format!("page has {} <h1> headings, use a single <h1>", count),
+        )),
+    }
+    if images_without_alt > 0 {
+        issues.push((
+            SeoSeverity::Warning,
+            format!("{} <img> without alt text", images_without_alt),
+        ));
+    }
+
+    let penalty: u32 = issues
+        .iter()
+        .map(|(severity, _)| u32::from(severity.penalty()))
+        .sum();
+    let score = 100u32.saturating_sub(penalty) as u8;
+    (score, issues)
 }
 
-pub fn render_page_html(meta: &PageMeta, html: &str) -> String {
-    let title = if meta.seo.title.is_empty() {
-        &meta.seo.seo_title
+fn seo_meta_issues(seo: &SeoMeta) -> Vec<(SeoSeverity, String)> {
+    let mut warnings = Vec::new();
+    let title = if seo.title.trim().is_empty() {
+        seo.seo_title.trim()
     } else {
-        &meta.seo.title
+        seo.title.trim()
     };
-    inject_seo_meta(html, title, &meta.seo)
+    let title_chars = title.chars().count();
+    if title_chars == 0 {
+        warnings.push((SeoSeverity::Error, "title is empty".to_string()));
+    } else if title_chars > SEO_TITLE_MAX_CHARS {
+        warnings.push((
+            SeoSeverity::Warning,
+            format!(
+                "title has {} characters, search results usually truncate after {}",
+                title_chars, SEO_TITLE_MAX_CHARS
+            ),
+        ));
+    }
+    let description_chars = seo.description.trim().chars().count();
+    if description_chars == 0 {
+        warnings.push((SeoSeverity::Error, "description is empty".to_string()));
+    } else if description_chars < SEO_DESCRIPTION_MIN_CHARS {
+        warnings.push((
+            SeoSeverity::Warning,
+            format!(
+                "description has {} characters, at least {} is recommended",
+                description_chars, SEO_DESCRIPTION_MIN_CHARS
+            ),
+        ));
+    } else if description_chars > SEO_DESCRIPTION_MAX_CHARS {
+        warnings.push((
+            SeoSeverity::Warning,
+            format!(
+                "description has {} characters, search results usually truncate after {}",
+                description_chars, SEO_DESCRIPTION_MAX_CHARS
+            ),
+        ));
+    }
+    warnings
 }
 
-pub fn markdown_to_html(markdown: &str) -> String {
-    let mut options = Options::empty();
-    options.insert(Options::ENABLE_TABLES);
-    options.insert(Options::ENABLE_FOOTNOTES);
-    options.insert(Options::ENABLE_STRIKETHROUGH);
-    options.insert(Options::ENABLE_TASKLISTS);
-
-    let parser = Parser::new_ext(markdown, options);
-    let mut output = String::new();
-    html::push_html(&mut output, parser);
-    output
+/// sitemap 中的一条页面记录
+#[derive(Debug, Clone)]
+pub struct SitemapEntry {
+    pub url: String,
+    /// `format_unix_timestamp` 格式的发布时间，见 `page_published_at`
+    pub lastmod: String,
+    pub page_uid: String,
+    pub title: String,
+    /// 正文 `<img>` 的完整 URL，最多 `SITEMAP_MAX_IMAGES_PER_URL` 个
+    pub images: Vec<String>,
 }
 
-pub fn render_markdown_page(markdown: &str) -> Result<String> {
-    let markdown_html = markdown_to_html(markdown);
-    let header_html = std::fs::read_to_string("front/header.html")
-        .context("read front/header.html template")?;
-    let template = std::fs::read_to_string("front/markdown.html")
-        .context("read front/markdown.html template")?;
-    let rendered = replace_template(
-        &template,
-        &[("site_header", &header_html), ("markdown_html", &markdown_html)],
-    )?;
-    Ok(rendered)
-}
+/// 图片 sitemap 扩展规定每个 `<url>` 最多 1000 个 `<image:image>`
+const SITEMAP_MAX_IMAGES_PER_URL: usize = 1000;
 
-pub fn render_sitemap_xml(store: &PageStore, base_url: &str) -> Result<String> {
-    let entries = store.list_page_entries().context("list page entries")?;
-    let mut body = String::new();
+/// 列出会写入 sitemap 的页面，跳过未公开与设置了 noindex 的页面
+pub async fn list_sitemap_entries(
+    store: &dyn PageStoreTrait,
+    base_url: &str,
+) -> Result<Vec<SitemapEntry>> {
+    let entries = store
+        .list_page_entries()
+        .await
+        .context("list page entries")?;
     let base = normalize_base_url(base_url);
+    let mut sitemap = Vec::new();
     for entry in entries {
         let meta = store
             .get_page_meta(&entry.page_id)
+            .await
             .with_context(|| format!("load page meta {}", entry.page_id))?;
+        if !is_page_indexable(&meta) {
+            continue;
+        }
         let page_path = build_page_url(&entry.page_id, &entry.seo.seo_title);
-        let page_url = format!("{}{}", base, page_path);
-        let lastmod = format_unix_timestamp(meta.updated_at);
+        // 正文读取失败只影响图片条目，页面本身仍然收录
+        let images = match store.get_page_html(&entry.page_id).await {
+            Ok(html) => extract_image_urls_from_html(&html)
+                .iter()
+                .map(|src| resolve_sitemap_image_url(&base, src))
+                .take(SITEMAP_MAX_IMAGES_PER_URL)
+                .collect(),
+            Err(err) => {
+                warn!(
+                    "load page html {} for sitemap images failed: {:#}",
+                    entry.page_id, err
+                );
+                Vec::new()
+            }
+        };
+        sitemap.push(SitemapEntry {
+            url: format!("{}{}", base, page_path),
+            lastmod: format_unix_timestamp(page_published_at(&meta)),
+            page_uid: meta.page_uid,
+            title: meta.seo.seo_title,
+            images,
+        });
+    }
+    Ok(sitemap)
+}
+
+pub async fn render_sitemap_xml(store: &dyn PageStoreTrait, base_url: &str) -> Result<String> {
+    let mut body = String::new();
+    for entry in list_sitemap_entries(store, base_url).await? {
         body.push_str("  <url>\n");
-        body.push_str(&format!(
-            "    <loc>{}</loc>\n",
-            escape_xml(&page_url)
-        ));
+        body.push_str(&format!("    <loc>{}</loc>\n", escape_xml(&entry.url)));
         body.push_str(&format!(
             "    <lastmod>{}</lastmod>\n",
-            escape_xml(&lastmod)
+            escape_xml(&entry.lastmod)
         ));
         body.push_str("    <changefreq>weekly</changefreq>\n");
         body.push_str("    <priority>0.8</priority>\n");
+        for image in &entry.images {
+            body.push_str(&format!(
+                "    <image:image>\n      <image:loc>{}</image:loc>\n    </image:image>\n",
+                escape_xml(image)
+            ));
+        }
         body.push_str("  </url>\n");
     }
 
     Ok(format!(
-        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n{}</urlset>",
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\" xmlns:image=\"http://www.google.com/schemas/sitemap-image/1.1\">\n{}</urlset>",
         body
     ))
 }
 
+/// 按出现顺序提取 `<img src>`（去重），跳过 `data:` 内联图片；返回属性原值，不补全站点地址
+pub fn extract_image_urls_from_html(html: &str) -> Vec<String> {
+    let bytes = html.as_bytes();
+    let mut urls: Vec<String> = Vec::new();
+    let mut index = 0usize;
+    while let Some(found) = html[index..].find('<') {
+        let start = index + found;
+        let Some(tag_end) = find_tag_end(bytes, start + 1) else {
+            break;
+        };
+        index = tag_end + 1;
+        let Some((name, _)) = parse_tag_name_ci(bytes, start + 1) else {
+            continue;
+        };
+        if !name.eq_ignore_ascii_case("img") {
+            continue;
+        }
+        let Some(src) = find_attr_value(&html[start..=tag_end], "src") else {
+            continue;
+        };
+        let inline = src
+            .get(..5)
+            .is_some_and(|scheme| scheme.eq_ignore_ascii_case("data:"));
+        if src.is_empty() || inline {
+            continue;
+        }
+        if !urls.contains(&src) {
+            urls.push(src);
+        }
+    }
+    urls
+}
+
+/// 绝对地址原样使用；`//` 开头沿用站点协议，`/` 开头拼接站点根地址，其余相对地址相对于 `/pages/`
+fn resolve_sitemap_image_url(base: &str, src: &str) -> String {
+    if src.starts_with("http://") || src.starts_with("https://") {
+        return src.to_string();
+    }
+    if let Some(rest) = src.strip_prefix("//") {
+        let scheme = base.split_once("://").map_or("https", |(scheme, _)| scheme);
+        return format!("{scheme}://{rest}");
+    }
+    if src.starts_with('/') {
+        return format!("{base}{src}");
+    }
+    format!("{base}/pages/{}", src.trim_start_matches("./"))
+}
+
 pub fn inject_seo_meta(html: &str, title: &str, seo: &crate::store::SeoMeta) -> String {
     let escaped_title = escape_html(title);
     let escaped_description = escape_html_attr(&seo.description);
@@ -228,23 +2170,29 @@ pub fn inject_seo_meta(html: &str, title: &str, seo: &crate::store::SeoMeta) ->
             keyword_value
         ));
     }
+    // 写入时已校验为对象，这里再过滤一次，避免旧数据输出数组或标量
+    if let Some(structured_data) = &seo.structured_data
+        && structured_data.is_object()
+    {
+        additions.push_str(&json_ld_script(structured_data));
+    }
 
     let mut out = String::new();
     let bytes = html.as_bytes();
     let mut index = 0usize;
     let mut head_range: Option<(usize, usize)> = None;
     while index < bytes.len() {
-        if bytes[index] == b'<' {
-            if let Some((name, after_name)) = parse_tag_name_ci(bytes, index + 1) {
-                let name = name.to_ascii_lowercase();
-                if name == "head" {
-                    if let Some(end) = find_tag_end(bytes, after_name) {
-                        let content_start = end + 1;
-                        if let Some(close_start) = find_bytes_ci(bytes, content_start, b"</head") {
-                            head_range = Some((content_start, close_start));
-                            break;
-                        }
-                    }
+        if bytes[index] == b'<'
+            && let Some((name, after_name)) = parse_tag_name_ci(bytes, index + 1)
+        {
+            let name = name.to_ascii_lowercase();
+            if name == "head"
+                && let Some(end) = find_tag_end(bytes, after_name)
+            {
+                let content_start = end + 1;
+                if let Some(close_start) = find_bytes_ci(bytes, content_start, b"</head") {
+                    head_range = Some((content_start, close_start));
+                    break;
                 }
             }
         }
@@ -256,6 +2204,12 @@ pub fn inject_seo_meta(html: &str, title: &str, seo: &crate::store::SeoMeta) ->
         let existing = &html[start..end];
         let cleaned = remove_head_seo_tags(existing);
         out.push_str(&additions);
+        if !head_has_link_rel(&cleaned, "icon") {
+            out.push_str(&icon_link_tags());
+        }
+        if !head_has_link_rel(&cleaned, "manifest") {
+            out.push_str(&pwa_head_tags());
+        }
         out.push_str(&cleaned);
         out.push_str(&html[end..]);
         return out;
@@ -265,6 +2219,8 @@ pub fn inject_seo_meta(html: &str, title: &str, seo: &crate::store::SeoMeta) ->
         out.push_str(&html[..insert_at]);
         out.push_str("<head>");
         out.push_str(&additions);
+        out.push_str(&icon_link_tags());
+        out.push_str(&pwa_head_tags());
         out.push_str("</head>");
         out.push_str(&html[insert_at..]);
         return out;
@@ -274,12 +2230,104 @@ pub fn inject_seo_meta(html: &str, title: &str, seo: &crate::store::SeoMeta) ->
         out.push_str(&html[..body_pos]);
         out.push_str("<head>");
         out.push_str(&additions);
+        out.push_str(&icon_link_tags());
+        out.push_str(&pwa_head_tags());
         out.push_str("</head>");
         out.push_str(&html[body_pos..]);
         return out;
     }
 
-    format!("<head>{}</head>{}", additions, html)
+    format!(
+        "<head>{}{}{}</head>{}",
+        additions,
+        icon_link_tags(),
+        pwa_head_tags(),
+        html
+    )
+}
+
+/// build.rs 由 `public/icon.png` 生成的 PWA 图标尺寸，对应 `public/icon-{size}.png`
+pub const PWA_ICON_SIZES: [u32; 2] = [192, 512];
+
+/// 生成 `/manifest.webmanifest`，每次请求读取环境变量，修改配置后无需重新编译
+pub fn render_web_manifest() -> String {
+    let site_title = resolve_site_title_from_env();
+    let site_subtitle = std::env::var("SITE_SUBTITLE")
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    let (theme_color, background_color) = resolve_pwa_colors_from_env();
+    let mut icons: Vec<serde_json::Value> = PWA_ICON_SIZES
+        .iter()
+        .filter(|size| std::path::Path::new(&format!("public/icon-{}.png", size)).is_file())
+        .map(|size| {
+            serde_json::json!({
+                "src": format!("/public/icon-{}.png", size),
+                "sizes": format!("{size}x{size}"),
+                "type": "image/png",
+                "purpose": "any",
+            })
+        })
+        .collect();
+    if icons.is_empty() {
+        icons.push(serde_json::json!({
+            "src": "/icon.png",
+            "type": "image/png",
+        }));
+    }
+    let manifest = serde_json::json!({
+        "name": site_title,
+        "short_name": site_title,
+        "description": site_subtitle,
+        "start_url": "/",
+        "scope": "/",
+        "display": "standalone",
+        "theme_color": theme_color,
+        "background_color": background_color,
+        "icons": icons,
+    });
+    manifest.to_string()
+}
+
+/// manifest 与 theme-color 标签，首页模板与文章页共用
+pub fn pwa_head_tags() -> String {
+    let (theme_color, _) = resolve_pwa_colors_from_env();
+    format!(
+        "<link rel=\"manifest\" href=\"/manifest.webmanifest\"><meta name=\"theme-color\" content=\"{}\">",
+        escape_html_attr(&theme_color)
+    )
+}
+
+/// 站点图标，对应 `/favicon.ico`、`/icon.png`、`/apple-touch-icon.png` 路由
+const ICON_LINK_TAGS: &str = "<link rel=\"icon\" href=\"/favicon.ico\" sizes=\"any\"><link rel=\"icon\" type=\"image/png\" href=\"/icon.png\"><link rel=\"apple-touch-icon\" href=\"/apple-touch-icon.png\">";
+/// build.rs 生成 `public/icon.webp` 后追加，放在 PNG 之后作为首选图标
+const ICON_WEBP_LINK_TAG: &str = "<link rel=\"icon\" type=\"image/webp\" href=\"/icon.webp\">";
+
+fn icon_link_tags() -> String {
+    if std::path::Path::new("public/icon.webp").is_file() {
+        format!("{ICON_LINK_TAGS}{ICON_WEBP_LINK_TAG}")
+    } else {
+        ICON_LINK_TAGS.to_string()
+    }
+}
+
+fn head_has_link_rel(head_html: &str, rel_name: &str) -> bool {
+    let bytes = head_html.as_bytes();
+    let mut index = 0usize;
+    while let Some(pos) = find_bytes_ci(bytes, index, b"<link") {
+        let Some(end) = find_tag_end(bytes, pos + 5) else {
+            return false;
+        };
+        let matched = find_attr_value(&head_html[pos..=end], "rel").is_some_and(|rel| {
+            rel.split_ascii_whitespace()
+                .any(|token| token.eq_ignore_ascii_case(rel_name))
+        });
+        if matched {
+            return true;
+        }
+        index = end + 1;
+    }
+    false
 }
 
 fn remove_head_seo_tags(head_html: &str) -> String {
@@ -294,28 +2342,25 @@ fn remove_head_seo_tags(head_html: &str) -> String {
         }
         if let Some((name, after_name)) = parse_tag_name_ci(bytes, index + 1) {
             let lower = name.to_ascii_lowercase();
-            if lower == "title" {
-                if let Some(tag_end) = find_tag_end(bytes, after_name) {
-                    if let Some(close_start) = find_bytes_ci(bytes, tag_end + 1, b"</title") {
-                        if let Some(close_end) = find_tag_end(bytes, close_start + 2) {
-                            result.push_str(&head_html[copy_from..index]);
-                            index = close_end + 1;
-                            copy_from = index;
-                            continue;
-                        }
-                    }
-                }
+            if lower == "title"
+                && let Some(tag_end) = find_tag_end(bytes, after_name)
+                && let Some(close_start) = find_bytes_ci(bytes, tag_end + 1, b"</title")
+                && let Some(close_end) = find_tag_end(bytes, close_start + 2)
+            {
+                result.push_str(&head_html[copy_from..index]);
+                index = close_end + 1;
+                copy_from = index;
+                continue;
             }
-            if lower == "meta" {
-                if let Some(tag_end) = find_tag_end(bytes, after_name) {
-                    let tag_html = &head_html[index..=tag_end];
-                    if is_meta_named(tag_html, "description") || is_meta_named(tag_html, "keywords")
-                    {
-                        result.push_str(&head_html[copy_from..index]);
-                        index = tag_end + 1;
-                        copy_from = index;
-                        continue;
-                    }
+            if lower == "meta"
+                && let Some(tag_end) = find_tag_end(bytes, after_name)
+            {
+                let tag_html = &head_html[index..=tag_end];
+                if is_meta_named(tag_html, "description") || is_meta_named(tag_html, "keywords") {
+                    result.push_str(&head_html[copy_from..index]);
+                    index = tag_end + 1;
+                    copy_from = index;
+                    continue;
                 }
             }
         }
@@ -335,12 +2380,12 @@ fn is_meta_named(tag_html: &str, name: &str) -> bool {
             if value.starts_with('"') {
                 value = &value[1..];
                 if let Some(end) = value.find('"') {
-                    return &value[..end] == name_lower;
+                    return value[..end] == name_lower;
                 }
             } else if value.starts_with('\'') {
                 value = &value[1..];
                 if let Some(end) = value.find('\'') {
-                    return &value[..end] == name_lower;
+                    return value[..end] == name_lower;
                 }
             } else {
                 let token = value
@@ -357,12 +2402,11 @@ fn is_meta_named(tag_html: &str, name: &str) -> bool {
 fn find_html_tag_end(bytes: &[u8]) -> Option<usize> {
     let mut index = 0usize;
     while index < bytes.len() {
-        if bytes[index] == b'<' {
-            if let Some((name, after_name)) = parse_tag_name_ci(bytes, index + 1) {
-                if name.eq_ignore_ascii_case("html") {
-                    return find_tag_end(bytes, after_name).map(|value| value + 1);
-                }
-            }
+        if bytes[index] == b'<'
+            && let Some((name, after_name)) = parse_tag_name_ci(bytes, index + 1)
+            && name.eq_ignore_ascii_case("html")
+        {
+            return find_tag_end(bytes, after_name).map(|value| value + 1);
         }
         index += 1;
     }
@@ -415,31 +2459,7 @@ fn find_tag_end(bytes: &[u8], mut index: usize) -> Option<usize> {
     None
 }
 
-fn find_bytes_ci(haystack: &[u8], start: usize, needle: &[u8]) -> Option<usize> {
-    if needle.is_empty() {
-        return Some(start.min(haystack.len()));
-    }
-    if start >= haystack.len() || needle.len() > haystack.len() {
-        return None;
-    }
-    let needle_lower: Vec<u8> = needle.iter().map(|byte| byte.to_ascii_lowercase()).collect();
-    let end = haystack.len().saturating_sub(needle_lower.len());
-    for index in start..=end {
-        let mut matched = true;
-        for (offset, expected) in needle_lower.iter().enumerate() {
-            if haystack[index + offset].to_ascii_lowercase() != *expected {
-                matched = false;
-                break;
-            }
-        }
-        if matched {
-            return Some(index);
-        }
-    }
-    None
-}
-
-fn escape_html(input: &str) -> String {
+pub(crate) fn escape_html(input: &str) -> String {
     let mut out = String::with_capacity(input.len());
     for ch in input.chars() {
         match ch {
@@ -452,7 +2472,7 @@ fn escape_html(input: &str) -> String {
     out
 }
 
-fn escape_html_attr(input: &str) -> String {
+pub(crate) fn escape_html_attr(input: &str) -> String {
     let mut out = String::with_capacity(input.len());
     for ch in input.chars() {
         match ch {
@@ -495,7 +2515,7 @@ fn format_unix_timestamp(timestamp: i64) -> String {
     datetime.to_rfc3339()
 }
 
-fn format_display_timestamp(timestamp: i64) -> String {
+pub(crate) fn format_display_timestamp(timestamp: i64) -> String {
     let safe_ts = timestamp.max(0);
     let datetime = Utc
         .timestamp_opt(safe_ts, 0)
@@ -0,0 +1,134 @@
+use std::time::Duration;
+
+use reqwest::Client;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tracing::{error, info, warn};
+
+use crate::{
+    config::{WebhookConfig, resolve_webhook_config_from_env},
+    store::now_unix_seconds,
+};
+
+/// 首次投递失败后的最多重试次数，间隔依次为 1s、2s、4s
+const WEBHOOK_MAX_RETRIES: u32 = 3;
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum PageEvent {
+    #[serde(rename = "page.created")]
+    Created,
+    #[serde(rename = "page.updated")]
+    Updated,
+    #[serde(rename = "page.deleted")]
+    Deleted,
+}
+
+impl PageEvent {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Created => "page.created",
+            Self::Updated => "page.updated",
+            Self::Deleted => "page.deleted",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct WebhookPayload<'a> {
+    event: PageEvent,
+    page_uid: &'a str,
+    url: &'a str,
+    timestamp: i64,
+}
+
+/// 未配置 `WEBHOOK_URL` 时直接返回；否则在后台任务中投递，不阻塞调用方
+pub fn dispatch_page_event(event: PageEvent, page_uid: &str, url: &str) {
+    let Some(config) = resolve_webhook_config_from_env() else {
+        return;
+    };
+    let payload = WebhookPayload {
+        event,
+        page_uid,
+        url,
+        timestamp: now_unix_seconds().unwrap_or_default(),
+    };
+    let body = match serde_json::to_vec(&payload) {
+        Ok(body) => body,
+        Err(err) => {
+            error!("webhook encode {} failed: {err}", event.as_str());
+            return;
+        }
+    };
+    let page_uid = page_uid.to_string();
+    tokio::spawn(async move {
+        deliver(&config, event, &page_uid, body).await;
+    });
+}
+
+async fn deliver(config: &WebhookConfig, event: PageEvent, page_uid: &str, body: Vec<u8>) {
+    let client = Client::new();
+    let signature = (!config.secret.is_empty()).then(|| {
+        format!(
+            "sha256={}",
+            hmac_sha256_hex(config.secret.as_bytes(), &body)
+        )
+    });
+    for attempt in 0..=WEBHOOK_MAX_RETRIES {
+        if attempt > 0 {
+            tokio::time::sleep(Duration::from_secs(1 << (attempt - 1))).await;
+        }
+        let mut request = client
+            .post(&config.url)
+            .timeout(WEBHOOK_TIMEOUT)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.clone());
+        if let Some(signature) = &signature {
+            request = request.header("X-Solin-Signature", signature);
+        }
+        let outcome = match request.send().await {
+            Ok(response) if response.status().is_success() => Ok(response.status()),
+            Ok(response) => Err(format!("status {}", response.status())),
+            Err(err) => Err(err.to_string()),
+        };
+        match outcome {
+            Ok(status) => {
+                info!(
+                    "webhook {} {page_uid} delivered (attempt {}, status {status})",
+                    event.as_str(),
+                    attempt + 1
+                );
+                return;
+            }
+            Err(err) => warn!(
+                "webhook {} {page_uid} attempt {} failed: {err}",
+                event.as_str(),
+                attempt + 1
+            ),
+        }
+    }
+    error!(
+        "webhook {} {page_uid} giving up after {} attempts",
+        event.as_str(),
+        WEBHOOK_MAX_RETRIES + 1
+    );
+}
+
+/// HMAC-SHA256（RFC 2104），直接基于 sha2 实现，返回小写十六进制
+fn hmac_sha256_hex(secret: &[u8], body: &[u8]) -> String {
+    const BLOCK_SIZE: usize = 64;
+    let mut key = [0u8; BLOCK_SIZE];
+    if secret.len() > BLOCK_SIZE {
+        let digest = Sha256::digest(secret);
+        key[..digest.len()].copy_from_slice(&digest);
+    } else {
+        key[..secret.len()].copy_from_slice(secret);
+    }
+    let mut inner = Sha256::new();
+    inner.update(key.map(|byte| byte ^ 0x36));
+    inner.update(body);
+    let mut outer = Sha256::new();
+    outer.update(key.map(|byte| byte ^ 0x5c));
+    outer.update(inner.finalize());
+    format!("{:x}", outer.finalize())
+}